@@ -1,16 +1,23 @@
-use crate::{Cache, CacheStats};
+use crate::{Cache, CacheEntry, CacheStats, EntryFilter};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use std::marker::PhantomData;
-use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+mod envelope;
+mod store;
+
+use store::DiskStore;
+pub use store::{EvictionPolicy, GcReport};
 
 /// Disk-based cache implementation using cacache
 pub struct DiskCache<K, V> {
-    cache_dir: PathBuf,
-    stats: Arc<RwLock<CacheStats>>,
+    store: DiskStore,
     _phantom: PhantomData<(K, V)>,
 }
 
@@ -18,25 +25,100 @@ impl<K, V> DiskCache<K, V> {
     /// Create a new disk cache with the specified directory
     pub fn new<P: AsRef<Path>>(cache_dir: P) -> Self {
         Self {
-            cache_dir: cache_dir.as_ref().to_path_buf(),
-            stats: Arc::new(RwLock::new(CacheStats {
-                capacity: usize::MAX, // Disk cache doesn't have a fixed item capacity
-                ..Default::default()
-            })),
+            store: DiskStore::new(cache_dir.as_ref().to_path_buf()),
             _phantom: PhantomData,
         }
     }
 
+    /// Expire entries after at most `ttl`; entries inserted with a shorter
+    /// TTL expire sooner. Entries written before a TTL was configured expire
+    /// `ttl` after they were written.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.store.ttl = Some(ttl);
+        self
+    }
+
+    /// Keep the cached content within `max_bytes`, evicting entries chosen by
+    /// the eviction policy once an insert goes over budget
+    pub fn with_max_size(mut self, max_bytes: u64) -> Self {
+        self.store.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set which entries are evicted first when over the size budget
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.store.eviction_policy = policy;
+        self
+    }
+
+    /// Discard entries written with a schema version other than
+    /// `schema_version` (by default
+    /// [`CACHE_SCHEMA_VERSION`](rustacean_docs_core::constants::CACHE_SCHEMA_VERSION))
+    pub fn with_schema_version(mut self, schema_version: u32) -> Self {
+        self.store.schema_version = schema_version;
+        self
+    }
+
     /// Get the cache directory path
     pub fn cache_dir(&self) -> &Path {
-        &self.cache_dir
+        self.store.cache_dir()
+    }
+
+    /// Maximum lifetime of an entry, if any
+    pub fn ttl(&self) -> Option<Duration> {
+        self.store.ttl
+    }
+
+    /// Size budget in bytes, if any
+    pub fn max_size(&self) -> Option<u64> {
+        self.store.max_bytes
+    }
+
+    /// Policy used to pick entries to evict
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.store.eviction_policy
+    }
+
+    /// Remove expired entries, evict down to the size budget and delete
+    /// content no longer referenced by any entry
+    pub async fn collect_garbage(&self) -> Result<GcReport> {
+        self.store.collect_garbage().await
+    }
+
+    /// Run [`collect_garbage`](Self::collect_garbage) now and then every
+    /// `interval` on a background task, until the returned handle is aborted.
+    ///
+    /// The first pass also loads the size accounting from the index, so the
+    /// cache reports its on-disk usage right after startup.
+    pub fn spawn_gc(&self, interval: Duration) -> JoinHandle<()> {
+        let store = self.store.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match store.collect_garbage().await {
+                    Ok(report) if report == GcReport::default() => {
+                        debug!("Disk cache garbage collection found nothing to remove")
+                    }
+                    Ok(report) => info!(
+                        expired = report.expired,
+                        evicted = report.evicted,
+                        orphans = report.orphans,
+                        bytes_freed = report.bytes_freed,
+                        "Disk cache garbage collection finished"
+                    ),
+                    Err(e) => warn!(error = %e, "Disk cache garbage collection failed"),
+                }
+            }
+        })
     }
 }
 
 #[async_trait]
 impl<K, V> Cache for DiskCache<K, V>
 where
-    K: ToString + Send + Sync,
+    K: ToString + FromStr + Send + Sync,
     V: Serialize + DeserializeOwned + Send + Sync,
 {
     type Key = K;
@@ -44,76 +126,95 @@ where
     type Error = anyhow::Error;
 
     async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
-        let mut stats = self.stats.write().await;
-        let key_str = key.to_string();
-
-        match cacache::read(&self.cache_dir, &key_str).await {
-            Ok(data) => {
-                stats.hits += 1;
-                let value: V =
-                    serde_json::from_slice(&data).context("Failed to deserialize cached value")?;
-                Ok(Some(value))
-            }
-            Err(e) => {
-                // Check if it's a not found error by examining the error message
-                let error_str = e.to_string();
-                if error_str.contains("not found")
-                    || error_str.contains("NotFound")
-                    || error_str.contains("Entry not found")
-                {
-                    stats.misses += 1;
-                    Ok(None)
-                } else {
-                    Err(anyhow::Error::from(e)).context("Failed to read from disk cache")
-                }
-            }
-        }
+        Ok(self.get_with_ttl(key).await?.map(|(value, _)| value))
     }
 
     async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
-        let key_str = key.to_string();
-        let data = serde_json::to_vec(&value).context("Failed to serialize value")?;
-
-        cacache::write(&self.cache_dir, &key_str, data)
-            .await
-            .context("Failed to write to disk cache")?;
+        self.insert_with_ttl(key, value, None).await
+    }
 
-        // Update size estimate
-        let mut stats = self.stats.write().await;
-        stats.size = stats.size.saturating_add(1);
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+        let Some((data, remaining)) = self.store.get(&key.to_string()).await? else {
+            return Ok(None);
+        };
+        let value: V =
+            serde_json::from_slice(&data).context("Failed to deserialize cached value")?;
+        Ok(Some((value, remaining)))
+    }
 
-        Ok(())
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        self.insert_tagged(key, value, ttl, Vec::new()).await
     }
 
-    async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
-        let key_str = key.to_string();
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
+        let data = serde_json::to_vec(&value).context("Failed to serialize value")?;
+        self.store.insert(&key.to_string(), &data, ttl, tags).await
+    }
 
-        cacache::remove(&self.cache_dir, &key_str)
-            .await
-            .context("Failed to remove from disk cache")?;
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        // Keys that do not parse back into `K` were not written by this cache
+        let removed = self
+            .store
+            .remove_matching(|key, tags| K::from_str(key).is_ok_and(|key| filter(&key, tags)))
+            .await?;
+        Ok(removed
+            .iter()
+            .filter_map(|key| K::from_str(key).ok())
+            .collect())
+    }
 
-        // Update size estimate
-        let mut stats = self.stats.write().await;
-        if stats.size > 0 {
-            stats.size = stats.size.saturating_sub(1);
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        let mut entries = Vec::new();
+        for stored in self.store.entries().await? {
+            let Ok(key) = K::from_str(&stored.key) else {
+                continue;
+            };
+            match serde_json::from_slice(&stored.data) {
+                Ok(value) => entries.push(CacheEntry {
+                    key,
+                    value,
+                    ttl: stored.ttl,
+                    tags: stored.tags,
+                }),
+                Err(e) => {
+                    warn!(key = %stored.key, error = %e, "Skipping undecodable disk cache entry")
+                }
+            }
         }
+        Ok(entries)
+    }
 
-        Ok(())
+    async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
+        self.store.remove(&key.to_string()).await
     }
 
     async fn clear(&self) -> Result<(), Self::Error> {
-        cacache::clear(&self.cache_dir)
-            .await
-            .context("Failed to clear disk cache")?;
-
-        let mut stats = self.stats.write().await;
-        stats.size = 0;
-
-        Ok(())
+        self.store.clear().await
     }
 
     fn stats(&self) -> CacheStats {
-        self.stats.try_read().map(|s| s.clone()).unwrap_or_default()
+        self.store.stats()
+    }
+
+    fn layer_name(&self) -> &str {
+        "disk"
     }
 }
 
@@ -222,4 +323,316 @@ mod tests {
         let result = cache.get(&"complex".to_string()).await.unwrap();
         assert_eq!(result, Some(value));
     }
+
+    #[tokio::test]
+    async fn test_disk_cache_entry_ttl() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DiskCache::<String, String>::new(temp_dir.path());
+
+        cache
+            .insert_with_ttl(
+                "short".to_string(),
+                "value".to_string(),
+                Some(Duration::from_millis(50)),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert("forever".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let (_, remaining) = cache
+            .get_with_ttl(&"short".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_millis(50));
+        assert_eq!(
+            cache.get_with_ttl(&"forever".to_string()).await.unwrap(),
+            Some(("value".to_string(), None))
+        );
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // The expiry survives a restart
+        let reopened = DiskCache::<String, String>::new(temp_dir.path());
+        assert_eq!(reopened.get(&"short".to_string()).await.unwrap(), None);
+        assert_eq!(
+            reopened.get(&"forever".to_string()).await.unwrap(),
+            Some("value".to_string())
+        );
+        assert!(cacache::metadata(temp_dir.path(), "short")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_layer_ttl_applies_to_existing_entries() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Written without any expiry
+        DiskCache::<String, String>::new(temp_dir.path())
+            .insert("old".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let cache =
+            DiskCache::<String, String>::new(temp_dir.path()).with_ttl(Duration::from_millis(20));
+        assert_eq!(cache.get(&"old".to_string()).await.unwrap(), None);
+
+        let cache =
+            DiskCache::<String, String>::new(temp_dir.path()).with_ttl(Duration::from_secs(60));
+        cache
+            .insert_with_ttl(
+                "capped".to_string(),
+                "value".to_string(),
+                Some(Duration::from_secs(3600)),
+            )
+            .await
+            .unwrap();
+        let (_, remaining) = cache
+            .get_with_ttl(&"capped".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_size_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let value = "x".repeat(100);
+        // Serialized as a JSON string behind the 17 byte envelope header: 119 bytes per entry
+        let cache = DiskCache::<String, String>::new(temp_dir.path()).with_max_size(250);
+
+        cache.insert("a".to_string(), value.clone()).await.unwrap();
+        cache.insert("b".to_string(), value.clone()).await.unwrap();
+        assert_eq!(cache.stats().bytes, 238);
+
+        // Reading `a` makes `b` the least recently used entry
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_some());
+        cache.insert("c".to_string(), value.clone()).await.unwrap();
+
+        assert!(cache.get(&"b".to_string()).await.unwrap().is_none());
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_some());
+        assert!(cache.get(&"c".to_string()).await.unwrap().is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.bytes, 238);
+        assert_eq!(stats.max_bytes, 250);
+        assert_eq!(stats.evictions, 1);
+
+        // Entries larger than the whole budget are not stored
+        cache
+            .insert("huge".to_string(), "x".repeat(300))
+            .await
+            .unwrap();
+        assert!(cache.get(&"huge".to_string()).await.unwrap().is_none());
+        assert_eq!(cache.stats().bytes, 238);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_lfu_eviction() {
+        let temp_dir = TempDir::new().unwrap();
+        let value = "x".repeat(100);
+        let cache = DiskCache::<String, String>::new(temp_dir.path())
+            .with_max_size(250)
+            .with_eviction_policy(EvictionPolicy::Lfu);
+
+        cache.insert("a".to_string(), value.clone()).await.unwrap();
+        cache.insert("b".to_string(), value.clone()).await.unwrap();
+        for _ in 0..3 {
+            cache.get(&"a".to_string()).await.unwrap();
+        }
+        cache.get(&"b".to_string()).await.unwrap();
+        cache.insert("c".to_string(), value).await.unwrap();
+
+        assert!(cache.get(&"b".to_string()).await.unwrap().is_none());
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_some());
+        assert!(cache.get(&"c".to_string()).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_usage_survives_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        {
+            let cache = DiskCache::<String, String>::new(temp_dir.path());
+            cache
+                .insert("a".to_string(), "x".repeat(100))
+                .await
+                .unwrap();
+            cache
+                .insert("b".to_string(), "x".repeat(100))
+                .await
+                .unwrap();
+            // Overwrites and removals must not be counted after a restart
+            cache
+                .insert("a".to_string(), "y".repeat(100))
+                .await
+                .unwrap();
+            cache.remove(&"b".to_string()).await.unwrap();
+        }
+
+        let cache = DiskCache::<String, String>::new(temp_dir.path()).with_max_size(150);
+        let report = cache.collect_garbage().await.unwrap();
+        assert_eq!(report.evicted, 0);
+        let stats = cache.stats();
+        assert_eq!(stats.size, 1);
+        assert_eq!(stats.bytes, 119);
+
+        // The second entry goes over budget and pushes out the older one
+        cache
+            .insert("c".to_string(), "x".repeat(100))
+            .await
+            .unwrap();
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_none());
+        assert_eq!(cache.stats().bytes, 119);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_tags_survive_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        {
+            let cache = DiskCache::<String, String>::new(temp_dir.path());
+            cache
+                .insert_tagged(
+                    "tokio".to_string(),
+                    "docs".to_string(),
+                    None,
+                    vec!["crate:tokio".to_string()],
+                )
+                .await
+                .unwrap();
+            cache
+                .insert("serde".to_string(), "docs".to_string())
+                .await
+                .unwrap();
+        }
+
+        let cache = DiskCache::<String, String>::new(temp_dir.path());
+        let removed = cache
+            .remove_matching(&|_: &String, tags: &[String]| {
+                tags.contains(&"crate:tokio".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(removed, vec!["tokio".to_string()]);
+        assert!(cache.get(&"tokio".to_string()).await.unwrap().is_none());
+        assert!(cache.get(&"serde".to_string()).await.unwrap().is_some());
+        assert_eq!(cache.stats().size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DiskCache::<String, String>::new(temp_dir.path());
+        cache
+            .insert_tagged(
+                "tokio".to_string(),
+                "docs".to_string(),
+                Some(Duration::from_secs(60)),
+                vec!["crate:tokio".to_string()],
+            )
+            .await
+            .unwrap();
+        cache
+            .insert_with_ttl(
+                "expired".to_string(),
+                "docs".to_string(),
+                Some(Duration::from_millis(1)),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let entries = cache.entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "tokio");
+        assert_eq!(entries[0].value, "docs");
+        assert_eq!(entries[0].tags, vec!["crate:tokio".to_string()]);
+        assert!(entries[0].ttl.unwrap() <= Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_garbage_collection() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DiskCache::<String, String>::new(temp_dir.path());
+
+        cache
+            .insert_with_ttl(
+                "expiring".to_string(),
+                "value".to_string(),
+                Some(Duration::from_millis(10)),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert("kept".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        // Content left behind by a plain index removal is orphaned
+        cacache::write(temp_dir.path(), "orphan", b"orphaned content")
+            .await
+            .unwrap();
+        cacache::remove(temp_dir.path(), "orphan").await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let report = cache.collect_garbage().await.unwrap();
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.orphans, 1);
+        assert_eq!(report.bytes_freed, 24 + 16);
+
+        // Shared content stays until its last reference goes
+        assert_eq!(
+            cache.get(&"kept".to_string()).await.unwrap(),
+            Some("value".to_string())
+        );
+        assert_eq!(cache.collect_garbage().await.unwrap(), GcReport::default());
+        assert_eq!(cache.stats().bytes, 24);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_compresses_large_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = DiskCache::<String, String>::new(temp_dir.path());
+        let value = "repeated documentation text ".repeat(100);
+
+        cache
+            .insert("large".to_string(), value.clone())
+            .await
+            .unwrap();
+        assert!(cache.stats().bytes < value.len() as u64 / 4);
+        assert_eq!(cache.get(&"large".to_string()).await.unwrap(), Some(value));
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_discards_other_schema_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        DiskCache::<String, String>::new(temp_dir.path())
+            .with_schema_version(1)
+            .insert("old".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        // Written before entries had envelopes
+        cacache::write(temp_dir.path(), "legacy", br#""value""#)
+            .await
+            .unwrap();
+
+        let cache = DiskCache::<String, String>::new(temp_dir.path()).with_schema_version(2);
+        assert!(cache.entries().await.unwrap().is_empty());
+        assert_eq!(cache.get(&"old".to_string()).await.unwrap(), None);
+        assert_eq!(cache.get(&"legacy".to_string()).await.unwrap(), None);
+
+        // Both were removed rather than kept around as misses
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 2));
+        assert_eq!(stats.size, 0);
+        assert!(cacache::metadata(temp_dir.path(), "old")
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
@@ -0,0 +1,104 @@
+//! Framing of disk cache content.
+//!
+//! Every value is stored behind a fixed header so that entries written by a
+//! release with different cached models can be recognised and dropped
+//! instead of failing to deserialize:
+//!
+//! | bytes | field |
+//! |---|---|
+//! | 4 | magic `RDCE` |
+//! | 4 | schema version, little endian |
+//! | 8 | creation time in unix milliseconds, little endian |
+//! | 1 | compression: 0 none, 1 zstd |
+//! | rest | payload |
+
+use anyhow::{bail, Context, Result};
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+use std::io::Read;
+
+const MAGIC: [u8; 4] = *b"RDCE";
+const HEADER_LEN: usize = 17;
+
+const COMPRESSION_NONE: u8 = 0;
+const COMPRESSION_ZSTD: u8 = 1;
+
+/// Payloads smaller than this are stored uncompressed
+const COMPRESSION_THRESHOLD: usize = 512;
+
+/// Wrap `payload` in an envelope, compressing it when that saves space
+pub(crate) fn seal(payload: &[u8], schema_version: u32, created_at: u128) -> Vec<u8> {
+    let compressed = (payload.len() >= COMPRESSION_THRESHOLD)
+        .then(|| compress_to_vec(payload, CompressionLevel::Fastest))
+        .filter(|compressed| compressed.len() < payload.len());
+    let (compression, body) = match &compressed {
+        Some(compressed) => (COMPRESSION_ZSTD, compressed.as_slice()),
+        None => (COMPRESSION_NONE, payload),
+    };
+
+    let mut sealed = Vec::with_capacity(HEADER_LEN + body.len());
+    sealed.extend_from_slice(&MAGIC);
+    sealed.extend_from_slice(&schema_version.to_le_bytes());
+    sealed.extend_from_slice(&(created_at as u64).to_le_bytes());
+    sealed.push(compression);
+    sealed.extend_from_slice(body);
+    sealed
+}
+
+/// The payload of an envelope written with `schema_version`.
+///
+/// Content written with another schema version, or before entries had
+/// envelopes, is `None`; a damaged envelope is an error.
+pub(crate) fn open(data: &[u8], schema_version: u32) -> Result<Option<Vec<u8>>> {
+    let Some(header) = data.get(..HEADER_LEN).filter(|header| header[..4] == MAGIC) else {
+        return Ok(None);
+    };
+    let version = u32::from_le_bytes(header[4..8].try_into().expect("4 byte slice"));
+    if version != schema_version {
+        return Ok(None);
+    }
+
+    let body = &data[HEADER_LEN..];
+    match header[16] {
+        COMPRESSION_NONE => Ok(Some(body.to_vec())),
+        COMPRESSION_ZSTD => {
+            let mut decoder = ruzstd::decoding::StreamingDecoder::new(body)
+                .context("Invalid zstd disk cache entry")?;
+            let mut payload = Vec::new();
+            decoder
+                .read_to_end(&mut payload)
+                .context("Failed to decompress disk cache entry")?;
+            Ok(Some(payload))
+        }
+        other => bail!("Unknown disk cache compression {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_compression() {
+        let small = br#"{"v":1}"#;
+        let sealed = seal(small, 3, 1_700_000_000_000);
+        assert_eq!(sealed.len(), HEADER_LEN + small.len());
+        assert_eq!(open(&sealed, 3).unwrap().as_deref(), Some(&small[..]));
+
+        let large = serde_json::to_vec(&vec!["repeated docs text"; 200]).unwrap();
+        let sealed = seal(&large, 3, 1_700_000_000_000);
+        assert_eq!(sealed[16], COMPRESSION_ZSTD);
+        assert!(sealed.len() < large.len() / 4);
+        assert_eq!(open(&sealed, 3).unwrap(), Some(large));
+    }
+
+    #[test]
+    fn test_other_schemas_and_legacy_content_are_rejected() {
+        let sealed = seal(b"{}", 1, 0);
+        assert_eq!(open(&sealed, 2).unwrap(), None);
+        assert_eq!(open(br#"{"legacy":"json"}"#, 1).unwrap(), None);
+
+        let mut damaged = sealed;
+        damaged[16] = 9;
+        assert!(open(&damaged, 1).is_err());
+    }
+}
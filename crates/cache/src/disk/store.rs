@@ -0,0 +1,694 @@
+use super::envelope;
+use crate::{effective_ttl, CacheStats};
+use anyhow::{Context, Result};
+use cacache::{Integrity, Metadata};
+use rustacean_docs_core::constants::CACHE_SCHEMA_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+/// Index metadata field holding the expiry time in unix milliseconds
+const EXPIRES_AT_FIELD: &str = "expires_at";
+
+/// Index metadata field holding the entry's invalidation tags
+const TAGS_FIELD: &str = "tags";
+
+/// Directory cacache stores content under, relative to the cache root
+const CONTENT_DIR: &str = "content-v2";
+
+/// Which entries a size-bounded disk cache evicts first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvictionPolicy {
+    /// Least recently read or written
+    #[default]
+    Lru,
+    /// Least frequently read, ties broken by recency
+    Lfu,
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvictionPolicy::Lru => write!(f, "lru"),
+            EvictionPolicy::Lfu => write!(f, "lfu"),
+        }
+    }
+}
+
+impl FromStr for EvictionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lru" => Ok(EvictionPolicy::Lru),
+            "lfu" => Ok(EvictionPolicy::Lfu),
+            _ => Err(format!(
+                "Invalid eviction policy '{s}'. Valid options: lru, lfu"
+            )),
+        }
+    }
+}
+
+/// Outcome of a garbage collection pass over the disk cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// Expired entries removed
+    pub expired: usize,
+    /// Entries evicted to get back under the size budget
+    pub evicted: usize,
+    /// Content files no index entry refers to any more
+    pub orphans: usize,
+    /// Bytes dropped from the size accounting, plus the size of deleted orphans
+    pub bytes_freed: u64,
+}
+
+/// Accounting for one live index entry
+#[derive(Debug, Clone)]
+struct Usage {
+    integrity: Integrity,
+    size: u64,
+    expires_at: Option<u128>,
+    last_access: u128,
+    hits: u64,
+    tags: Vec<String>,
+}
+
+/// Live entries of the cache directory, loaded from the index on first use.
+///
+/// Access recency and frequency are tracked in memory; after a restart entries
+/// are ordered by the time they were written.
+#[derive(Debug, Default)]
+struct UsageIndex {
+    loaded: bool,
+    entries: HashMap<String, Usage>,
+    bytes: u64,
+    clock: u128,
+}
+
+impl UsageIndex {
+    /// Strictly increasing access time, close to wall-clock milliseconds
+    fn tick(&mut self) -> u128 {
+        self.clock = now_millis().max(self.clock + 1);
+        self.clock
+    }
+
+    fn record(&mut self, key: String, usage: Usage) -> Option<Usage> {
+        self.clock = self.clock.max(usage.last_access);
+        self.bytes += usage.size;
+        let previous = self.entries.insert(key, usage);
+        if let Some(previous) = &previous {
+            self.bytes = self.bytes.saturating_sub(previous.size);
+        }
+        previous
+    }
+
+    fn touch(&mut self, key: &str) {
+        let now = self.tick();
+        if let Some(usage) = self.entries.get_mut(key) {
+            usage.last_access = now;
+            usage.hits += 1;
+        }
+    }
+
+    fn forget(&mut self, key: &str) -> Option<Usage> {
+        let usage = self.entries.remove(key)?;
+        self.bytes = self.bytes.saturating_sub(usage.size);
+        Some(usage)
+    }
+
+    fn references(&self, integrity: &Integrity) -> bool {
+        self.entries
+            .values()
+            .any(|usage| &usage.integrity == integrity)
+    }
+
+    /// Keys to evict, in order, until at most `budget` bytes remain
+    fn victims(&self, policy: EvictionPolicy, budget: u64, keep: Option<&str>) -> Vec<String> {
+        let mut candidates: Vec<(&String, &Usage)> = self
+            .entries
+            .iter()
+            .filter(|(key, _)| Some(key.as_str()) != keep)
+            .collect();
+        match policy {
+            EvictionPolicy::Lru => candidates.sort_by_key(|(key, usage)| (usage.last_access, *key)),
+            EvictionPolicy::Lfu => {
+                candidates.sort_by_key(|(key, usage)| (usage.hits, usage.last_access, *key))
+            }
+        }
+
+        let mut remaining = self.bytes;
+        let mut victims = Vec::new();
+        for (key, usage) in candidates {
+            if remaining <= budget {
+                break;
+            }
+            remaining = remaining.saturating_sub(usage.size);
+            victims.push(key.clone());
+        }
+        victims
+    }
+
+    fn expired(&self, now: u128) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(_, usage)| usage.expires_at.is_some_and(|expires_at| expires_at <= now))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+/// A live entry read back from the store
+pub(crate) struct StoredEntry {
+    pub(crate) key: String,
+    pub(crate) data: Vec<u8>,
+    pub(crate) ttl: Option<Duration>,
+    pub(crate) tags: Vec<String>,
+}
+
+/// Byte-level storage behind [`DiskCache`](crate::DiskCache): a cacache
+/// directory plus the accounting needed to keep it within a size budget.
+#[derive(Clone)]
+pub(crate) struct DiskStore {
+    cache_dir: PathBuf,
+    pub(crate) ttl: Option<Duration>,
+    pub(crate) max_bytes: Option<u64>,
+    pub(crate) eviction_policy: EvictionPolicy,
+    /// Entries written with another schema version are discarded on read
+    pub(crate) schema_version: u32,
+    usage: Arc<Mutex<UsageIndex>>,
+    stats: Arc<RwLock<CacheStats>>,
+}
+
+impl DiskStore {
+    pub(crate) fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            cache_dir,
+            ttl: None,
+            max_bytes: None,
+            eviction_policy: EvictionPolicy::default(),
+            schema_version: CACHE_SCHEMA_VERSION,
+            usage: Arc::new(Mutex::new(UsageIndex::default())),
+            stats: Arc::new(RwLock::new(CacheStats {
+                capacity: usize::MAX, // Disk cache doesn't have a fixed item capacity
+                ..Default::default()
+            })),
+        }
+    }
+
+    pub(crate) fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub(crate) async fn get(&self, key: &str) -> Result<Option<(Vec<u8>, Option<Duration>)>> {
+        let mut usage = self.usage().await?;
+
+        let entry = cacache::metadata(&self.cache_dir, key)
+            .await
+            .context("Failed to read from disk cache")?;
+        let Some(entry) = entry else {
+            self.stats.write().await.misses += 1;
+            return Ok(None);
+        };
+
+        let now = now_millis();
+        let expires_at = expiry(&entry, self.ttl);
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            self.remove_entry(&mut usage, key)
+                .await
+                .context("Failed to remove expired entry from disk cache")?;
+            self.stats.write().await.misses += 1;
+            self.sync_stats(&usage).await;
+            return Ok(None);
+        }
+
+        match cacache::read_hash(&self.cache_dir, &entry.integrity).await {
+            Ok(data) => {
+                let Some(data) = envelope::open(&data, self.schema_version)? else {
+                    debug!(
+                        key,
+                        "Discarding disk cache entry from another schema version"
+                    );
+                    self.remove_entry(&mut usage, key)
+                        .await
+                        .context("Failed to remove outdated entry from disk cache")?;
+                    self.stats.write().await.misses += 1;
+                    self.sync_stats(&usage).await;
+                    return Ok(None);
+                };
+                usage.touch(key);
+                self.stats.write().await.hits += 1;
+                let remaining =
+                    expires_at.map(|expires_at| Duration::from_millis((expires_at - now) as u64));
+                Ok(Some((data, remaining)))
+            }
+            Err(e) => {
+                // Check if it's a not found error by examining the error message
+                let error_str = e.to_string();
+                if error_str.contains("not found")
+                    || error_str.contains("NotFound")
+                    || error_str.contains("Entry not found")
+                {
+                    self.stats.write().await.misses += 1;
+                    Ok(None)
+                } else {
+                    Err(anyhow::Error::from(e)).context("Failed to read from disk cache")
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn insert(
+        &self,
+        key: &str,
+        data: &[u8],
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let mut usage = self.usage().await?;
+        let ttl = effective_ttl(ttl, self.ttl);
+        let now = now_millis();
+        let data = envelope::seal(data, self.schema_version, now);
+        let size = data.len() as u64;
+
+        // Already expired, or too large to ever fit: drop any older copy instead
+        if ttl.is_some_and(|ttl| ttl.is_zero())
+            || self.max_bytes.is_some_and(|max_bytes| size > max_bytes)
+        {
+            debug!(key, size, "Not storing entry in disk cache");
+            self.remove_entry(&mut usage, key).await?;
+            self.sync_stats(&usage).await;
+            return Ok(());
+        }
+
+        let integrity = cacache::write_hash(&self.cache_dir, &data)
+            .await
+            .context("Failed to write to disk cache")?;
+
+        let expires_at = ttl.map(|ttl| now + ttl.as_millis());
+        let mut metadata = serde_json::Map::new();
+        if let Some(expires_at) = expires_at {
+            metadata.insert(EXPIRES_AT_FIELD.to_string(), (expires_at as u64).into());
+        }
+        if !tags.is_empty() {
+            metadata.insert(TAGS_FIELD.to_string(), tags.clone().into());
+        }
+        let opts = cacache::WriteOpts::new()
+            .integrity(integrity.clone())
+            .size(data.len())
+            .metadata(metadata.into());
+        cacache::index::insert_async(&self.cache_dir, key, opts)
+            .await
+            .context("Failed to write to disk cache")?;
+
+        let last_access = usage.tick();
+        let replaced = usage.record(
+            key.to_string(),
+            Usage {
+                integrity: integrity.clone(),
+                size,
+                expires_at,
+                last_access,
+                hits: 0,
+                tags,
+            },
+        );
+        // The previous value's content is unreachable unless another key shares it
+        if let Some(replaced) = replaced {
+            if replaced.integrity != integrity && !usage.references(&replaced.integrity) {
+                remove_content(&self.cache_dir, &replaced.integrity).await?;
+            }
+        }
+
+        let evicted = self.enforce_budget(&mut usage, Some(key)).await?;
+        if evicted > 0 {
+            debug!(
+                evicted,
+                bytes = usage.bytes,
+                "Evicted entries from disk cache"
+            );
+        }
+        self.sync_stats(&usage).await;
+
+        Ok(())
+    }
+
+    pub(crate) async fn remove(&self, key: &str) -> Result<()> {
+        let mut usage = self.usage().await?;
+        self.remove_entry(&mut usage, key)
+            .await
+            .context("Failed to remove from disk cache")?;
+        self.sync_stats(&usage).await;
+        Ok(())
+    }
+
+    /// Read every live entry, skipping expired ones, entries from other schema
+    /// versions and content that can no longer be read
+    pub(crate) async fn entries(&self) -> Result<Vec<StoredEntry>> {
+        let usage = self.usage().await?;
+        let now = now_millis();
+
+        let mut entries = Vec::with_capacity(usage.entries.len());
+        for (key, entry) in &usage.entries {
+            if entry.expires_at.is_some_and(|expires_at| expires_at <= now) {
+                continue;
+            }
+            let data = match cacache::read_hash(&self.cache_dir, &entry.integrity).await {
+                Ok(data) => envelope::open(&data, self.schema_version),
+                Err(e) => Err(e.into()),
+            };
+            match data {
+                Ok(Some(data)) => entries.push(StoredEntry {
+                    key: key.clone(),
+                    data,
+                    ttl: entry
+                        .expires_at
+                        .map(|expires_at| Duration::from_millis((expires_at - now) as u64)),
+                    tags: entry.tags.clone(),
+                }),
+                Ok(None) => debug!(key, "Skipping disk cache entry from another schema version"),
+                Err(e) => warn!(key, error = %e, "Skipping unreadable disk cache entry"),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Remove every entry whose key and tags satisfy `filter`, returning the
+    /// removed keys
+    pub(crate) async fn remove_matching(
+        &self,
+        filter: impl Fn(&str, &[String]) -> bool,
+    ) -> Result<Vec<String>> {
+        let mut usage = self.usage().await?;
+        let removed: Vec<String> = usage
+            .entries
+            .iter()
+            .filter(|(key, entry)| filter(key, &entry.tags))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &removed {
+            self.remove_entry(&mut usage, key)
+                .await
+                .context("Failed to remove from disk cache")?;
+        }
+        self.sync_stats(&usage).await;
+        Ok(removed)
+    }
+
+    pub(crate) async fn clear(&self) -> Result<()> {
+        let mut usage = self.usage.lock().await;
+        cacache::clear(&self.cache_dir)
+            .await
+            .context("Failed to clear disk cache")?;
+
+        *usage = UsageIndex {
+            loaded: true,
+            ..UsageIndex::default()
+        };
+        self.sync_stats(&usage).await;
+
+        Ok(())
+    }
+
+    /// Remove expired entries, evict down to the size budget and delete
+    /// content files no index entry refers to
+    pub(crate) async fn collect_garbage(&self) -> Result<GcReport> {
+        let mut usage = self.usage().await?;
+        let mut report = GcReport::default();
+        let bytes_before = usage.bytes;
+
+        for key in usage.expired(now_millis()) {
+            self.remove_entry(&mut usage, &key).await?;
+            report.expired += 1;
+        }
+        report.evicted = self.enforce_budget(&mut usage, None).await?;
+        report.bytes_freed = bytes_before.saturating_sub(usage.bytes);
+
+        let live: HashSet<PathBuf> = usage
+            .entries
+            .values()
+            .map(|entry| content_path(&self.cache_dir, &entry.integrity))
+            .collect();
+        let content_root = self.cache_dir.join(CONTENT_DIR);
+        let (orphans, orphan_bytes) =
+            tokio::task::spawn_blocking(move || remove_orphans(&content_root, &live))
+                .await
+                .context("Disk cache garbage collection panicked")??;
+        report.orphans = orphans;
+        report.bytes_freed += orphan_bytes;
+
+        self.sync_stats(&usage).await;
+        Ok(report)
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        self.stats.try_read().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Lock the accounting, loading it from the index on first use
+    async fn usage(&self) -> Result<tokio::sync::MutexGuard<'_, UsageIndex>> {
+        let mut usage = self.usage.lock().await;
+        if !usage.loaded {
+            let cache_dir = self.cache_dir.clone();
+            let ttl = self.ttl;
+            *usage = tokio::task::spawn_blocking(move || load_usage(&cache_dir, ttl))
+                .await
+                .context("Loading disk cache index panicked")?;
+            self.sync_stats(&usage).await;
+            debug!(
+                entries = usage.entries.len(),
+                bytes = usage.bytes,
+                "Loaded disk cache index"
+            );
+        }
+        Ok(usage)
+    }
+
+    /// Evict entries until the cache fits its size budget, never evicting `keep`
+    async fn enforce_budget(&self, usage: &mut UsageIndex, keep: Option<&str>) -> Result<usize> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(0);
+        };
+        if usage.bytes <= max_bytes {
+            return Ok(0);
+        }
+
+        let victims = usage.victims(self.eviction_policy, max_bytes, keep);
+        for key in &victims {
+            self.remove_entry(usage, key).await?;
+        }
+        self.stats.write().await.evictions += victims.len() as u64;
+        Ok(victims.len())
+    }
+
+    /// Delete an entry's index bucket, and its content unless another key shares it
+    async fn remove_entry(&self, usage: &mut UsageIndex, key: &str) -> Result<()> {
+        let Some(entry) = usage.forget(key) else {
+            return cacache::remove(&self.cache_dir, key)
+                .await
+                .map_err(anyhow::Error::from);
+        };
+
+        if usage.references(&entry.integrity) {
+            return cacache::remove(&self.cache_dir, key)
+                .await
+                .map_err(anyhow::Error::from);
+        }
+
+        if let Err(e) = cacache::RemoveOpts::new()
+            .remove_fully(true)
+            .remove(&self.cache_dir, key)
+            .await
+        {
+            // Content already gone; still make sure the key stops resolving
+            warn!(key, error = %e, "Failed to fully remove disk cache entry");
+            cacache::remove(&self.cache_dir, key).await?;
+        }
+        Ok(())
+    }
+
+    async fn sync_stats(&self, usage: &UsageIndex) {
+        let mut stats = self.stats.write().await;
+        stats.size = usage.entries.len();
+        stats.bytes = usage.bytes;
+        stats.max_bytes = self.max_bytes.unwrap_or_default();
+    }
+}
+
+/// Current time in unix milliseconds
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default()
+}
+
+/// Expiry of an indexed entry in unix milliseconds.
+///
+/// Entries written before a TTL was configured expire `ttl` after they were written.
+fn expiry(entry: &Metadata, ttl: Option<Duration>) -> Option<u128> {
+    let recorded = entry
+        .metadata
+        .get(EXPIRES_AT_FIELD)
+        .and_then(serde_json::Value::as_u64)
+        .map(u128::from);
+    let layer_limit = ttl.map(|ttl| entry.time + ttl.as_millis());
+    match (recorded, layer_limit) {
+        (Some(recorded), Some(limit)) => Some(recorded.min(limit)),
+        (recorded, limit) => recorded.or(limit),
+    }
+}
+
+/// Invalidation tags recorded for an indexed entry
+fn tags(entry: &Metadata) -> Vec<String> {
+    entry
+        .metadata
+        .get(TAGS_FIELD)
+        .and_then(serde_json::Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|tag| tag.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Build the accounting for every live key in the index
+fn load_usage(cache_dir: &Path, ttl: Option<Duration>) -> UsageIndex {
+    let mut usage = UsageIndex {
+        loaded: true,
+        ..UsageIndex::default()
+    };
+    if !cache_dir.exists() {
+        return usage;
+    }
+
+    // The index lists every write, including overwritten and removed ones
+    let keys: HashSet<String> = cacache::index::ls(cache_dir)
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.key)
+        .collect();
+    for key in keys {
+        match cacache::index::find(cache_dir, &key) {
+            Ok(Some(entry)) => {
+                let expires_at = expiry(&entry, ttl);
+                let tags = tags(&entry);
+                usage.record(
+                    key,
+                    Usage {
+                        integrity: entry.integrity,
+                        size: entry.size as u64,
+                        expires_at,
+                        last_access: entry.time,
+                        hits: 0,
+                        tags,
+                    },
+                );
+            }
+            Ok(None) => {}
+            Err(e) => warn!(key, error = %e, "Skipping unreadable disk cache entry"),
+        }
+    }
+    usage
+}
+
+/// Where cacache keeps the content for `integrity`
+fn content_path(cache_dir: &Path, integrity: &Integrity) -> PathBuf {
+    let (algorithm, hex) = integrity.to_hex();
+    cache_dir
+        .join(CONTENT_DIR)
+        .join(algorithm.to_string())
+        .join(&hex[0..2])
+        .join(&hex[2..4])
+        .join(&hex[4..])
+}
+
+async fn remove_content(cache_dir: &Path, integrity: &Integrity) -> Result<()> {
+    match tokio::fs::remove_file(content_path(cache_dir, integrity)).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(anyhow::Error::from(e)).context("Failed to remove disk cache content")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Delete content files under `dir` that are not in `live`, returning how
+/// many files and bytes were removed
+fn remove_orphans(dir: &Path, live: &HashSet<PathBuf>) -> Result<(usize, u64)> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(e).context("Failed to read disk cache content"),
+    };
+
+    let mut removed = (0, 0);
+    for entry in entries {
+        let entry = entry.context("Failed to read disk cache content")?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let (files, bytes) = remove_orphans(&path, live)?;
+            removed.0 += files;
+            removed.1 += bytes;
+        } else if !live.contains(&path) {
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            std::fs::remove_file(&path).context("Failed to remove orphaned content")?;
+            removed.0 += 1;
+            removed.1 += bytes;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(size: u64, last_access: u128, hits: u64) -> Usage {
+        Usage {
+            integrity: Integrity::from(format!("{size}-{last_access}-{hits}")),
+            size,
+            expires_at: None,
+            tags: Vec::new(),
+            last_access,
+            hits,
+        }
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let mut index = UsageIndex::default();
+        index.record("old".to_string(), usage(10, 1, 5));
+        index.record("popular".to_string(), usage(10, 2, 9));
+        index.record("recent".to_string(), usage(10, 3, 0));
+        assert_eq!(index.bytes, 30);
+
+        assert_eq!(
+            index.victims(EvictionPolicy::Lru, 15, None),
+            vec!["old".to_string(), "popular".to_string()]
+        );
+        assert_eq!(
+            index.victims(EvictionPolicy::Lfu, 15, None),
+            vec!["recent".to_string(), "old".to_string()]
+        );
+        assert_eq!(
+            index.victims(EvictionPolicy::Lfu, 25, Some("recent")),
+            vec!["old".to_string()]
+        );
+        assert!(index.victims(EvictionPolicy::Lru, 30, None).is_empty());
+    }
+
+    #[test]
+    fn test_eviction_policy_parsing() {
+        assert_eq!("LRU".parse::<EvictionPolicy>(), Ok(EvictionPolicy::Lru));
+        assert_eq!("lfu".parse::<EvictionPolicy>(), Ok(EvictionPolicy::Lfu));
+        assert!("fifo".parse::<EvictionPolicy>().is_err());
+        assert_eq!(EvictionPolicy::Lfu.to_string(), "lfu");
+    }
+}
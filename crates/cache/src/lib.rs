@@ -1,12 +1,25 @@
 use async_trait::async_trait;
 use std::fmt::Debug;
+use std::time::Duration;
 
 pub mod disk;
 pub mod memory;
+pub mod metrics;
+pub mod scope;
+pub mod single_flight;
+pub mod snapshot;
+pub mod sqlite;
 pub mod tiered;
 
-pub use disk::DiskCache;
+pub use disk::{DiskCache, EvictionPolicy, GcReport};
 pub use memory::MemoryCache;
+pub use metrics::{
+    CacheMetrics, CacheOutcome, LatencyHistogram, LayerMetrics, MetricsSnapshot, OutcomeCounts,
+};
+pub use scope::CacheScope;
+pub use single_flight::SingleFlight;
+pub use snapshot::{ImportReport, Snapshot, SNAPSHOT_VERSION};
+pub use sqlite::SqliteCache;
 pub use tiered::{TieredCache, WriteStrategy};
 
 /// Simplified cache statistics
@@ -16,6 +29,12 @@ pub struct CacheStats {
     pub misses: u64,
     pub size: usize,
     pub capacity: usize,
+    /// Bytes of content stored on disk (0 for in-memory layers)
+    pub bytes: u64,
+    /// Byte budget for on-disk content (0 when unbounded)
+    pub max_bytes: u64,
+    /// Entries evicted to stay within capacity or the byte budget
+    pub evictions: u64,
 }
 
 impl CacheStats {
@@ -35,8 +54,40 @@ impl CacheStats {
             (self.size as f64 / self.capacity as f64) * 100.0
         }
     }
+
+    /// Percentage of the byte budget in use (0 when unbounded)
+    pub fn byte_utilization(&self) -> f64 {
+        if self.max_bytes == 0 {
+            0.0
+        } else {
+            (self.bytes as f64 / self.max_bytes as f64) * 100.0
+        }
+    }
+}
+
+/// Lifetime of an entry inserted with `ttl` into a layer whose entries live
+/// at most `max_ttl`
+pub fn effective_ttl(ttl: Option<Duration>, max_ttl: Option<Duration>) -> Option<Duration> {
+    match (ttl, max_ttl) {
+        (Some(ttl), Some(max_ttl)) => Some(ttl.min(max_ttl)),
+        (ttl, max_ttl) => ttl.or(max_ttl),
+    }
+}
+
+/// A live cache entry, as listed for snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry<K, V> {
+    pub key: K,
+    pub value: V,
+    /// Remaining lifetime (`None` if it never expires)
+    pub ttl: Option<Duration>,
+    /// Invalidation tags
+    pub tags: Vec<String>,
 }
 
+/// Predicate over an entry's key and tags, picking entries to remove
+pub type EntryFilter<'f, K> = dyn for<'a> Fn(&'a K, &'a [String]) -> bool + Send + Sync + 'f;
+
 /// Unified cache trait that all cache implementations follow
 #[async_trait]
 pub trait Cache: Send + Sync {
@@ -50,6 +101,69 @@ pub trait Cache: Send + Sync {
     /// Insert a value into the cache
     async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error>;
 
+    /// Get a value together with its remaining lifetime (`None` if it never expires).
+    ///
+    /// Layers without expiry support report every entry as never expiring.
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+        Ok(self.get(key).await?.map(|value| (value, None)))
+    }
+
+    /// Insert a value that expires after `ttl`.
+    ///
+    /// `None` leaves the lifetime to the layer's own policy. Layers without
+    /// expiry support ignore the TTL.
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        let _ = ttl;
+        self.insert(key, value).await
+    }
+
+    /// Insert a value that expires after `ttl`, indexed under `tags` so it
+    /// can later be removed by [`remove_matching`](Self::remove_matching).
+    ///
+    /// Layers that do not track tags ignore them.
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
+        let _ = tags;
+        self.insert_with_ttl(key, value, ttl).await
+    }
+
+    /// Remove every entry whose key and tags satisfy `filter`, returning the
+    /// removed keys.
+    ///
+    /// Layers that cannot list their entries remove nothing, so every layer
+    /// shipped with this crate overrides it.
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        let _ = filter;
+        tracing::warn!(
+            layer = self.layer_name(),
+            "Cache layer does not support selective removal; no entries removed"
+        );
+        Ok(Vec::new())
+    }
+
+    /// List every live entry with its remaining lifetime and tags.
+    ///
+    /// Layers that cannot list their entries report none.
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        Ok(Vec::new())
+    }
+
     /// Remove a value from the cache
     async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error>;
 
@@ -58,4 +172,9 @@ pub trait Cache: Send + Sync {
 
     /// Get cache statistics (non-async for simplicity)
     fn stats(&self) -> CacheStats;
+
+    /// Name of the layer in per-layer statistics, e.g. `memory` or `disk`
+    fn layer_name(&self) -> &str {
+        "cache"
+    }
 }
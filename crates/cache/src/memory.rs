@@ -1,16 +1,38 @@
-use crate::{Cache, CacheStats};
+use crate::{effective_ttl, Cache, CacheEntry, CacheStats, EntryFilter};
 use async_trait::async_trait;
 use lru::LruCache;
 use std::convert::Infallible;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// A cached value, the moment it stops being served and its invalidation tags
+#[derive(Clone)]
+struct Entry<V> {
+    value: V,
+    expires_at: Option<Instant>,
+    tags: Vec<String>,
+}
+
+impl<V> Entry<V> {
+    /// Remaining lifetime, `Some(Duration::ZERO)` once expired
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(now))
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
 /// In-memory LRU cache implementation
 pub struct MemoryCache<K, V> {
-    cache: Arc<RwLock<LruCache<K, V>>>,
+    cache: Arc<RwLock<LruCache<K, Entry<V>>>>,
     stats: Arc<RwLock<CacheStats>>,
+    ttl: Option<Duration>,
 }
 
 impl<K, V> MemoryCache<K, V>
@@ -27,8 +49,21 @@ where
                 capacity,
                 ..Default::default()
             })),
+            ttl: None,
         }
     }
+
+    /// Expire entries after at most `ttl`; entries inserted with a shorter
+    /// TTL expire sooner. A zero TTL disables caching.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Maximum lifetime of an entry, if any
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
 }
 
 #[async_trait]
@@ -42,13 +77,32 @@ where
     type Error = Infallible;
 
     async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
+        Ok(self.get_with_ttl(key).await?.map(|(value, _)| value))
+    }
+
+    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+        self.insert_with_ttl(key, value, None).await
+    }
+
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
         let mut cache = self.cache.write().await;
         let mut stats = self.stats.write().await;
+        let now = Instant::now();
 
-        match cache.get(key) {
-            Some(value) => {
+        match cache.get(key).map(|entry| (entry.is_expired(now), entry)) {
+            Some((false, entry)) => {
+                let remaining = entry.remaining(now);
                 stats.hits += 1;
-                Ok(Some(value.clone()))
+                Ok(Some((entry.value.clone(), remaining)))
+            }
+            Some((true, _)) => {
+                cache.pop(key);
+                stats.size = cache.len();
+                stats.misses += 1;
+                Ok(None)
             }
             None => {
                 stats.misses += 1;
@@ -57,16 +111,84 @@ where
         }
     }
 
-    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        self.insert_tagged(key, value, ttl, Vec::new()).await
+    }
+
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
         let mut cache = self.cache.write().await;
         let mut stats = self.stats.write().await;
 
-        cache.put(key, value);
+        match effective_ttl(ttl, self.ttl) {
+            // Already expired, and must not shadow a fresher copy elsewhere
+            Some(ttl) if ttl.is_zero() => {
+                cache.pop(&key);
+            }
+            ttl => {
+                let expires_at = ttl.map(|ttl| Instant::now() + ttl);
+                let entry = Entry {
+                    value,
+                    expires_at,
+                    tags,
+                };
+                let displaced = cache.push(key.clone(), entry);
+                if displaced.is_some_and(|(displaced, _)| displaced != key) {
+                    stats.evictions += 1;
+                }
+            }
+        }
         stats.size = cache.len();
 
         Ok(())
     }
 
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        let mut cache = self.cache.write().await;
+        let mut stats = self.stats.write().await;
+
+        let removed: Vec<K> = cache
+            .iter()
+            .filter(|(key, entry)| filter(key, &entry.tags))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &removed {
+            cache.pop(key);
+        }
+        stats.size = cache.len();
+
+        Ok(removed)
+    }
+
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        let cache = self.cache.read().await;
+        let now = Instant::now();
+
+        Ok(cache
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(key, entry)| CacheEntry {
+                key: key.clone(),
+                value: entry.value.clone(),
+                ttl: entry.remaining(now),
+                tags: entry.tags.clone(),
+            })
+            .collect())
+    }
+
     async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
         let mut cache = self.cache.write().await;
         let mut stats = self.stats.write().await;
@@ -91,6 +213,10 @@ where
         // Use try_read to avoid potential deadlock in stats() call
         self.stats.try_read().map(|s| s.clone()).unwrap_or_default()
     }
+
+    fn layer_name(&self) -> &str {
+        "memory"
+    }
 }
 
 #[cfg(test)]
@@ -148,6 +274,7 @@ mod tests {
 
         let stats = cache.stats();
         assert_eq!(stats.size, 3);
+        assert_eq!(stats.evictions, 1);
     }
 
     #[tokio::test]
@@ -174,4 +301,127 @@ mod tests {
         // Verify items are gone
         assert_eq!(cache.get(&"key0".to_string()).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_memory_cache_entry_ttl() {
+        let cache = MemoryCache::<String, String>::new(10);
+
+        cache
+            .insert_with_ttl(
+                "short".to_string(),
+                "value".to_string(),
+                Some(Duration::from_millis(20)),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert("forever".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let (_, remaining) = cache
+            .get_with_ttl(&"short".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_millis(20));
+        assert_eq!(
+            cache.get_with_ttl(&"forever".to_string()).await.unwrap(),
+            Some(("value".to_string(), None))
+        );
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        // Expired entries are misses and are dropped
+        assert_eq!(cache.get(&"short".to_string()).await.unwrap(), None);
+        assert_eq!(
+            cache.get(&"forever".to_string()).await.unwrap(),
+            Some("value".to_string())
+        );
+        let stats = cache.stats();
+        assert_eq!(stats.size, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_layer_ttl() {
+        let cache = MemoryCache::<String, String>::new(10).with_ttl(Duration::from_millis(20));
+
+        // The layer TTL applies to entries without one and caps longer ones
+        cache
+            .insert("default".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        cache
+            .insert_with_ttl(
+                "long".to_string(),
+                "value".to_string(),
+                Some(Duration::from_secs(3600)),
+            )
+            .await
+            .unwrap();
+        let (_, remaining) = cache
+            .get_with_ttl(&"long".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_millis(20));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.get(&"default".to_string()).await.unwrap(), None);
+        assert_eq!(cache.get(&"long".to_string()).await.unwrap(), None);
+
+        // A zero TTL disables caching
+        let disabled = MemoryCache::<String, String>::new(10).with_ttl(Duration::ZERO);
+        disabled
+            .insert("key".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        assert_eq!(disabled.get(&"key".to_string()).await.unwrap(), None);
+        assert_eq!(disabled.stats().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_remove_matching() {
+        let cache = MemoryCache::<String, i32>::new(10);
+        cache
+            .insert_tagged("a".to_string(), 1, None, vec!["crate:tokio".to_string()])
+            .await
+            .unwrap();
+        cache.insert("b".to_string(), 2).await.unwrap();
+
+        let removed = cache
+            .remove_matching(&|key: &String, tags: &[String]| {
+                key == "b" || tags.contains(&"crate:tokio".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(cache.stats().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_entries() {
+        let cache = MemoryCache::<String, i32>::new(10);
+        cache
+            .insert_tagged(
+                "a".to_string(),
+                1,
+                Some(Duration::from_secs(60)),
+                vec!["crate:tokio".to_string()],
+            )
+            .await
+            .unwrap();
+        cache
+            .insert_with_ttl("expired".to_string(), 2, Some(Duration::from_millis(1)))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let entries = cache.entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "a");
+        assert_eq!(entries[0].tags, vec!["crate:tokio".to_string()]);
+        assert!(entries[0].ttl.unwrap() <= Duration::from_secs(60));
+    }
 }
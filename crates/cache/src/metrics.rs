@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the read latency histogram buckets, in microseconds; a
+/// last bucket collects everything slower
+const LATENCY_BUCKETS_MICROS: [u64; 10] = [
+    50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 50_000, 250_000,
+];
+
+/// How a cache lookup turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheOutcome {
+    /// A fresh value was found
+    Hit,
+    /// Nothing usable was cached
+    Miss,
+    /// An expired value was served while or after refreshing it
+    Stale,
+    /// The lookup, or the fetch after a miss, failed
+    Error,
+}
+
+/// Lookups counted by outcome
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutcomeCounts {
+    pub hits: u64,
+    pub misses: u64,
+    pub stale: u64,
+    pub errors: u64,
+}
+
+impl OutcomeCounts {
+    pub fn record(&mut self, outcome: CacheOutcome) {
+        match outcome {
+            CacheOutcome::Hit => self.hits += 1,
+            CacheOutcome::Miss => self.misses += 1,
+            CacheOutcome::Stale => self.stale += 1,
+            CacheOutcome::Error => self.errors += 1,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.hits + self.misses + self.stale + self.errors
+    }
+
+    /// Share of lookups answered from the cache, stale answers included
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            0.0
+        } else {
+            (self.hits + self.stale) as f64 / total as f64
+        }
+    }
+}
+
+/// Distribution of read latencies over fixed buckets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Count per bucket of [`LATENCY_BUCKETS_MICROS`], plus the overflow bucket
+    counts: Vec<u64>,
+    sum_micros: u64,
+    max_micros: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; LATENCY_BUCKETS_MICROS.len() + 1],
+            sum_micros: 0,
+            max_micros: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS_MICROS
+            .iter()
+            .position(|&bound| micros <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MICROS.len());
+        self.counts[bucket] += 1;
+        self.sum_micros = self.sum_micros.saturating_add(micros);
+        self.max_micros = self.max_micros.max(micros);
+    }
+
+    /// Number of recorded reads
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.count() {
+            0 => Duration::ZERO,
+            count => Duration::from_micros(self.sum_micros / count),
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max_micros)
+    }
+
+    /// Upper bound of the bucket holding the `quantile` (0.0 to 1.0) of
+    /// reads; reads in the overflow bucket report the slowest read
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        let count = self.count();
+        if count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, bucket_count) in self.counts.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= rank {
+                let bound = LATENCY_BUCKETS_MICROS
+                    .get(bucket)
+                    .map_or(self.max_micros, |&bound| bound.min(self.max_micros));
+                return Duration::from_micros(bound);
+            }
+        }
+        self.max()
+    }
+
+    /// Upper bound and count of every bucket; the overflow bucket has no bound
+    pub fn buckets(&self) -> Vec<(Option<Duration>, u64)> {
+        LATENCY_BUCKETS_MICROS
+            .iter()
+            .map(|&bound| Some(Duration::from_micros(bound)))
+            .chain(std::iter::once(None))
+            .zip(self.counts.iter().copied())
+            .collect()
+    }
+}
+
+/// Reads of one cache layer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerMetrics {
+    /// Layer name, e.g. `memory` or `disk`
+    pub name: String,
+    /// Hits, misses and errors of the layer's reads
+    pub outcomes: OutcomeCounts,
+    pub read_latency: LatencyHistogram,
+}
+
+/// Point-in-time copy of [`CacheMetrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// One entry per layer, in lookup order
+    pub layers: Vec<LayerMetrics>,
+    /// Latency of whole tiered reads, across all layers consulted
+    pub read_latency: LatencyHistogram,
+    /// Lookups by the tool that made them
+    pub tools: BTreeMap<String, OutcomeCounts>,
+    /// Lookups by key namespace, the part of the key before the first `:`
+    pub namespaces: BTreeMap<String, OutcomeCounts>,
+}
+
+/// Breakdown of cache lookups by layer, tool, key namespace and outcome.
+///
+/// Layer reads are recorded by [`TieredCache`](crate::TieredCache) itself;
+/// callers that know which tool is asking record their lookups with
+/// [`record_lookup`](Self::record_lookup).
+#[derive(Debug, Default)]
+pub struct CacheMetrics {
+    inner: Mutex<MetricsSnapshot>,
+}
+
+impl CacheMetrics {
+    /// Create empty metrics for layers with the given names
+    pub fn new<'a>(layer_names: impl IntoIterator<Item = &'a str>) -> Self {
+        let layers = layer_names
+            .into_iter()
+            .map(|name| LayerMetrics {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        Self {
+            inner: Mutex::new(MetricsSnapshot {
+                layers,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Record one layer's answer to a read
+    pub fn record_layer_read(&self, layer: usize, outcome: CacheOutcome, latency: Duration) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(metrics) = inner.layers.get_mut(layer) {
+            metrics.outcomes.record(outcome);
+            metrics.read_latency.record(latency);
+        }
+    }
+
+    /// Record the latency of a read across all layers
+    pub fn record_read(&self, latency: Duration) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.read_latency.record(latency);
+    }
+
+    /// Record how a tool's lookup of `key` turned out
+    pub fn record_lookup(&self, tool: &str, key: &str, outcome: CacheOutcome) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner
+            .tools
+            .entry(tool.to_string())
+            .or_default()
+            .record(outcome);
+        inner
+            .namespaces
+            .entry(key_namespace(key).to_string())
+            .or_default()
+            .record(outcome);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// The namespace of a cache key: everything before the first `:`
+pub fn key_namespace(key: &str) -> &str {
+    key.split_once(':').map_or(key, |(namespace, _)| namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_and_quantiles() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.quantile(0.5), Duration::ZERO);
+
+        for micros in [40, 80, 90, 2_000, 400_000] {
+            histogram.record(Duration::from_micros(micros));
+        }
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(histogram.mean(), Duration::from_micros(80_442));
+        assert_eq!(histogram.max(), Duration::from_micros(400_000));
+        assert_eq!(histogram.quantile(0.5), Duration::from_micros(100));
+        assert_eq!(histogram.quantile(0.8), Duration::from_micros(2_500));
+        assert_eq!(histogram.quantile(0.99), Duration::from_micros(400_000));
+
+        let buckets = histogram.buckets();
+        assert_eq!(buckets[0], (Some(Duration::from_micros(50)), 1));
+        assert_eq!(buckets[1], (Some(Duration::from_micros(100)), 2));
+        assert_eq!(buckets.last(), Some(&(None, 1)));
+    }
+
+    #[test]
+    fn test_lookups_by_tool_and_namespace() {
+        let metrics = CacheMetrics::new(["memory", "disk"]);
+        metrics.record_lookup(
+            "get_crate_docs",
+            "get_crate_docs:tokio:latest",
+            CacheOutcome::Hit,
+        );
+        metrics.record_lookup(
+            "get_crate_docs",
+            "get_crate_docs@internal:tokio:latest",
+            CacheOutcome::Stale,
+        );
+        metrics.record_lookup("search_crate", "search_crate:async", CacheOutcome::Miss);
+        metrics.record_layer_read(1, CacheOutcome::Error, Duration::from_millis(1));
+
+        let snapshot = metrics.snapshot();
+        let docs = &snapshot.tools["get_crate_docs"];
+        assert_eq!((docs.hits, docs.stale), (1, 1));
+        assert_eq!(docs.hit_rate(), 1.0);
+        assert_eq!(snapshot.namespaces["get_crate_docs@internal"].stale, 1);
+        assert_eq!(snapshot.namespaces["search_crate"].misses, 1);
+        assert_eq!(snapshot.layers[0].outcomes.total(), 0);
+        assert_eq!(snapshot.layers[1].name, "disk");
+        assert_eq!(snapshot.layers[1].outcomes.errors, 1);
+    }
+}
@@ -0,0 +1,160 @@
+use rustacean_docs_core::{crate_tag, crate_version_tag, kind_tag, tool_tag};
+use std::fmt;
+
+/// Which cache entries an invalidation removes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheScope {
+    /// Every entry
+    All,
+    /// Entries about a crate, across all of its versions
+    Crate(String),
+    /// Entries about one version of a crate; `1.38` also covers `1.38.x`
+    Version { crate_name: String, version: String },
+    /// Entries cached by a tool
+    Tool(String),
+    /// Entries holding one kind of data, such as `metadata`
+    Kind(String),
+    /// Entries carrying an exact tag
+    Tag(String),
+    /// Entries whose key matches a glob, where `*` matches any run of
+    /// characters and `?` matches exactly one
+    KeyGlob(String),
+}
+
+impl CacheScope {
+    /// Whether an entry with `key` and `tags` falls within this scope
+    pub fn matches(&self, key: &str, tags: &[String]) -> bool {
+        match self {
+            Self::KeyGlob(pattern) => glob_match(pattern.as_bytes(), key.as_bytes()),
+            _ => self.matches_tags(tags),
+        }
+    }
+
+    /// Whether an entry with `tags` falls within this scope, for caches whose
+    /// keys are not strings. Key globs match nothing here.
+    pub fn matches_tags(&self, tags: &[String]) -> bool {
+        match self {
+            Self::All => true,
+            Self::Crate(crate_name) => tags.contains(&crate_tag(crate_name)),
+            Self::Version {
+                crate_name,
+                version,
+            } => {
+                let prefix = format!("{}@", crate_tag(crate_name));
+                let version = version.trim();
+                tags.iter().any(|t| {
+                    t.strip_prefix(&prefix).is_some_and(|tagged| {
+                        tagged == version
+                            || tagged
+                                .strip_prefix(version)
+                                .is_some_and(|rest| rest.starts_with(['.', '-', '+']))
+                    })
+                })
+            }
+            Self::Tool(tool_name) => tags.contains(&tool_tag(tool_name)),
+            Self::Kind(kind) => tags.contains(&kind_tag(kind)),
+            Self::Tag(tag) => tags.contains(tag),
+            Self::KeyGlob(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for CacheScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all entries"),
+            Self::Crate(crate_name) => write!(f, "{}", crate_tag(crate_name)),
+            Self::Version {
+                crate_name,
+                version,
+            } => write!(f, "{}", crate_version_tag(crate_name, version)),
+            Self::Tool(tool_name) => write!(f, "{}", tool_tag(tool_name)),
+            Self::Kind(kind) => write!(f, "{}", kind_tag(kind)),
+            Self::Tag(tag) => write!(f, "{tag}"),
+            Self::KeyGlob(pattern) => write!(f, "keys matching {pattern}"),
+        }
+    }
+}
+
+/// Match `text` against a glob `pattern` supporting `*` and `?`
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently absorbs up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, absorbed)) => {
+                    p = star + 1;
+                    t = absorbed + 1;
+                    backtrack = Some((star, absorbed + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_crate_and_version_scopes() {
+        let entry = tags(&[
+            "tool:get_crate_docs",
+            "crate:serde-json",
+            "crate:serde-json@1.0.120",
+        ]);
+
+        assert!(CacheScope::Crate("serde_json".into()).matches("k", &entry));
+        assert!(!CacheScope::Crate("serde".into()).matches("k", &entry));
+
+        let version = |v: &str| CacheScope::Version {
+            crate_name: "serde-json".into(),
+            version: v.into(),
+        };
+        assert!(version("1.0.120").matches("k", &entry));
+        assert!(version("1.0").matches("k", &entry));
+        assert!(!version("1.0.12").matches("k", &entry));
+        assert!(!version("2").matches("k", &entry));
+    }
+
+    #[test]
+    fn test_tool_kind_and_tag_scopes() {
+        let entry = tags(&["tool:search_crate", "kind:search"]);
+
+        assert!(CacheScope::Tool("search_crate".into()).matches("k", &entry));
+        assert!(!CacheScope::Tool("get_crate_docs".into()).matches("k", &entry));
+        assert!(CacheScope::Kind("search".into()).matches("k", &entry));
+        assert!(CacheScope::Tag("kind:search".into()).matches("k", &entry));
+        assert!(CacheScope::All.matches("k", &[]));
+    }
+
+    #[test]
+    fn test_key_glob_scope() {
+        let scope = CacheScope::KeyGlob("get_crate_docs:tok*:*".into());
+        assert!(scope.matches("get_crate_docs:tokio:latest", &[]));
+        assert!(!scope.matches("get_item_docs:tokio:latest", &[]));
+
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"a?c", b"abc"));
+        assert!(!glob_match(b"a?c", b"ac"));
+        assert!(glob_match(b"*b*b", b"abxbb"));
+        assert!(!glob_match(b"abc", b"abcd"));
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Deduplicates concurrent calls for the same key: the first caller runs the
+/// operation and everyone who asks for that key while it is running receives
+/// a clone of its result.
+///
+/// Results are not kept once the operation finishes; pair this with a cache
+/// so later callers find the value there. If the running caller is cancelled,
+/// one of the waiting callers runs its own operation instead.
+pub struct SingleFlight<K, T> {
+    calls: Mutex<HashMap<K, watch::Receiver<Option<T>>>>,
+}
+
+/// What a caller does for a key: run the operation, or wait for the runner
+enum Role<T> {
+    Runner(watch::Sender<Option<T>>),
+    Waiter(watch::Receiver<Option<T>>),
+}
+
+impl<K, T> SingleFlight<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Clone,
+{
+    /// Create an empty single-flight group
+    pub fn new() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `operation` for `key`, or wait for the call already in flight
+    pub async fn run<F, Fut>(&self, key: K, operation: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let mut operation = Some(operation);
+        loop {
+            let role = {
+                let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+                match calls.get(&key) {
+                    Some(call) => Role::Waiter(call.clone()),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        calls.insert(key.clone(), receiver);
+                        Role::Runner(sender)
+                    }
+                }
+            };
+
+            match role {
+                Role::Runner(sender) => {
+                    let _call = CallGuard {
+                        calls: &self.calls,
+                        key: &key,
+                    };
+                    // Only the caller that registers a call ever runs its operation
+                    let operation = operation.take().expect("operation runs at most once");
+                    let result = operation().await;
+                    sender.send_replace(Some(result.clone()));
+                    return result;
+                }
+                Role::Waiter(mut call) => {
+                    if let Ok(result) = call.wait_for(Option::is_some).await {
+                        if let Some(result) = result.as_ref() {
+                            return result.clone();
+                        }
+                    }
+                    // The running caller was cancelled; retry, possibly as the runner
+                }
+            }
+        }
+    }
+
+    /// Whether a call for `key` is currently running
+    pub fn is_running(&self, key: &K) -> bool {
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(key)
+    }
+}
+
+impl<K, T> Default for SingleFlight<K, T>
+where
+    K: Hash + Eq + Clone,
+    T: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unregisters a call when its runner finishes or is cancelled
+struct CallGuard<'a, K: Hash + Eq, T> {
+    calls: &'a Mutex<HashMap<K, watch::Receiver<Option<T>>>>,
+    key: &'a K,
+}
+
+impl<K: Hash + Eq, T> Drop for CallGuard<'_, K, T> {
+    fn drop(&mut self) {
+        self.calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_run() {
+        let flights = Arc::new(SingleFlight::<String, usize>::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let callers: Vec<_> = (0..8)
+            .map(|_| {
+                let flights = flights.clone();
+                let runs = runs.clone();
+                tokio::spawn(async move {
+                    flights
+                        .run("tokio".to_string(), || async move {
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            runs.fetch_add(1, Ordering::SeqCst) + 1
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for caller in callers {
+            assert_eq!(caller.await.unwrap(), 1);
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(!flights.is_running(&"tokio".to_string()));
+
+        // Finished calls are not remembered
+        let result = flights.run("tokio".to_string(), || async { 2 }).await;
+        assert_eq!(result, 2);
+    }
+
+    #[tokio::test]
+    async fn test_distinct_keys_run_separately() {
+        let flights = SingleFlight::<&str, &str>::new();
+        let (a, b) = tokio::join!(
+            flights.run("a", || async { "a" }),
+            flights.run("b", || async { "b" })
+        );
+        assert_eq!((a, b), ("a", "b"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_runner_hands_over() {
+        let flights = Arc::new(SingleFlight::<&str, &str>::new());
+
+        let runner = {
+            let flights = flights.clone();
+            tokio::spawn(async move {
+                flights
+                    .run("key", || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        "never"
+                    })
+                    .await
+            })
+        };
+        while !flights.is_running(&"key") {
+            tokio::task::yield_now().await;
+        }
+
+        let waiter = {
+            let flights = flights.clone();
+            tokio::spawn(async move { flights.run("key", || async { "retried" }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        runner.abort();
+
+        assert_eq!(waiter.await.unwrap(), "retried");
+    }
+}
@@ -0,0 +1,291 @@
+//! Portable snapshots of cache contents, for warm starts on new machines.
+//!
+//! A snapshot is a JSON document listing every live entry with its absolute
+//! expiry time and tags. Expiry is stored as wall-clock time so an entry
+//! imported later keeps only the lifetime it had left.
+
+use crate::{Cache, CacheEntry, TieredCache};
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::hash::Hash;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Identifies snapshot files written by this crate
+pub const SNAPSHOT_FORMAT: &str = "rustacean-docs-cache-snapshot";
+
+/// Snapshot layout version, bumped on incompatible changes
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The cache entries live when a snapshot was taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot<K, V> {
+    pub format: String,
+    pub version: u32,
+    /// When the snapshot was taken, in unix milliseconds
+    pub created_at: u64,
+    pub entries: Vec<SnapshotEntry<K, V>>,
+}
+
+/// One entry of a [`Snapshot`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry<K, V> {
+    pub key: K,
+    pub value: V,
+    /// Expiry in unix milliseconds (`None` if it never expires)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Fields checked before the entries are decoded
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    format: String,
+    version: u32,
+}
+
+/// Outcome of importing a snapshot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Entries written to the cache
+    pub imported: usize,
+    /// Entries skipped because they expired since the snapshot was taken
+    pub expired: usize,
+}
+
+impl<K, V> Snapshot<K, V> {
+    /// Build a snapshot of `entries` as of now
+    pub fn from_entries(entries: Vec<CacheEntry<K, V>>) -> Self {
+        let now = now_millis();
+        Self {
+            format: SNAPSHOT_FORMAT.to_string(),
+            version: SNAPSHOT_VERSION,
+            created_at: now,
+            entries: entries
+                .into_iter()
+                .map(|entry| SnapshotEntry {
+                    key: entry.key,
+                    value: entry.value,
+                    expires_at: entry
+                        .ttl
+                        .map(|ttl| now.saturating_add(ttl.as_millis() as u64)),
+                    tags: entry.tags,
+                })
+                .collect(),
+        }
+    }
+
+    /// The entries still live now, with their remaining lifetime, and how
+    /// many have expired
+    pub fn into_entries(self) -> (Vec<CacheEntry<K, V>>, usize) {
+        let now = now_millis();
+        let total = self.entries.len();
+        let live: Vec<_> = self
+            .entries
+            .into_iter()
+            .filter(|entry| entry.expires_at.is_none_or(|expires_at| expires_at > now))
+            .map(|entry| CacheEntry {
+                key: entry.key,
+                value: entry.value,
+                ttl: entry
+                    .expires_at
+                    .map(|expires_at| Duration::from_millis(expires_at - now)),
+                tags: entry.tags,
+            })
+            .collect();
+        let expired = total - live.len();
+        (live, expired)
+    }
+}
+
+impl<K, V> Snapshot<K, V>
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    /// Write the snapshot to `path`, replacing any existing file only once
+    /// the new one is complete
+    pub async fn write_to(&self, path: &Path) -> Result<()> {
+        let data = serde_json::to_vec(self).context("Failed to serialize cache snapshot")?;
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, data)
+            .await
+            .with_context(|| format!("Failed to write cache snapshot {}", partial.display()))?;
+        tokio::fs::rename(&partial, path)
+            .await
+            .with_context(|| format!("Failed to write cache snapshot {}", path.display()))
+    }
+
+    /// Read a snapshot from `path`, rejecting other files and snapshots
+    /// written by an incompatible version
+    pub async fn read_from(path: &Path) -> Result<Self> {
+        let data = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read cache snapshot {}", path.display()))?;
+
+        let header: SnapshotHeader = serde_json::from_slice(&data)
+            .with_context(|| format!("{} is not a cache snapshot", path.display()))?;
+        if header.format != SNAPSHOT_FORMAT {
+            bail!("{} is not a cache snapshot", path.display());
+        }
+        if header.version != SNAPSHOT_VERSION {
+            bail!(
+                "Cache snapshot {} has version {}, expected {}",
+                path.display(),
+                header.version,
+                SNAPSHOT_VERSION
+            );
+        }
+
+        serde_json::from_slice(&data)
+            .with_context(|| format!("Failed to decode cache snapshot {}", path.display()))
+    }
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    /// Snapshot every live entry across the layers
+    pub async fn export_snapshot(&self) -> Result<Snapshot<K, V>> {
+        Ok(Snapshot::from_entries(self.entries().await?))
+    }
+
+    /// Insert the still-live entries of `snapshot`, keeping their remaining
+    /// lifetime and tags. Existing entries with the same keys are replaced.
+    pub async fn import_snapshot(&self, snapshot: Snapshot<K, V>) -> Result<ImportReport> {
+        let (entries, expired) = snapshot.into_entries();
+        let mut report = ImportReport {
+            imported: 0,
+            expired,
+        };
+        for entry in entries {
+            self.insert_tagged(entry.key, entry.value, entry.ttl, entry.tags)
+                .await?;
+            report.imported += 1;
+        }
+        Ok(report)
+    }
+}
+
+/// Current time in unix milliseconds
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiskCache;
+    use tempfile::TempDir;
+
+    fn disk_tiered(dir: &Path) -> TieredCache<String, String> {
+        TieredCache::builder()
+            .add_layer(Box::new(DiskCache::<String, String>::new(dir)))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let source_dir = TempDir::new().unwrap();
+        let source = disk_tiered(source_dir.path());
+        source
+            .insert_tagged(
+                "docs:tokio".to_string(),
+                "tokio docs".to_string(),
+                Some(Duration::from_secs(3600)),
+                vec!["crate:tokio".to_string()],
+            )
+            .await
+            .unwrap();
+        source
+            .insert("search:async".to_string(), "results".to_string())
+            .await
+            .unwrap();
+
+        let snapshot_dir = TempDir::new().unwrap();
+        let path = snapshot_dir.path().join("cache.json");
+        source
+            .export_snapshot()
+            .await
+            .unwrap()
+            .write_to(&path)
+            .await
+            .unwrap();
+
+        let target_dir = TempDir::new().unwrap();
+        let target = disk_tiered(target_dir.path());
+        let snapshot = Snapshot::read_from(&path).await.unwrap();
+        let report = target.import_snapshot(snapshot).await.unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                imported: 2,
+                expired: 0
+            }
+        );
+
+        let (value, ttl) = target
+            .get_with_ttl(&"docs:tokio".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, "tokio docs");
+        assert!(ttl.unwrap() <= Duration::from_secs(3600));
+        assert_eq!(
+            target.get(&"search:async".to_string()).await.unwrap(),
+            Some("results".to_string())
+        );
+
+        // Tags survive, so the imported entries can be invalidated by scope
+        let scope = crate::CacheScope::Crate("tokio".to_string());
+        assert_eq!(target.invalidate(&scope).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_not_imported() {
+        let mut snapshot = Snapshot::from_entries(vec![CacheEntry {
+            key: "stale".to_string(),
+            value: "value".to_string(),
+            ttl: Some(Duration::from_secs(60)),
+            tags: Vec::new(),
+        }]);
+        snapshot.entries[0].expires_at = Some(snapshot.created_at - 1);
+
+        let dir = TempDir::new().unwrap();
+        let report = disk_tiered(dir.path())
+            .import_snapshot(snapshot)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                imported: 0,
+                expired: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_snapshots_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache.json");
+
+        let mut snapshot = Snapshot::<String, String>::from_entries(Vec::new());
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        snapshot.write_to(&path).await.unwrap();
+        let error = Snapshot::<String, String>::read_from(&path)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("version"));
+
+        tokio::fs::write(&path, b"{\"entries\": []}").await.unwrap();
+        assert!(Snapshot::<String, String>::read_from(&path).await.is_err());
+    }
+}
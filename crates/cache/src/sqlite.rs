@@ -0,0 +1,1226 @@
+use crate::{effective_ttl, Cache, CacheEntry, CacheStats, EntryFilter, EvictionPolicy, GcReport};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use rustacean_docs_core::constants::CACHE_SCHEMA_VERSION;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entries (
+        key TEXT PRIMARY KEY NOT NULL,
+        value BLOB NOT NULL,
+        size INTEGER NOT NULL,
+        schema_version INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        expires_at INTEGER,
+        last_access INTEGER NOT NULL,
+        hits INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS entries_expires_at ON entries (expires_at);
+    CREATE INDEX IF NOT EXISTS entries_last_access ON entries (last_access);
+    CREATE TABLE IF NOT EXISTS tags (
+        tag TEXT NOT NULL,
+        key TEXT NOT NULL,
+        PRIMARY KEY (tag, key)
+    );
+    CREATE INDEX IF NOT EXISTS tags_key ON tags (key);
+    CREATE TABLE IF NOT EXISTS usage (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        entries INTEGER NOT NULL,
+        bytes INTEGER NOT NULL
+    );
+    INSERT INTO usage (id, entries, bytes)
+        SELECT 0, (SELECT COUNT(*) FROM entries), (SELECT COALESCE(SUM(size), 0) FROM entries)
+        WHERE NOT EXISTS (SELECT 1 FROM usage);
+    CREATE TRIGGER IF NOT EXISTS entries_usage_insert AFTER INSERT ON entries BEGIN
+        UPDATE usage SET entries = entries + 1, bytes = bytes + NEW.size WHERE id = 0;
+    END;
+    CREATE TRIGGER IF NOT EXISTS entries_usage_delete AFTER DELETE ON entries BEGIN
+        UPDATE usage SET entries = entries - 1, bytes = bytes - OLD.size WHERE id = 0;
+    END;
+    CREATE TRIGGER IF NOT EXISTS entries_usage_update AFTER UPDATE OF size ON entries BEGIN
+        UPDATE usage SET bytes = bytes - OLD.size + NEW.size WHERE id = 0;
+    END;
+";
+
+/// Idle read connections kept open for reuse
+const MAX_IDLE_READERS: usize = 4;
+/// How long a connection waits for a lock held by another connection
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cache backed by a single SQLite file.
+///
+/// Keys are the table's primary key, so prefix listing and removal use the
+/// index; expiry, size and tags are columns, so scoped clears and garbage
+/// collection are single queries, and bulk changes run in one transaction.
+/// Entry and byte totals are kept in a one-row table maintained by triggers.
+/// Reads use their own connections and never wait for the writer; the access
+/// times and hit counts they produce are applied with the next write. Values
+/// are stored as JSON.
+pub struct SqliteCache<K, V> {
+    store: SqliteStore,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<K, V> SqliteCache<K, V> {
+    /// Open the cache file at `path`, creating it and its parent directories
+    /// if needed
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            store: SqliteStore::open(path.as_ref())?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Expire entries after at most `ttl`; entries inserted with a shorter
+    /// TTL expire sooner. Entries written before a TTL was configured expire
+    /// `ttl` after they were written.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.store.ttl = Some(ttl);
+        self
+    }
+
+    /// Keep the stored values within `max_bytes`, evicting entries chosen by
+    /// the eviction policy once an insert goes over budget
+    pub fn with_max_size(mut self, max_bytes: u64) -> Self {
+        self.store.max_bytes = Some(max_bytes);
+        self.store.lock_stats().max_bytes = max_bytes;
+        self
+    }
+
+    /// Set which entries are evicted first when over the size budget
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.store.eviction_policy = policy;
+        self
+    }
+
+    /// Discard entries written with a schema version other than
+    /// `schema_version` (by default
+    /// [`CACHE_SCHEMA_VERSION`](rustacean_docs_core::constants::CACHE_SCHEMA_VERSION))
+    pub fn with_schema_version(mut self, schema_version: u32) -> Self {
+        self.store.schema_version = schema_version;
+        self
+    }
+
+    /// Path of the SQLite file
+    pub fn path(&self) -> &Path {
+        &self.store.path
+    }
+
+    /// Remove expired entries and entries from other schema versions, then
+    /// evict down to the size budget
+    pub async fn collect_garbage(&self) -> Result<GcReport> {
+        self.store.collect_garbage().await
+    }
+
+    /// Run [`collect_garbage`](Self::collect_garbage) now and then every
+    /// `interval` on a background task, until the returned handle is aborted
+    pub fn spawn_gc(&self, interval: Duration) -> JoinHandle<()> {
+        let store = self.store.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match store.collect_garbage().await {
+                    Ok(report) if report == GcReport::default() => {
+                        debug!("SQLite cache garbage collection found nothing to remove")
+                    }
+                    Ok(report) => info!(
+                        expired = report.expired,
+                        evicted = report.evicted,
+                        bytes_freed = report.bytes_freed,
+                        "SQLite cache garbage collection finished"
+                    ),
+                    Err(e) => warn!(error = %e, "SQLite cache garbage collection failed"),
+                }
+            }
+        })
+    }
+}
+
+impl<K, V> SqliteCache<K, V>
+where
+    K: ToString + FromStr + Send + Sync,
+    V: Serialize + Send + Sync,
+{
+    /// Keys of the live entries starting with `prefix`
+    pub async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<K>> {
+        let keys = self.store.keys_with_prefix(prefix).await?;
+        Ok(keys
+            .iter()
+            .filter_map(|key| K::from_str(key).ok())
+            .collect())
+    }
+
+    /// Remove every entry whose key starts with `prefix`, returning how many
+    /// were removed
+    pub async fn remove_prefix(&self, prefix: &str) -> Result<usize> {
+        self.store.remove_prefix(prefix).await
+    }
+
+    /// Insert several entries in one transaction; either all are stored or
+    /// none are
+    pub async fn insert_many(&self, entries: Vec<CacheEntry<K, V>>) -> Result<()> {
+        let rows = entries
+            .into_iter()
+            .map(|entry| {
+                let data = serde_json::to_vec(&entry.value).context("Failed to serialize value")?;
+                Ok(Row {
+                    key: entry.key.to_string(),
+                    data,
+                    ttl: entry.ttl,
+                    tags: entry.tags,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.store.insert(rows).await
+    }
+}
+
+#[async_trait]
+impl<K, V> Cache for SqliteCache<K, V>
+where
+    K: ToString + FromStr + Send + Sync,
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    type Key = K;
+    type Value = V;
+    type Error = anyhow::Error;
+
+    async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
+        Ok(self.get_with_ttl(key).await?.map(|(value, _)| value))
+    }
+
+    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+        self.insert_with_ttl(key, value, None).await
+    }
+
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+        let Some((data, remaining)) = self.store.get(key.to_string()).await? else {
+            return Ok(None);
+        };
+        let value: V =
+            serde_json::from_slice(&data).context("Failed to deserialize cached value")?;
+        Ok(Some((value, remaining)))
+    }
+
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        self.insert_tagged(key, value, ttl, Vec::new()).await
+    }
+
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
+        self.insert_many(vec![CacheEntry {
+            key,
+            value,
+            ttl,
+            tags,
+        }])
+        .await
+    }
+
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        // Keys that do not parse back into `K` were not written by this cache
+        let matching: Vec<String> = self
+            .store
+            .tagged_keys()
+            .await?
+            .into_iter()
+            .filter(|(key, tags)| K::from_str(key).is_ok_and(|key| filter(&key, tags)))
+            .map(|(key, _)| key)
+            .collect();
+        self.store.remove_keys(matching.clone()).await?;
+        Ok(matching
+            .iter()
+            .filter_map(|key| K::from_str(key).ok())
+            .collect())
+    }
+
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        let mut entries = Vec::new();
+        for row in self.store.entries().await? {
+            let Ok(key) = K::from_str(&row.key) else {
+                continue;
+            };
+            match serde_json::from_slice(&row.data) {
+                Ok(value) => entries.push(CacheEntry {
+                    key,
+                    value,
+                    ttl: row.ttl,
+                    tags: row.tags,
+                }),
+                Err(e) => {
+                    warn!(key = %row.key, error = %e, "Skipping undecodable SQLite cache entry")
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
+        self.store.remove_keys(vec![key.to_string()]).await
+    }
+
+    async fn clear(&self) -> Result<(), Self::Error> {
+        self.store.clear().await
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.store.lock_stats().clone()
+    }
+
+    fn layer_name(&self) -> &str {
+        "sqlite"
+    }
+}
+
+/// An entry as stored: serialized value, remaining lifetime and tags
+struct Row {
+    key: String,
+    data: Vec<u8>,
+    ttl: Option<Duration>,
+    tags: Vec<String>,
+}
+
+/// The connections and settings behind [`SqliteCache`], shared with its
+/// garbage collection task
+#[derive(Clone)]
+struct SqliteStore {
+    path: PathBuf,
+    writer: Arc<Mutex<Connection>>,
+    readers: Arc<Mutex<Vec<Connection>>>,
+    /// Reads not yet applied to `last_access` and `hits`, by key
+    accesses: Arc<Mutex<HashMap<String, Access>>>,
+    ttl: Option<Duration>,
+    max_bytes: Option<u64>,
+    eviction_policy: EvictionPolicy,
+    schema_version: u32,
+    stats: Arc<Mutex<CacheStats>>,
+}
+
+/// Reads of one entry since the last write transaction
+#[derive(Clone, Copy)]
+struct Access {
+    last_access: i64,
+    hits: i64,
+}
+
+/// What a read found under a key
+enum Lookup {
+    Found(Vec<u8>, Option<Duration>),
+    /// Present but expired or from another schema version
+    Stale,
+    Missing,
+}
+
+impl SqliteStore {
+    fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent).context("Failed to create SQLite cache directory")?;
+        }
+        let conn = connect(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create SQLite cache schema")?;
+        let (size, bytes) = usage(&conn)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Arc::new(Mutex::new(conn)),
+            readers: Arc::new(Mutex::new(Vec::new())),
+            accesses: Arc::new(Mutex::new(HashMap::new())),
+            ttl: None,
+            max_bytes: None,
+            eviction_policy: EvictionPolicy::default(),
+            schema_version: CACHE_SCHEMA_VERSION,
+            stats: Arc::new(Mutex::new(CacheStats {
+                size,
+                capacity: usize::MAX, // Bounded by bytes, not entries
+                bytes,
+                ..Default::default()
+            })),
+        })
+    }
+
+    fn lock_stats(&self) -> std::sync::MutexGuard<'_, CacheStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_accesses(&self) -> std::sync::MutexGuard<'_, HashMap<String, Access>> {
+        self.accesses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn settings(&self) -> StoreSettings {
+        StoreSettings {
+            layer_ttl_ms: self.ttl.map(|ttl| ttl.as_millis() as i64),
+            max_bytes: self.max_bytes,
+            eviction_policy: self.eviction_policy,
+            schema_version: self.schema_version,
+        }
+    }
+
+    /// Run `operation` in a write transaction on a blocking thread, after
+    /// applying the reads recorded since the last one, then refresh the
+    /// size accounting. When the transaction fails the reads stay pending.
+    async fn transaction<T, F>(&self, operation: F) -> Result<T>
+    where
+        F: FnOnce(&Transaction<'_>, &StoreSettings) -> rusqlite::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let writer = Arc::clone(&self.writer);
+        let settings = self.settings();
+        let accesses = Arc::new(std::mem::take(&mut *self.lock_accesses()));
+        let applied = Arc::clone(&accesses);
+        let outcome = tokio::task::spawn_blocking(move || {
+            let mut conn = writer.lock().unwrap_or_else(|e| e.into_inner());
+            let tx = conn.transaction()?;
+            apply_accesses(&tx, &applied)?;
+            let result = operation(&tx, &settings)?;
+            let usage = usage(&tx)?;
+            tx.commit()?;
+            Ok::<_, rusqlite::Error>((result, usage))
+        })
+        .await
+        .context("SQLite cache task panicked")
+        .and_then(|outcome| outcome.context("SQLite cache query failed"));
+        let (result, (size, bytes)) = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                // The task is done, so this is the only reference left
+                let accesses = Arc::try_unwrap(accesses).unwrap_or_else(|arc| (*arc).clone());
+                self.restore_accesses(accesses);
+                return Err(e);
+            }
+        };
+
+        let mut stats = self.lock_stats();
+        stats.size = size;
+        stats.bytes = bytes;
+        Ok(result)
+    }
+
+    /// Put back reads taken by a failed transaction, merged with any
+    /// recorded since
+    fn restore_accesses(&self, accesses: HashMap<String, Access>) {
+        let mut pending = self.lock_accesses();
+        for (key, access) in accesses {
+            pending
+                .entry(key)
+                .and_modify(|pending| {
+                    pending.last_access = pending.last_access.max(access.last_access);
+                    pending.hits += access.hits;
+                })
+                .or_insert(access);
+        }
+    }
+
+    /// Run the read-only `operation` on a pooled read connection on a
+    /// blocking thread, without waiting for the writer
+    async fn read<T, F>(&self, operation: F) -> Result<T>
+    where
+        F: FnOnce(&Connection, &StoreSettings) -> rusqlite::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let readers = Arc::clone(&self.readers);
+        let path = self.path.clone();
+        let settings = self.settings();
+        tokio::task::spawn_blocking(move || {
+            let idle = readers.lock().unwrap_or_else(|e| e.into_inner()).pop();
+            let conn = match idle {
+                Some(conn) => conn,
+                None => connect(&path)?,
+            };
+            let result = operation(&conn, &settings).context("SQLite cache query failed")?;
+            let mut readers = readers.lock().unwrap_or_else(|e| e.into_inner());
+            if readers.len() < MAX_IDLE_READERS {
+                readers.push(conn);
+            }
+            Ok(result)
+        })
+        .await
+        .context("SQLite cache task panicked")?
+    }
+
+    async fn get(&self, key: String) -> Result<Option<(Vec<u8>, Option<Duration>)>> {
+        let lookup = self
+            .read({
+                let key = key.clone();
+                move |conn, settings| {
+                    let now = now_millis();
+                    let row = conn
+                        .prepare_cached(
+                            "SELECT value, schema_version, created_at, expires_at
+                             FROM entries WHERE key = ?1",
+                        )?
+                        .query_row(params![key], |row| {
+                            Ok((
+                                row.get::<_, Vec<u8>>(0)?,
+                                row.get::<_, u32>(1)?,
+                                row.get::<_, i64>(2)?,
+                                row.get::<_, Option<i64>>(3)?,
+                            ))
+                        })
+                        .optional()?;
+                    let Some((data, schema_version, created_at, expires_at)) = row else {
+                        return Ok(Lookup::Missing);
+                    };
+
+                    let expires_at = settings.expiry(created_at, expires_at);
+                    if schema_version != settings.schema_version
+                        || expires_at.is_some_and(|expires_at| expires_at <= now)
+                    {
+                        return Ok(Lookup::Stale);
+                    }
+                    let remaining = expires_at
+                        .map(|expires_at| Duration::from_millis((expires_at - now).max(0) as u64));
+                    Ok(Lookup::Found(data, remaining))
+                }
+            })
+            .await?;
+
+        let found = match lookup {
+            Lookup::Found(data, remaining) => {
+                let access = Access {
+                    last_access: now_millis(),
+                    hits: 1,
+                };
+                self.lock_accesses()
+                    .entry(key)
+                    .and_modify(|pending| {
+                        pending.last_access = access.last_access;
+                        pending.hits += 1;
+                    })
+                    .or_insert(access);
+                Some((data, remaining))
+            }
+            Lookup::Stale => {
+                self.remove_keys(vec![key]).await?;
+                None
+            }
+            Lookup::Missing => None,
+        };
+
+        let mut stats = self.lock_stats();
+        match found {
+            Some(_) => stats.hits += 1,
+            None => stats.misses += 1,
+        }
+        Ok(found)
+    }
+
+    async fn insert(&self, rows: Vec<Row>) -> Result<()> {
+        let evicted = self
+            .transaction(move |tx, settings| {
+                let now = now_millis();
+                let mut keep = Vec::with_capacity(rows.len());
+                for row in rows {
+                    delete_keys(tx, std::slice::from_ref(&row.key))?;
+
+                    // Already expired, or too large to ever fit: drop any older copy instead
+                    let ttl = effective_ttl(row.ttl, settings.layer_ttl());
+                    let size = row.data.len() as u64;
+                    if ttl.is_some_and(|ttl| ttl.is_zero())
+                        || settings.max_bytes.is_some_and(|max_bytes| size > max_bytes)
+                    {
+                        continue;
+                    }
+
+                    tx.execute(
+                        "INSERT INTO entries
+                             (key, value, size, schema_version, created_at, expires_at, last_access)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?5)",
+                        params![
+                            row.key,
+                            row.data,
+                            size as i64,
+                            settings.schema_version,
+                            now,
+                            ttl.map(|ttl| now + ttl.as_millis() as i64),
+                        ],
+                    )?;
+                    let mut insert_tag =
+                        tx.prepare_cached("INSERT OR IGNORE INTO tags (tag, key) VALUES (?1, ?2)")?;
+                    for tag in &row.tags {
+                        insert_tag.execute(params![tag, row.key])?;
+                    }
+                    keep.push(row.key);
+                }
+                enforce_budget(tx, settings, &keep)
+            })
+            .await?;
+
+        if evicted > 0 {
+            debug!(evicted, "Evicted entries from SQLite cache");
+            self.lock_stats().evictions += evicted as u64;
+        }
+        Ok(())
+    }
+
+    async fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.to_string();
+        self.read(move |conn, settings| {
+            let mut statement = conn.prepare(
+                "SELECT key, created_at, expires_at FROM entries
+                 WHERE key >= ?1 AND (?2 IS NULL OR key < ?2) AND schema_version = ?3
+                 ORDER BY key",
+            )?;
+            let now = now_millis();
+            let rows = statement.query_map(
+                params![prefix, prefix_end(&prefix), settings.schema_version],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                    ))
+                },
+            )?;
+            let mut keys = Vec::new();
+            for row in rows {
+                let (key, created_at, expires_at) = row?;
+                if settings
+                    .expiry(created_at, expires_at)
+                    .is_none_or(|expires_at| expires_at > now)
+                {
+                    keys.push(key);
+                }
+            }
+            Ok(keys)
+        })
+        .await
+    }
+
+    async fn remove_prefix(&self, prefix: &str) -> Result<usize> {
+        let prefix = prefix.to_string();
+        self.transaction(move |tx, _| {
+            let end = prefix_end(&prefix);
+            tx.execute(
+                "DELETE FROM tags WHERE key >= ?1 AND (?2 IS NULL OR key < ?2)",
+                params![prefix, end],
+            )?;
+            tx.execute(
+                "DELETE FROM entries WHERE key >= ?1 AND (?2 IS NULL OR key < ?2)",
+                params![prefix, end],
+            )
+        })
+        .await
+    }
+
+    /// Every stored key with its tags
+    async fn tagged_keys(&self) -> Result<Vec<(String, Vec<String>)>> {
+        self.read(|conn, _| {
+            let mut tags = all_tags(conn)?;
+            let mut statement = conn.prepare("SELECT key FROM entries")?;
+            let keys = statement.query_map([], |row| row.get::<_, String>(0))?;
+            keys.map(|key| {
+                let key = key?;
+                let tags = tags.remove(&key).unwrap_or_default();
+                Ok((key, tags))
+            })
+            .collect()
+        })
+        .await
+    }
+
+    /// Every live entry of the current schema version
+    async fn entries(&self) -> Result<Vec<Row>> {
+        self.read(|conn, settings| {
+            let now = now_millis();
+            let mut tags = all_tags(conn)?;
+            let mut statement = conn.prepare(
+                "SELECT key, value, created_at, expires_at FROM entries
+                 WHERE schema_version = ?1",
+            )?;
+            let rows = statement.query_map(params![settings.schema_version], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            })?;
+
+            let mut entries = Vec::new();
+            for row in rows {
+                let (key, data, created_at, expires_at) = row?;
+                let expires_at = settings.expiry(created_at, expires_at);
+                if expires_at.is_some_and(|expires_at| expires_at <= now) {
+                    continue;
+                }
+                let tags = tags.remove(&key).unwrap_or_default();
+                entries.push(Row {
+                    key,
+                    data,
+                    ttl: expires_at
+                        .map(|expires_at| Duration::from_millis((expires_at - now) as u64)),
+                    tags,
+                });
+            }
+            Ok(entries)
+        })
+        .await
+    }
+
+    async fn remove_keys(&self, keys: Vec<String>) -> Result<()> {
+        self.transaction(move |tx, _| delete_keys(tx, &keys)).await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.transaction(|tx, _| tx.execute_batch("DELETE FROM tags; DELETE FROM entries;"))
+            .await
+    }
+
+    async fn collect_garbage(&self) -> Result<GcReport> {
+        let bytes_before = self.lock_stats().bytes;
+        let (expired, evicted) = self
+            .transaction(|tx, settings| {
+                let now = now_millis();
+                let condition = "schema_version != ?1
+                    OR expires_at <= ?2
+                    OR (?3 IS NOT NULL AND created_at + ?3 <= ?2)";
+                let args = params![settings.schema_version, now, settings.layer_ttl_ms];
+                tx.execute(
+                    &format!(
+                        "DELETE FROM tags WHERE key IN (SELECT key FROM entries WHERE {condition})"
+                    ),
+                    args,
+                )?;
+                let expired =
+                    tx.execute(&format!("DELETE FROM entries WHERE {condition}"), args)?;
+                let evicted = enforce_budget(tx, settings, &[])?;
+                Ok((expired, evicted))
+            })
+            .await?;
+
+        let mut stats = self.lock_stats();
+        stats.evictions += evicted as u64;
+        Ok(GcReport {
+            expired,
+            evicted,
+            orphans: 0,
+            bytes_freed: bytes_before.saturating_sub(stats.bytes),
+        })
+    }
+}
+
+/// Settings a transaction needs, copied out of the store
+struct StoreSettings {
+    layer_ttl_ms: Option<i64>,
+    max_bytes: Option<u64>,
+    eviction_policy: EvictionPolicy,
+    schema_version: u32,
+}
+
+impl StoreSettings {
+    fn layer_ttl(&self) -> Option<Duration> {
+        self.layer_ttl_ms
+            .map(|ttl| Duration::from_millis(ttl as u64))
+    }
+
+    /// Expiry of an entry in unix milliseconds, capped by the layer TTL
+    fn expiry(&self, created_at: i64, expires_at: Option<i64>) -> Option<i64> {
+        let layer_limit = self.layer_ttl_ms.map(|ttl| created_at + ttl);
+        match (expires_at, layer_limit) {
+            (Some(expires_at), Some(limit)) => Some(expires_at.min(limit)),
+            (expires_at, limit) => expires_at.or(limit),
+        }
+    }
+}
+
+/// Open a connection to the cache file at `path`
+fn connect(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)
+        .with_context(|| format!("Failed to open SQLite cache {}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    Ok(conn)
+}
+
+/// Number of entries and bytes of stored values, from the usage row
+fn usage(conn: &Connection) -> rusqlite::Result<(usize, u64)> {
+    conn.query_row("SELECT entries, bytes FROM usage WHERE id = 0", [], |row| {
+        Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as u64))
+    })
+}
+
+/// Record reads made since the last write, so eviction sees them
+fn apply_accesses(
+    tx: &Transaction<'_>,
+    accesses: &HashMap<String, Access>,
+) -> rusqlite::Result<()> {
+    let mut update = tx.prepare_cached(
+        "UPDATE entries SET last_access = MAX(last_access, ?2), hits = hits + ?3 WHERE key = ?1",
+    )?;
+    for (key, access) in accesses {
+        update.execute(params![key, access.last_access, access.hits])?;
+    }
+    Ok(())
+}
+
+fn delete_keys(tx: &Transaction<'_>, keys: &[String]) -> rusqlite::Result<()> {
+    let mut delete_tags = tx.prepare_cached("DELETE FROM tags WHERE key = ?1")?;
+    let mut delete_entry = tx.prepare_cached("DELETE FROM entries WHERE key = ?1")?;
+    for key in keys {
+        delete_tags.execute(params![key])?;
+        delete_entry.execute(params![key])?;
+    }
+    Ok(())
+}
+
+/// Tags of every entry, by key
+fn all_tags(conn: &Connection) -> rusqlite::Result<HashMap<String, Vec<String>>> {
+    let mut statement = conn.prepare("SELECT key, tag FROM tags ORDER BY key, tag")?;
+    let rows = statement.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (key, tag) = row?;
+        tags.entry(key).or_default().push(tag);
+    }
+    Ok(tags)
+}
+
+/// Evict entries until the stored values fit the size budget, never
+/// evicting `keep`; returns how many were evicted
+fn enforce_budget(
+    tx: &Transaction<'_>,
+    settings: &StoreSettings,
+    keep: &[String],
+) -> rusqlite::Result<usize> {
+    let Some(max_bytes) = settings.max_bytes else {
+        return Ok(0);
+    };
+    let (_, mut bytes) = usage(tx)?;
+    if bytes <= max_bytes {
+        return Ok(0);
+    }
+
+    let order = match settings.eviction_policy {
+        EvictionPolicy::Lru => "last_access, key",
+        EvictionPolicy::Lfu => "hits, last_access, key",
+    };
+    let candidates: Vec<(String, u64)> = tx
+        .prepare(&format!("SELECT key, size FROM entries ORDER BY {order}"))?
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut victims = Vec::new();
+    for (key, size) in candidates {
+        if bytes <= max_bytes {
+            break;
+        }
+        if keep.contains(&key) {
+            continue;
+        }
+        bytes = bytes.saturating_sub(size);
+        victims.push(key);
+    }
+    delete_keys(tx, &victims)?;
+    Ok(victims.len())
+}
+
+/// Smallest string greater than every string starting with `prefix`, for
+/// range scans over the key index; `None` when no such string exists, as
+/// for the empty prefix
+///
+/// Keys compare by their UTF-8 bytes, which orders them by code point, so
+/// this is `prefix` with its last character incremented after dropping any
+/// trailing `char::MAX`.
+fn prefix_end(prefix: &str) -> Option<String> {
+    let mut end = prefix.trim_end_matches(char::MAX).to_string();
+    let last = end.pop()?;
+    // Surrogates are not characters; the next one after them is U+E000
+    let next = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32)?;
+    end.push(next);
+    Some(end)
+}
+
+/// Current time in unix milliseconds
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open(dir: &TempDir) -> SqliteCache<String, String> {
+        SqliteCache::open(dir.path().join("cache.sqlite")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_basic_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+
+        cache
+            .insert("key1".to_string(), "value1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.get(&"key1".to_string()).await.unwrap(),
+            Some("value1".to_string())
+        );
+        assert_eq!(cache.get(&"missing".to_string()).await.unwrap(), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!((stats.size, stats.bytes), (1, 8));
+
+        cache.remove(&"key1".to_string()).await.unwrap();
+        assert_eq!(cache.get(&"key1".to_string()).await.unwrap(), None);
+        assert_eq!(cache.stats().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_persistence() {
+        let temp_dir = TempDir::new().unwrap();
+        open(&temp_dir)
+            .insert_tagged(
+                "persistent".to_string(),
+                "value".to_string(),
+                None,
+                vec!["crate:tokio".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let cache = open(&temp_dir);
+        assert_eq!(cache.stats().size, 1);
+        let entries = cache.entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].tags, vec!["crate:tokio".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_counts_files_without_usage_row() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cache.sqlite");
+        {
+            let cache = open(&temp_dir);
+            cache
+                .insert("key".to_string(), "value".to_string())
+                .await
+                .unwrap();
+        }
+        // Files written before the usage row existed
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "DROP TRIGGER entries_usage_insert;
+                 DROP TRIGGER entries_usage_delete;
+                 DROP TRIGGER entries_usage_update;
+                 DROP TABLE usage;",
+            )
+            .unwrap();
+
+        let cache = open(&temp_dir);
+        assert_eq!((cache.stats().size, cache.stats().bytes), (1, 7));
+        cache.remove(&"key".to_string()).await.unwrap();
+        assert_eq!((cache.stats().size, cache.stats().bytes), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_entry_ttl() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir).with_ttl(Duration::from_secs(60));
+
+        cache
+            .insert_with_ttl(
+                "short".to_string(),
+                "value".to_string(),
+                Some(Duration::from_millis(20)),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert("long".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let (_, remaining) = cache
+            .get_with_ttl(&"long".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_secs(60));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.get(&"short".to_string()).await.unwrap(), None);
+        assert_eq!(cache.stats().size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_prefix_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        for key in [
+            "get_crate_docs:tokio:latest",
+            "get_crate_docs:serde:latest",
+            "get_crate_docs@internal:tokio:latest",
+            "search_crate:async",
+        ] {
+            cache
+                .insert(key.to_string(), "value".to_string())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            cache.keys_with_prefix("get_crate_docs:").await.unwrap(),
+            vec![
+                "get_crate_docs:serde:latest".to_string(),
+                "get_crate_docs:tokio:latest".to_string()
+            ]
+        );
+        assert_eq!(cache.remove_prefix("get_crate_docs").await.unwrap(), 3);
+        assert_eq!(
+            cache.keys_with_prefix("").await.unwrap(),
+            vec!["search_crate:async".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_prefix_end() {
+        assert_eq!(prefix_end("docs:").as_deref(), Some("docs;"));
+        assert_eq!(prefix_end("a\u{10FFFF}").as_deref(), Some("b"));
+        assert_eq!(prefix_end("\u{D7FF}").as_deref(), Some("\u{E000}"));
+        assert_eq!(prefix_end(""), None);
+        assert_eq!(prefix_end("\u{10FFFF}"), None);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_prefix_includes_max_char_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        for key in ["docs:\u{10FFFF}tail", "docs;next"] {
+            cache
+                .insert(key.to_string(), "value".to_string())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            cache.keys_with_prefix("docs:").await.unwrap(),
+            vec!["docs:\u{10FFFF}tail".to_string()]
+        );
+        assert_eq!(cache.remove_prefix("docs:").await.unwrap(), 1);
+        assert_eq!(cache.stats().size, 1);
+    }
+
+    // Holding the writer while reading is the point of the test
+    #[allow(clippy::await_holding_lock)]
+    #[tokio::test]
+    async fn test_sqlite_cache_reads_do_not_wait_for_writer() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        cache
+            .insert("key".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let writer = cache.store.writer.lock().unwrap();
+        let read = tokio::time::timeout(Duration::from_secs(5), async {
+            (
+                cache.get(&"key".to_string()).await.unwrap(),
+                cache.keys_with_prefix("k").await.unwrap(),
+            )
+        })
+        .await
+        .expect("reads should not wait for the writer");
+        drop(writer);
+        assert_eq!(read.0, Some("value".to_string()));
+        assert_eq!(read.1, vec!["key".to_string()]);
+
+        // The hit is applied to the entry with the next write
+        cache
+            .insert("other".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        let hits: i64 = cache
+            .store
+            .writer
+            .lock()
+            .unwrap()
+            .query_row("SELECT hits FROM entries WHERE key = 'key'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_keeps_reads_of_failed_transactions() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        cache
+            .insert("key".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        cache.get(&"key".to_string()).await.unwrap();
+
+        let failed = cache
+            .store
+            .transaction(|_, _| Err::<(), _>(rusqlite::Error::InvalidQuery))
+            .await;
+        assert!(failed.is_err());
+        cache.get(&"key".to_string()).await.unwrap();
+        assert_eq!(cache.store.lock_accesses()["key"].hits, 2);
+
+        // Both hits are applied with the next write that succeeds
+        cache
+            .insert("other".to_string(), "value".to_string())
+            .await
+            .unwrap();
+        let hits: i64 = cache
+            .store
+            .writer
+            .lock()
+            .unwrap()
+            .query_row("SELECT hits FROM entries WHERE key = 'key'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(hits, 2);
+        assert!(cache.store.lock_accesses().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_remove_matching_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        cache
+            .insert_many(vec![
+                CacheEntry {
+                    key: "docs:tokio".to_string(),
+                    value: "value".to_string(),
+                    ttl: None,
+                    tags: vec!["crate:tokio".to_string()],
+                },
+                CacheEntry {
+                    key: "docs:serde".to_string(),
+                    value: "value".to_string(),
+                    ttl: None,
+                    tags: vec!["crate:serde".to_string()],
+                },
+            ])
+            .await
+            .unwrap();
+
+        let removed = cache
+            .remove_matching(&|_: &String, tags: &[String]| tags.contains(&"crate:tokio".into()))
+            .await
+            .unwrap();
+        assert_eq!(removed, vec!["docs:tokio".to_string()]);
+        assert!(cache
+            .get(&"docs:serde".to_string())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_size_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let value = "x".repeat(100);
+        // Serialized as a JSON string: 102 bytes per entry
+        let cache = open(&temp_dir).with_max_size(250);
+
+        cache.insert("a".to_string(), value.clone()).await.unwrap();
+        cache.insert("b".to_string(), value.clone()).await.unwrap();
+        // Access times have millisecond resolution
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_some());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cache.insert("c".to_string(), value.clone()).await.unwrap();
+
+        assert!(cache.get(&"b".to_string()).await.unwrap().is_none());
+        assert!(cache.get(&"a".to_string()).await.unwrap().is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.bytes, 204);
+        assert_eq!(stats.max_bytes, 250);
+        assert_eq!(stats.evictions, 1);
+
+        // Entries larger than the whole budget are not stored
+        cache
+            .insert("huge".to_string(), "x".repeat(300))
+            .await
+            .unwrap();
+        assert!(cache.get(&"huge".to_string()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_discards_other_schema_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        open(&temp_dir)
+            .with_schema_version(1)
+            .insert("old".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let cache = open(&temp_dir).with_schema_version(2);
+        assert!(cache.entries().await.unwrap().is_empty());
+        assert_eq!(cache.get(&"old".to_string()).await.unwrap(), None);
+        assert_eq!(cache.stats().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_garbage_collection() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = open(&temp_dir);
+        cache
+            .insert_with_ttl(
+                "expiring".to_string(),
+                "value".to_string(),
+                Some(Duration::from_millis(10)),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert("kept".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let report = cache.collect_garbage().await.unwrap();
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.bytes_freed, 7);
+        assert_eq!(cache.stats().size, 1);
+        assert_eq!(cache.collect_garbage().await.unwrap(), GcReport::default());
+    }
+}
@@ -1,6 +1,9 @@
-use crate::{Cache, CacheStats};
+use crate::{Cache, CacheEntry, CacheMetrics, CacheOutcome, CacheScope, CacheStats, EntryFilter};
 use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
 
 /// Write strategy for tiered cache
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,6 +18,7 @@ pub enum WriteStrategy {
 pub struct TieredCache<K, V> {
     layers: Vec<Box<dyn Cache<Key = K, Value = V, Error = anyhow::Error>>>,
     write_strategy: WriteStrategy,
+    metrics: CacheMetrics,
 }
 
 impl<K, V> TieredCache<K, V>
@@ -27,12 +31,27 @@ where
         layers: Vec<Box<dyn Cache<Key = K, Value = V, Error = anyhow::Error>>>,
         write_strategy: WriteStrategy,
     ) -> Self {
+        let metrics = CacheMetrics::new(layers.iter().map(|layer| layer.layer_name()));
         Self {
             layers,
             write_strategy,
+            metrics,
         }
     }
 
+    /// Per-layer, per-tool and per-namespace lookup metrics
+    pub fn metrics(&self) -> &CacheMetrics {
+        &self.metrics
+    }
+
+    /// Statistics of each layer, in lookup order, under the layer's name
+    pub fn layer_stats(&self) -> Vec<(String, CacheStats)> {
+        self.layers
+            .iter()
+            .map(|layer| (layer.layer_name().to_string(), layer.stats()))
+            .collect()
+    }
+
     /// Create a builder for constructing a tiered cache
     pub fn builder() -> TieredCacheBuilder<K, V> {
         TieredCacheBuilder {
@@ -42,6 +61,29 @@ where
     }
 }
 
+impl<K, V> TieredCache<K, V>
+where
+    K: Clone + Eq + Hash + ToString + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    /// Remove the entries within `scope` from every layer, returning how
+    /// many distinct keys were removed
+    pub async fn invalidate(&self, scope: &CacheScope) -> anyhow::Result<usize> {
+        if *scope == CacheScope::All {
+            // Layers hold their own copies of promoted entries, so count keys
+            // rather than summing layer sizes
+            let removed = self.remove_matching(&|_: &K, _: &[String]| true).await?;
+            self.clear().await?;
+            return Ok(removed.len());
+        }
+
+        let removed = self
+            .remove_matching(&|key: &K, tags: &[String]| scope.matches(&key.to_string(), tags))
+            .await?;
+        Ok(removed.len())
+    }
+}
+
 impl<K, V> Debug for TieredCache<K, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TieredCache")
@@ -54,7 +96,7 @@ impl<K, V> Debug for TieredCache<K, V> {
 #[async_trait]
 impl<K, V> Cache for TieredCache<K, V>
 where
-    K: Clone + Send + Sync,
+    K: Clone + Eq + Hash + Send + Sync,
     V: Clone + Send + Sync,
 {
     type Key = K;
@@ -62,15 +104,40 @@ where
     type Error = anyhow::Error;
 
     async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
+        Ok(self.get_with_ttl(key).await?.map(|(value, _)| value))
+    }
+
+    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+        self.insert_with_ttl(key, value, None).await
+    }
+
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+        let started = Instant::now();
         for (index, layer) in self.layers.iter().enumerate() {
-            match layer.get(key).await {
-                Ok(Some(value)) => {
-                    // Promote to higher priority layers
+            let layer_started = Instant::now();
+            let result = layer.get_with_ttl(key).await;
+            let outcome = match &result {
+                Ok(Some(_)) => CacheOutcome::Hit,
+                Ok(None) => CacheOutcome::Miss,
+                Err(_) => CacheOutcome::Error,
+            };
+            self.metrics
+                .record_layer_read(index, outcome, layer_started.elapsed());
+
+            match result {
+                Ok(Some((value, ttl))) => {
+                    // Promote to higher priority layers, keeping the remaining lifetime
                     for i in 0..index {
                         // Ignore errors during promotion
-                        let _ = self.layers[i].insert(key.clone(), value.clone()).await;
+                        let _ = self.layers[i]
+                            .insert_with_ttl(key.clone(), value.clone(), ttl)
+                            .await;
                     }
-                    return Ok(Some(value));
+                    self.metrics.record_read(started.elapsed());
+                    return Ok(Some((value, ttl)));
                 }
                 Ok(None) => continue,
                 Err(e) => {
@@ -80,10 +147,26 @@ where
                 }
             }
         }
+        self.metrics.record_read(started.elapsed());
         Ok(None)
     }
 
-    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        self.insert_tagged(key, value, ttl, Vec::new()).await
+    }
+
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
         match self.write_strategy {
             WriteStrategy::WriteThrough => {
                 // Write to all layers
@@ -91,7 +174,10 @@ where
                     let key_clone = key.clone();
                     let value_clone = value.clone();
 
-                    match layer.insert(key_clone, value_clone).await {
+                    match layer
+                        .insert_tagged(key_clone, value_clone, ttl, tags.clone())
+                        .await
+                    {
                         Ok(_) => {}
                         Err(e) => {
                             tracing::warn!("Failed to write to cache layer: {}", e);
@@ -103,7 +189,7 @@ where
             WriteStrategy::WriteBack => {
                 // Write only to the first layer
                 if let Some(first_layer) = self.layers.first() {
-                    first_layer.insert(key, value).await
+                    first_layer.insert_tagged(key, value, ttl, tags).await
                 } else {
                     Ok(())
                 }
@@ -111,6 +197,64 @@ where
         }
     }
 
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        let mut removed: HashSet<K> = HashSet::new();
+        for (index, layer) in self.layers.iter().enumerate() {
+            match layer.remove_matching(filter).await {
+                Ok(keys) => removed.extend(keys),
+                Err(e) => {
+                    tracing::warn!("Failed to invalidate cache layer {}: {}", index, e);
+                }
+            }
+        }
+
+        // Copies promoted between layers are stored without tags
+        for layer in &self.layers {
+            for key in &removed {
+                if let Err(e) = layer.remove(key).await {
+                    tracing::warn!("Failed to remove from cache layer: {}", e);
+                }
+            }
+        }
+        Ok(removed.into_iter().collect())
+    }
+
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        let mut entries: Vec<CacheEntry<K, V>> = Vec::new();
+        let mut positions: HashMap<K, usize> = HashMap::new();
+        for (index, layer) in self.layers.iter().enumerate() {
+            let layer_entries = match layer.entries().await {
+                Ok(layer_entries) => layer_entries,
+                Err(e) => {
+                    tracing::warn!("Failed to list cache layer {}: {}", index, e);
+                    continue;
+                }
+            };
+            for entry in layer_entries {
+                // Higher priority layers hold the value that `get` would return;
+                // lower ones may know tags a promoted copy lacks
+                match positions.get(&entry.key) {
+                    Some(&position) => {
+                        let existing = &mut entries[position];
+                        for tag in entry.tags {
+                            if !existing.tags.contains(&tag) {
+                                existing.tags.push(tag);
+                            }
+                        }
+                    }
+                    None => {
+                        positions.insert(entry.key.clone(), entries.len());
+                        entries.push(entry);
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
     async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
         // Remove from all layers
         for layer in &self.layers {
@@ -147,6 +291,9 @@ where
             combined.misses += layer_stats.misses;
             combined.size += layer_stats.size;
             combined.capacity = combined.capacity.saturating_add(layer_stats.capacity);
+            combined.bytes += layer_stats.bytes;
+            combined.max_bytes += layer_stats.max_bytes;
+            combined.evictions += layer_stats.evictions;
         }
 
         combined
@@ -181,10 +328,7 @@ where
 
     /// Build the tiered cache
     pub fn build(self) -> TieredCache<K, V> {
-        TieredCache {
-            layers: self.layers,
-            write_strategy: self.write_strategy,
-        }
+        TieredCache::new(self.layers, self.write_strategy)
     }
 }
 
@@ -233,9 +377,65 @@ mod tests {
             self.0.clear().await.map_err(|_: Infallible| unreachable!())
         }
 
+        async fn get_with_ttl(
+            &self,
+            key: &Self::Key,
+        ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+            self.0
+                .get_with_ttl(key)
+                .await
+                .map_err(|_: Infallible| unreachable!())
+        }
+
+        async fn insert_with_ttl(
+            &self,
+            key: Self::Key,
+            value: Self::Value,
+            ttl: Option<Duration>,
+        ) -> Result<(), Self::Error> {
+            self.0
+                .insert_with_ttl(key, value, ttl)
+                .await
+                .map_err(|_: Infallible| unreachable!())
+        }
+
+        async fn insert_tagged(
+            &self,
+            key: Self::Key,
+            value: Self::Value,
+            ttl: Option<Duration>,
+            tags: Vec<String>,
+        ) -> Result<(), Self::Error> {
+            self.0
+                .insert_tagged(key, value, ttl, tags)
+                .await
+                .map_err(|_: Infallible| unreachable!())
+        }
+
+        async fn remove_matching(
+            &self,
+            filter: &EntryFilter<'_, Self::Key>,
+        ) -> Result<Vec<Self::Key>, Self::Error> {
+            self.0
+                .remove_matching(filter)
+                .await
+                .map_err(|_: Infallible| unreachable!())
+        }
+
+        async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+            self.0
+                .entries()
+                .await
+                .map_err(|_: Infallible| unreachable!())
+        }
+
         fn stats(&self) -> CacheStats {
             self.0.stats()
         }
+
+        fn layer_name(&self) -> &str {
+            self.0.layer_name()
+        }
     }
 
     #[tokio::test]
@@ -323,4 +523,161 @@ mod tests {
             assert_eq!(disk_only.get(&"writeback".to_string()).await.unwrap(), None);
         }
     }
+
+    #[tokio::test]
+    async fn test_tiered_cache_promotion_keeps_ttl() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let memory = MemoryCache::<String, String>::new(10);
+        let disk = DiskCache::<String, String>::new(temp_dir.path());
+        disk.insert_with_ttl(
+            "expiring".to_string(),
+            "value".to_string(),
+            Some(Duration::from_millis(80)),
+        )
+        .await
+        .unwrap();
+
+        let cache = TieredCache::builder()
+            .add_layer(Box::new(MemoryCacheWrapper(memory)))
+            .add_layer(Box::new(disk))
+            .build();
+
+        let (_, remaining) = cache
+            .get_with_ttl(&"expiring".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(remaining.unwrap() <= Duration::from_millis(80));
+
+        // The promoted copy expires with the original rather than living forever
+        let (_, promoted) = cache.layers[0]
+            .get_with_ttl(&"expiring".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(promoted.unwrap() <= remaining.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(cache.get(&"expiring".to_string()).await.unwrap(), None);
+        assert_eq!(
+            cache.layers[0].get(&"expiring".to_string()).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tiered_cache_scoped_invalidation() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = TieredCache::builder()
+            .add_layer(Box::new(MemoryCacheWrapper(
+                MemoryCache::<String, String>::new(10),
+            )))
+            .add_layer(Box::new(DiskCache::<String, String>::new(temp_dir.path())))
+            .build();
+
+        let tagged = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        for (key, tags) in [
+            (
+                "docs:tokio:1.38.0",
+                tagged(&["crate:tokio", "crate:tokio@1.38.0"]),
+            ),
+            (
+                "docs:tokio:1.37.0",
+                tagged(&["crate:tokio", "crate:tokio@1.37.0"]),
+            ),
+            (
+                "docs:serde:1.0.0",
+                tagged(&["crate:serde", "crate:serde@1.0.0"]),
+            ),
+            ("search:async", tagged(&["tool:search_crate"])),
+        ] {
+            cache
+                .insert_tagged(key.to_string(), "value".to_string(), None, tags)
+                .await
+                .unwrap();
+        }
+
+        let version = CacheScope::Version {
+            crate_name: "tokio".to_string(),
+            version: "1.38".to_string(),
+        };
+        assert_eq!(cache.invalidate(&version).await.unwrap(), 1);
+        assert!(cache
+            .get(&"docs:tokio:1.38.0".to_string())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(cache
+            .get(&"docs:tokio:1.37.0".to_string())
+            .await
+            .unwrap()
+            .is_some());
+
+        // Promoted copies carry no tags but are removed alongside the tagged original
+        cache.layers[0].clear().await.unwrap();
+        assert!(cache
+            .get(&"docs:tokio:1.37.0".to_string())
+            .await
+            .unwrap()
+            .is_some());
+        let crate_scope = CacheScope::Crate("tokio".to_string());
+        assert_eq!(cache.invalidate(&crate_scope).await.unwrap(), 1);
+        for layer in &cache.layers {
+            assert!(layer
+                .get(&"docs:tokio:1.37.0".to_string())
+                .await
+                .unwrap()
+                .is_none());
+        }
+
+        let glob = CacheScope::KeyGlob("search:*".to_string());
+        assert_eq!(cache.invalidate(&glob).await.unwrap(), 1);
+        assert!(cache
+            .get(&"docs:serde:1.0.0".to_string())
+            .await
+            .unwrap()
+            .is_some());
+
+        // The remaining entry is held by both layers but counted once
+        assert_eq!(cache.stats().size, 2);
+        assert_eq!(cache.invalidate(&CacheScope::All).await.unwrap(), 1);
+        assert_eq!(cache.stats().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tiered_cache_metrics_by_layer() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let memory = MemoryCacheWrapper(MemoryCache::<String, String>::new(10));
+        let disk = DiskCache::<String, String>::new(temp_dir.path());
+        disk.insert("on_disk".to_string(), "value".to_string())
+            .await
+            .unwrap();
+
+        let cache = TieredCache::builder()
+            .add_layer(Box::new(memory))
+            .add_layer(Box::new(disk))
+            .build();
+
+        // Disk hit, then a memory hit on the promoted copy, then a miss in both
+        for key in ["on_disk", "on_disk", "absent"] {
+            cache.get(&key.to_string()).await.unwrap();
+        }
+
+        let snapshot = cache.metrics().snapshot();
+        let [memory, disk] = &snapshot.layers[..] else {
+            panic!("expected two layers");
+        };
+        assert_eq!(memory.name, "memory");
+        assert_eq!((memory.outcomes.hits, memory.outcomes.misses), (1, 2));
+        assert_eq!(disk.name, "disk");
+        assert_eq!((disk.outcomes.hits, disk.outcomes.misses), (1, 1));
+        assert_eq!(disk.read_latency.count(), 2);
+        assert_eq!(snapshot.read_latency.count(), 3);
+
+        let layer_stats = cache.layer_stats();
+        assert_eq!(layer_stats[0].0, "memory");
+        assert_eq!(layer_stats[0].1.size, 1);
+    }
 }
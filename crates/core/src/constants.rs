@@ -25,6 +25,14 @@ pub const DEFAULT_SEARCH_TTL: u64 = 300;
 /// Default cache TTL for recent releases (30 minutes)
 pub const DEFAULT_RECENT_RELEASES_TTL: u64 = 1800;
 
+/// Default time an expired response may still be served while it is
+/// refreshed, or when refreshing fails (24 hours)
+pub const DEFAULT_STALE_TTL: u64 = 86400;
+
+/// Version of the cached response models; bump it whenever a cached model
+/// changes shape so disk cache entries written by older releases are discarded
+pub const CACHE_SCHEMA_VERSION: u32 = 1;
+
 /// Maximum crate name length
 pub const MAX_CRATE_NAME_LENGTH: usize = 64;
 
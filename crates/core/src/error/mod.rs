@@ -14,6 +14,7 @@ pub use docs::DocsError;
 pub use network::NetworkError;
 pub use protocol::ProtocolError;
 
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Main error type that encompasses all domain-specific errors
@@ -45,12 +46,23 @@ pub enum Error {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// The failure of one operation whose result was shared by concurrent callers
+    #[error(transparent)]
+    Shared(Arc<Error>),
 }
 
 impl Error {
+    /// Turn an error shared between callers back into an owned one, taking
+    /// the original when no other caller still holds it
+    pub fn shared(error: Arc<Error>) -> Self {
+        Arc::try_unwrap(error).unwrap_or_else(Error::Shared)
+    }
+
     /// Check if this error is recoverable (can retry the operation)
     pub fn is_recoverable(&self) -> bool {
         match self {
+            Error::Shared(e) => e.is_recoverable(),
             Error::Network(e) => e.is_recoverable(),
             Error::Cache(e) => e.is_recoverable(),
             Error::Io(_) => true,
@@ -61,6 +73,7 @@ impl Error {
     /// Check if this error indicates a temporary issue
     pub fn is_temporary(&self) -> bool {
         match self {
+            Error::Shared(e) => e.is_temporary(),
             Error::Network(e) => e.is_temporary(),
             Error::Cache(e) => e.is_temporary(),
             _ => false,
@@ -78,6 +91,7 @@ impl Error {
             Error::Serialization(_) | Error::UrlParse(_) => ErrorCategory::Data,
             Error::Io(_) => ErrorCategory::Io,
             Error::Internal(_) => ErrorCategory::Internal,
+            Error::Shared(e) => e.category(),
         }
     }
 }
@@ -12,21 +12,31 @@ pub use error::{
 
 // Re-export commonly used models for convenience
 pub use models::{
+    diff::{CrateDiffResponse, CrateDiffSummary, ItemChange, ItemChangeKind, ModuleDiff},
     docs::{
-        CodeExample, CrateCategories, CrateDocsRequest, CrateDocsResponse, CrateItem, CrateRelease,
-        CrateSummary, ItemDocsRequest, ItemDocsResponse, ItemKind, RecentReleasesRequest,
-        RecentReleasesResponse, Visibility,
+        AssociatedItem, AssociatedItemKind, CodeExample, CrateCategories, CrateDocsRequest,
+        CrateDocsResponse, CrateItem, CrateRelease, CrateSummary, Deprecation, ItemDocsRequest,
+        ItemDocsResponse, ItemDocsSection, ItemKind, RecentReleasesRequest, RecentReleasesResponse,
+        TraitImpl, Visibility,
     },
     metadata::{
         CacheConfig, CacheStats, ClearCacheResponse, CrateMetadata, CrateMetadataRequest,
         Dependency, DependencyKind, DownloadStats, VersionInfo,
     },
-    search::{CrateSearchResult, SearchRequest, SearchResponse},
+    project::{ProjectAnalysis, ResolvedDependency, UnresolvedDependency, VersionSource},
+    search::{
+        CrateSearchResult, ItemSearchHit, ItemSearchMode, ItemSearchRequest, ItemSearchResponse,
+        SearchRequest, SearchResponse, SignatureMatch, SignatureSearchResponse,
+    },
 };
 
+// Re-export cache tag utilities
+pub use utils::cache_tags::{crate_tag, crate_tags, crate_version_tag, kind_tag, tool_tag};
+
 // Re-export version utilities
 pub use utils::version::{
-    is_latest_version, normalize_version, resolve_version, to_optional_version, DEFAULT_VERSION,
+    is_latest_version, is_semver_compatible, is_version_requirement, normalize_version,
+    parse_version_requirement, resolve_version, to_optional_version, DEFAULT_VERSION,
 };
 
 // Re-export common types
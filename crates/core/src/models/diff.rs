@@ -0,0 +1,137 @@
+use crate::models::docs::{Deprecation, ItemKind};
+use serde::{Deserialize, Serialize};
+
+/// What happened to an item between two releases
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemChangeKind {
+    /// Only present in the newer release
+    Added,
+    /// Only present in the older release
+    Removed,
+    /// Present in both, with a different signature, kind or deprecation
+    Changed,
+}
+
+/// Public API differences between two releases of a crate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CrateDiffResponse {
+    /// Crate name
+    pub crate_name: String,
+    /// Release the comparison starts from
+    pub from_version: String,
+    /// Release the comparison ends at
+    pub to_version: String,
+    /// Whether Cargo treats `to_version` as a compatible upgrade of `from_version`
+    pub semver_compatible: bool,
+    /// Change counts
+    pub summary: CrateDiffSummary,
+    /// Changes grouped by the module declaring the item, crate root first
+    pub modules: Vec<ModuleDiff>,
+}
+
+impl CrateDiffResponse {
+    /// Iterate over every change in every module
+    pub fn changes(&self) -> impl Iterator<Item = &ItemChange> {
+        self.modules.iter().flat_map(|module| module.changes.iter())
+    }
+
+    /// Iterate over the changes likely to break dependent code
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &ItemChange> {
+        self.changes().filter(|change| change.breaking)
+    }
+}
+
+/// Number of changes of each kind
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CrateDiffSummary {
+    /// Items added
+    pub added: usize,
+    /// Items removed
+    pub removed: usize,
+    /// Items whose signature, kind or deprecation changed
+    pub changed: usize,
+    /// Items deprecated in the newer release
+    pub newly_deprecated: usize,
+    /// Changes likely to break dependent code
+    pub breaking: usize,
+}
+
+/// The changes to the items of one module
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModuleDiff {
+    /// Module path from the crate root (`sync::mpsc`), empty for the crate root
+    pub module: String,
+    /// Changes sorted by item path
+    pub changes: Vec<ItemChange>,
+}
+
+/// A single added, removed or changed item
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemChange {
+    /// Path from the crate root (`sync::mpsc::channel`)
+    pub path: String,
+    /// Item name
+    pub name: String,
+    /// Item kind in the newer release, or the older one for removed items
+    pub kind: ItemKind,
+    /// What happened to the item
+    pub change: ItemChangeKind,
+    /// Declaration in the older release
+    pub old_signature: Option<String>,
+    /// Declaration in the newer release
+    pub new_signature: Option<String>,
+    /// Deprecation notice in the newer release
+    pub deprecation: Option<Deprecation>,
+    /// Human-readable description of each difference
+    pub details: Vec<String>,
+    /// Whether the change is likely to break code using the older release
+    pub breaking: bool,
+    /// Documentation URL fragment in the newer release, or the older one for removed items
+    pub docs_path: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crate_diff_serialization() {
+        let removed = ItemChange {
+            path: "blocking::get".to_string(),
+            name: "get".to_string(),
+            kind: ItemKind::Function,
+            change: ItemChangeKind::Removed,
+            old_signature: Some("pub fn get<T: IntoUrl>(url: T) -> Result<Response>".to_string()),
+            new_signature: None,
+            deprecation: None,
+            details: vec!["removed".to_string()],
+            breaking: true,
+            docs_path: Some("blocking/fn.get.html".to_string()),
+        };
+        let diff = CrateDiffResponse {
+            crate_name: "reqwest".to_string(),
+            from_version: "0.11.27".to_string(),
+            to_version: "0.12.0".to_string(),
+            semver_compatible: false,
+            summary: CrateDiffSummary {
+                removed: 1,
+                breaking: 1,
+                ..CrateDiffSummary::default()
+            },
+            modules: vec![ModuleDiff {
+                module: "blocking".to_string(),
+                changes: vec![removed],
+            }],
+        };
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["modules"][0]["changes"][0]["change"], "removed");
+        assert_eq!(json["summary"]["breaking"], 1);
+
+        let roundtrip: CrateDiffResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, diff);
+        assert_eq!(roundtrip.changes().count(), 1);
+        assert_eq!(roundtrip.breaking_changes().count(), 1);
+    }
+}
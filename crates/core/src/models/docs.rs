@@ -2,6 +2,7 @@ use crate::{
     constants::*,
     traits::*,
     types::{CrateName, ItemPath, Version},
+    utils::cache_tags::{crate_tag, crate_tags, kind_tag},
     Result,
 };
 use serde::{Deserialize, Serialize};
@@ -90,6 +91,12 @@ impl Cacheable for CrateDocsResponse {
     fn ttl_seconds(&self) -> u64 {
         DEFAULT_CRATE_DOCS_TTL
     }
+
+    fn cache_tags(&self) -> Vec<String> {
+        let mut tags = crate_tags(&self.name, Some(&self.version));
+        tags.push(kind_tag("docs"));
+        tags
+    }
 }
 
 /// Key information and metrics about the crate
@@ -147,6 +154,18 @@ pub struct CrateItem {
     pub signature: Option<String>,
     /// Documentation URL fragment
     pub docs_path: Option<String>,
+    /// Deprecation notice, if the item is deprecated
+    #[serde(default)]
+    pub deprecation: Option<Deprecation>,
+}
+
+/// A `#[deprecated]` notice
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Version the item was deprecated in
+    pub since: Option<String>,
+    /// Explanation, usually naming the replacement
+    pub note: Option<String>,
 }
 
 /// Type of documentation item
@@ -262,6 +281,129 @@ pub struct ItemDocsResponse {
     pub docs_url: Option<Url>,
     /// Related items (implementations, traits, etc.)
     pub related_items: Vec<String>,
+    /// Inherent methods, associated constants and associated types
+    #[serde(default)]
+    pub associated_items: Vec<AssociatedItem>,
+    /// Trait implementations of a struct, enum or union
+    #[serde(default)]
+    pub trait_impls: Vec<TraitImpl>,
+    /// Items an implementor must define (traits only)
+    #[serde(default)]
+    pub required_items: Vec<AssociatedItem>,
+    /// Items with a default definition (traits only)
+    #[serde(default)]
+    pub provided_items: Vec<AssociatedItem>,
+}
+
+impl ItemDocsResponse {
+    /// Clear every section not listed in `sections`.
+    ///
+    /// The identifying fields and the signature are always kept.
+    pub fn retain_sections(&mut self, sections: &[ItemDocsSection]) {
+        let keep = |section| sections.contains(&section);
+
+        if !keep(ItemDocsSection::Description) {
+            self.description = None;
+        }
+        if !keep(ItemDocsSection::Examples) {
+            self.examples.clear();
+        }
+        if !keep(ItemDocsSection::Methods) {
+            self.associated_items.clear();
+        }
+        if !keep(ItemDocsSection::TraitImpls) {
+            self.trait_impls.clear();
+        }
+        if !keep(ItemDocsSection::TraitItems) {
+            self.required_items.clear();
+            self.provided_items.clear();
+        }
+        if !keep(ItemDocsSection::RelatedItems) {
+            self.related_items.clear();
+        }
+    }
+}
+
+/// Optional sections of an item documentation response
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemDocsSection {
+    Description,
+    Examples,
+    /// Inherent methods and associated items
+    Methods,
+    TraitImpls,
+    /// Required and provided trait items
+    TraitItems,
+    RelatedItems,
+}
+
+impl ItemDocsSection {
+    /// Every section, in response order
+    pub const ALL: [ItemDocsSection; 6] = [
+        ItemDocsSection::Description,
+        ItemDocsSection::Examples,
+        ItemDocsSection::Methods,
+        ItemDocsSection::TraitImpls,
+        ItemDocsSection::TraitItems,
+        ItemDocsSection::RelatedItems,
+    ];
+}
+
+/// A method, associated constant or associated type of a type or trait
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AssociatedItem {
+    /// Item name
+    pub name: String,
+    /// Kind of associated item
+    pub kind: AssociatedItemKind,
+    /// Declaration, e.g. `pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<TcpStream>`
+    pub signature: Option<String>,
+    /// First paragraph of the item's documentation
+    pub summary: Option<String>,
+    /// Anchor on the parent item's page, e.g. `method.connect`
+    pub anchor: String,
+    /// Whether the item is an async method
+    pub is_async: bool,
+}
+
+/// Kind of an associated item
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AssociatedItemKind {
+    Method,
+    Constant,
+    Type,
+}
+
+impl AssociatedItemKind {
+    /// Prefix rustdoc uses for anchors of this kind (`method`, `associatedconstant`, `associatedtype`)
+    pub fn anchor_prefix(&self) -> &'static str {
+        match self {
+            AssociatedItemKind::Method => "method",
+            AssociatedItemKind::Constant => "associatedconstant",
+            AssociatedItemKind::Type => "associatedtype",
+        }
+    }
+
+    /// Anchor of an item of this kind on its parent's page
+    pub fn anchor(&self, name: &str) -> String {
+        format!("{}.{name}", self.anchor_prefix())
+    }
+}
+
+/// A trait implementation block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TraitImpl {
+    /// Implemented trait, e.g. `AsyncRead` or `From<std::net::TcpStream>`
+    pub trait_name: String,
+    /// Full impl header, e.g. `impl<T: Clone> Clone for Wrapper<T>`
+    pub header: String,
+    /// Generic bounds and where predicates of the impl
+    pub bounds: Vec<String>,
+    /// Whether this is a blanket impl (`impl<T: Trait> Other for T`)
+    pub is_blanket: bool,
+    /// Whether this is a compiler-derived auto trait impl (`Send`, `Sync`, ...)
+    pub is_auto: bool,
 }
 
 impl Response for ItemDocsResponse {
@@ -278,6 +420,10 @@ impl Cacheable for ItemDocsResponse {
     fn ttl_seconds(&self) -> u64 {
         DEFAULT_ITEM_DOCS_TTL
     }
+
+    fn cache_tags(&self) -> Vec<String> {
+        vec![crate_tag(&self.crate_name), kind_tag("docs")]
+    }
 }
 
 /// Recent releases information
@@ -348,6 +494,10 @@ impl Cacheable for RecentReleasesResponse {
     fn ttl_seconds(&self) -> u64 {
         DEFAULT_RECENT_RELEASES_TTL
     }
+
+    fn cache_tags(&self) -> Vec<String> {
+        vec![kind_tag("releases")]
+    }
 }
 
 /// Information about a recent crate release
@@ -517,6 +667,10 @@ mod tests {
             is_async: false,
             signature: Some("pub fn spawn<T>(future: T) -> JoinHandle<T::Output>".to_string()),
             docs_path: Some("fn.spawn.html".to_string()),
+            deprecation: Some(Deprecation {
+                since: Some("1.36.0".to_string()),
+                note: Some("use `tokio::task::spawn`".to_string()),
+            }),
         };
 
         let json = serde_json::to_string(&item).unwrap();
@@ -572,4 +726,46 @@ mod tests {
         let deserialized: CrateDocsResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(response, deserialized);
     }
+
+    #[test]
+    fn test_item_docs_response_sections() {
+        // Responses cached before the associated item fields existed still load
+        let mut response: ItemDocsResponse = serde_json::from_value(serde_json::json!({
+            "crate_name": "demo",
+            "item_path": "trait.Handler.html",
+            "name": "Handler",
+            "kind": "Trait",
+            "signature": "pub trait Handler {}",
+            "description": "Handles things.",
+            "examples": [],
+            "docs_url": null,
+            "related_items": ["impl Handler for Config"]
+        }))
+        .unwrap();
+        assert!(response.required_items.is_empty());
+
+        response.required_items.push(AssociatedItem {
+            name: "handle".to_string(),
+            kind: AssociatedItemKind::Method,
+            signature: Some("fn handle(&self)".to_string()),
+            summary: None,
+            anchor: AssociatedItemKind::Method.anchor("handle"),
+            is_async: false,
+        });
+        assert_eq!(response.required_items[0].anchor, "method.handle");
+
+        let mut trimmed = response.clone();
+        trimmed.retain_sections(&[ItemDocsSection::TraitItems]);
+        assert_eq!(trimmed.signature, response.signature);
+        assert_eq!(trimmed.description, None);
+        assert!(trimmed.related_items.is_empty());
+        assert_eq!(trimmed.required_items.len(), 1);
+
+        response.retain_sections(&ItemDocsSection::ALL);
+        assert!(response.description.is_some());
+        assert_eq!(
+            serde_json::to_value(ItemDocsSection::TraitImpls).unwrap(),
+            serde_json::json!("trait_impls")
+        );
+    }
 }
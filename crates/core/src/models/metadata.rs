@@ -2,6 +2,7 @@ use crate::{
     constants::*,
     traits::*,
     types::{CrateName, Version},
+    utils::cache_tags::{crate_tags, kind_tag},
     Result,
 };
 use chrono::{DateTime, Utc};
@@ -116,6 +117,12 @@ impl Cacheable for CrateMetadata {
     fn ttl_seconds(&self) -> u64 {
         DEFAULT_METADATA_TTL
     }
+
+    fn cache_tags(&self) -> Vec<String> {
+        let mut tags = crate_tags(&self.name, Some(&self.version));
+        tags.push(kind_tag("metadata"));
+        tags
+    }
 }
 
 /// Download statistics for a crate
@@ -1,3 +1,5 @@
+pub mod diff;
 pub mod docs;
 pub mod metadata;
+pub mod project;
 pub mod search;
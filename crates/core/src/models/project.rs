@@ -0,0 +1,113 @@
+use crate::models::metadata::DependencyKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Where the version of a project dependency came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    /// The exact version recorded in `Cargo.lock`
+    Lockfile,
+    /// The highest published version matching the manifest requirement
+    Registry,
+}
+
+/// The crate versions a Cargo project builds against
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectAnalysis {
+    /// Manifest the analysis started from, if one was found
+    pub manifest_path: Option<String>,
+    /// Lockfile the versions were read from, if one was found
+    pub lockfile_path: Option<String>,
+    /// Root package name, `None` for virtual workspace manifests
+    pub package: Option<String>,
+    /// Direct dependencies of the project
+    pub dependencies: Vec<ResolvedDependency>,
+    /// Direct dependencies no version could be found for
+    pub unresolved: Vec<UnresolvedDependency>,
+    /// Version to use for every registry crate in the build, direct or not
+    pub versions: BTreeMap<String, String>,
+}
+
+impl ProjectAnalysis {
+    /// Version the project uses for a crate
+    pub fn version_of(&self, crate_name: &str) -> Option<&str> {
+        self.versions.get(crate_name).map(String::as_str)
+    }
+}
+
+/// A direct dependency with the exact version in use
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    /// Crate name as published (after `package = "..."` renames)
+    pub name: String,
+    /// Version requirement from the manifest, unknown when only a lockfile was given
+    pub requirement: Option<String>,
+    /// Exact version in use
+    pub version: String,
+    /// Where `version` came from
+    pub source: VersionSource,
+    /// Dependency kind, unknown when only a lockfile was given
+    pub kind: Option<DependencyKind>,
+    /// Whether the dependency is optional
+    pub optional: bool,
+    /// Crate description from the registry
+    pub description: Option<String>,
+    /// Minimum supported Rust version of the version in use
+    pub rust_version: Option<String>,
+    /// Newest stable version published, when newer than the one in use
+    pub latest_version: Option<String>,
+    /// Whether the version in use has been yanked
+    pub yanked: bool,
+}
+
+/// A direct dependency whose version could not be determined
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnresolvedDependency {
+    /// Crate name
+    pub name: String,
+    /// Version requirement from the manifest
+    pub requirement: Option<String>,
+    /// Why it could not be resolved
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_analysis_serialization() {
+        let analysis = ProjectAnalysis {
+            manifest_path: Some("/work/demo/Cargo.toml".to_string()),
+            lockfile_path: Some("/work/demo/Cargo.lock".to_string()),
+            package: Some("demo".to_string()),
+            dependencies: vec![ResolvedDependency {
+                name: "serde".to_string(),
+                requirement: Some("1.0".to_string()),
+                version: "1.0.190".to_string(),
+                source: VersionSource::Lockfile,
+                kind: Some(DependencyKind::Normal),
+                optional: false,
+                description: None,
+                rust_version: None,
+                latest_version: Some("1.0.210".to_string()),
+                yanked: false,
+            }],
+            unresolved: vec![],
+            versions: BTreeMap::from([
+                ("serde".to_string(), "1.0.190".to_string()),
+                ("serde_derive".to_string(), "1.0.190".to_string()),
+            ]),
+        };
+
+        let json = serde_json::to_value(&analysis).unwrap();
+        assert_eq!(json["dependencies"][0]["source"], "lockfile");
+        assert_eq!(json["versions"]["serde_derive"], "1.0.190");
+
+        let roundtrip: ProjectAnalysis = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, analysis);
+        assert_eq!(roundtrip.version_of("serde"), Some("1.0.190"));
+        assert_eq!(roundtrip.version_of("tokio"), None);
+    }
+}
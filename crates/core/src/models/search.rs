@@ -1,4 +1,11 @@
-use crate::{constants::*, traits::*, Result};
+use crate::{
+    constants::*,
+    models::docs::ItemKind,
+    traits::*,
+    types::{CrateName, Version},
+    utils::cache_tags::kind_tag,
+    Result,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -95,6 +102,10 @@ impl Cacheable for SearchResponse {
     fn ttl_seconds(&self) -> u64 {
         DEFAULT_SEARCH_TTL
     }
+
+    fn cache_tags(&self) -> Vec<String> {
+        vec![kind_tag("search")]
+    }
 }
 
 /// Individual crate search result with metadata
@@ -122,6 +133,175 @@ pub struct CrateSearchResult {
     pub categories: Vec<String>,
 }
 
+/// Request for searching the items of a single crate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemSearchRequest {
+    /// Crate whose items are searched
+    pub crate_name: CrateName,
+    /// Item name, words from the documentation, or a type signature such as
+    /// `fn(&str) -> Result<_, _>`
+    pub query: String,
+    /// Optional version (defaults to latest)
+    pub version: Option<Version>,
+    /// Only return items of this kind
+    pub kind: Option<ItemKind>,
+    /// Maximum number of results to return (default: 10, max: 50)
+    pub limit: Option<usize>,
+}
+
+impl ItemSearchRequest {
+    pub fn new(crate_name: CrateName, query: impl Into<String>) -> Self {
+        Self {
+            crate_name,
+            query: query.into(),
+            version: None,
+            kind: None,
+            limit: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn with_kind(mut self, kind: ItemKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .min(MAX_SEARCH_LIMIT)
+    }
+}
+
+impl Request for ItemSearchRequest {
+    type Response = ItemSearchResponse;
+
+    fn validate(&self) -> Result<()> {
+        if self.query.trim().is_empty() {
+            return Err(crate::ErrorBuilder::protocol()
+                .invalid_input("search_items", "Query cannot be empty"));
+        }
+        Ok(())
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        Some(format!(
+            "search_items:{}:{}:{}:{:?}:{}",
+            self.crate_name.as_str(),
+            self.version
+                .as_ref()
+                .map(|v| v.as_str())
+                .unwrap_or("latest"),
+            self.query,
+            self.kind,
+            self.limit()
+        ))
+    }
+}
+
+impl PaginatedRequest for ItemSearchRequest {
+    fn limit(&self) -> usize {
+        self.limit()
+    }
+}
+
+/// How an item search query was interpreted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemSearchMode {
+    /// Matched against item names, paths and documentation
+    Text,
+    /// Matched against function signatures
+    Signature,
+}
+
+/// Ranked items of a crate matching a query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemSearchResponse {
+    /// Crate name
+    pub crate_name: String,
+    /// Version of the searched documentation
+    pub version: String,
+    /// Query as given
+    pub query: String,
+    /// How the query was interpreted
+    pub mode: ItemSearchMode,
+    /// Best matches first
+    pub results: Vec<ItemSearchHit>,
+    /// Number of matching items before the limit was applied
+    pub total: usize,
+}
+
+impl Response for ItemSearchResponse {
+    fn cache_ttl(&self) -> Option<u64> {
+        Some(DEFAULT_CRATE_DOCS_TTL)
+    }
+}
+
+/// A single item search result
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemSearchHit {
+    /// Item name
+    pub name: String,
+    /// Path from the crate root (`sync::Mutex`)
+    pub path: String,
+    /// Item kind
+    pub kind: ItemKind,
+    /// Brief description
+    pub summary: Option<String>,
+    /// Declaration, when known
+    pub signature: Option<String>,
+    /// Documentation page relative to the crate root, usable with `get_item_docs`
+    pub docs_path: String,
+    /// Relevance score, higher is better
+    pub score: u32,
+}
+
+/// Functions and methods matching a type-signature query, across one crate
+/// or a dependency tree
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignatureSearchResponse {
+    /// Parameter type patterns as given
+    pub inputs: Vec<String>,
+    /// Return type pattern as given
+    pub output: Option<String>,
+    /// Crates searched, as `name@version`
+    pub crates_searched: Vec<String>,
+    /// Crates whose documentation could not be loaded
+    pub crates_skipped: Vec<String>,
+    /// Best matches first
+    pub results: Vec<SignatureMatch>,
+    /// Number of matching items before the limit was applied
+    pub total: usize,
+}
+
+impl Response for SignatureSearchResponse {
+    fn cache_ttl(&self) -> Option<u64> {
+        Some(DEFAULT_CRATE_DOCS_TTL)
+    }
+}
+
+/// A function or method matching a signature query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignatureMatch {
+    /// Crate declaring the item
+    pub crate_name: String,
+    /// Version of the searched documentation
+    pub version: String,
+    /// The matching item
+    #[serde(flatten)]
+    pub item: ItemSearchHit,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +330,23 @@ mod tests {
         assert_eq!(req.limit(), MAX_SEARCH_LIMIT); // Should be clamped to max
     }
 
+    #[test]
+    fn test_item_search_request() {
+        let crate_name = CrateName::new("tokio").unwrap();
+        let req = ItemSearchRequest::new(crate_name, "spawn")
+            .with_kind(ItemKind::Function)
+            .with_limit(500);
+        assert_eq!(req.limit(), MAX_SEARCH_LIMIT);
+        assert!(req.validate().is_ok());
+        assert_eq!(
+            Request::cache_key(&req).unwrap(),
+            "search_items:tokio:latest:spawn:Some(Function):50"
+        );
+
+        let empty = ItemSearchRequest::new(CrateName::new("tokio").unwrap(), "  ");
+        assert!(empty.validate().is_err());
+    }
+
     #[test]
     fn test_search_request_serialization() {
         let req = SearchRequest::with_limit("tokio", 20);
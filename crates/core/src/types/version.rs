@@ -1,3 +1,5 @@
+use crate::utils::version::parse_version_requirement;
+use semver::VersionReq;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -21,12 +23,24 @@ impl Version {
         &self.0
     }
 
+    /// The semver requirement this version stands for, if it is one
+    /// (`^1.2`, `~0.11`, `>=1, <2`, `1.*`) rather than a concrete version
+    pub fn requirement(&self) -> Option<VersionReq> {
+        parse_version_requirement(&self.0)
+    }
+
+    /// Whether this is a semver requirement that still has to be resolved
+    /// to a concrete version
+    pub fn is_requirement(&self) -> bool {
+        self.requirement().is_some()
+    }
+
     fn validate(version: &str) -> Result<(), VersionError> {
         if version.is_empty() {
             return Err(VersionError::Empty);
         }
 
-        if version == "latest" {
+        if version == "latest" || parse_version_requirement(version).is_some() {
             return Ok(());
         }
 
@@ -107,6 +121,18 @@ mod tests {
         assert!(Version::new("0.1.0").is_ok());
     }
 
+    #[test]
+    fn test_version_requirements() {
+        for requirement in ["^1.2", "~0.11", ">=1, <2", "1.*", "1.2"] {
+            let version = Version::new(requirement).unwrap();
+            assert!(version.is_requirement(), "{requirement}");
+        }
+
+        assert!(!Version::new("1.2.3").unwrap().is_requirement());
+        assert!(!Version::latest().is_requirement());
+        assert!(Version::new(">=").is_err());
+    }
+
     #[test]
     fn test_invalid_versions() {
         assert!(Version::new("").is_err());
@@ -0,0 +1,72 @@
+//! Cache tag construction, shared by everything that tags cache entries and
+//! everything that invalidates them by tag.
+//!
+//! Crate names are normalized the way crates.io compares them: ASCII
+//! lowercase with `_` treated as `-`.
+
+/// Prefix of tags naming a crate
+pub const CRATE_TAG_PREFIX: &str = "crate:";
+
+/// Prefix of tags naming the tool that produced an entry
+pub const TOOL_TAG_PREFIX: &str = "tool:";
+
+/// Prefix of tags naming the kind of data in an entry
+pub const KIND_TAG_PREFIX: &str = "kind:";
+
+/// Normalize a crate name for use in a tag
+pub fn normalize_crate_name(crate_name: &str) -> String {
+    crate_name.trim().to_ascii_lowercase().replace('_', "-")
+}
+
+/// Tag for every entry about a crate, e.g. `crate:tokio`
+pub fn crate_tag(crate_name: &str) -> String {
+    format!("{CRATE_TAG_PREFIX}{}", normalize_crate_name(crate_name))
+}
+
+/// Tag for entries about one version of a crate, e.g. `crate:tokio@1.38`
+pub fn crate_version_tag(crate_name: &str, version: &str) -> String {
+    format!("{}@{}", crate_tag(crate_name), version.trim())
+}
+
+/// Tag for entries cached by a tool, e.g. `tool:search_crate`
+pub fn tool_tag(tool_name: &str) -> String {
+    format!("{TOOL_TAG_PREFIX}{tool_name}")
+}
+
+/// Tag for entries holding one kind of data, e.g. `kind:metadata`
+pub fn kind_tag(kind: &str) -> String {
+    format!("{KIND_TAG_PREFIX}{kind}")
+}
+
+/// The crate and version tags for an entry about `crate_name`, at `version`
+/// when one is known
+pub fn crate_tags(crate_name: &str, version: Option<&str>) -> Vec<String> {
+    let mut tags = vec![crate_tag(crate_name)];
+    if let Some(version) = version {
+        tags.push(crate_version_tag(crate_name, version));
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_formats() {
+        assert_eq!(crate_tag("tokio"), "crate:tokio");
+        assert_eq!(crate_version_tag("tokio", "1.38"), "crate:tokio@1.38");
+        assert_eq!(tool_tag("search_crate"), "tool:search_crate");
+        assert_eq!(kind_tag("metadata"), "kind:metadata");
+    }
+
+    #[test]
+    fn test_crate_names_are_normalized() {
+        assert_eq!(crate_tag("Serde_JSON"), crate_tag("serde-json"));
+        assert_eq!(
+            crate_tags("serde_json", Some("1.0")),
+            vec!["crate:serde-json", "crate:serde-json@1.0"]
+        );
+        assert_eq!(crate_tags("serde", None), vec!["crate:serde"]);
+    }
+}
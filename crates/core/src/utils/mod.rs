@@ -1 +1,2 @@
+pub mod cache_tags;
 pub mod version;
@@ -1,3 +1,5 @@
+use semver::{Version as SemVersion, VersionReq};
+
 /// Version handling utilities for consistent version resolution across all endpoints.
 /// Default version to use when no version is specified
 pub const DEFAULT_VERSION: &str = "latest";
@@ -43,6 +45,36 @@ pub fn to_optional_version(version: &str) -> Option<String> {
     }
 }
 
+/// Parse a version string that is a semver requirement rather than a
+/// concrete version
+///
+/// Returns `Some` for requirements such as `^1.2`, `~0.11`, `>=1, <2`, `1.*`
+/// or a bare `1.2`, and `None` for `latest`, exact versions like `1.2.3` and
+/// strings that are not requirements at all.
+pub fn parse_version_requirement(version: &str) -> Option<VersionReq> {
+    let version = version.trim();
+    if is_latest_version(version) || SemVersion::parse(version).is_ok() {
+        return None;
+    }
+    VersionReq::parse(version).ok()
+}
+
+/// Check if a version string is a semver requirement rather than a concrete version
+pub fn is_version_requirement(version: &str) -> bool {
+    parse_version_requirement(version).is_some()
+}
+
+/// Check if Cargo would accept `to` for a dependency on `from`
+///
+/// True when both are exact versions and `to` matches the caret requirement
+/// `^from`: `1.2.0` to `1.4.1` is compatible, `0.11.27` to `0.12.0` is not.
+pub fn is_semver_compatible(from: &str, to: &str) -> bool {
+    let (Ok(from), Ok(to)) = (SemVersion::parse(from.trim()), SemVersion::parse(to.trim())) else {
+        return false;
+    };
+    VersionReq::parse(&format!("^{from}")).is_ok_and(|req| req.matches(&to))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +134,28 @@ mod tests {
         assert_eq!(to_optional_version("0.2.1"), Some("0.2.1".to_string()));
     }
 
+    #[test]
+    fn test_version_requirements() {
+        for requirement in ["^1.2", "~0.11", ">=1, <2", "1.*", "1.2", "1", "=1.2.3", "*"] {
+            assert!(
+                is_version_requirement(requirement),
+                "{requirement} should be a requirement"
+            );
+        }
+
+        // Concrete versions and latest are not requirements
+        for version in ["1.2.3", "2.0.0-beta.1", "latest", "", "abc"] {
+            assert!(
+                !is_version_requirement(version),
+                "{version} should not be a requirement"
+            );
+        }
+
+        let requirement = parse_version_requirement(" >=1, <2 ").unwrap();
+        assert!(requirement.matches(&SemVersion::new(1, 9, 0)));
+        assert!(!requirement.matches(&SemVersion::new(2, 0, 0)));
+    }
+
     #[test]
     fn test_round_trip_conversion() {
         // Test that resolve_version and to_optional_version are inverses
@@ -125,4 +179,14 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_is_semver_compatible() {
+        assert!(is_semver_compatible("1.2.0", "1.4.1"));
+        assert!(is_semver_compatible("0.11.2", "0.11.27"));
+        assert!(!is_semver_compatible("0.11.27", "0.12.0"));
+        assert!(!is_semver_compatible("1.4.1", "1.2.0"));
+        assert!(!is_semver_compatible("1.9.0", "2.0.0"));
+        assert!(!is_semver_compatible("latest", "1.0.0"));
+    }
 }
@@ -1,6 +1,16 @@
-use reqwest::{header, Client, ClientBuilder, Response};
+use crate::{
+    error_handling::{handle_cache_error, handle_http_response, retry_after_secs},
+    fixtures::{FixtureConfig, FixtureMode, FixtureStore},
+    local_source::{DocsSourceMode, LocalDocsSource},
+    project::VersionPins,
+    rate_limit::{RateLimitConfig, RateLimitStats, RateLimiter, RequestPriority},
+    registry::{Registry, RegistrySet},
+    retry::{CircuitBreakerConfig, CircuitBreakerStats, HostRetryPolicies, RetryConfig},
+    revalidation::{build_response, ResponseStore, StoredResponse},
+};
+use reqwest::{header, Client, ClientBuilder, RequestBuilder, Response};
 use rustacean_docs_core::{ErrorBuilder, Result};
-use std::time::Duration;
+use std::{future::Future, sync::Arc, time::Duration};
 use tracing::{debug, trace, warn};
 
 /// Configuration for the HTTP client
@@ -20,6 +30,18 @@ pub struct ClientConfig {
     pub pool_idle_timeout: Duration,
     /// Maximum idle connections per host
     pub pool_max_idle_per_host: usize,
+    /// Retry behavior for failed requests
+    pub retry: RetryConfig,
+    /// Circuit breaker settings, applied to each upstream host separately
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Request rate limits by upstream host
+    pub rate_limits: RateLimitConfig,
+    /// Allow plain HTTP upstreams, e.g. a local mock server; HTTPS is
+    /// required otherwise
+    pub allow_http: bool,
+    /// Record or replay upstream responses; when unset, the
+    /// `RUSTACEAN_DOCS_FIXTURE_MODE` environment variable decides
+    pub fixtures: Option<FixtureConfig>,
 }
 
 impl Default for ClientConfig {
@@ -32,16 +54,33 @@ impl Default for ClientConfig {
             gzip: true,
             pool_idle_timeout: Duration::from_secs(90),
             pool_max_idle_per_host: 10,
+            retry: RetryConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            rate_limits: RateLimitConfig::default(),
+            allow_http: false,
+            fixtures: None,
         }
     }
 }
 
 /// HTTP client for interacting with docs.rs and related APIs
+///
+/// Requests go to the active [`Registry`], crates.io and docs.rs by default.
+/// Other configured registries can be selected with [`DocsClient::for_registry`].
 #[derive(Debug, Clone)]
 pub struct DocsClient {
     client: Client,
     config: ClientConfig,
-    base_url: String,
+    registry: Registry,
+    registries: Arc<RegistrySet>,
+    local_docs: Option<Arc<LocalDocsSource>>,
+    source_mode: DocsSourceMode,
+    version_pins: VersionPins,
+    retry: Arc<HostRetryPolicies>,
+    rate_limiter: Arc<RateLimiter>,
+    priority: RequestPriority,
+    response_store: Option<ResponseStore>,
+    fixtures: Option<FixtureStore>,
 }
 
 impl DocsClient {
@@ -52,7 +91,11 @@ impl DocsClient {
 
     /// Create a new client with custom configuration
     pub fn with_config(config: ClientConfig) -> Result<Self> {
-        let client = Self::build_client(&config, false)?;
+        let client = Self::build_client(&config, config.allow_http)?;
+        let fixtures = match &config.fixtures {
+            Some(fixtures) => Some(fixtures.clone()),
+            None => FixtureConfig::from_env()?,
+        };
 
         debug!(
             user_agent = %config.user_agent,
@@ -60,13 +103,26 @@ impl DocsClient {
             connect_timeout_secs = config.connect_timeout.as_secs(),
             max_redirects = config.max_redirects,
             gzip = config.gzip,
+            fixture_mode = %fixtures.as_ref().map_or(FixtureMode::Off, |f| f.mode),
             "Created HTTP client with configuration"
         );
 
         Ok(Self {
             client,
+            retry: Arc::new(HostRetryPolicies::new(
+                config.retry.clone(),
+                config.circuit_breaker.clone(),
+            )),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limits.clone())),
+            priority: RequestPriority::default(),
+            response_store: None,
+            fixtures: fixtures.as_ref().and_then(FixtureStore::new),
             config,
-            base_url: "https://docs.rs".to_string(),
+            registry: Registry::crates_io(),
+            registries: Arc::new(RegistrySet::default()),
+            local_docs: None,
+            source_mode: DocsSourceMode::default(),
+            version_pins: VersionPins::default(),
         })
     }
 
@@ -125,12 +181,120 @@ impl DocsClient {
 
     /// Get the base URL being used
     pub fn base_url(&self) -> &str {
-        &self.base_url
+        self.registry.docs_base()
     }
 
     /// Set a custom base URL (useful for testing)
     pub fn with_base_url(mut self, base_url: String) -> Self {
-        self.base_url = base_url;
+        self.registry.docs_url = base_url;
+        self
+    }
+
+    /// Set the registry web API base URL, e.g. a crates.io stand-in
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.registry.api_url = Some(api_url);
+        self
+    }
+
+    /// Set the registry sparse index base URL
+    pub fn with_index_url(mut self, index_url: String) -> Self {
+        self.registry.index_url = Some(index_url);
+        self
+    }
+
+    /// Get the active registry
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Use the given registry for all requests
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Register alternate registries that requests may select by name
+    pub fn with_registries(mut self, registries: RegistrySet) -> Self {
+        self.registries = Arc::new(registries);
+        self
+    }
+
+    /// Get a client targeting the named registry, or this client's registry
+    /// when no name is given
+    pub fn for_registry(&self, name: Option<&str>) -> Result<Self> {
+        let Some(name) = name.filter(|name| *name != self.registry.name) else {
+            return Ok(self.clone());
+        };
+
+        let registry = self.registries.get(name).cloned().ok_or_else(|| {
+            let mut known = self.registries.names();
+            known.insert(0, &self.registry.name);
+            ErrorBuilder::config().invalid_field(
+                "registry",
+                format!(
+                    "Unknown registry '{name}' (configured: {})",
+                    known.join(", ")
+                ),
+            )
+        })?;
+
+        Ok(self.clone().with_registry(registry))
+    }
+
+    /// Answer documentation and metadata requests from local crate sources
+    /// according to the given mode
+    pub fn with_local_docs(mut self, source: Arc<LocalDocsSource>, mode: DocsSourceMode) -> Self {
+        self.local_docs = Some(source);
+        self.source_mode = mode;
+        self
+    }
+
+    /// Get the local documentation source, if configured
+    pub fn local_docs(&self) -> Option<&Arc<LocalDocsSource>> {
+        self.local_docs.as_ref()
+    }
+
+    /// Get the documentation source mode
+    pub fn source_mode(&self) -> DocsSourceMode {
+        self.source_mode
+    }
+
+    /// Share version pins with other clients
+    pub fn with_version_pins(mut self, pins: VersionPins) -> Self {
+        self.version_pins = pins;
+        self
+    }
+
+    /// Crate versions used when a request does not name one. Clients derived
+    /// from this one share the same pins.
+    pub fn version_pins(&self) -> &VersionPins {
+        &self.version_pins
+    }
+
+    /// Get a client whose requests wait for rate limit slots with the given
+    /// priority; it shares this client's limiter
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Get the priority of this client's requests
+    pub fn priority(&self) -> RequestPriority {
+        self.priority
+    }
+
+    /// Keep revalidatable responses in `store` and revalidate them with
+    /// conditional requests instead of downloading them again
+    pub fn with_response_store(mut self, store: ResponseStore) -> Self {
+        self.response_store = Some(store);
+        self
+    }
+
+    /// Record upstream responses to, or replay them from, a fixture
+    /// directory
+    pub fn with_fixtures(mut self, fixtures: FixtureConfig) -> Self {
+        self.fixtures = FixtureStore::new(&fixtures);
+        self.config.fixtures = Some(fixtures);
         self
     }
 
@@ -142,21 +306,174 @@ impl DocsClient {
 
         Ok(Self {
             client,
+            retry: Arc::new(HostRetryPolicies::new(
+                config.retry.clone(),
+                config.circuit_breaker.clone(),
+            )),
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limits.clone())),
+            priority: RequestPriority::default(),
+            response_store: None,
+            fixtures: config.fixtures.as_ref().and_then(FixtureStore::new),
             config,
-            base_url: "https://docs.rs".to_string(),
+            registry: Registry::crates_io(),
+            registries: Arc::new(RegistrySet::default()),
+            local_docs: None,
+            source_mode: DocsSourceMode::default(),
+            version_pins: VersionPins::default(),
         })
     }
 
+    /// Start a GET request to an absolute URL on the active registry,
+    /// attaching its token when one is configured
+    pub fn registry_request(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+        match &self.registry.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a GET request to `url` through the rate limiter, retry policy
+    /// and circuit breaker of its host, turning unsuccessful responses into
+    /// errors with `check`. With a response store, a stored copy of the
+    /// response is revalidated instead of downloaded again.
+    ///
+    /// A revalidated response saves the download but not the parsing: the
+    /// stored body is handed back as a new response, which callers parse
+    /// again just like a fresh one.
+    ///
+    /// When replaying fixtures the recorded response is checked instead and
+    /// nothing is sent; when recording, every response is written out and
+    /// stored copies are ignored so that fixtures hold complete responses.
+    async fn send<F, Fut>(&self, url: &str, check: F) -> Result<Response>
+    where
+        F: Fn(Response) -> Fut,
+        Fut: Future<Output = Result<Response>>,
+    {
+        let recorder = match &self.fixtures {
+            Some(fixtures) if fixtures.mode() == FixtureMode::Replay => {
+                return check(fixtures.replay(url).await?).await;
+            }
+            fixtures => fixtures.as_ref(),
+        };
+
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let stored = match recorder {
+            Some(_) => None,
+            None => self.stored_response(url).await,
+        };
+
+        let response = self
+            .retry
+            .policy_for(&host)
+            .execute_throttled_request(
+                || self.rate_limiter.acquire(&host, self.priority),
+                || async {
+                    let mut request = self.registry_request(url);
+                    if let Some(stored) = &stored {
+                        request = stored.conditional(request);
+                    }
+                    let mut response = request.send().await.map_err(|e| {
+                        rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
+                    })?;
+                    if let Some(recorder) = recorder {
+                        response = recorder.record(url, response).await?;
+                    }
+                    if stored.is_some() && response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                        return Ok(None);
+                    }
+                    check(response).await.map(Some)
+                },
+            )
+            .await?;
+
+        match (response, stored) {
+            (Some(response), _) => self.store_response(url, response).await,
+            (None, Some(stored)) => {
+                debug!(url = %url, "Stored response is still current");
+                // Inserting again restarts the entry's TTL
+                self.insert_stored_response(url, stored.clone()).await;
+                stored.to_response(url)
+            }
+            (None, None) => unreachable!("304 responses are only accepted for stored URLs"),
+        }
+    }
+
+    /// The stored copy of the response to `url`, if any
+    async fn stored_response(&self, url: &str) -> Option<StoredResponse> {
+        let store = self.response_store.as_ref()?;
+        store
+            .get(url)
+            .await
+            .inspect_err(|e| handle_cache_error(e, "read stored response"))
+            .ok()
+            .flatten()
+    }
+
+    /// Keep a copy of a successful response if it can be revalidated,
+    /// returning an equivalent response
+    async fn store_response(&self, url: &str, response: Response) -> Result<Response> {
+        if self.response_store.is_none() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let (body, stored) = StoredResponse::read(response).await?;
+        if let Some(stored) = stored {
+            self.insert_stored_response(url, stored).await;
+        }
+        build_response(url, status, headers, body)
+    }
+
+    async fn insert_stored_response(&self, url: &str, stored: StoredResponse) {
+        if let Some(store) = &self.response_store {
+            if let Err(e) = store.insert(url, stored).await {
+                handle_cache_error(e, "store response");
+            }
+        }
+    }
+
+    /// Perform a GET request to an absolute URL on the active registry
+    pub async fn get_url(&self, url: &str, context: &str) -> Result<Response> {
+        trace!(url = %url, "Making registry request");
+
+        self.send(url, |response| handle_http_response(response, context))
+            .await
+    }
+
+    /// Perform a GET request to a path on the registry's web API
+    pub async fn get_api(&self, path: &str, context: &str) -> Result<Response> {
+        let url = self.registry.api_endpoint(path).ok_or_else(|| {
+            ErrorBuilder::protocol().not_supported(format!(
+                "{context}: registry '{}' has no web API configured",
+                self.registry.name
+            ))
+        })?;
+
+        trace!(url = %url, "Making registry API request");
+
+        self.send(&url, |response| handle_http_response(response, context))
+            .await
+    }
+
     /// Perform a GET request to the specified path
     pub async fn get(&self, path: &str) -> Result<Response> {
-        let url = format!("{}{}", self.base_url, path);
+        let url = format!("{}{}", self.base_url(), path);
 
         trace!(url = %url, "Making GET request");
 
-        let response = self.client.get(&url).send().await.map_err(|e| {
-            rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
-        })?;
+        self.send(&url, |response| async {
+            Self::check_status(&url, response)
+        })
+        .await
+    }
 
+    /// Turn an unsuccessful docs response into an error
+    fn check_status(url: &str, response: Response) -> Result<Response> {
         let status = response.status();
 
         if status.is_success() {
@@ -166,6 +483,13 @@ impl DocsClient {
                 "GET request successful"
             );
             Ok(response)
+        } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            warn!(
+                url = %url,
+                status = %status,
+                "GET request was rate limited"
+            );
+            Err(ErrorBuilder::network().rate_limit(retry_after_secs(&response)))
         } else if status.is_client_error() {
             warn!(
                 url = %url,
@@ -211,6 +535,22 @@ impl DocsClient {
         Ok(text)
     }
 
+    /// Perform a GET request and return the raw response body
+    pub async fn get_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        let response = self.get(path).await?;
+        let bytes = response.bytes().await.map_err(|e| {
+            rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
+        })?;
+
+        trace!(
+            path = %path,
+            byte_length = bytes.len(),
+            "Retrieved binary response"
+        );
+
+        Ok(bytes.to_vec())
+    }
+
     /// Perform a GET request and return the response as JSON
     pub async fn get_json<T>(&self, path: &str) -> Result<T>
     where
@@ -235,6 +575,17 @@ impl DocsClient {
         &self.client
     }
 
+    /// Circuit breaker statistics of every upstream host contacted so far
+    pub fn circuit_breaker_stats(&self) -> Vec<(String, CircuitBreakerStats)> {
+        self.retry.circuit_breaker_stats()
+    }
+
+    /// Rate limiter queue and wait statistics of every upstream host
+    /// contacted so far
+    pub fn rate_limit_stats(&self) -> Vec<RateLimitStats> {
+        self.rate_limiter.stats()
+    }
+
     /// Check if the client can connect to the base URL
     pub async fn health_check(&self) -> Result<bool> {
         trace!("Performing health check");
@@ -285,6 +636,11 @@ mod tests {
             gzip: false,
             pool_idle_timeout: Duration::from_secs(120),
             pool_max_idle_per_host: 5,
+            retry: RetryConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            rate_limits: RateLimitConfig::unlimited(),
+            allow_http: false,
+            fixtures: None,
         };
 
         assert_eq!(config.user_agent, "test-agent/1.0");
@@ -347,6 +703,145 @@ mod tests {
         assert_eq!(client.config().user_agent, "rustacean-docs-mcp/0.1.0");
     }
 
+    #[test]
+    fn test_for_registry() {
+        let internal = Registry::new("internal", "https://docs.example.com")
+            .with_api_url("https://crates.example.com");
+        let client = DocsClient::new()
+            .unwrap()
+            .with_registries([internal.clone()].into_iter().collect());
+
+        let selected = client.for_registry(Some("internal")).unwrap();
+        assert_eq!(selected.registry(), &internal);
+        assert_eq!(selected.base_url(), "https://docs.example.com");
+
+        assert_eq!(
+            client.for_registry(None).unwrap().base_url(),
+            "https://docs.rs"
+        );
+        assert_eq!(
+            client.for_registry(Some("crates-io")).unwrap().base_url(),
+            "https://docs.rs"
+        );
+        assert!(client.for_registry(Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_with_priority_shares_rate_limiter() {
+        let client = DocsClient::new().unwrap();
+        let background = client.clone().with_priority(RequestPriority::Background);
+
+        assert_eq!(client.priority(), RequestPriority::Interactive);
+        assert_eq!(background.priority(), RequestPriority::Background);
+        assert!(Arc::ptr_eq(&client.rate_limiter, &background.rate_limiter));
+    }
+
+    #[tokio::test]
+    async fn test_stored_response_is_revalidated() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = rustacean_docs_cache::DiskCache::new(dir.path());
+        let client = DocsClient::test_client()
+            .unwrap()
+            .with_response_store(ResponseStore::new(store));
+
+        let mut server = mockito::Server::new_async().await;
+        let url = format!("{}/crate/serde", server.url());
+        let fresh = server
+            .mock("GET", "/crate/serde")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("content-type", "text/html")
+            .with_header("etag", "\"v1\"")
+            .with_body("<html>serde</html>")
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/crate/serde")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create_async()
+            .await;
+
+        for _ in 0..2 {
+            let response = client.get_url(&url, "fetch docs").await.unwrap();
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "text/html");
+            assert_eq!(response.text().await.unwrap(), "<html>serde</html>");
+        }
+        fresh.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_not_modified_response_is_not_refetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = rustacean_docs_cache::DiskCache::new(dir.path());
+        let client = DocsClient::test_client()
+            .unwrap()
+            .with_response_store(ResponseStore::new(store));
+
+        let mut server = mockito::Server::new_async().await;
+        let url = format!("{}/crate/tokio", server.url());
+        let fresh = server
+            .mock("GET", "/crate/tokio")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body("<html>tokio</html>")
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/crate/tokio")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(3)
+            .create_async()
+            .await;
+
+        for _ in 0..4 {
+            let response = client.get_url(&url, "fetch docs").await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "<html>tokio</html>");
+        }
+        fresh.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_recorded_responses_replay_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let url = format!("{}/api/v1/crates/serde", server.url());
+        let mock = server
+            .mock("GET", "/api/v1/crates/serde")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"crate":{"name":"serde"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let recorder = DocsClient::test_client()
+            .unwrap()
+            .with_fixtures(FixtureConfig::record(dir.path()));
+        let response = recorder.get_url(&url, "fetch crate").await.unwrap();
+        assert_eq!(
+            response.text().await.unwrap(),
+            r#"{"crate":{"name":"serde"}}"#
+        );
+
+        let replayer = DocsClient::test_client()
+            .unwrap()
+            .with_fixtures(FixtureConfig::replay(dir.path()));
+        let response = replayer.get_url(&url, "fetch crate").await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(
+            response.text().await.unwrap(),
+            r#"{"crate":{"name":"serde"}}"#
+        );
+        mock.assert_async().await;
+
+        let unknown = format!("{}/api/v1/crates/tokio", server.url());
+        let error = replayer.get_url(&unknown, "fetch crate").await.unwrap_err();
+        assert!(error.to_string().contains("No fixture recorded"));
+    }
+
     // Integration tests with mock server
     #[cfg(feature = "integration-tests")]
     mod integration_tests {
@@ -467,6 +962,8 @@ mod tests {
             let mock = server
                 .mock("GET", "/error")
                 .with_status(500)
+                // Server errors are retried
+                .expect(RetryConfig::default().max_attempts)
                 .create_async()
                 .await;
 
@@ -561,9 +1058,19 @@ mod tests {
 
             let client = DocsClient {
                 client: reqwest_client,
+                retry: Arc::new(HostRetryPolicies::default()),
+                rate_limiter: Arc::new(RateLimiter::default()),
+                priority: RequestPriority::default(),
+                response_store: None,
+                fixtures: None,
                 config,
-                base_url: server.url(),
-            };
+                registry: Registry::crates_io(),
+                registries: Arc::new(RegistrySet::default()),
+                local_docs: None,
+                source_mode: DocsSourceMode::default(),
+                version_pins: VersionPins::default(),
+            }
+            .with_base_url(server.url());
 
             let response = client.get("/custom").await;
             assert!(response.is_ok());
@@ -571,6 +1078,39 @@ mod tests {
             mock.assert_async().await;
         }
 
+        #[tokio::test]
+        async fn test_registry_token_sent_as_bearer() {
+            let mut server = Server::new_async().await;
+            let docs = server
+                .mock("GET", "/page")
+                .match_header("authorization", "Bearer secret")
+                .with_status(200)
+                .with_body("OK")
+                .create_async()
+                .await;
+            let api = server
+                .mock("GET", "/api/v1/crates/demo")
+                .match_header("authorization", "Bearer secret")
+                .with_status(200)
+                .with_body("{}")
+                .create_async()
+                .await;
+
+            let registry = Registry::new("internal", server.url())
+                .with_api_url(server.url())
+                .with_token("secret");
+            let client = DocsClient::test_client().unwrap().with_registry(registry);
+
+            assert!(client.get("/page").await.is_ok());
+            assert!(client
+                .get_api("/api/v1/crates/demo", "demo metadata")
+                .await
+                .is_ok());
+
+            docs.assert_async().await;
+            api.assert_async().await;
+        }
+
         #[tokio::test]
         async fn test_health_check_success() {
             let mut server = Server::new_async().await;
@@ -598,6 +1138,8 @@ mod tests {
             let mock = server
                 .mock("GET", "/")
                 .with_status(500)
+                // Server errors are retried
+                .expect(RetryConfig::default().max_attempts)
                 .create_async()
                 .await;
 
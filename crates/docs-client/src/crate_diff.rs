@@ -0,0 +1,485 @@
+//! Public API differences between two releases of a crate.
+//!
+//! Items are matched by their path from the crate root and kind. An item
+//! whose kind changed at the same path is reported as changed rather than as
+//! a removal plus an addition. Changes are flagged as likely breaking when
+//! code written against the older release may stop compiling: removed items,
+//! items that are no longer public, and changed kinds or signatures.
+
+use crate::search_index::rust_path;
+use rustacean_docs_core::{
+    is_semver_compatible,
+    models::{
+        diff::{CrateDiffResponse, CrateDiffSummary, ItemChange, ItemChangeKind, ModuleDiff},
+        docs::{CrateDocsResponse, CrateItem, Deprecation, ItemKind, Visibility},
+    },
+};
+use std::collections::BTreeMap;
+
+/// An item keyed by where it is reachable from the crate root
+struct PathedItem<'a> {
+    path: String,
+    item: &'a CrateItem,
+}
+
+/// Compare the items of two releases of a crate
+pub fn diff_crate_docs(old: &CrateDocsResponse, new: &CrateDocsResponse) -> CrateDiffResponse {
+    let mut old_items = index_items(&old.items);
+    let mut new_items = index_items(&new.items);
+    let mut changes = Vec::new();
+
+    // Items present in both releases under the same path and kind
+    let shared: Vec<(String, ItemKind)> = old_items
+        .keys()
+        .filter(|key| new_items.contains_key(*key))
+        .cloned()
+        .collect();
+    for key in shared {
+        let before = old_items.remove(&key).expect("shared key");
+        let after = new_items.remove(&key).expect("shared key");
+        changes.extend(compare(&before, &after));
+    }
+
+    // A path left exactly once on each side changed its kind
+    let retyped: Vec<((String, ItemKind), (String, ItemKind))> = old_items
+        .keys()
+        .filter_map(|old_key| {
+            let mut candidates = new_items.keys().filter(|new_key| new_key.0 == old_key.0);
+            let new_key = candidates.next()?;
+            let unique_new = candidates.next().is_none();
+            let unique_old = old_items.keys().filter(|k| k.0 == old_key.0).count() == 1;
+            (unique_new && unique_old).then(|| (old_key.clone(), new_key.clone()))
+        })
+        .collect();
+    for (old_key, new_key) in retyped {
+        let before = old_items.remove(&old_key).expect("retyped key");
+        let after = new_items.remove(&new_key).expect("retyped key");
+        changes.extend(compare(&before, &after));
+    }
+
+    for before in old_items.values() {
+        let mut change = removed(before);
+        // A removal next to an addition of the same name and kind is likely a move
+        let moved_to: Vec<&str> = new_items
+            .values()
+            .filter(|after| {
+                after.item.name == before.item.name && after.item.kind == before.item.kind
+            })
+            .map(|after| after.path.as_str())
+            .collect();
+        for path in moved_to {
+            change.details.push(format!("possibly moved to `{path}`"));
+        }
+        changes.push(change);
+    }
+    changes.extend(new_items.values().map(added));
+
+    let summary = summarize(&changes);
+    CrateDiffResponse {
+        crate_name: new.name.clone(),
+        from_version: old.version.clone(),
+        to_version: new.version.clone(),
+        semver_compatible: is_semver_compatible(&old.version, &new.version),
+        summary,
+        modules: group_by_module(changes),
+    }
+}
+
+fn index_items(items: &[CrateItem]) -> BTreeMap<(String, ItemKind), PathedItem<'_>> {
+    let mut index = BTreeMap::new();
+    for item in items {
+        let docs_path = item.docs_path.as_deref().unwrap_or(&item.path);
+        let path = rust_path(docs_path, &item.name, item.kind == ItemKind::Method);
+        index
+            .entry((path.clone(), item.kind.clone()))
+            .or_insert(PathedItem { path, item });
+    }
+    index
+}
+
+/// Describe how an item present in both releases changed, if it did
+fn compare(before: &PathedItem, after: &PathedItem) -> Option<ItemChange> {
+    let (old, new) = (before.item, after.item);
+    let mut details = Vec::new();
+    let mut breaking = false;
+
+    if old.kind != new.kind {
+        details.push(format!(
+            "changed from {} to {}",
+            kind_name(&old.kind),
+            kind_name(&new.kind)
+        ));
+        breaking = true;
+    }
+
+    if old.visibility == Visibility::Public && new.visibility != Visibility::Public {
+        details.push("no longer public".to_string());
+        breaking = true;
+    }
+
+    match (&old.signature, &new.signature) {
+        (Some(old_signature), Some(new_signature))
+            if normalize_signature(old_signature) != normalize_signature(new_signature) =>
+        {
+            details.push("signature changed".to_string());
+            breaking = true;
+        }
+        // Without signatures to compare, asyncness is the only visible difference
+        (None, _) | (_, None) if old.is_async != new.is_async => {
+            details.push(if new.is_async {
+                "became async".to_string()
+            } else {
+                "no longer async".to_string()
+            });
+            breaking = true;
+        }
+        _ => {}
+    }
+
+    match (&old.deprecation, &new.deprecation) {
+        (None, Some(deprecation)) => details.push(describe_deprecation(deprecation)),
+        (Some(_), None) => details.push("no longer deprecated".to_string()),
+        _ => {}
+    }
+
+    if details.is_empty() {
+        return None;
+    }
+
+    Some(ItemChange {
+        path: after.path.clone(),
+        name: new.name.clone(),
+        kind: new.kind.clone(),
+        change: ItemChangeKind::Changed,
+        old_signature: old.signature.clone(),
+        new_signature: new.signature.clone(),
+        deprecation: new.deprecation.clone(),
+        details,
+        breaking,
+        docs_path: new.docs_path.clone(),
+    })
+}
+
+fn removed(before: &PathedItem) -> ItemChange {
+    let item = before.item;
+    let mut details = vec!["removed".to_string()];
+    if let Some(deprecation) = &item.deprecation {
+        details.push(format!("was {}", describe_deprecation(deprecation)));
+    }
+
+    ItemChange {
+        path: before.path.clone(),
+        name: item.name.clone(),
+        kind: item.kind.clone(),
+        change: ItemChangeKind::Removed,
+        old_signature: item.signature.clone(),
+        new_signature: None,
+        deprecation: None,
+        details,
+        // Items that were never public could not have been used
+        breaking: item.visibility == Visibility::Public,
+        docs_path: item.docs_path.clone(),
+    }
+}
+
+fn added(after: &PathedItem) -> ItemChange {
+    let item = after.item;
+    let mut details = vec!["added".to_string()];
+    if let Some(deprecation) = &item.deprecation {
+        details.push(describe_deprecation(deprecation));
+    }
+
+    ItemChange {
+        path: after.path.clone(),
+        name: item.name.clone(),
+        kind: item.kind.clone(),
+        change: ItemChangeKind::Added,
+        old_signature: None,
+        new_signature: item.signature.clone(),
+        deprecation: item.deprecation.clone(),
+        details,
+        breaking: false,
+        docs_path: item.docs_path.clone(),
+    }
+}
+
+fn summarize(changes: &[ItemChange]) -> CrateDiffSummary {
+    let mut summary = CrateDiffSummary::default();
+    for change in changes {
+        match change.change {
+            ItemChangeKind::Added => summary.added += 1,
+            ItemChangeKind::Removed => summary.removed += 1,
+            ItemChangeKind::Changed => summary.changed += 1,
+        }
+        let newly_deprecated = change.change == ItemChangeKind::Changed
+            && change.details.iter().any(|d| d.starts_with("deprecated"));
+        if newly_deprecated {
+            summary.newly_deprecated += 1;
+        }
+        if change.breaking {
+            summary.breaking += 1;
+        }
+    }
+    summary
+}
+
+/// Group changes by the module declaring the item, crate root first
+fn group_by_module(changes: Vec<ItemChange>) -> Vec<ModuleDiff> {
+    let mut modules: BTreeMap<String, Vec<ItemChange>> = BTreeMap::new();
+    for change in changes {
+        modules
+            .entry(module_of(&change.path, &change.kind))
+            .or_default()
+            .push(change);
+    }
+
+    modules
+        .into_iter()
+        .map(|(module, mut changes)| {
+            changes.sort_by(|a, b| a.path.cmp(&b.path).then(a.kind.cmp(&b.kind)));
+            ModuleDiff { module, changes }
+        })
+        .collect()
+}
+
+/// Module path of an item (`sync::Mutex` is in `sync`, `sync::Mutex::lock` too)
+fn module_of(path: &str, kind: &ItemKind) -> String {
+    let mut segments: Vec<&str> = path.split("::").collect();
+    segments.pop();
+    if *kind == ItemKind::Method {
+        segments.pop();
+    }
+    segments.join("::")
+}
+
+/// Collapse whitespace so re-rendered declarations compare equal
+fn normalize_signature(signature: &str) -> String {
+    signature.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn describe_deprecation(deprecation: &Deprecation) -> String {
+    let mut description = "deprecated".to_string();
+    if let Some(since) = &deprecation.since {
+        description.push_str(&format!(" since {since}"));
+    }
+    if let Some(note) = &deprecation.note {
+        description.push_str(&format!(": {note}"));
+    }
+    description
+}
+
+fn kind_name(kind: &ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Module => "module",
+        ItemKind::Struct => "struct",
+        ItemKind::Enum => "enum",
+        ItemKind::Trait => "trait",
+        ItemKind::Function => "function",
+        ItemKind::Method => "method",
+        ItemKind::Macro => "macro",
+        ItemKind::Constant => "constant",
+        ItemKind::TypeAlias => "type alias",
+        ItemKind::Union => "union",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_parser::docs_parser::{categorize_items, generate_crate_summary};
+
+    fn item(path: &str, kind: ItemKind, signature: Option<&str>) -> CrateItem {
+        let name = path
+            .rsplit('/')
+            .next()
+            .unwrap()
+            .trim_end_matches(".html")
+            .rsplit('.')
+            .next()
+            .unwrap()
+            .to_string();
+        CrateItem {
+            name,
+            kind,
+            summary: None,
+            path: path.to_string(),
+            visibility: Visibility::Public,
+            is_async: false,
+            signature: signature.map(str::to_string),
+            docs_path: Some(path.to_string()),
+            deprecation: None,
+        }
+    }
+
+    fn docs(version: &str, items: Vec<CrateItem>) -> CrateDocsResponse {
+        CrateDocsResponse {
+            name: "reqwest".to_string(),
+            version: version.to_string(),
+            summary: generate_crate_summary(&items, None),
+            categories: categorize_items(&items),
+            items,
+            examples: Vec::new(),
+            docs_url: None,
+        }
+    }
+
+    fn change<'a>(diff: &'a CrateDiffResponse, path: &str) -> &'a ItemChange {
+        diff.changes()
+            .find(|change| change.path == path)
+            .unwrap_or_else(|| panic!("missing {path}"))
+    }
+
+    #[test]
+    fn test_diff_crate_docs() {
+        let old = docs(
+            "0.11.27",
+            vec![
+                item(
+                    "struct.Client.html",
+                    ItemKind::Struct,
+                    Some("pub struct Client { /* private fields */ }"),
+                ),
+                item(
+                    "fn.get.html",
+                    ItemKind::Function,
+                    Some("pub async fn get<T: IntoUrl>(url: T) -> Result<Response>"),
+                ),
+                item(
+                    "blocking/fn.get.html",
+                    ItemKind::Function,
+                    Some("pub fn get<T: IntoUrl>(url: T) -> Result<Response>"),
+                ),
+                item(
+                    "struct.Body.html",
+                    ItemKind::Struct,
+                    Some("pub struct Body { /* private fields */ }"),
+                ),
+                item(
+                    "type.Result.html",
+                    ItemKind::TypeAlias,
+                    Some("pub type Result<T> = Result<T, Error>;"),
+                ),
+                item(
+                    "header/index.html",
+                    ItemKind::Module,
+                    Some("pub mod header"),
+                ),
+            ],
+        );
+        let mut deprecated_body = item(
+            "struct.Body.html",
+            ItemKind::Struct,
+            Some("pub struct Body {\n    /* private fields */\n}"),
+        );
+        deprecated_body.deprecation = Some(Deprecation {
+            since: Some("0.12.0".to_string()),
+            note: Some("use `Body::wrap`".to_string()),
+        });
+        let new = docs(
+            "0.12.0",
+            vec![
+                item(
+                    "struct.Client.html",
+                    ItemKind::Struct,
+                    Some("pub struct Client { /* private fields */ }"),
+                ),
+                item(
+                    "fn.get.html",
+                    ItemKind::Function,
+                    Some("pub async fn get<T: IntoUrl>(url: T) -> crate::Result<Response>"),
+                ),
+                deprecated_body,
+                item(
+                    "enum.Result.html",
+                    ItemKind::Enum,
+                    Some("pub enum Result { Ok, Err }"),
+                ),
+                item(
+                    "header/index.html",
+                    ItemKind::Module,
+                    Some("pub mod header"),
+                ),
+                item(
+                    "header/fn.get.html",
+                    ItemKind::Function,
+                    Some("pub fn get<T: IntoUrl>(url: T) -> Result<Response>"),
+                ),
+                item("header/struct.HeaderMap.html", ItemKind::Struct, None),
+            ],
+        );
+
+        let diff = diff_crate_docs(&old, &new);
+        assert_eq!(diff.from_version, "0.11.27");
+        assert_eq!(diff.to_version, "0.12.0");
+        assert!(!diff.semver_compatible);
+
+        // Unchanged items are left out
+        assert!(diff
+            .changes()
+            .all(|c| c.path != "Client" && c.path != "header"));
+
+        let get = change(&diff, "get");
+        assert_eq!(get.change, ItemChangeKind::Changed);
+        assert!(get.breaking);
+        assert_eq!(get.details, vec!["signature changed".to_string()]);
+
+        // Whitespace-only differences are not signature changes
+        let body = change(&diff, "Body");
+        assert!(!body.breaking);
+        assert_eq!(
+            body.details,
+            vec!["deprecated since 0.12.0: use `Body::wrap`".to_string()]
+        );
+
+        let result = change(&diff, "Result");
+        assert_eq!(result.change, ItemChangeKind::Changed);
+        assert_eq!(result.kind, ItemKind::Enum);
+        assert!(result.details[0].contains("from type alias to enum"));
+
+        let removed = change(&diff, "blocking::get");
+        assert_eq!(removed.change, ItemChangeKind::Removed);
+        assert!(removed.breaking);
+        assert!(removed
+            .details
+            .contains(&"possibly moved to `header::get`".to_string()));
+
+        let added = change(&diff, "header::HeaderMap");
+        assert_eq!(added.change, ItemChangeKind::Added);
+        assert!(!added.breaking);
+
+        let modules: Vec<&str> = diff.modules.iter().map(|m| m.module.as_str()).collect();
+        assert_eq!(modules, vec!["", "blocking", "header"]);
+        assert_eq!(
+            diff.summary,
+            CrateDiffSummary {
+                added: 2,
+                removed: 1,
+                changed: 3,
+                newly_deprecated: 1,
+                breaking: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_identical_releases() {
+        let items = vec![item(
+            "sync/struct.Mutex.html",
+            ItemKind::Struct,
+            Some("pub struct Mutex<T>"),
+        )];
+        let diff = diff_crate_docs(&docs("1.2.0", items.clone()), &docs("1.3.0", items));
+
+        assert!(diff.semver_compatible);
+        assert!(diff.modules.is_empty());
+        assert_eq!(diff.summary, CrateDiffSummary::default());
+    }
+
+    #[test]
+    fn test_module_of() {
+        assert_eq!(module_of("sync::Mutex", &ItemKind::Struct), "sync");
+        assert_eq!(module_of("sync::Mutex::lock", &ItemKind::Method), "sync");
+        assert_eq!(module_of("spawn", &ItemKind::Function), "");
+        assert_eq!(module_of("sync", &ItemKind::Module), "");
+    }
+}
@@ -1,4 +1,5 @@
-use rustacean_docs_cache::MemoryCache;
+use rustacean_docs_cache::{Cache, CacheScope, MemoryCache, SingleFlight};
+use rustacean_docs_core::{Cacheable, Error, Response};
 use std::{hash::Hash, sync::Arc, time::Duration};
 
 /// Trait for cache key types that can be used across different endpoints
@@ -26,7 +27,47 @@ where
     K: CacheKey,
     V: Clone + Send + Sync + 'static,
 {
-    Arc::new(MemoryCache::new(config.capacity))
+    Arc::new(MemoryCache::new(config.capacity).with_ttl(config.ttl))
+}
+
+/// In-flight fetches of a service, so concurrent cache misses for the same
+/// key share one request and its outcome
+pub type ServiceFlights<K, V> = SingleFlight<K, std::result::Result<V, Arc<Error>>>;
+
+/// How long a response may be cached, as declared by [`Response::cache_ttl`]
+pub fn response_ttl<R: Response>(response: &R) -> Option<Duration> {
+    response.cache_ttl().map(Duration::from_secs)
+}
+
+/// Cache a response for its declared TTL, tagged with its
+/// [`Cacheable::cache_tags`] so it can be invalidated by scope
+pub async fn cache_response<K, R>(cache: &MemoryCache<K, R>, key: K, response: &R)
+where
+    K: CacheKey,
+    R: Response + Cacheable + Clone + 'static,
+{
+    let _ = cache
+        .insert_tagged(
+            key,
+            response.clone(),
+            response_ttl(response),
+            response.cache_tags(),
+        )
+        .await;
+}
+
+/// Remove the entries within `scope` from a service cache, returning how many
+/// were removed. Service caches use structured keys, so key globs match nothing.
+pub async fn invalidate_service_cache<K, V>(cache: &MemoryCache<K, V>, scope: &CacheScope) -> usize
+where
+    K: CacheKey,
+    V: Clone + Send + Sync + 'static,
+{
+    let filter = |_: &K, tags: &[String]| scope.matches_tags(tags);
+    match cache.remove_matching(&filter).await {
+        Ok(removed) => removed.len(),
+        Err(never) => match never {},
+    }
 }
 
 // Implement CacheKey for common types
@@ -35,7 +76,7 @@ impl<T> CacheKey for T where T: Clone + PartialEq + Eq + Hash + Send + Sync + 's
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rustacean_docs_cache::Cache;
+    use rustacean_docs_core::RecentReleasesResponse;
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     struct TestKey {
@@ -81,4 +122,51 @@ mod tests {
         assert_eq!(config.capacity, 1000);
         assert_eq!(config.ttl, Duration::from_secs(3600));
     }
+
+    #[tokio::test]
+    async fn test_service_cache_expires_entries() {
+        let config = ServiceCacheConfig {
+            capacity: 10,
+            ttl: Duration::from_millis(20),
+        };
+        let cache = create_service_cache::<TestKey, TestValue>(config);
+
+        let key = TestKey {
+            id: "expiring".to_string(),
+        };
+        let value = TestValue {
+            data: "test_data".to_string(),
+        };
+
+        // A longer per-entry TTL is capped by the service TTL
+        cache
+            .insert_with_ttl(key.clone(), value, Some(Duration::from_secs(3600)))
+            .await
+            .unwrap();
+        assert!(cache.get(&key).await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(cache.get(&key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cached_responses_are_invalidated_by_tag() {
+        let cache =
+            create_service_cache::<TestKey, RecentReleasesResponse>(ServiceCacheConfig::default());
+        let key = TestKey {
+            id: "releases".to_string(),
+        };
+        cache_response(
+            &cache,
+            key.clone(),
+            &RecentReleasesResponse { releases: vec![] },
+        )
+        .await;
+
+        let search = CacheScope::Kind("search".to_string());
+        assert_eq!(invalidate_service_cache(&cache, &search).await, 0);
+        let releases = CacheScope::Kind("releases".to_string());
+        assert_eq!(invalidate_service_cache(&cache, &releases).await, 1);
+        assert!(cache.get(&key).await.unwrap().is_none());
+    }
 }
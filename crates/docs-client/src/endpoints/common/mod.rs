@@ -1,3 +1,6 @@
 pub mod cache_traits;
 
-pub use cache_traits::{create_service_cache, CacheKey, ServiceCacheConfig};
+pub use cache_traits::{
+    cache_response, create_service_cache, invalidate_service_cache, response_ttl, CacheKey,
+    ServiceCacheConfig, ServiceFlights,
+};
@@ -1,33 +1,48 @@
 use super::cache_keys::{CrateDocsCacheKey, ItemDocsCacheKey, RecentReleasesCacheKey};
 use crate::{
     client::DocsClient,
+    endpoints::common::{cache_response, invalidate_service_cache, ServiceFlights},
     html_parser::{parse_crate_documentation, parse_item_documentation, parse_recent_releases},
+    local_source::lookup_local,
+    rustdoc_json::{build_crate_documentation, build_item_documentation, RustdocJsonSource},
+    search_index::{CrateCallables, ItemSearchIndex},
 };
-use rustacean_docs_cache::{Cache, MemoryCache};
+use rustacean_docs_cache::{Cache, CacheScope, MemoryCache};
 use rustacean_docs_core::{
     models::docs::{
         CrateDocsRequest, CrateDocsResponse, ItemDocsRequest, ItemDocsResponse,
         RecentReleasesRequest, RecentReleasesResponse,
     },
-    Result,
+    models::search::{ItemSearchRequest, ItemSearchResponse},
+    Error, ItemPath, Request, Result,
 };
 use std::{sync::Arc, time::Duration};
-use tracing::{debug, trace};
-
-/// Documentation service that combines HTTP client with caching
+use tracing::{debug, trace, warn};
+
+/// Documentation service that combines HTTP client with caching.
+///
+/// Crate and item documentation is built from rustdoc JSON when docs.rs has
+/// published it for the requested release, falling back to scraping the
+/// rendered HTML otherwise. Depending on the client's source mode, local crate
+/// sources are consulted first or exclusively. Concurrent cache misses for the
+/// same request share a single fetch.
 pub struct DocsService {
     client: DocsClient,
+    rustdoc_json: RustdocJsonSource,
     crate_docs_cache: Arc<MemoryCache<CrateDocsCacheKey, CrateDocsResponse>>,
     item_docs_cache: Arc<MemoryCache<ItemDocsCacheKey, ItemDocsResponse>>,
     releases_cache: Arc<MemoryCache<RecentReleasesCacheKey, RecentReleasesResponse>>,
+    crate_docs_flights: ServiceFlights<CrateDocsCacheKey, CrateDocsResponse>,
+    item_docs_flights: ServiceFlights<ItemDocsCacheKey, ItemDocsResponse>,
+    releases_flights: ServiceFlights<RecentReleasesCacheKey, RecentReleasesResponse>,
 }
 
 impl DocsService {
     /// Create a new documentation service with cache
     pub fn new(client: DocsClient, cache_capacity: usize, cache_ttl: Duration) -> Self {
-        let crate_docs_cache = Arc::new(MemoryCache::new(cache_capacity));
-        let item_docs_cache = Arc::new(MemoryCache::new(cache_capacity));
-        let releases_cache = Arc::new(MemoryCache::new(cache_capacity / 10));
+        let crate_docs_cache = Arc::new(MemoryCache::new(cache_capacity).with_ttl(cache_ttl));
+        let item_docs_cache = Arc::new(MemoryCache::new(cache_capacity).with_ttl(cache_ttl));
+        let releases_cache = Arc::new(MemoryCache::new(cache_capacity / 10).with_ttl(cache_ttl));
 
         debug!(
             cache_capacity = cache_capacity,
@@ -36,10 +51,14 @@ impl DocsService {
         );
 
         Self {
+            rustdoc_json: RustdocJsonSource::new(client.clone()),
             client,
             crate_docs_cache,
             item_docs_cache,
             releases_cache,
+            crate_docs_flights: ServiceFlights::new(),
+            item_docs_flights: ServiceFlights::new(),
+            releases_flights: ServiceFlights::new(),
         }
     }
 
@@ -60,30 +79,39 @@ impl DocsService {
         trace!(
             crate_name = %request.crate_name,
             version = ?request.version,
-            "Crate docs cache miss, fetching from docs.rs"
+            source_mode = %self.client.source_mode(),
+            "Crate docs cache miss"
         );
 
-        // Cache miss - fetch from docs.rs
-        let version = request
-            .version
-            .as_ref()
-            .map(|v| v.as_str())
-            .unwrap_or("latest");
-        let path = format!(
-            "/{}/{}/{}/",
-            request.crate_name.as_str(),
-            version,
-            request.crate_name.as_str()
-        );
-        let html = self.client.get_text(&path).await?;
-        let version_str = request.version.as_ref().map(|v| v.as_str().to_string());
-        let response = parse_crate_documentation(&html, request.crate_name.as_str(), &version_str)?;
+        self.crate_docs_flights
+            .run(cache_key.clone(), || async {
+                self.load_crate_docs(&request, cache_key)
+                    .await
+                    .map_err(Arc::new)
+            })
+            .await
+            .map_err(Error::shared)
+    }
+
+    /// Load crate documentation from local sources or docs.rs and cache it
+    async fn load_crate_docs(
+        &self,
+        request: &CrateDocsRequest,
+        cache_key: CrateDocsCacheKey,
+    ) -> Result<CrateDocsResponse> {
+        let docs_base = self.client.base_url();
+        let local = lookup_local(&self.client, &request.crate_name, |source| async move {
+            source.get_crate_docs(request, docs_base).await
+        })
+        .await?;
+
+        let response = match local {
+            Some(response) => response,
+            None => self.fetch_crate_docs(request).await?,
+        };
 
         // Store in cache for future requests
-        let _ = self
-            .crate_docs_cache
-            .insert(cache_key, response.clone())
-            .await;
+        cache_response(&self.crate_docs_cache, cache_key, &response).await;
 
         debug!(
             crate_name = %response.name,
@@ -114,45 +142,234 @@ impl DocsService {
             crate_name = %request.crate_name,
             item_path = %request.item_path,
             version = ?request.version,
-            "Item docs cache miss, fetching from docs.rs"
+            source_mode = %self.client.source_mode(),
+            "Item docs cache miss"
         );
 
-        // Cache miss - fetch from docs.rs
+        self.item_docs_flights
+            .run(cache_key.clone(), || async {
+                self.load_item_docs(&request, cache_key)
+                    .await
+                    .map_err(Arc::new)
+            })
+            .await
+            .map_err(Error::shared)
+    }
+
+    /// Load item documentation from local sources or docs.rs and cache it
+    async fn load_item_docs(
+        &self,
+        request: &ItemDocsRequest,
+        cache_key: ItemDocsCacheKey,
+    ) -> Result<ItemDocsResponse> {
+        let docs_base = self.client.base_url();
+        let local = lookup_local(&self.client, &request.crate_name, |source| async move {
+            source.get_item_docs(request, docs_base).await
+        })
+        .await?;
+
+        let response = match local {
+            Some(response) => response,
+            None => self.fetch_item_docs(request).await?,
+        };
+
+        // Store in cache for future requests
+        cache_response(&self.item_docs_cache, cache_key, &response).await;
+
+        debug!(
+            crate_name = %response.crate_name,
+            item_name = %response.name,
+            "Item documentation fetched and cached"
+        );
+
+        Ok(response)
+    }
+
+    /// Fetch crate documentation from docs.rs, preferring rustdoc JSON
+    async fn fetch_crate_docs(&self, request: &CrateDocsRequest) -> Result<CrateDocsResponse> {
         let version = request
             .version
             .as_ref()
             .map(|v| v.as_str())
             .unwrap_or("latest");
-        let url = format!(
-            "/{}/{}/{}",
-            request.crate_name.as_str(),
-            version,
-            request.item_path.as_str()
-        );
-        let html = self.client.get_text(&url).await?;
         let version_str = request.version.as_ref().map(|v| v.as_str().to_string());
-        let response = parse_item_documentation(
-            &html,
-            request.crate_name.as_str(),
-            request.item_path.as_str(),
-            &version_str,
-        )?;
+        let docs_base = self.client.base_url();
+
+        let response = match self
+            .fetch_rustdoc_json(request.crate_name.as_str(), version)
+            .await
+        {
+            Some(krate) => build_crate_documentation(
+                &krate,
+                docs_base,
+                request.crate_name.as_str(),
+                &version_str,
+            )?,
+            None => {
+                let path = format!(
+                    "/{}/{}/{}/",
+                    request.crate_name.as_str(),
+                    version,
+                    request.crate_name.as_str()
+                );
+                let html = self.client.get_text(&path).await?;
+                parse_crate_documentation(
+                    &html,
+                    docs_base,
+                    request.crate_name.as_str(),
+                    &version_str,
+                )?
+            }
+        };
 
-        // Store in cache for future requests
-        let _ = self
-            .item_docs_cache
-            .insert(cache_key, response.clone())
-            .await;
+        Ok(response)
+    }
+
+    /// Fetch item documentation from docs.rs, preferring rustdoc JSON
+    async fn fetch_item_docs(&self, request: &ItemDocsRequest) -> Result<ItemDocsResponse> {
+        let version = request
+            .version
+            .as_ref()
+            .map(|v| v.as_str())
+            .unwrap_or("latest");
+        let version_str = request.version.as_ref().map(|v| v.as_str().to_string());
+        let docs_base = self.client.base_url();
+
+        let response = match self
+            .fetch_rustdoc_json(request.crate_name.as_str(), version)
+            .await
+        {
+            Some(krate) => build_item_documentation(
+                &krate,
+                docs_base,
+                request.crate_name.as_str(),
+                request.item_path.as_str(),
+                &version_str,
+            )?,
+            None => {
+                let url = format!(
+                    "/{}/{}/{}",
+                    request.crate_name.as_str(),
+                    version,
+                    request.item_path.as_str()
+                );
+                let html = self.client.get_text(&url).await?;
+                parse_item_documentation(
+                    &html,
+                    docs_base,
+                    request.crate_name.as_str(),
+                    request.item_path.as_str(),
+                    &version_str,
+                )?
+            }
+        };
+
+        Ok(response)
+    }
+
+    /// Fetch rustdoc JSON for a release, returning `None` when the HTML
+    /// parser should be used instead
+    async fn fetch_rustdoc_json(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Option<Arc<crate::rustdoc_json::RustdocCrate>> {
+        match self.rustdoc_json.fetch_crate(crate_name, version).await {
+            Ok(Some(krate)) => Some(krate),
+            Ok(None) => {
+                debug!(
+                    crate_name = %crate_name,
+                    version = %version,
+                    "No rustdoc JSON available, falling back to HTML parsing"
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    crate_name = %crate_name,
+                    version = %version,
+                    error = %e,
+                    "Failed to load rustdoc JSON, falling back to HTML parsing"
+                );
+                None
+            }
+        }
+    }
+
+    /// Search the items of a crate by name, documentation or type signature.
+    ///
+    /// The crate's documentation is fetched through [`Self::get_crate_docs`],
+    /// so repeated searches against the same release reuse its cache.
+    pub async fn search_items(&self, request: ItemSearchRequest) -> Result<ItemSearchResponse> {
+        request.validate()?;
+
+        let docs_request = CrateDocsRequest {
+            crate_name: request.crate_name.clone(),
+            version: request.version.clone(),
+        };
+        let docs = self.get_crate_docs(docs_request).await?;
+        let response = ItemSearchIndex::from_docs(&docs).search(&request);
 
         debug!(
-            crate_name = %response.crate_name,
-            item_name = %response.name,
-            "Item documentation fetched and cached"
+            crate_name = %request.crate_name,
+            query = %request.query,
+            total = response.total,
+            "Searched crate items"
         );
 
         Ok(response)
     }
 
+    /// Collect the functions and methods of a crate for signature search.
+    ///
+    /// Methods are read from the item docs of the crate's types and traits;
+    /// at most `max_containers` of them are fetched, and pages that fail to
+    /// load are skipped.
+    pub async fn get_crate_callables(
+        &self,
+        request: CrateDocsRequest,
+        max_containers: usize,
+    ) -> Result<CrateCallables> {
+        let docs = self.get_crate_docs(request.clone()).await?;
+        let mut callables = CrateCallables::new(&docs);
+
+        let containers = docs
+            .items
+            .iter()
+            .filter(|item| CrateCallables::is_method_container(item))
+            .take(max_containers);
+        for item in containers {
+            let page = item.docs_path.as_deref().unwrap_or(&item.path);
+            let Ok(item_path) = ItemPath::new(page) else {
+                continue;
+            };
+            let item_request = ItemDocsRequest {
+                crate_name: request.crate_name.clone(),
+                item_path,
+                version: request.version.clone(),
+            };
+
+            match self.get_item_docs(item_request).await {
+                Ok(item_docs) => callables.add_item_docs(page, &item_docs),
+                Err(e) => warn!(
+                    crate_name = %request.crate_name,
+                    item_path = %page,
+                    error = %e,
+                    "Skipping methods of item that failed to load"
+                ),
+            }
+        }
+
+        debug!(
+            crate_name = %callables.crate_name,
+            version = %callables.version,
+            callable_count = callables.items.len(),
+            "Collected crate callables"
+        );
+
+        Ok(callables)
+    }
+
     /// Get recent releases with caching
     pub async fn get_recent_releases(
         &self,
@@ -171,16 +388,28 @@ impl DocsService {
             "Recent releases cache miss, fetching from docs.rs"
         );
 
-        // Cache miss - fetch from docs.rs
+        self.releases_flights
+            .run(cache_key.clone(), || async {
+                self.load_recent_releases(&request, cache_key)
+                    .await
+                    .map_err(Arc::new)
+            })
+            .await
+            .map_err(Error::shared)
+    }
+
+    /// Fetch recent releases from docs.rs and cache them
+    async fn load_recent_releases(
+        &self,
+        request: &RecentReleasesRequest,
+        cache_key: RecentReleasesCacheKey,
+    ) -> Result<RecentReleasesResponse> {
         let html = self.client.get_text("/").await?; // docs.rs homepage
         let releases = parse_recent_releases(&html, request.limit())?;
         let response = RecentReleasesResponse { releases };
 
         // Store in cache for future requests
-        let _ = self
-            .releases_cache
-            .insert(cache_key, response.clone())
-            .await;
+        cache_response(&self.releases_cache, cache_key, &response).await;
 
         debug!(
             release_count = response.releases.len(),
@@ -211,6 +440,14 @@ impl DocsService {
         let _ = self.releases_cache.clear().await;
         Ok(())
     }
+
+    /// Remove the cached documentation and releases within `scope`, returning
+    /// how many entries were removed
+    pub async fn invalidate_cache(&self, scope: &CacheScope) -> usize {
+        invalidate_service_cache(&self.crate_docs_cache, scope).await
+            + invalidate_service_cache(&self.item_docs_cache, scope).await
+            + invalidate_service_cache(&self.releases_cache, scope).await
+    }
 }
 
 #[cfg(test)]
@@ -251,4 +488,34 @@ mod tests {
         assert_eq!(item_stats.size, 0);
         assert_eq!(releases_stats.size, 0);
     }
+
+    #[cfg(feature = "integration-tests")]
+    mod integration_tests {
+        use super::*;
+        use mockito::Server;
+
+        #[tokio::test]
+        async fn test_concurrent_misses_share_one_fetch() {
+            let mut server = Server::new_async().await;
+            let mock = server
+                .mock("GET", "/")
+                .with_status(200)
+                .with_header("content-type", "text/html")
+                .with_body("<html><body></body></html>")
+                .create_async()
+                .await;
+
+            let client = create_test_client().with_base_url(server.url());
+            let service = DocsService::new(client, 10, Duration::from_secs(60));
+
+            let (a, b, c) = tokio::join!(
+                service.get_recent_releases(RecentReleasesRequest::new()),
+                service.get_recent_releases(RecentReleasesRequest::new()),
+                service.get_recent_releases(RecentReleasesRequest::new())
+            );
+            assert!(a.is_ok() && b.is_ok() && c.is_ok());
+
+            mock.expect(1).assert_async().await;
+        }
+    }
 }
@@ -1,15 +1,20 @@
 use crate::{
     client::DocsClient,
-    error_handling::{build_basic_docs_url, handle_http_response, parse_json_response},
+    endpoints::common::{cache_response, invalidate_service_cache, ServiceFlights},
+    error_handling::{build_basic_docs_url, parse_json_response},
+    local_source::lookup_local,
+    registry::{parse_index_file, IndexDependency, IndexEntry},
 };
-use chrono::Utc;
-use rustacean_docs_cache::{Cache, MemoryCache};
+use chrono::{DateTime, Utc};
+use rustacean_docs_cache::{Cache, CacheScope, MemoryCache};
 use rustacean_docs_core::{
     error::Error,
     models::metadata::{
         CrateMetadata, CrateMetadataRequest, Dependency, DependencyKind, DownloadStats, VersionInfo,
     },
+    CrateName, ErrorBuilder, Version,
 };
+use semver::{Version as SemVersion, VersionReq};
 use serde::Deserialize;
 use std::{collections::HashMap, hash::Hash, sync::Arc, time::Duration};
 use tracing::{debug, error, trace};
@@ -113,6 +118,26 @@ struct CratesIoDependency {
     downloads: Option<u64>,
 }
 
+impl From<IndexDependency> for Dependency {
+    fn from(dep: IndexDependency) -> Self {
+        let kind = match dep.kind.as_deref() {
+            Some("dev") => DependencyKind::Dev,
+            Some("build") => DependencyKind::Build,
+            _ => DependencyKind::Normal,
+        };
+
+        Self {
+            name: dep.package.unwrap_or(dep.name),
+            version_req: dep.req,
+            features: dep.features,
+            optional: dep.optional,
+            default_features: dep.default_features,
+            target: dep.target,
+            kind,
+        }
+    }
+}
+
 impl From<CratesIoDependency> for Dependency {
     fn from(dep: CratesIoDependency) -> Self {
         let kind = match dep.kind.as_str() {
@@ -137,6 +162,7 @@ impl From<CratesIoDependency> for Dependency {
 pub struct MetadataService {
     client: DocsClient,
     cache: Arc<MemoryCache<MetadataCacheKey, CrateMetadata>>,
+    flights: ServiceFlights<MetadataCacheKey, CrateMetadata>,
 }
 
 impl MetadataService {
@@ -151,7 +177,7 @@ impl MetadataService {
         cache_capacity: usize,
         cache_ttl: Duration,
     ) -> Self {
-        let cache = Arc::new(MemoryCache::new(cache_capacity));
+        let cache = Arc::new(MemoryCache::new(cache_capacity).with_ttl(cache_ttl));
 
         debug!(
             cache_capacity = cache_capacity,
@@ -159,7 +185,11 @@ impl MetadataService {
             "Created metadata service with cache"
         );
 
-        Self { client, cache }
+        Self {
+            client,
+            cache,
+            flights: ServiceFlights::new(),
+        }
     }
 
     /// Fetch comprehensive metadata for a crate
@@ -182,13 +212,42 @@ impl MetadataService {
         trace!(
             crate_name = %request.crate_name,
             version = ?request.version,
-            "Metadata cache miss, fetching from API"
+            source_mode = %self.client.source_mode(),
+            "Metadata cache miss"
         );
 
-        let metadata = self.fetch_metadata_from_api(request).await?;
+        self.flights
+            .run(cache_key.clone(), || async {
+                self.load_crate_metadata(request, cache_key)
+                    .await
+                    .map_err(Arc::new)
+            })
+            .await
+            .map_err(Error::shared)
+    }
+
+    /// Load crate metadata from local sources or the registry and cache it
+    async fn load_crate_metadata(
+        &self,
+        request: &CrateMetadataRequest,
+        cache_key: MetadataCacheKey,
+    ) -> Result<CrateMetadata, Error> {
+        let local = lookup_local(&self.client, &request.crate_name, |source| async move {
+            source.get_crate_metadata(request).await
+        })
+        .await?;
+
+        let registry = self.client.registry();
+        let metadata = match local {
+            Some(metadata) => metadata,
+            None if registry.api_url.is_none() && registry.index_url.is_some() => {
+                self.fetch_metadata_from_index(request).await?
+            }
+            None => self.fetch_metadata_from_api(request).await?,
+        };
 
         // Store in cache for future requests
-        let _ = self.cache.insert(cache_key, metadata.clone()).await;
+        cache_response(&self.cache, cache_key, &metadata).await;
 
         debug!(
             crate_name = %request.crate_name,
@@ -199,32 +258,52 @@ impl MetadataService {
         Ok(metadata)
     }
 
+    /// Resolve a semver requirement to the newest non-yanked published
+    /// version matching it
+    pub async fn resolve_version_requirement(
+        &self,
+        crate_name: &CrateName,
+        requirement: &VersionReq,
+    ) -> Result<Version, Error> {
+        let metadata = self
+            .get_crate_metadata(&CrateMetadataRequest::new(crate_name.clone()))
+            .await?;
+
+        let version = select_version(&metadata.versions, requirement).ok_or_else(|| {
+            ErrorBuilder::docs().invalid_version(format!(
+                "No published version of {crate_name} matches '{requirement}'"
+            ))
+        })?;
+
+        debug!(
+            crate_name = %crate_name,
+            requirement = %requirement,
+            version = %version,
+            "Resolved version requirement"
+        );
+
+        Version::new(version.to_string())
+            .map_err(|e| ErrorBuilder::docs().invalid_version(e.to_string()))
+    }
+
     async fn fetch_metadata_from_api(
         &self,
         request: &CrateMetadataRequest,
     ) -> Result<CrateMetadata, Error> {
-        let url = format!("https://crates.io/api/v1/crates/{}", request.crate_name);
+        let path = format!("/api/v1/crates/{}", request.crate_name);
 
-        debug!("Requesting metadata from: {}", url);
+        debug!(
+            registry = %self.client.registry().name,
+            "Requesting metadata from: {}", path
+        );
 
         let response = self
             .client
-            .inner_client()
-            .get(&url)
-            .send()
+            .get_api(&path, &format!("metadata for {}", request.crate_name))
             .await
-            .map_err(|e| {
-                error!("Failed to fetch metadata from crates.io: {}", e);
-                rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
-            })?;
-
-        let response = handle_http_response(
-            response,
-            &format!("crates.io metadata for {}", request.crate_name),
-        )
-        .await?;
+            .inspect_err(|e| error!("Failed to fetch metadata from registry: {}", e))?;
         let mut crates_io_response: CratesIoResponse =
-            parse_json_response(response, "crates.io metadata").await?;
+            parse_json_response(response, "registry metadata").await?;
 
         // Fetch dependencies separately for the target version
         let target_version = request
@@ -255,33 +334,57 @@ impl MetadataService {
         crate_name: &str,
         version: &str,
     ) -> Result<Vec<CratesIoDependency>, Error> {
-        let url = format!("https://crates.io/api/v1/crates/{crate_name}/{version}/dependencies");
+        let path = format!("/api/v1/crates/{crate_name}/{version}/dependencies");
 
-        debug!("Requesting dependencies from: {}", url);
+        debug!("Requesting dependencies from: {}", path);
 
         let response = self
             .client
-            .inner_client()
-            .get(&url)
-            .send()
+            .get_api(&path, &format!("dependencies for {crate_name}:{version}"))
             .await
-            .map_err(|e| {
-                error!("Failed to fetch dependencies from crates.io: {}", e);
-                rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
-            })?;
-
-        let response = handle_http_response(
-            response,
-            &format!("crates.io dependencies for {crate_name}:{version}"),
-        )
-        .await?;
+            .inspect_err(|e| error!("Failed to fetch dependencies from registry: {}", e))?;
 
         let deps_response: DependenciesResponse =
-            parse_json_response(response, "crates.io dependencies").await?;
+            parse_json_response(response, "registry dependencies").await?;
 
         Ok(deps_response.dependencies)
     }
 
+    /// Build metadata from the registry's sparse index, for registries without
+    /// a web API. The index only records versions, dependencies and features,
+    /// so descriptive fields are left empty and downloads are reported as zero.
+    async fn fetch_metadata_from_index(
+        &self,
+        request: &CrateMetadataRequest,
+    ) -> Result<CrateMetadata, Error> {
+        let registry = self.client.registry();
+        let url = registry
+            .index_endpoint(request.crate_name.as_str())
+            .ok_or_else(|| {
+                ErrorBuilder::protocol().not_supported(format!(
+                    "metadata: registry '{}' has no index configured",
+                    registry.name
+                ))
+            })?;
+
+        debug!(registry = %registry.name, "Requesting index file from: {}", url);
+
+        let response = self
+            .client
+            .get_url(&url, &format!("index file for {}", request.crate_name))
+            .await
+            .inspect_err(|e| error!("Failed to fetch index file from registry: {}", e))?;
+        let contents = response.text().await.map_err(|e| {
+            rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
+        })?;
+
+        metadata_from_index(
+            parse_index_file(&contents)?,
+            request,
+            self.client.base_url(),
+        )
+    }
+
     async fn transform_metadata(
         &self,
         response: CratesIoResponse,
@@ -325,8 +428,8 @@ impl MetadataService {
             .as_ref()
             .and_then(|url_str| Url::parse(url_str).ok())
             .or_else(|| {
-                // Default to docs.rs URL if no documentation URL provided
-                build_basic_docs_url(&crate_info.name)
+                // Default to the registry's docs host if no documentation URL provided
+                build_basic_docs_url(self.client.base_url(), &crate_info.name)
             });
 
         // Parse timestamps
@@ -445,6 +548,109 @@ impl MetadataService {
         let _ = self.cache.clear().await;
         Ok(())
     }
+
+    /// Remove the cached metadata within `scope`, returning how many entries
+    /// were removed
+    pub async fn invalidate_cache(&self, scope: &CacheScope) -> usize {
+        invalidate_service_cache(&self.cache, scope).await
+    }
+}
+
+/// Highest non-yanked published version satisfying a requirement
+pub fn select_version(versions: &[VersionInfo], requirement: &VersionReq) -> Option<SemVersion> {
+    versions
+        .iter()
+        .filter(|info| !info.yanked)
+        .filter_map(|info| SemVersion::parse(&info.num).ok())
+        .filter(|version| requirement.matches(version))
+        .max()
+}
+
+/// Convert sparse index entries into crate metadata for the requested version,
+/// defaulting to the highest non-yanked stable release
+fn metadata_from_index(
+    entries: Vec<IndexEntry>,
+    request: &CrateMetadataRequest,
+    docs_base: &str,
+) -> Result<CrateMetadata, Error> {
+    let parse = |entry: &IndexEntry| SemVersion::parse(&entry.vers).ok();
+
+    let target = match request.version.as_ref().filter(|v| v.as_str() != "latest") {
+        Some(version) => entries
+            .iter()
+            .find(|entry| entry.vers == version.as_str())
+            .ok_or_else(|| {
+                ErrorBuilder::docs().version_not_found(request.crate_name.clone(), version.clone())
+            })?,
+        None => entries
+            .iter()
+            .filter_map(|entry| parse(entry).map(|version| (version, entry)))
+            .max_by_key(|(version, entry)| (!entry.yanked, version.pre.is_empty(), version.clone()))
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| ErrorBuilder::docs().crate_not_found(request.crate_name.clone()))?,
+    };
+
+    let published = |entry: &IndexEntry| {
+        entry
+            .pubtime
+            .as_deref()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc))
+    };
+
+    let mut dependencies = Vec::new();
+    let mut dev_dependencies = Vec::new();
+    let mut build_dependencies = Vec::new();
+    for dependency in target.deps.iter().cloned().map(Dependency::from) {
+        match dependency.kind {
+            DependencyKind::Normal => dependencies.push(dependency),
+            DependencyKind::Dev => dev_dependencies.push(dependency),
+            DependencyKind::Build => build_dependencies.push(dependency),
+        }
+    }
+
+    let mut versions: Vec<(Option<SemVersion>, VersionInfo)> = entries
+        .iter()
+        .map(|entry| {
+            let info = VersionInfo {
+                num: entry.vers.clone(),
+                // Older index entries carry no publish time
+                created_at: published(entry).unwrap_or(DateTime::UNIX_EPOCH),
+                yanked: entry.yanked,
+                rust_version: entry.rust_version.clone(),
+                downloads: 0,
+                features: entry.all_features(),
+            };
+            (parse(entry), info)
+        })
+        .collect();
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+
+    Ok(CrateMetadata {
+        name: target.name.clone(),
+        version: target.vers.clone(),
+        description: None,
+        license: None,
+        repository: None,
+        homepage: None,
+        documentation: build_basic_docs_url(docs_base, &target.name),
+        authors: Vec::new(),
+        keywords: Vec::new(),
+        categories: Vec::new(),
+        downloads: DownloadStats {
+            total: 0,
+            version: 0,
+            recent: 0,
+        },
+        versions: versions.into_iter().map(|(_, info)| info).collect(),
+        dependencies,
+        dev_dependencies,
+        build_dependencies,
+        features: target.all_features(),
+        rust_version: target.rust_version.clone(),
+        created_at: entries.iter().filter_map(published).min(),
+        updated_at: entries.iter().filter_map(published).max(),
+    })
 }
 
 #[cfg(test)]
@@ -472,6 +678,52 @@ mod tests {
         assert_eq!(key2.version, Some("1.0.0".to_string()));
     }
 
+    #[test]
+    fn test_metadata_from_index() {
+        let index = r#"{"name":"demo","vers":"0.1.0","deps":[],"features":{},"yanked":false,"pubtime":"2024-01-01T00:00:00Z"}
+{"name":"demo","vers":"0.2.0","deps":[{"name":"serde1","package":"serde","req":"^1","features":[],"optional":true,"default_features":false,"target":null,"kind":"normal"},{"name":"tempfile","req":"^3","features":[],"optional":false,"default_features":true,"target":null,"kind":"dev"}],"features":{"std":[]},"yanked":false,"rust_version":"1.70","pubtime":"2024-06-01T00:00:00Z"}
+{"name":"demo","vers":"0.3.0-beta.1","deps":[],"features":{},"yanked":false}
+{"name":"demo","vers":"0.4.0","deps":[],"features":{},"yanked":true}
+"#;
+        let entries = parse_index_file(index).unwrap();
+        let crate_name = rustacean_docs_core::CrateName::new("demo").unwrap();
+
+        let latest = metadata_from_index(
+            entries.clone(),
+            &CrateMetadataRequest::new(crate_name.clone()),
+            "https://docs.example.com",
+        )
+        .unwrap();
+        assert_eq!(latest.version, "0.2.0");
+        assert_eq!(latest.rust_version.as_deref(), Some("1.70"));
+        assert_eq!(latest.dependencies[0].name, "serde");
+        assert!(!latest.dependencies[0].default_features);
+        assert_eq!(latest.dev_dependencies.len(), 1);
+        assert_eq!(
+            latest.documentation.unwrap().as_str(),
+            "https://docs.example.com/demo"
+        );
+        let versions: Vec<&str> = latest.versions.iter().map(|v| v.num.as_str()).collect();
+        assert_eq!(versions, vec!["0.4.0", "0.3.0-beta.1", "0.2.0", "0.1.0"]);
+        assert_eq!(
+            latest.updated_at.unwrap().to_rfc3339(),
+            "2024-06-01T00:00:00+00:00"
+        );
+
+        let pinned = CrateMetadataRequest::with_version(
+            crate_name.clone(),
+            rustacean_docs_core::Version::new("0.1.0").unwrap(),
+        );
+        let metadata = metadata_from_index(entries.clone(), &pinned, "https://docs.rs").unwrap();
+        assert_eq!(metadata.version, "0.1.0");
+
+        let missing = CrateMetadataRequest::with_version(
+            crate_name,
+            rustacean_docs_core::Version::new("9.0.0").unwrap(),
+        );
+        assert!(metadata_from_index(entries, &missing, "https://docs.rs").is_err());
+    }
+
     #[tokio::test]
     async fn test_dependency_transformation() {
         let crates_dep = CratesIoDependency {
@@ -552,4 +804,80 @@ mod tests {
         // Basic verification that service can be created
         // Test passes by completing without panic
     }
+
+    fn version_info(num: &str, yanked: bool) -> VersionInfo {
+        VersionInfo {
+            num: num.to_string(),
+            created_at: DateTime::UNIX_EPOCH,
+            yanked,
+            rust_version: None,
+            downloads: 0,
+            features: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_version() {
+        let versions = vec![
+            version_info("1.0.0", false),
+            version_info("1.2.0", true),
+            version_info("1.1.0", false),
+            version_info("2.0.0-rc.1", false),
+        ];
+        let req = VersionReq::parse("1").unwrap();
+        assert_eq!(
+            select_version(&versions, &req),
+            Some(SemVersion::new(1, 1, 0))
+        );
+        assert_eq!(
+            select_version(&versions, &VersionReq::STAR),
+            Some(SemVersion::new(1, 1, 0))
+        );
+        assert_eq!(
+            select_version(&versions, &VersionReq::parse(">=2.0.0-rc.1").unwrap()),
+            Some(SemVersion::parse("2.0.0-rc.1").unwrap())
+        );
+        assert_eq!(
+            select_version(&versions, &VersionReq::parse("3").unwrap()),
+            None
+        );
+    }
+
+    #[cfg(feature = "integration-tests")]
+    mod integration_tests {
+        use super::*;
+        use crate::registry::Registry;
+        use mockito::Server;
+
+        #[tokio::test]
+        async fn test_resolve_version_requirement() {
+            let mut server = Server::new_async().await;
+            let index = r#"{"name":"demo","vers":"1.1.0","deps":[],"features":{},"yanked":false}
+{"name":"demo","vers":"1.2.0","deps":[],"features":{},"yanked":false}
+{"name":"demo","vers":"1.3.0","deps":[],"features":{},"yanked":true}
+{"name":"demo","vers":"2.0.0","deps":[],"features":{},"yanked":false}
+"#;
+            server
+                .mock("GET", "/de/mo/demo")
+                .with_body(index)
+                .create_async()
+                .await;
+
+            let client = create_test_client()
+                .with_registry(Registry::new("test", server.url()).with_index_url(server.url()));
+            let service = MetadataService::new(client);
+            let crate_name = CrateName::new("demo").unwrap();
+
+            let version = service
+                .resolve_version_requirement(&crate_name, &VersionReq::parse("^1.1").unwrap())
+                .await
+                .unwrap();
+            assert_eq!(version.as_str(), "1.2.0");
+
+            let missing = service
+                .resolve_version_requirement(&crate_name, &VersionReq::parse("~3").unwrap())
+                .await;
+            assert!(missing.is_err());
+        }
+    }
 }
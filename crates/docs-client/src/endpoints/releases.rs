@@ -1,10 +1,11 @@
 use crate::{
     client::DocsClient,
+    endpoints::common::{cache_response, invalidate_service_cache},
     endpoints::docs_modules::cache_keys::RecentReleasesCacheKey,
-    error_handling::{build_docs_url, handle_http_response, parse_json_response},
+    error_handling::{build_docs_url, parse_json_response},
 };
 use chrono::{DateTime, Utc};
-use rustacean_docs_cache::{Cache, MemoryCache};
+use rustacean_docs_cache::{Cache, CacheScope, MemoryCache};
 use rustacean_docs_core::{
     error::Error,
     models::docs::{CrateRelease, RecentReleasesRequest, RecentReleasesResponse},
@@ -59,7 +60,7 @@ impl ReleasesService {
         cache_capacity: usize,
         cache_ttl: Duration,
     ) -> Self {
-        let cache = Arc::new(MemoryCache::new(cache_capacity));
+        let cache = Arc::new(MemoryCache::new(cache_capacity).with_ttl(cache_ttl));
 
         debug!(
             cache_capacity = cache_capacity,
@@ -92,7 +93,7 @@ impl ReleasesService {
         let response = RecentReleasesResponse { releases };
 
         // Store in cache for future requests
-        let _ = self.cache.insert(cache_key, response.clone()).await;
+        cache_response(&self.cache, cache_key, &response).await;
 
         debug!(
             limit = request.limit(),
@@ -107,26 +108,19 @@ impl ReleasesService {
         &self,
         request: &RecentReleasesRequest,
     ) -> Result<Vec<CrateRelease>, Error> {
-        // Use crates.io API to get recently updated crates
-        let url = format!(
-            "https://crates.io/api/v1/crates?sort=recent-updates&per_page={}",
+        // Use the registry API to get recently updated crates
+        let path = format!(
+            "/api/v1/crates?sort=recent-updates&per_page={}",
             request.limit()
         );
 
-        debug!("Requesting recent releases from: {}", url);
+        debug!("Requesting recent releases from: {}", path);
 
         let response = self
             .client
-            .inner_client()
-            .get(&url)
-            .send()
+            .get_api(&path, "registry recent releases")
             .await
-            .map_err(|e| {
-                error!("Failed to fetch recent releases from crates.io: {}", e);
-                rustacean_docs_core::Error::from(rustacean_docs_core::NetworkError::from(e))
-            })?;
-
-        let response = handle_http_response(response, "crates.io recent releases").await?;
+            .inspect_err(|e| error!("Failed to fetch recent releases from registry: {}", e))?;
         let crates_io_response: CratesIoRecentResponse =
             parse_json_response(response, "crates.io recent releases").await?;
 
@@ -163,8 +157,12 @@ impl ReleasesService {
             })?
             .with_timezone(&Utc);
 
-        // Generate docs.rs URL for this crate and version
-        let docs_url = build_docs_url(&crate_data.name, &crate_data.version)?;
+        // Generate the docs URL for this crate and version
+        let docs_url = build_docs_url(
+            self.client.base_url(),
+            &crate_data.name,
+            &crate_data.version,
+        )?;
 
         Ok(CrateRelease {
             name: crate_data.name,
@@ -185,6 +183,11 @@ impl ReleasesService {
         let _ = self.cache.clear().await;
         Ok(())
     }
+
+    /// Remove the cached releases within `scope`, returning how many were removed
+    pub async fn invalidate_cache(&self, scope: &CacheScope) -> usize {
+        invalidate_service_cache(&self.cache, scope).await
+    }
 }
 
 #[cfg(test)]
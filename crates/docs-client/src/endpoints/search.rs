@@ -1,12 +1,13 @@
 use crate::{
     client::DocsClient,
-    error_handling::{build_docs_url, handle_http_response, parse_json_response},
+    endpoints::common::{cache_response, invalidate_service_cache, ServiceFlights},
+    error_handling::{build_docs_url, parse_json_response},
 };
 use chrono::{DateTime, Utc};
-use rustacean_docs_cache::{Cache, MemoryCache};
+use rustacean_docs_cache::{Cache, CacheScope, MemoryCache};
 use rustacean_docs_core::{
     models::search::{CrateSearchResult, SearchRequest, SearchResponse},
-    Result, DEFAULT_VERSION,
+    Error, Result, DEFAULT_VERSION,
 };
 use serde::{Deserialize, Serialize};
 use std::{hash::Hash, sync::Arc, time::Duration};
@@ -50,12 +51,13 @@ impl SearchCacheKey {
 pub struct SearchService {
     client: DocsClient,
     cache: Arc<MemoryCache<SearchCacheKey, SearchResponse>>,
+    flights: ServiceFlights<SearchCacheKey, SearchResponse>,
 }
 
 impl SearchService {
     /// Create a new search service with cache
     pub fn new(client: DocsClient, cache_capacity: usize, cache_ttl: Duration) -> Self {
-        let cache = Arc::new(MemoryCache::new(cache_capacity));
+        let cache = Arc::new(MemoryCache::new(cache_capacity).with_ttl(cache_ttl));
 
         debug!(
             cache_capacity = cache_capacity,
@@ -63,7 +65,11 @@ impl SearchService {
             "Created search service with cache"
         );
 
-        Self { client, cache }
+        Self {
+            client,
+            cache,
+            flights: ServiceFlights::new(),
+        }
     }
 
     /// Search for crates with caching
@@ -86,11 +92,24 @@ impl SearchService {
             "Search cache miss, fetching from API"
         );
 
-        // Cache miss - fetch from API
+        self.flights
+            .run(cache_key.clone(), || async {
+                self.load_search(request, cache_key).await.map_err(Arc::new)
+            })
+            .await
+            .map_err(Error::shared)
+    }
+
+    /// Run a search against the API and cache the response
+    async fn load_search(
+        &self,
+        request: SearchRequest,
+        cache_key: SearchCacheKey,
+    ) -> Result<SearchResponse> {
         let response = self.client.search_crates(request).await?;
 
         // Store in cache for future requests
-        let _ = self.cache.insert(cache_key, response.clone()).await;
+        cache_response(&self.cache, cache_key, &response).await;
 
         debug!(
             query = %response.results.first().map(|r| &r.name).unwrap_or(&"none".to_string()),
@@ -112,6 +131,11 @@ impl SearchService {
         let _ = self.cache.clear().await;
         Ok(())
     }
+
+    /// Remove the cached searches within `scope`, returning how many were removed
+    pub async fn invalidate_cache(&self, scope: &CacheScope) -> usize {
+        invalidate_service_cache(&self.cache, scope).await
+    }
 }
 
 /// Individual crate data from crates.io API
@@ -178,28 +202,13 @@ pub async fn search_crates_impl(
         query = %request.query,
         limit = limit,
         path = %path,
-        "Searching crates via crates.io API"
+        registry = %client.registry().name,
+        "Searching crates via registry API"
     );
 
-    // Make the API request using crates.io base URL
-    let crates_io_url = "https://crates.io";
-    let full_url = format!("{crates_io_url}{path}");
-
-    let response = client
-        .inner_client()
-        .get(&full_url)
-        .send()
-        .await
-        .map_err(|e| {
-            rustacean_docs_core::error::ErrorBuilder::network().http_request(
-                format!("Failed to send search request to crates.io: {e}"),
-                None,
-            )
-        })?;
-
-    let response = handle_http_response(response, "crates.io search").await?;
+    let response = client.get_api(&path, "registry search").await?;
     let crates_io_response: CratesIoSearchResponse =
-        parse_json_response(response, "crates.io search results").await?;
+        parse_json_response(response, "registry search results").await?;
 
     debug!(
         query = %request.query,
@@ -209,7 +218,7 @@ pub async fn search_crates_impl(
     );
 
     // Transform the crates.io response to our internal format
-    let search_results = transform_search_results(crates_io_response.crates)?;
+    let search_results = transform_search_results(crates_io_response.crates, client.base_url())?;
 
     let response = SearchResponse::with_total(search_results, crates_io_response.meta.total);
 
@@ -217,18 +226,21 @@ pub async fn search_crates_impl(
 }
 
 impl DocsClient {
-    /// Search for Rust crates using the crates.io API
+    /// Search for Rust crates using the registry's crates.io-compatible API
     pub async fn search_crates(&self, request: SearchRequest) -> Result<SearchResponse> {
         search_crates_impl(self, request).await
     }
 }
 
 /// Transform crates.io API response to our internal search result format
-fn transform_search_results(crates: Vec<CratesIoCrate>) -> Result<Vec<CrateSearchResult>> {
+fn transform_search_results(
+    crates: Vec<CratesIoCrate>,
+    docs_base: &str,
+) -> Result<Vec<CrateSearchResult>> {
     let mut results = Vec::with_capacity(crates.len());
 
     for crate_data in crates {
-        let result = transform_crate_data(crate_data)?;
+        let result = transform_crate_data(crate_data, docs_base)?;
         results.push(result);
     }
 
@@ -236,7 +248,7 @@ fn transform_search_results(crates: Vec<CratesIoCrate>) -> Result<Vec<CrateSearc
 }
 
 /// Transform a single crate from crates.io format to our internal format
-fn transform_crate_data(crate_data: CratesIoCrate) -> Result<CrateSearchResult> {
+fn transform_crate_data(crate_data: CratesIoCrate, docs_base: &str) -> Result<CrateSearchResult> {
     // Parse repository URL if present
     let repository = match crate_data.repository {
         Some(ref repo_str) if !repo_str.is_empty() => match Url::parse(repo_str) {
@@ -272,7 +284,11 @@ fn transform_crate_data(crate_data: CratesIoCrate) -> Result<CrateSearchResult>
     };
 
     // Generate docs.rs URL with version
-    let docs_url = Some(build_docs_url(&crate_data.name, DEFAULT_VERSION)?);
+    let docs_url = Some(build_docs_url(
+        docs_base,
+        &crate_data.name,
+        DEFAULT_VERSION,
+    )?);
 
     Ok(CrateSearchResult {
         name: crate_data.name,
@@ -291,6 +307,7 @@ fn transform_crate_data(crate_data: CratesIoCrate) -> Result<CrateSearchResult>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::registry::DOCS_RS_URL;
     use chrono::Utc;
     use serde_json::json;
     use std::time::Duration;
@@ -332,7 +349,7 @@ mod tests {
         crate_data.keywords = Some(vec!["async".to_string(), "io".to_string()]);
         crate_data.categories = Some(vec!["asynchronous".to_string()]);
 
-        let result = transform_crate_data(crate_data.clone()).unwrap();
+        let result = transform_crate_data(crate_data.clone(), DOCS_RS_URL).unwrap();
 
         assert_eq!(result.name, "tokio");
         assert_eq!(result.version, "1.0.0");
@@ -356,7 +373,7 @@ mod tests {
     fn test_transform_crate_data_minimal() {
         let crate_data = create_test_crate("minimal", "0.1.0");
 
-        let result = transform_crate_data(crate_data).unwrap();
+        let result = transform_crate_data(crate_data, DOCS_RS_URL).unwrap();
 
         assert_eq!(result.name, "minimal");
         assert_eq!(result.version, "0.1.0");
@@ -379,7 +396,7 @@ mod tests {
         crate_data.repository = Some("not-a-valid-url".to_string());
         crate_data.homepage = Some("also-not-valid".to_string());
 
-        let result = transform_crate_data(crate_data).unwrap();
+        let result = transform_crate_data(crate_data, DOCS_RS_URL).unwrap();
 
         assert_eq!(result.name, "badurls");
         assert_eq!(result.repository, None); // Should be None due to invalid URL
@@ -393,7 +410,7 @@ mod tests {
         crate_data.repository = Some("".to_string());
         crate_data.homepage = Some("".to_string());
 
-        let result = transform_crate_data(crate_data).unwrap();
+        let result = transform_crate_data(crate_data, DOCS_RS_URL).unwrap();
 
         assert_eq!(result.name, "emptyurls");
         assert_eq!(result.repository, None); // Should be None due to empty string
@@ -412,7 +429,7 @@ mod tests {
 
         let crates = vec![crate1, crate2];
 
-        let results = transform_search_results(crates).unwrap();
+        let results = transform_search_results(crates, DOCS_RS_URL).unwrap();
 
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].name, "crate1");
@@ -591,7 +608,8 @@ mod tests {
             let response = client.inner_client().get(&full_url).send().await.unwrap();
 
             let crates_io_response: CratesIoSearchResponse = response.json().await.unwrap();
-            let search_results = transform_search_results(crates_io_response.crates).unwrap();
+            let search_results =
+                transform_search_results(crates_io_response.crates, DOCS_RS_URL).unwrap();
             let final_response =
                 SearchResponse::with_total(search_results, crates_io_response.meta.total);
 
@@ -640,7 +658,8 @@ mod tests {
             let response = client.inner_client().get(&full_url).send().await.unwrap();
 
             let crates_io_response: CratesIoSearchResponse = response.json().await.unwrap();
-            let search_results = transform_search_results(crates_io_response.crates).unwrap();
+            let search_results =
+                transform_search_results(crates_io_response.crates, DOCS_RS_URL).unwrap();
             let final_response =
                 SearchResponse::with_total(search_results, crates_io_response.meta.total);
 
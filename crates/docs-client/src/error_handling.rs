@@ -1,4 +1,4 @@
-use reqwest::Response;
+use reqwest::{header, Response};
 use rustacean_docs_core::{ErrorBuilder, ErrorContext, Result};
 use tracing::warn;
 use url::Url;
@@ -7,6 +7,7 @@ use url::Url;
 pub(crate) async fn handle_http_response(response: Response, context: &str) -> Result<Response> {
     let status = response.status();
     let url = response.url().clone();
+    let retry_after = retry_after_secs(&response);
 
     if status.is_success() {
         Ok(response)
@@ -22,7 +23,7 @@ pub(crate) async fn handle_http_response(response: Response, context: &str) -> R
         let error = match status.as_u16() {
             404 => ErrorBuilder::docs()
                 .crate_not_found(rustacean_docs_core::CrateName::new("unknown").unwrap()),
-            429 => ErrorBuilder::network().rate_limit(None),
+            429 => ErrorBuilder::network().rate_limit(retry_after),
             _ => ErrorBuilder::network().http_request(
                 format!("{context}: HTTP {status}: {body}"),
                 Some(status.as_u16()),
@@ -32,6 +33,18 @@ pub(crate) async fn handle_http_response(response: Response, context: &str) -> R
     }
 }
 
+/// Seconds a response's `Retry-After` header asks to wait, given either as
+/// a number of seconds or as an HTTP date
+pub(crate) fn retry_after_secs(response: &Response) -> Option<u64> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+    if let Ok(secs) = value.trim().parse() {
+        return Some(secs);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let secs = (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(secs.max(0) as u64)
+}
+
 /// Standard JSON parsing with context
 pub(crate) async fn parse_json_response<T>(response: Response, context: &str) -> Result<T>
 where
@@ -42,29 +55,31 @@ where
     })
 }
 
-/// Build docs.rs URL with standard error handling
-pub(crate) fn build_docs_url(crate_name: &str, version: &str) -> Result<Url> {
-    Url::parse(&format!(
-        "https://docs.rs/{crate_name}/{version}/{crate_name}/"
-    ))
-    .context("Failed to construct docs.rs URL")
+/// Build a crate's docs URL on a docs.rs-style host with standard error handling
+pub(crate) fn build_docs_url(docs_base: &str, crate_name: &str, version: &str) -> Result<Url> {
+    Url::parse(&format!("{docs_base}/{crate_name}/{version}/{crate_name}/"))
+        .context("Failed to construct docs URL")
 }
 
-/// Build docs.rs URL for specific item with standard error handling
-pub(crate) fn build_item_docs_url(crate_name: &str, version: &str, item_path: &str) -> Result<Url> {
+/// Build an item's docs URL on a docs.rs-style host with standard error handling
+pub(crate) fn build_item_docs_url(
+    docs_base: &str,
+    crate_name: &str,
+    version: &str,
+    item_path: &str,
+) -> Result<Url> {
     Url::parse(&format!(
-        "https://docs.rs/{crate_name}/{version}/{crate_name}/{item_path}"
+        "{docs_base}/{crate_name}/{version}/{crate_name}/{item_path}"
     ))
     .context("Failed to construct item docs URL")
 }
 
-/// Build basic docs.rs URL (no version, for documentation links)
-pub(crate) fn build_basic_docs_url(crate_name: &str) -> Option<Url> {
-    Url::parse(&format!("https://docs.rs/{crate_name}")).ok()
+/// Build basic docs URL (no version, for documentation links)
+pub(crate) fn build_basic_docs_url(docs_base: &str, crate_name: &str) -> Option<Url> {
+    Url::parse(&format!("{docs_base}/{crate_name}")).ok()
 }
 
 /// Standard cache error handling - logs but doesn't fail the operation
-#[allow(dead_code)]
 pub(crate) fn handle_cache_error(error: impl std::fmt::Display, operation: &str) {
     warn!(
         error = %error,
@@ -142,6 +157,7 @@ mod tests {
         let mock = server
             .mock("GET", "/test")
             .with_status(429)
+            .with_header("retry-after", "2")
             .with_body("rate limited")
             .create_async()
             .await;
@@ -158,8 +174,10 @@ mod tests {
 
         match result.unwrap_err() {
             rustacean_docs_core::Error::Network(network_err) => {
-                if let rustacean_docs_core::error::NetworkError::RateLimit { .. } = network_err {
-                    // Expected
+                if let rustacean_docs_core::error::NetworkError::RateLimit { retry_after } =
+                    network_err
+                {
+                    assert_eq!(retry_after, Some(2));
                 } else {
                     panic!("Expected RateLimit error, got: {network_err:?}");
                 }
@@ -172,13 +190,14 @@ mod tests {
 
     #[test]
     fn test_build_docs_url() {
-        let url = build_docs_url("serde", "1.0.0").unwrap();
+        let url = build_docs_url("https://docs.rs", "serde", "1.0.0").unwrap();
         assert_eq!(url.as_str(), "https://docs.rs/serde/1.0.0/serde/");
     }
 
     #[test]
     fn test_build_item_docs_url() {
-        let url = build_item_docs_url("serde", "1.0.0", "trait.Serialize.html").unwrap();
+        let url = build_item_docs_url("https://docs.rs", "serde", "1.0.0", "trait.Serialize.html")
+            .unwrap();
         assert_eq!(
             url.as_str(),
             "https://docs.rs/serde/1.0.0/serde/trait.Serialize.html"
@@ -0,0 +1,382 @@
+//! Record and replay of upstream HTTP traffic
+//!
+//! In [`FixtureMode::Record`] every response the client receives is written
+//! to a fixture directory; in [`FixtureMode::Replay`] requests are answered
+//! from that directory without touching the network, and a request with no
+//! recorded response is an error. Each exchange is kept as two files named
+//! after the URL: `<name>.json` with the URL, status and headers, and
+//! `<name>.body` with the raw body.
+
+use crate::revalidation::build_response;
+use reqwest::{
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    Response, StatusCode,
+};
+use rustacean_docs_core::{Error, ErrorBuilder, NetworkError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{debug, error};
+
+/// Environment variable selecting the fixture mode
+pub const FIXTURE_MODE_ENV: &str = "RUSTACEAN_DOCS_FIXTURE_MODE";
+
+/// Environment variable naming the fixture directory
+pub const FIXTURE_DIR_ENV: &str = "RUSTACEAN_DOCS_FIXTURE_DIR";
+
+/// Fixture directory used when none is configured
+pub const DEFAULT_FIXTURE_DIR: &str = "fixtures";
+
+/// Response headers kept in fixtures; the rest vary between runs
+const RECORDED_HEADERS: [HeaderName; 4] = [
+    header::CONTENT_TYPE,
+    header::ETAG,
+    header::LAST_MODIFIED,
+    header::RETRY_AFTER,
+];
+
+/// Longest readable part of a fixture file name
+const MAX_NAME_LEN: usize = 80;
+
+/// Whether requests go to the network, are recorded, or are replayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureMode {
+    /// Talk to upstream hosts without recording anything
+    #[default]
+    Off,
+    /// Talk to upstream hosts and record every response
+    Record,
+    /// Answer requests from recorded responses only
+    Replay,
+}
+
+impl fmt::Display for FixtureMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixtureMode::Off => write!(f, "off"),
+            FixtureMode::Record => write!(f, "record"),
+            FixtureMode::Replay => write!(f, "replay"),
+        }
+    }
+}
+
+impl FromStr for FixtureMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "off" | "live" => Ok(FixtureMode::Off),
+            "record" => Ok(FixtureMode::Record),
+            "replay" => Ok(FixtureMode::Replay),
+            other => Err(format!(
+                "Unknown fixture mode '{other}' (expected off, record or replay)"
+            )),
+        }
+    }
+}
+
+/// Fixture mode and the directory fixtures are kept in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureConfig {
+    pub mode: FixtureMode,
+    pub dir: PathBuf,
+}
+
+impl FixtureConfig {
+    /// Record responses into `dir`
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: FixtureMode::Record,
+            dir: dir.into(),
+        }
+    }
+
+    /// Replay responses from `dir`
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: FixtureMode::Replay,
+            dir: dir.into(),
+        }
+    }
+
+    /// Read the configuration from [`FIXTURE_MODE_ENV`] and
+    /// [`FIXTURE_DIR_ENV`]; `None` when fixtures are off
+    pub fn from_env() -> Result<Option<Self>> {
+        let mode = match env::var(FIXTURE_MODE_ENV) {
+            Ok(mode) => mode
+                .parse::<FixtureMode>()
+                .map_err(|e| ErrorBuilder::config().invalid_field(FIXTURE_MODE_ENV, e))?,
+            Err(_) => FixtureMode::Off,
+        };
+        if mode == FixtureMode::Off {
+            return Ok(None);
+        }
+
+        let dir = env::var_os(FIXTURE_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_FIXTURE_DIR));
+        Ok(Some(Self { mode, dir }))
+    }
+}
+
+/// Status and headers of a recorded response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FixtureMeta {
+    url: String,
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+}
+
+/// Recorded responses in a fixture directory
+#[derive(Debug, Clone)]
+pub(crate) struct FixtureStore {
+    mode: FixtureMode,
+    dir: PathBuf,
+}
+
+impl FixtureStore {
+    /// A store for `config`, or `None` when fixtures are off
+    pub(crate) fn new(config: &FixtureConfig) -> Option<Self> {
+        (config.mode != FixtureMode::Off).then(|| Self {
+            mode: config.mode,
+            dir: config.dir.clone(),
+        })
+    }
+
+    pub(crate) fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    /// Write `response` to the fixture directory, returning an equivalent
+    /// response
+    pub(crate) async fn record(&self, url: &str, response: Response) -> Result<Response> {
+        let status = response.status();
+        let headers: HeaderMap = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| RECORDED_HEADERS.contains(name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::from(NetworkError::from(e)))?
+            .to_vec();
+
+        let meta = FixtureMeta {
+            url: url.to_string(),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+        };
+        let meta = serde_json::to_vec_pretty(&meta)
+            .map_err(|e| ErrorBuilder::internal(format!("Failed to encode fixture: {e}")))?;
+
+        let base = self.base_path(url);
+        if let Some(parent) = base.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| self.io_error(url, e))?;
+        }
+        tokio::fs::write(file_path(&base, "body"), &body)
+            .await
+            .map_err(|e| self.io_error(url, e))?;
+        tokio::fs::write(file_path(&base, "json"), meta)
+            .await
+            .map_err(|e| self.io_error(url, e))?;
+        debug!(url = %url, path = %base.display(), status = %status, "Recorded fixture");
+
+        build_response(url, status, headers, body)
+    }
+
+    /// The recorded response to `url`
+    pub(crate) async fn replay(&self, url: &str) -> Result<Response> {
+        let base = self.base_path(url);
+        let meta = match tokio::fs::read(file_path(&base, "json")).await {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                error!(url = %url, path = %base.display(), "No fixture recorded for request");
+                return Err(ErrorBuilder::internal(format!(
+                    "No fixture recorded for GET {url} in {} (record it with {FIXTURE_MODE_ENV}=record)",
+                    self.dir.display()
+                )));
+            }
+            Err(e) => return Err(self.io_error(url, e)),
+        };
+        let meta: FixtureMeta = serde_json::from_slice(&meta).map_err(|e| {
+            ErrorBuilder::internal(format!(
+                "Invalid fixture {}: {e}",
+                file_path(&base, "json").display()
+            ))
+        })?;
+        if meta.url != url {
+            return Err(ErrorBuilder::internal(format!(
+                "Fixture {} was recorded for {}, not {url}",
+                file_path(&base, "json").display(),
+                meta.url
+            )));
+        }
+        let body = tokio::fs::read(file_path(&base, "body"))
+            .await
+            .map_err(|e| self.io_error(url, e))?;
+
+        let status = StatusCode::from_u16(meta.status).map_err(|e| {
+            ErrorBuilder::internal(format!("Invalid status in fixture for {url}: {e}"))
+        })?;
+        let headers = meta
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_str(name).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect();
+        debug!(url = %url, status = %status, "Replayed fixture");
+
+        build_response(url, status, headers, body)
+    }
+
+    /// Fixture path of `url` without extension: the host as a directory and
+    /// a readable rendering of the path and query, suffixed with a hash of
+    /// the whole URL to keep similar URLs apart
+    fn base_path(&self, url: &str) -> PathBuf {
+        let (host, rest) = match url::Url::parse(url) {
+            Ok(parsed) => {
+                let host = match (parsed.host_str(), parsed.port()) {
+                    (Some(host), Some(port)) => format!("{host}_{port}"),
+                    (Some(host), None) => host.to_string(),
+                    (None, _) => "_".to_string(),
+                };
+                let rest = match parsed.query() {
+                    Some(query) => format!("{}?{query}", parsed.path()),
+                    None => parsed.path().to_string(),
+                };
+                (host, rest)
+            }
+            Err(_) => ("_".to_string(), url.to_string()),
+        };
+
+        let mut name: String = rest
+            .trim_start_matches('/')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_NAME_LEN)
+            .collect();
+        if name.is_empty() {
+            name.push_str("index");
+        }
+
+        self.dir
+            .join(host)
+            .join(format!("{name}-{:016x}", fnv1a(url.as_bytes())))
+    }
+
+    fn io_error(&self, url: &str, e: std::io::Error) -> Error {
+        ErrorBuilder::internal(format!(
+            "Fixture for {url} in {} is unusable: {e}",
+            self.dir.display()
+        ))
+    }
+}
+
+/// `base` with `extension` appended; fixture names may contain dots, so
+/// `Path::with_extension` would cut them short
+fn file_path(base: &Path, extension: &str) -> PathBuf {
+    let mut path = base.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases so fixture names
+/// never change
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://crates.io/api/v1/crates?q=async%20runtime&per_page=10";
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        headers.insert(header::DATE, HeaderValue::from_static("today"));
+        let response =
+            build_response(url, StatusCode::OK, headers, br#"{"crates":[]}"#.to_vec()).unwrap();
+
+        let recorder = FixtureStore::new(&FixtureConfig::record(dir.path())).unwrap();
+        let response = recorder.record(url, response).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), r#"{"crates":[]}"#);
+
+        let replayer = FixtureStore::new(&FixtureConfig::replay(dir.path())).unwrap();
+        let replayed = replayer.replay(url).await.unwrap();
+        assert_eq!(replayed.status(), StatusCode::OK);
+        assert_eq!(replayed.url().as_str(), url);
+        assert_eq!(replayed.headers()[header::CONTENT_TYPE], "application/json");
+        assert!(replayed.headers().get(header::DATE).is_none());
+        assert_eq!(replayed.text().await.unwrap(), r#"{"crates":[]}"#);
+
+        let missing = replayer
+            .replay("https://crates.io/api/v1/crates/serde")
+            .await
+            .unwrap_err();
+        assert!(missing.to_string().contains("No fixture recorded"));
+    }
+
+    #[test]
+    fn test_fixture_paths_are_readable_and_distinct() {
+        let store = FixtureStore::new(&FixtureConfig::replay("fixtures")).unwrap();
+        let path = store.base_path("https://docs.rs/serde/latest/serde/index.html");
+        assert!(path.starts_with("fixtures/docs.rs"));
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("serde_latest_serde_index.html-"));
+        assert_eq!(
+            file_path(&path, "json").file_name().unwrap().to_str(),
+            Some(format!("{name}.json").as_str())
+        );
+
+        assert_ne!(
+            store.base_path("https://crates.io/api/v1/crates/serde-json"),
+            store.base_path("https://crates.io/api/v1/crates/serde_json")
+        );
+        assert!(store
+            .base_path("http://127.0.0.1:8080/")
+            .starts_with("fixtures/127.0.0.1_8080"));
+    }
+
+    #[test]
+    fn test_fixture_mode_parsing() {
+        assert_eq!("Replay".parse::<FixtureMode>(), Ok(FixtureMode::Replay));
+        assert_eq!("record".parse::<FixtureMode>(), Ok(FixtureMode::Record));
+        assert_eq!("off".parse::<FixtureMode>(), Ok(FixtureMode::Off));
+        assert!("rewind".parse::<FixtureMode>().is_err());
+        assert_eq!(FixtureMode::Replay.to_string(), "replay");
+    }
+}
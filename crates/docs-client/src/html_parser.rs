@@ -1,4 +1,5 @@
 pub mod docs_parser;
+pub mod impl_items;
 pub mod path_resolver;
 
 use crate::config::{ApiItemPatterns, HtmlParsingConfig};
@@ -1,11 +1,15 @@
 use crate::{
     config::{ApiItemPatterns, HtmlParsingConfig},
     error_handling::{build_docs_url, build_item_docs_url},
-    html_parser::HtmlParser,
+    html_parser::{
+        impl_items::{extract_associated_items, extract_trait_impls, extract_trait_items},
+        HtmlParser,
+    },
+    registry::DOCS_RS_URL,
 };
 use rustacean_docs_core::{
     models::docs::{
-        CrateCategories, CrateDocsResponse, CrateItem, CrateRelease, CrateSummary,
+        CrateCategories, CrateDocsResponse, CrateItem, CrateRelease, CrateSummary, Deprecation,
         ItemDocsResponse, ItemKind, Visibility,
     },
     resolve_version, ErrorBuilder, Result,
@@ -16,6 +20,7 @@ use tracing::trace;
 /// Parse comprehensive crate documentation from HTML content
 pub fn parse_crate_documentation(
     html: &str,
+    docs_base: &str,
     crate_name: &str,
     version: &Option<String>,
 ) -> Result<CrateDocsResponse> {
@@ -42,7 +47,7 @@ pub fn parse_crate_documentation(
     let examples = parser.extract_code_examples();
 
     // Generate docs URL
-    let docs_url = Some(build_docs_url(crate_name, &actual_version)?);
+    let docs_url = Some(build_docs_url(docs_base, crate_name, &actual_version)?);
 
     Ok(CrateDocsResponse {
         name: crate_name.to_string(),
@@ -58,6 +63,7 @@ pub fn parse_crate_documentation(
 /// Parse specific item documentation from HTML content
 pub fn parse_item_documentation(
     html: &str,
+    docs_base: &str,
     crate_name: &str,
     item_path: &str,
     version: &Option<String>,
@@ -85,9 +91,27 @@ pub fn parse_item_documentation(
     // Extract related items
     let related_items = extract_related_items(document);
 
+    // Extract methods, trait impls and trait items
+    let (associated_items, trait_impls) = match kind {
+        ItemKind::Struct | ItemKind::Enum | ItemKind::Union => (
+            extract_associated_items(document),
+            extract_trait_impls(document),
+        ),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let (required_items, provided_items) = match kind {
+        ItemKind::Trait => extract_trait_items(document),
+        _ => (Vec::new(), Vec::new()),
+    };
+
     // Generate docs URL
     let actual_version = resolve_version(version.clone());
-    let docs_url = Some(build_item_docs_url(crate_name, &actual_version, item_path)?);
+    let docs_url = Some(build_item_docs_url(
+        docs_base,
+        crate_name,
+        &actual_version,
+        item_path,
+    )?);
 
     Ok(ItemDocsResponse {
         crate_name: crate_name.to_string(),
@@ -99,6 +123,10 @@ pub fn parse_item_documentation(
         examples,
         docs_url,
         related_items,
+        associated_items,
+        trait_impls,
+        required_items,
+        provided_items,
     })
 }
 
@@ -130,6 +158,7 @@ pub fn parse_recent_releases(html: &str, limit: usize) -> Result<Vec<CrateReleas
 fn parse_navigation_items(parser: &HtmlParser) -> Result<Vec<CrateItem>> {
     let api_links = parser.extract_api_links();
     let summaries = extract_item_summaries_from_page(parser);
+    let deprecated = extract_deprecated_items_from_page(parser);
     let mut items = Vec::new();
 
     for (text, href) in api_links {
@@ -140,6 +169,11 @@ fn parse_navigation_items(parser: &HtmlParser) -> Result<Vec<CrateItem>> {
             item.summary = Some(summary.clone());
         }
 
+        // The item tables only carry a marker; since and note are on the item page
+        if deprecated.contains(&item.name) || deprecated.contains(&href) {
+            item.deprecation = Some(Deprecation::default());
+        }
+
         trace!(name = %item.name, kind = ?item.kind, path = %item.path, summary = ?item.summary, "Extracted API item");
         items.push(item);
     }
@@ -188,6 +222,34 @@ fn extract_item_summaries_from_page(
     summaries
 }
 
+/// Collect the names and links of items marked deprecated in the item tables
+fn extract_deprecated_items_from_page(parser: &HtmlParser) -> std::collections::HashSet<String> {
+    let mut deprecated = std::collections::HashSet::new();
+
+    let (Ok(dt_selector), Ok(link_selector), Ok(marker_selector)) = (
+        Selector::parse(".item-table dt"),
+        Selector::parse("a"),
+        Selector::parse(".stab.deprecated"),
+    ) else {
+        return deprecated;
+    };
+
+    for dt in parser.document().select(&dt_selector) {
+        if dt.select(&marker_selector).next().is_none() {
+            continue;
+        }
+        if let Some(link_element) = dt.select(&link_selector).next() {
+            let name = link_element.text().collect::<String>();
+            deprecated.insert(normalize_item_name(&name));
+            if let Some(href) = link_element.value().attr("href") {
+                deprecated.insert(href.to_string());
+            }
+        }
+    }
+
+    deprecated
+}
+
 /// Create a CrateItem from extracted link text and href
 fn create_crate_item_from_link(name: String, path: String) -> CrateItem {
     // Clean up the item name by removing module paths and normalizing text
@@ -205,6 +267,7 @@ fn create_crate_item_from_link(name: String, path: String) -> CrateItem {
         is_async: false,                // Would need more analysis to determine
         signature: None,
         docs_path: Some(path),
+        deprecation: None,
     }
 }
 
@@ -286,7 +349,10 @@ fn infer_item_kind(path: &str, _name: &str) -> ItemKind {
 }
 
 /// Generate crate summary from parsed items
-fn generate_crate_summary(items: &[CrateItem], description: Option<String>) -> CrateSummary {
+pub(crate) fn generate_crate_summary(
+    items: &[CrateItem],
+    description: Option<String>,
+) -> CrateSummary {
     let mut module_count = 0;
     let mut struct_count = 0;
     let mut trait_count = 0;
@@ -316,7 +382,7 @@ fn generate_crate_summary(items: &[CrateItem], description: Option<String>) -> C
 }
 
 /// Categorize items by type
-fn categorize_items(items: &[CrateItem]) -> CrateCategories {
+pub(crate) fn categorize_items(items: &[CrateItem]) -> CrateCategories {
     let mut core_types = Vec::new();
     let mut traits = Vec::new();
     let mut modules = Vec::new();
@@ -536,7 +602,7 @@ fn extract_release_info(element: &scraper::ElementRef) -> Option<CrateRelease> {
     };
 
     // Generate docs URL
-    let docs_url = build_docs_url(&name, &version).ok();
+    let docs_url = build_docs_url(DOCS_RS_URL, &name, &version).ok();
 
     Some(CrateRelease {
         name,
@@ -616,7 +682,7 @@ fn extract_releases_fallback(document: &Html, limit: usize) -> Result<Vec<CrateR
     releases.dedup_by(|a, b| a.name == b.name && a.version == b.version);
 
     // Sort by publication date (newest first)
-    releases.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+    releases.sort_by_key(|r| std::cmp::Reverse(r.published_at));
 
     // Limit to requested amount
     releases.truncate(limit);
@@ -663,7 +729,7 @@ fn extract_release_info_fallback(element: &scraper::ElementRef) -> Option<CrateR
         None
     };
 
-    let docs_url = build_docs_url(&name, &version).ok();
+    let docs_url = build_docs_url(DOCS_RS_URL, &name, &version).ok();
 
     Some(CrateRelease {
         name,
@@ -702,3 +768,33 @@ fn extract_version_from_href(href: &str) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_crate_documentation_marks_deprecated_items() {
+        let html = r#"
+            <html>
+                <body>
+                    <dl class="item-table">
+                        <dt><a class="fn" href="fn.get.html">get</a><span class="stab deprecated">Deprecated</span></dt>
+                        <dd>Shortcut for a GET request</dd>
+                        <dt><a class="struct" href="struct.Client.html">Client</a></dt>
+                        <dd>An HTTP client</dd>
+                    </dl>
+                </body>
+            </html>
+        "#;
+
+        let docs = parse_crate_documentation(html, DOCS_RS_URL, "demo", &Some("1.0.0".to_string()))
+            .unwrap();
+
+        let get = docs.items.iter().find(|i| i.name == "get").unwrap();
+        assert_eq!(get.deprecation, Some(Deprecation::default()));
+        assert_eq!(get.summary.as_deref(), Some("Shortcut for a GET request"));
+        let client = docs.items.iter().find(|i| i.name == "Client").unwrap();
+        assert_eq!(client.deprecation, None);
+    }
+}
@@ -0,0 +1,391 @@
+use rustacean_docs_core::models::docs::{AssociatedItem, AssociatedItemKind, TraitImpl};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Section headers of a trait page listing items an implementor must define
+const REQUIRED_TRAIT_SECTIONS: &[&str] = &[
+    "required-associated-types",
+    "required-associated-consts",
+    "required-methods",
+];
+
+/// Section headers of a trait page listing items with a default
+const PROVIDED_TRAIT_SECTIONS: &[&str] = &[
+    "provided-associated-types",
+    "provided-associated-consts",
+    "provided-methods",
+];
+
+/// Extract inherent methods, associated constants and associated types from
+/// the "Implementations" section of a type page
+pub fn extract_associated_items(document: &Html) -> Vec<AssociatedItem> {
+    let selector = Selector::parse(
+        r#"#implementations-list [id^="method."], #implementations-list [id^="associatedconstant."], #implementations-list [id^="associatedtype."]"#,
+    )
+    .expect("valid selector");
+
+    document
+        .select(&selector)
+        .filter_map(associated_item_from_element)
+        .collect()
+}
+
+/// Extract trait, auto trait and blanket implementations of a type page
+pub fn extract_trait_impls(document: &Html) -> Vec<TraitImpl> {
+    let lists = [
+        ("trait-implementations-list", false, false),
+        ("blanket-implementations-list", true, false),
+        ("synthetic-implementations-list", false, true),
+    ];
+
+    let mut impls = Vec::new();
+    for (list_id, is_blanket, is_auto) in lists {
+        let Ok(selector) = Selector::parse(&format!("#{list_id} .impl .code-header")) else {
+            continue;
+        };
+        for header in document.select(&selector) {
+            if let Some(trait_impl) =
+                parse_trait_impl_header(&code_header_text(header), is_blanket, is_auto)
+            {
+                impls.push(trait_impl);
+            }
+        }
+    }
+
+    impls.dedup_by(|a, b| a.header == b.header);
+    impls
+}
+
+/// Extract the required and provided items of a trait page
+pub fn extract_trait_items(document: &Html) -> (Vec<AssociatedItem>, Vec<AssociatedItem>) {
+    (
+        trait_section_items(document, REQUIRED_TRAIT_SECTIONS),
+        trait_section_items(document, PROVIDED_TRAIT_SECTIONS),
+    )
+}
+
+fn trait_section_items(document: &Html, section_ids: &[&str]) -> Vec<AssociatedItem> {
+    let item_selector = Selector::parse("[id]").expect("valid selector");
+    let mut items = Vec::new();
+
+    for section_id in section_ids {
+        let Ok(header_selector) = Selector::parse(&format!("h2#{section_id}")) else {
+            continue;
+        };
+        let Some(header) = document.select(&header_selector).next() else {
+            continue;
+        };
+        // rustdoc lists the items in the element following the section header
+        let Some(list) = header.next_siblings().find_map(ElementRef::wrap) else {
+            continue;
+        };
+        items.extend(
+            list.select(&item_selector)
+                .filter_map(associated_item_from_element),
+        );
+    }
+
+    items
+}
+
+/// Build an associated item from an element whose id is its rustdoc anchor
+fn associated_item_from_element(element: ElementRef) -> Option<AssociatedItem> {
+    let anchor = element.value().id()?;
+    let (prefix, name) = anchor.split_once('.')?;
+    let kind = match prefix {
+        "method" | "tymethod" => AssociatedItemKind::Method,
+        "associatedconstant" => AssociatedItemKind::Constant,
+        "associatedtype" => AssociatedItemKind::Type,
+        _ => return None,
+    };
+
+    let header_selector = Selector::parse(".code-header").expect("valid selector");
+    let signature = element
+        .select(&header_selector)
+        .next()
+        .map(code_header_text)
+        .unwrap_or_else(|| code_header_text(element));
+    let signature = (!signature.is_empty()).then_some(signature);
+    let is_async = signature
+        .as_deref()
+        .is_some_and(|s| s.split_whitespace().any(|word| word == "async"));
+
+    Some(AssociatedItem {
+        name: name.to_string(),
+        kind,
+        signature,
+        summary: item_summary(element),
+        anchor: anchor.to_string(),
+        is_async,
+    })
+}
+
+/// First paragraph of the docblock belonging to an item.
+///
+/// Current rustdoc wraps the item header in `<details><summary>` with the
+/// docblock as a sibling of the summary; older versions put the docblock
+/// right after the header.
+fn item_summary(element: ElementRef) -> Option<String> {
+    let paragraph = Selector::parse("p").expect("valid selector");
+    let is_docblock = |e: &ElementRef| e.value().classes().any(|c| c == "docblock");
+
+    let details = element
+        .ancestors()
+        .take(2)
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().name() == "details");
+    let docblock = match details {
+        Some(details) => details
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(is_docblock),
+        None => element
+            .next_siblings()
+            .find_map(ElementRef::wrap)
+            .filter(is_docblock),
+    }?;
+
+    let text = docblock
+        .select(&paragraph)
+        .next()?
+        .text()
+        .collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Text of a code header with whitespace normalized. rustdoc renders the
+/// `where` clause as a separate block without a leading space.
+fn code_header_text(element: ElementRef) -> String {
+    let mut text = String::new();
+    for node in element.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.classes().any(|c| c == "where") => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(',')
+        .to_string()
+}
+
+/// Split an impl header (`impl<T: Clone> From<T> for Wrapper<T> where T: Send`)
+/// into the trait name and its generic bounds
+pub fn parse_trait_impl_header(header: &str, is_blanket: bool, is_auto: bool) -> Option<TraitImpl> {
+    let rest = header.strip_prefix("unsafe ").unwrap_or(header);
+    let rest = rest.strip_prefix("impl")?;
+
+    let (params, rest) = match rest.strip_prefix('<') {
+        Some(inner) => {
+            let end = matching_angle_bracket(inner)?;
+            (&inner[..end], &inner[end + 1..])
+        }
+        None => ("", rest),
+    };
+    let (declaration, where_clause) = match rest.split_once(" where ") {
+        Some((declaration, where_clause)) => (declaration, where_clause),
+        None => (rest, ""),
+    };
+    let (trait_name, _) = declaration.trim().split_once(" for ")?;
+
+    let bounds = split_top_level(params)
+        .into_iter()
+        .filter(|param| param.contains(':'))
+        .chain(split_top_level(where_clause))
+        .collect();
+
+    Some(TraitImpl {
+        trait_name: trait_name.trim().to_string(),
+        header: header.to_string(),
+        bounds,
+        is_blanket,
+        is_auto,
+    })
+}
+
+/// Byte index of the `>` closing an already opened `<`
+fn matching_angle_bracket(text: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if index > 0 && text[..index].ends_with('-') => {}
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a comma separated list, ignoring commas nested in brackets
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' if !current.ends_with('-') => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRUCT_PAGE: &str = r##"<html><body>
+<h2 id="implementations" class="section-header">Implementations</h2>
+<div id="implementations-list">
+  <details class="toggle implementors-toggle" open>
+    <summary><section id="impl-TcpStream" class="impl"><h3 class="code-header">impl <a class="struct">TcpStream</a></h3></section></summary>
+    <div class="impl-items">
+      <details class="toggle method-toggle" open>
+        <summary><section id="method.connect" class="method"><h4 class="code-header">pub async fn <a href="#method.connect" class="fn">connect</a>&lt;A: ToSocketAddrs&gt;(addr: A) -&gt; Result&lt;TcpStream&gt;</h4></section></summary>
+        <div class="docblock"><p>Opens a TCP connection
+        to a remote host.</p><p>More text.</p></div>
+      </details>
+      <section id="associatedconstant.MAX" class="associatedconstant"><h4 class="code-header">pub const MAX: usize</h4></section>
+    </div>
+  </details>
+</div>
+<h2 id="trait-implementations" class="section-header">Trait Implementations</h2>
+<div id="trait-implementations-list">
+  <details class="toggle implementors-toggle"><summary><section id="impl-AsRawFd-for-TcpStream" class="impl"><h3 class="code-header">impl <a class="trait">AsRawFd</a> for <a class="struct">TcpStream</a></h3></section></summary>
+    <div class="impl-items"><section id="method.as_raw_fd" class="method trait-impl"><h4 class="code-header">fn as_raw_fd(&amp;self) -&gt; RawFd</h4></section></div>
+  </details>
+</div>
+<h2 id="synthetic-implementations" class="section-header">Auto Trait Implementations</h2>
+<div id="synthetic-implementations-list">
+  <section id="impl-Send-for-TcpStream" class="impl"><h3 class="code-header">impl Send for TcpStream</h3></section>
+</div>
+<h2 id="blanket-implementations" class="section-header">Blanket Implementations</h2>
+<div id="blanket-implementations-list">
+  <section id="impl-Any-for-T" class="impl"><h3 class="code-header">impl&lt;T&gt; Any for T<div class="where">where
+    T: 'static + ?Sized,</div></h3></section>
+  <section id="impl-From%3CT%3E-for-T" class="impl"><h3 class="code-header">impl&lt;T: Clone&gt; From&lt;T&gt; for T</h3></section>
+</div>
+</body></html>"##;
+
+    const TRAIT_PAGE: &str = r##"<html><body>
+<h2 id="required-associated-types" class="section-header">Required Associated Types</h2>
+<div class="methods">
+  <details class="toggle" open><summary><section id="associatedtype.Item" class="method"><h4 class="code-header">type <a href="#associatedtype.Item">Item</a></h4></section></summary>
+  <div class="docblock"><p>The type of the elements.</p></div></details>
+</div>
+<h2 id="required-methods" class="section-header">Required Methods</h2>
+<div class="methods">
+  <details class="toggle method-toggle" open><summary><section id="tymethod.next" class="method"><h4 class="code-header">fn <a href="#tymethod.next">next</a>(&amp;mut self) -&gt; Option&lt;Self::Item&gt;</h4></section></summary>
+  <div class="docblock"><p>Advances the iterator.</p></div></details>
+</div>
+<h2 id="provided-methods" class="section-header">Provided Methods</h2>
+<div class="methods">
+  <details class="toggle method-toggle" open><summary><section id="method.count" class="method"><h4 class="code-header">fn <a href="#method.count">count</a>(self) -&gt; usize</h4></section></summary>
+  <div class="docblock"><p>Consumes the iterator.</p></div></details>
+</div>
+</body></html>"##;
+
+    #[test]
+    fn test_extract_associated_items() {
+        let document = Html::parse_document(STRUCT_PAGE);
+        let items = extract_associated_items(&document);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "connect");
+        assert_eq!(items[0].kind, AssociatedItemKind::Method);
+        assert_eq!(
+            items[0].signature.as_deref(),
+            Some("pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<TcpStream>")
+        );
+        assert_eq!(
+            items[0].summary.as_deref(),
+            Some("Opens a TCP connection to a remote host.")
+        );
+        assert!(items[0].is_async);
+        assert_eq!(items[1].anchor, "associatedconstant.MAX");
+        assert_eq!(items[1].kind, AssociatedItemKind::Constant);
+        assert_eq!(items[1].summary, None);
+    }
+
+    #[test]
+    fn test_extract_trait_impls() {
+        let document = Html::parse_document(STRUCT_PAGE);
+        let impls = extract_trait_impls(&document);
+
+        let summary: Vec<(&str, bool, bool)> = impls
+            .iter()
+            .map(|i| (i.trait_name.as_str(), i.is_blanket, i.is_auto))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("AsRawFd", false, false),
+                ("Any", true, false),
+                ("From<T>", true, false),
+                ("Send", false, true),
+            ]
+        );
+        assert_eq!(
+            impls[1].header,
+            "impl<T> Any for T where T: 'static + ?Sized"
+        );
+        assert_eq!(impls[1].bounds, vec!["T: 'static + ?Sized".to_string()]);
+        assert_eq!(impls[2].bounds, vec!["T: Clone".to_string()]);
+    }
+
+    #[test]
+    fn test_extract_trait_items() {
+        let document = Html::parse_document(TRAIT_PAGE);
+        let (required, provided) = extract_trait_items(&document);
+
+        let required: Vec<&str> = required.iter().map(|i| i.anchor.as_str()).collect();
+        assert_eq!(required, vec!["associatedtype.Item", "tymethod.next"]);
+        assert_eq!(provided.len(), 1);
+        assert_eq!(provided[0].name, "count");
+        assert_eq!(
+            provided[0].summary.as_deref(),
+            Some("Consumes the iterator.")
+        );
+    }
+
+    #[test]
+    fn test_parse_trait_impl_header() {
+        let parsed = parse_trait_impl_header(
+            "impl<'a, T: Fn(u8) -> u8, U> Handler<T> for Wrapper<'a, U> where U: Send + Sync",
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(parsed.trait_name, "Handler<T>");
+        assert_eq!(
+            parsed.bounds,
+            vec!["T: Fn(u8) -> u8".to_string(), "U: Send + Sync".to_string()]
+        );
+
+        let negative = parse_trait_impl_header("impl !Sync for Cell", false, true).unwrap();
+        assert_eq!(negative.trait_name, "!Sync");
+        assert!(parse_trait_impl_header("impl Cell", false, false).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+//! Shared item addressing for structured documentation sources.
+//!
+//! Both the rustdoc JSON source and the local source produce a flat list of
+//! items located by their module path. This module maps those locations onto
+//! docs.rs page paths and resolves user supplied item paths against them.
+
+/// An item located inside a crate's public module tree
+pub trait IndexedItem {
+    /// Name under which the item is reachable
+    fn name(&self) -> &str;
+
+    /// Module path from the crate root, excluding the crate name
+    fn parents(&self) -> &[String];
+
+    /// rustdoc page prefix (`struct`, `fn`, `derive`, ...), or `index` for modules
+    fn page_kind(&self) -> &str;
+
+    /// Path relative to the crate root, joined with `::`
+    fn rust_path(&self) -> String {
+        let mut segments = self.parents().to_vec();
+        segments.push(self.name().to_string());
+        segments.join("::")
+    }
+
+    /// Path of the rendered HTML page relative to the crate root directory,
+    /// matching the hrefs used on docs.rs (`sync/struct.Mutex.html`)
+    fn html_path(&self) -> String {
+        let mut prefix = self.parents().join("/");
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+
+        match self.page_kind() {
+            "index" => format!("{prefix}{}/index.html", self.name()),
+            page => format!("{prefix}{page}.{}.html", self.name()),
+        }
+    }
+}
+
+/// Find an item from a user supplied path.
+///
+/// Accepts docs.rs page paths (`sync/struct.Mutex.html`), Rust paths with or
+/// without the crate prefix (`tokio::sync::Mutex`, `sync::Mutex`), and bare
+/// names (`Mutex`), preferring the shallowest match for bare names.
+pub fn find_item<'a, T: IndexedItem>(
+    items: &'a [T],
+    crate_name: &str,
+    item_path: &str,
+) -> Option<&'a T> {
+    let query = item_path.trim().trim_start_matches('/');
+
+    if query.ends_with(".html") {
+        return items.iter().find(|item| item.html_path() == query);
+    }
+
+    let crate_prefix = format!("{}::", crate_name.replace('-', "_"));
+    let query = query
+        .strip_prefix(&crate_prefix)
+        .or_else(|| query.strip_prefix("crate::"))
+        .unwrap_or(query)
+        .replace('/', "::");
+
+    if let Some(item) = items.iter().find(|item| item.rust_path() == query) {
+        return Some(item);
+    }
+
+    let name = query.rsplit("::").next().unwrap_or(&query);
+    items
+        .iter()
+        .filter(|item| item.name() == name)
+        .min_by_key(|item| item.parents().len())
+        .or_else(|| {
+            items
+                .iter()
+                .filter(|item| item.name().eq_ignore_ascii_case(name))
+                .min_by_key(|item| item.parents().len())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestItem {
+        name: String,
+        parents: Vec<String>,
+        page: &'static str,
+    }
+
+    impl IndexedItem for TestItem {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn parents(&self) -> &[String] {
+            &self.parents
+        }
+
+        fn page_kind(&self) -> &str {
+            self.page
+        }
+    }
+
+    fn item(parents: &[&str], name: &str, page: &'static str) -> TestItem {
+        TestItem {
+            name: name.to_string(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            page,
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        let mutex = item(&["sync"], "Mutex", "struct");
+        assert_eq!(mutex.rust_path(), "sync::Mutex");
+        assert_eq!(mutex.html_path(), "sync/struct.Mutex.html");
+
+        let module = item(&[], "sync", "index");
+        assert_eq!(module.html_path(), "sync/index.html");
+    }
+
+    #[test]
+    fn test_find_item() {
+        let items = vec![
+            item(&["sync"], "Mutex", "struct"),
+            item(&["sync", "raw"], "Mutex", "struct"),
+            item(&[], "spawn", "fn"),
+        ];
+
+        let by_html = find_item(&items, "my-crate", "sync/raw/struct.Mutex.html").unwrap();
+        assert_eq!(by_html.parents.len(), 2);
+
+        let by_path = find_item(&items, "my-crate", "my_crate::sync::raw::Mutex").unwrap();
+        assert_eq!(by_path.parents.len(), 2);
+
+        let by_name = find_item(&items, "my-crate", "Mutex").unwrap();
+        assert_eq!(by_name.parents.len(), 1);
+
+        let case_insensitive = find_item(&items, "my-crate", "SPAWN").unwrap();
+        assert_eq!(case_insensitive.name, "spawn");
+
+        assert!(find_item(&items, "my-crate", "Missing").is_none());
+    }
+}
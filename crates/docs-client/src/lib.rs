@@ -1,15 +1,38 @@
 pub mod client;
 pub mod config;
+pub mod crate_diff;
 pub mod endpoints;
 pub mod error_handling;
+pub mod fixtures;
 pub mod html_parser;
+pub mod item_index;
+pub mod local_source;
+pub mod project;
+pub mod rate_limit;
+pub mod registry;
+pub mod retry;
+pub mod revalidation;
+pub mod rustdoc_json;
+pub mod search_index;
 pub mod service_config;
 
 pub use client::{ClientConfig, DocsClient};
 pub use config::{ApiItemPatterns, DocsClientConfig, HtmlParsingConfig, UrlConfig};
+pub use crate_diff::diff_crate_docs;
 pub use endpoints::{
     CrateDocsCacheKey, DocsService, ItemDocsCacheKey, MetadataCacheKey, MetadataService,
     RecentReleasesCacheKey, ReleasesService, SearchCacheKey, SearchService,
 };
+pub use fixtures::{FixtureConfig, FixtureMode};
 pub use html_parser::HtmlParser;
+pub use local_source::{DocsSourceMode, LocalDocsConfig, LocalDocsSource};
+pub use project::{Lockfile, ProjectFiles, ProjectService, VersionPins};
+pub use rate_limit::{
+    HostRateLimit, RateLimitConfig, RateLimitStats, RateLimiter, RequestPriority,
+};
+pub use registry::{Registry, RegistrySet, DEFAULT_REGISTRY};
+pub use retry::{CircuitBreakerConfig, HostRetryPolicies, RetryConfig, RetryPolicy};
+pub use revalidation::{ResponseStore, StoredResponse};
+pub use rustdoc_json::RustdocJsonSource;
+pub use search_index::ItemSearchIndex;
 pub use service_config::{ServiceBuilder, ServiceConfig, ServicesRegistry};
@@ -0,0 +1,512 @@
+pub mod discovery;
+pub mod docs_builder;
+pub mod manifest;
+pub mod parser;
+
+use crate::client::DocsClient;
+use chrono::{DateTime, Utc};
+use rustacean_docs_cache::{Cache, MemoryCache};
+use rustacean_docs_core::{
+    models::{
+        docs::{CrateDocsRequest, CrateDocsResponse, ItemDocsRequest, ItemDocsResponse},
+        metadata::{CrateMetadata, CrateMetadataRequest, VersionInfo},
+    },
+    CrateName, ErrorBuilder, Result, Version,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, future::Future, path::PathBuf, str::FromStr, sync::Arc};
+use tracing::{debug, trace, warn};
+
+pub use discovery::{CrateOrigin, LocalCrateDir};
+pub use manifest::LocalManifest;
+pub use parser::{parse_crate, LocalCrate, LocalItem};
+
+/// Number of parsed registry crates kept in memory
+const PARSED_CRATE_CACHE_CAPACITY: usize = 16;
+
+/// Which documentation sources answer crate docs, item docs and metadata requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DocsSourceMode {
+    /// Only use crate sources available on disk
+    #[serde(alias = "local")]
+    LocalOnly,
+    /// Only use docs.rs and crates.io
+    #[default]
+    #[serde(alias = "remote")]
+    RemoteOnly,
+    /// Use local sources when the crate is on disk, otherwise go remote
+    LocalFirst,
+}
+
+impl fmt::Display for DocsSourceMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocsSourceMode::LocalOnly => write!(f, "local-only"),
+            DocsSourceMode::RemoteOnly => write!(f, "remote-only"),
+            DocsSourceMode::LocalFirst => write!(f, "local-first"),
+        }
+    }
+}
+
+impl FromStr for DocsSourceMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "local" | "local-only" => Ok(DocsSourceMode::LocalOnly),
+            "remote" | "remote-only" => Ok(DocsSourceMode::RemoteOnly),
+            "local-first" => Ok(DocsSourceMode::LocalFirst),
+            other => Err(format!(
+                "Unknown docs source '{other}' (expected local-only, remote-only or local-first)"
+            )),
+        }
+    }
+}
+
+/// Where local crate sources are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalDocsConfig {
+    /// Unpacked registry sources, e.g. `~/.cargo/registry/src`
+    pub registry_paths: Vec<PathBuf>,
+    /// Workspaces or project directories searched for packages
+    pub workspace_paths: Vec<PathBuf>,
+}
+
+impl Default for LocalDocsConfig {
+    fn default() -> Self {
+        Self {
+            registry_paths: default_registry_src().into_iter().collect(),
+            workspace_paths: Vec::new(),
+        }
+    }
+}
+
+/// The registry source directory of the current Cargo home
+/// (`$CARGO_HOME/registry/src`, defaulting to `~/.cargo/registry/src`)
+pub fn default_registry_src() -> Option<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))?;
+    Some(cargo_home.join("registry").join("src"))
+}
+
+/// Documentation source that answers requests from crate sources on disk:
+/// unpacked registry sources and packages in configured workspaces.
+///
+/// Sources are parsed directly, so no toolchain or build is required.
+pub struct LocalDocsSource {
+    config: LocalDocsConfig,
+    parsed_cache: MemoryCache<PathBuf, Arc<LocalCrate>>,
+}
+
+impl fmt::Debug for LocalDocsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalDocsSource")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalDocsSource {
+    /// Create a new local source searching the given paths
+    pub fn new(config: LocalDocsConfig) -> Self {
+        debug!(
+            registry_paths = ?config.registry_paths,
+            workspace_paths = ?config.workspace_paths,
+            "Created local documentation source"
+        );
+
+        Self {
+            config,
+            parsed_cache: MemoryCache::new(PARSED_CRATE_CACHE_CAPACITY),
+        }
+    }
+
+    /// Get the configured search paths
+    pub fn config(&self) -> &LocalDocsConfig {
+        &self.config
+    }
+
+    /// Find every local copy of a crate
+    pub async fn find_all(&self, crate_name: &str) -> Vec<LocalCrateDir> {
+        let crate_name = crate_name.to_string();
+        let config = self.config.clone();
+        tokio::task::spawn_blocking(move || {
+            discovery::discover_crate(&crate_name, &config.registry_paths, &config.workspace_paths)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Find the local copy of a crate matching the requested version.
+    ///
+    /// Returns `Ok(None)` when the crate isn't available locally at all, and
+    /// a version-not-found error when other versions are.
+    pub async fn find_crate(
+        &self,
+        crate_name: &CrateName,
+        version: Option<&Version>,
+    ) -> Result<Option<LocalCrateDir>> {
+        let candidates = self.find_all(crate_name.as_str()).await;
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        match discovery::select_version(&candidates, version.map(|v| v.as_str())) {
+            Some(found) => Ok(Some(found.clone())),
+            None => Err(ErrorBuilder::docs().version_not_found(
+                crate_name.clone(),
+                version.cloned().unwrap_or_else(Version::latest),
+            )),
+        }
+    }
+
+    /// Parse the public API of a local crate.
+    ///
+    /// Registry sources are immutable and cached; workspace crates are
+    /// re-parsed so edits are picked up.
+    pub async fn load_crate(&self, location: &LocalCrateDir) -> Result<Arc<LocalCrate>> {
+        let cacheable = location.origin == CrateOrigin::Registry;
+        if cacheable {
+            if let Ok(Some(krate)) = self.parsed_cache.get(&location.root).await {
+                trace!(root = %location.root.display(), "Parsed local crate cache hit");
+                return Ok(krate);
+            }
+        }
+
+        let manifest_path = location.manifest_path();
+        let root = location.root.clone();
+        let krate = tokio::task::spawn_blocking(move || {
+            let manifest = LocalManifest::load(&manifest_path)?;
+            parse_crate(&root.join(manifest.lib_path()))
+        })
+        .await
+        .map_err(|e| ErrorBuilder::internal(format!("Local source parsing task failed: {e}")))??;
+
+        let krate = Arc::new(krate);
+        if cacheable {
+            let _ = self
+                .parsed_cache
+                .insert(location.root.clone(), krate.clone())
+                .await;
+        }
+        Ok(krate)
+    }
+
+    /// Build crate documentation from local sources, if the crate is on disk.
+    ///
+    /// Registry crates link to their pages under `docs_base`.
+    pub async fn get_crate_docs(
+        &self,
+        request: &CrateDocsRequest,
+        docs_base: &str,
+    ) -> Result<Option<CrateDocsResponse>> {
+        let Some(location) = self
+            .find_crate(&request.crate_name, request.version.as_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let krate = self.load_crate(&location).await?;
+        docs_builder::build_crate_documentation(
+            &krate,
+            &location,
+            docs_base,
+            request.crate_name.as_str(),
+        )
+        .map(Some)
+    }
+
+    /// Build item documentation from local sources, if the crate is on disk.
+    ///
+    /// Registry crates link to their pages under `docs_base`.
+    pub async fn get_item_docs(
+        &self,
+        request: &ItemDocsRequest,
+        docs_base: &str,
+    ) -> Result<Option<ItemDocsResponse>> {
+        let Some(location) = self
+            .find_crate(&request.crate_name, request.version.as_ref())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let krate = self.load_crate(&location).await?;
+        docs_builder::build_item_documentation(
+            &krate,
+            &location,
+            docs_base,
+            request.crate_name.as_str(),
+            request.item_path.as_str(),
+        )
+        .map(Some)
+    }
+
+    /// Build crate metadata from the local manifest, if the crate is on disk.
+    ///
+    /// The version history lists the locally available versions; download
+    /// statistics are not known locally and are reported as zero.
+    pub async fn get_crate_metadata(
+        &self,
+        request: &CrateMetadataRequest,
+    ) -> Result<Option<CrateMetadata>> {
+        let candidates = self.find_all(request.crate_name.as_str()).await;
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let location =
+            discovery::select_version(&candidates, request.version.as_ref().map(|v| v.as_str()))
+                .cloned()
+                .ok_or_else(|| {
+                    ErrorBuilder::docs().version_not_found(
+                        request.crate_name.clone(),
+                        request.version.clone().unwrap_or_else(Version::latest),
+                    )
+                })?;
+
+        let manifest_path = location.manifest_path();
+        let manifest = tokio::task::spawn_blocking(move || LocalManifest::load(&manifest_path))
+            .await
+            .map_err(|e| {
+                ErrorBuilder::internal(format!("Local manifest loading task failed: {e}"))
+            })??;
+
+        let mut versions: HashMap<String, VersionInfo> = HashMap::new();
+        for candidate in &candidates {
+            versions
+                .entry(candidate.version.to_string())
+                .or_insert_with(|| VersionInfo {
+                    num: candidate.version.to_string(),
+                    created_at: modified_at(candidate).unwrap_or_else(Utc::now),
+                    yanked: false,
+                    rust_version: None,
+                    downloads: 0,
+                    features: HashMap::new(),
+                });
+        }
+        let mut versions: Vec<VersionInfo> = versions.into_values().collect();
+        versions.sort_by(|a, b| {
+            let parse = |v: &VersionInfo| semver::Version::parse(&v.num).ok();
+            parse(b).cmp(&parse(a))
+        });
+
+        Ok(Some(manifest.to_metadata(
+            request.crate_name.as_str(),
+            &location.version,
+            versions,
+            modified_at(&location),
+        )))
+    }
+}
+
+fn modified_at(location: &LocalCrateDir) -> Option<DateTime<Utc>> {
+    location.modified().map(DateTime::<Utc>::from)
+}
+
+/// Answer a request from local sources according to the client's
+/// [`DocsSourceMode`].
+///
+/// Returns `Ok(None)` when the caller should use the remote source: always in
+/// remote-only mode, and in local-first mode when the crate isn't on disk or
+/// the local lookup failed. In local-only mode a crate missing locally is an
+/// error.
+pub(crate) async fn lookup_local<T, F, Fut>(
+    client: &DocsClient,
+    crate_name: &CrateName,
+    lookup: F,
+) -> Result<Option<T>>
+where
+    F: FnOnce(Arc<LocalDocsSource>) -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let mode = client.source_mode();
+    if mode == DocsSourceMode::RemoteOnly {
+        return Ok(None);
+    }
+
+    let result = match client.local_docs() {
+        Some(source) => lookup(source.clone()).await,
+        None => Ok(None),
+    };
+
+    match (mode, result) {
+        (DocsSourceMode::LocalOnly, Ok(None)) => {
+            Err(ErrorBuilder::docs().crate_not_found(crate_name.clone()))
+        }
+        (DocsSourceMode::LocalFirst, Err(e)) => {
+            warn!(
+                crate_name = %crate_name,
+                error = %e,
+                "Local documentation lookup failed, falling back to remote"
+            );
+            Ok(None)
+        }
+        (_, result) => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DOCS_RS_URL;
+    use rustacean_docs_core::{models::docs::ItemKind, ItemPath};
+    use std::{fs, path::Path};
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn sample_registry() -> (tempfile::TempDir, LocalDocsSource) {
+        let temp = tempfile::tempdir().unwrap();
+        let index = temp.path().join("registry/src/index.crates.io-0000");
+
+        for version in ["0.1.0", "0.2.0"] {
+            let root = format!("demo-crate-{version}");
+            write(
+                &index,
+                &format!("{root}/Cargo.toml"),
+                &format!(
+                    "[package]\nname = \"demo-crate\"\nversion = \"{version}\"\n\
+                     description = \"A demo.\"\nlicense = \"MIT\"\n\n\
+                     [dependencies]\nserde = \"1\"\n"
+                ),
+            );
+            write(
+                &index,
+                &format!("{root}/src/lib.rs"),
+                "//! Demo crate.\n\n/// Widget docs.\npub struct Widget;\n\n/// Make one.\npub fn make() -> Widget { Widget }\n",
+            );
+        }
+
+        let source = LocalDocsSource::new(LocalDocsConfig {
+            registry_paths: vec![temp.path().join("registry/src")],
+            workspace_paths: Vec::new(),
+        });
+        (temp, source)
+    }
+
+    #[test]
+    fn test_source_mode_parsing() {
+        assert_eq!("local".parse(), Ok(DocsSourceMode::LocalOnly));
+        assert_eq!("local-only".parse(), Ok(DocsSourceMode::LocalOnly));
+        assert_eq!("REMOTE_ONLY".parse(), Ok(DocsSourceMode::RemoteOnly));
+        assert_eq!("local-first".parse(), Ok(DocsSourceMode::LocalFirst));
+        assert!("sometimes".parse::<DocsSourceMode>().is_err());
+        assert_eq!(DocsSourceMode::default(), DocsSourceMode::RemoteOnly);
+        assert_eq!(DocsSourceMode::LocalFirst.to_string(), "local-first");
+
+        let mode: DocsSourceMode = serde_json::from_str("\"local\"").unwrap();
+        assert_eq!(mode, DocsSourceMode::LocalOnly);
+    }
+
+    #[tokio::test]
+    async fn test_crate_and_item_docs_from_registry_sources() {
+        let (_temp, source) = sample_registry();
+        let crate_name = CrateName::new("demo-crate").unwrap();
+
+        let docs = source
+            .get_crate_docs(&CrateDocsRequest::new(crate_name.clone()), DOCS_RS_URL)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(docs.version, "0.2.0");
+        assert_eq!(docs.summary.description.as_deref(), Some("Demo crate."));
+        assert_eq!(docs.items.len(), 2);
+        assert_eq!(
+            docs.docs_url.unwrap().as_str(),
+            "https://docs.rs/demo-crate/0.2.0/demo-crate/"
+        );
+
+        let item = source
+            .get_item_docs(
+                &ItemDocsRequest::new(crate_name.clone(), ItemPath::new("make").unwrap()),
+                DOCS_RS_URL,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.kind, ItemKind::Function);
+        assert_eq!(item.item_path, "fn.make.html");
+        assert_eq!(item.signature.as_deref(), Some("pub fn make() -> Widget"));
+
+        let missing = CrateDocsRequest::new(CrateName::new("not-on-disk").unwrap());
+        assert!(source
+            .get_crate_docs(&missing, DOCS_RS_URL)
+            .await
+            .unwrap()
+            .is_none());
+
+        let wrong_version = CrateDocsRequest {
+            version: Some(Version::new("9.9.9").unwrap()),
+            ..CrateDocsRequest::new(crate_name)
+        };
+        assert!(source
+            .get_crate_docs(&wrong_version, DOCS_RS_URL)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_crate_metadata_from_manifest() {
+        let (_temp, source) = sample_registry();
+        let request = CrateMetadataRequest::with_version(
+            CrateName::new("demo_crate").unwrap(),
+            Version::new("0.1.0").unwrap(),
+        );
+
+        let metadata = source.get_crate_metadata(&request).await.unwrap().unwrap();
+        assert_eq!(metadata.name, "demo-crate");
+        assert_eq!(metadata.version, "0.1.0");
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+        assert_eq!(metadata.dependencies.len(), 1);
+        let versions: Vec<&str> = metadata.versions.iter().map(|v| v.num.as_str()).collect();
+        assert_eq!(versions, vec!["0.2.0", "0.1.0"]);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_local_respects_source_mode() {
+        let (_temp, source) = sample_registry();
+        let source = Arc::new(source);
+        let present = CrateName::new("demo-crate").unwrap();
+        let absent = CrateName::new("not-on-disk").unwrap();
+
+        let lookup = |mode: DocsSourceMode, crate_name: CrateName| {
+            let client = DocsClient::new()
+                .unwrap()
+                .with_local_docs(source.clone(), mode);
+            async move {
+                let request = CrateDocsRequest::new(crate_name.clone());
+                lookup_local(&client, &crate_name, |local| async move {
+                    local.get_crate_docs(&request, DOCS_RS_URL).await
+                })
+                .await
+            }
+        };
+
+        assert!(lookup(DocsSourceMode::RemoteOnly, present.clone())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(lookup(DocsSourceMode::LocalOnly, present.clone())
+            .await
+            .unwrap()
+            .is_some());
+        assert!(lookup(DocsSourceMode::LocalOnly, absent.clone())
+            .await
+            .is_err());
+        assert!(lookup(DocsSourceMode::LocalFirst, present)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(lookup(DocsSourceMode::LocalFirst, absent)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,252 @@
+use super::manifest::read_package_identity;
+use semver::Version as SemVersion;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tracing::trace;
+
+/// Maximum directory depth searched below a workspace path for crate manifests
+const WORKSPACE_SCAN_DEPTH: usize = 4;
+
+/// Where a local crate was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrateOrigin {
+    /// Unpacked registry sources (`~/.cargo/registry/src/<index>/<name>-<version>`)
+    Registry,
+    /// A package inside a configured workspace or project directory
+    Workspace,
+}
+
+/// A crate whose sources are available on disk
+#[derive(Debug, Clone)]
+pub struct LocalCrateDir {
+    /// Package name as declared in its manifest
+    pub name: String,
+    /// Package version
+    pub version: SemVersion,
+    /// Directory containing the package `Cargo.toml`
+    pub root: PathBuf,
+    /// Where the crate was found
+    pub origin: CrateOrigin,
+}
+
+impl LocalCrateDir {
+    /// Path of the package manifest
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root.join("Cargo.toml")
+    }
+
+    /// Last modification time of the crate directory
+    pub fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.root).and_then(|m| m.modified()).ok()
+    }
+}
+
+/// Normalize a crate name so that `-` and `_` compare equal
+pub fn normalize_crate_name(name: &str) -> String {
+    name.replace('-', "_").to_ascii_lowercase()
+}
+
+/// Split a registry source directory name (`serde_json-1.0.108`) into the
+/// crate name and version.
+///
+/// Crate names may themselves contain dashes, so the split happens at the
+/// first dash followed by a valid semver version.
+pub fn split_registry_dir_name(dir_name: &str) -> Option<(&str, SemVersion)> {
+    dir_name
+        .match_indices('-')
+        .find_map(|(index, _)| {
+            let version = SemVersion::parse(&dir_name[index + 1..]).ok()?;
+            Some((&dir_name[..index], version))
+        })
+        .filter(|(name, _)| !name.is_empty())
+}
+
+/// Find every local copy of a crate in the given registry and workspace paths
+pub fn discover_crate(
+    crate_name: &str,
+    registry_paths: &[PathBuf],
+    workspace_paths: &[PathBuf],
+) -> Vec<LocalCrateDir> {
+    let wanted = normalize_crate_name(crate_name);
+    let mut found = Vec::new();
+
+    for workspace in workspace_paths {
+        scan_workspace(workspace, 0, &wanted, &mut found);
+    }
+
+    for registry in registry_paths {
+        scan_registry(registry, &wanted, true, &mut found);
+    }
+
+    trace!(
+        crate_name = %crate_name,
+        candidates = found.len(),
+        "Discovered local crate sources"
+    );
+
+    found
+}
+
+/// Pick the crate matching the requested version.
+///
+/// `None` and `"latest"` select the highest stable version (or the highest
+/// pre-release if no stable version exists). Workspace copies win over
+/// registry copies of the same version.
+pub fn select_version<'a>(
+    candidates: &'a [LocalCrateDir],
+    version: Option<&str>,
+) -> Option<&'a LocalCrateDir> {
+    let rank = |c: &&LocalCrateDir| (c.version.clone(), c.origin == CrateOrigin::Workspace);
+
+    match version.map(str::trim) {
+        None | Some("latest") | Some("") => candidates
+            .iter()
+            .filter(|c| c.version.pre.is_empty())
+            .max_by_key(rank)
+            .or_else(|| candidates.iter().max_by_key(rank)),
+        Some(requested) => {
+            let requested = SemVersion::parse(requested).ok()?;
+            candidates
+                .iter()
+                .filter(|c| c.version == requested)
+                .max_by_key(rank)
+        }
+    }
+}
+
+/// Scan a registry source directory. `registry/src` contains one directory
+/// per index, so directories that aren't crate sources are searched once more.
+fn scan_registry(path: &Path, wanted: &str, descend: bool, found: &mut Vec<LocalCrateDir>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let dir_name = entry.file_name();
+        let dir_name = dir_name.to_string_lossy();
+        let crate_dir =
+            split_registry_dir_name(&dir_name).filter(|_| dir.join("Cargo.toml").is_file());
+        match crate_dir {
+            Some((name, version)) if normalize_crate_name(name) == wanted => {
+                found.push(LocalCrateDir {
+                    name: name.to_string(),
+                    version,
+                    root: dir,
+                    origin: CrateOrigin::Registry,
+                });
+            }
+            Some(_) => {}
+            None if descend => scan_registry(&dir, wanted, false, found),
+            None => {}
+        }
+    }
+}
+
+fn scan_workspace(dir: &Path, depth: usize, wanted: &str, found: &mut Vec<LocalCrateDir>) {
+    let manifest = dir.join("Cargo.toml");
+    if manifest.is_file() {
+        if let Some((name, version)) = read_package_identity(&manifest) {
+            if normalize_crate_name(&name) == wanted {
+                found.push(LocalCrateDir {
+                    name,
+                    version,
+                    root: dir.to_path_buf(),
+                    origin: CrateOrigin::Workspace,
+                });
+            }
+        }
+    }
+
+    if depth >= WORKSPACE_SCAN_DEPTH {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" || name == "node_modules" {
+            continue;
+        }
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            scan_workspace(&entry.path(), depth + 1, wanted, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(dir: &Path, name: &str, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_split_registry_dir_name() {
+        let (name, version) = split_registry_dir_name("serde_json-1.0.108").unwrap();
+        assert_eq!(name, "serde_json");
+        assert_eq!(version, SemVersion::new(1, 0, 108));
+
+        let (name, version) = split_registry_dir_name("wasm-bindgen-0.2.92-rc.1").unwrap();
+        assert_eq!(name, "wasm-bindgen");
+        assert_eq!(version.to_string(), "0.2.92-rc.1");
+
+        assert!(split_registry_dir_name("index.crates.io-6f17d22bba15001f").is_none());
+        assert!(split_registry_dir_name("-1.0.0").is_none());
+    }
+
+    #[test]
+    fn test_discover_registry_and_workspace_crates() {
+        let temp = tempfile::tempdir().unwrap();
+        let registry = temp.path().join("registry/src");
+        let index = registry.join("index.crates.io-6f17d22bba15001f");
+        write_manifest(&index.join("my-crate-0.1.0"), "my-crate", "0.1.0");
+        write_manifest(&index.join("my-crate-0.2.0"), "my-crate", "0.2.0");
+        write_manifest(
+            &index.join("my-crate-0.3.0-beta.1"),
+            "my-crate",
+            "0.3.0-beta.1",
+        );
+        write_manifest(&index.join("other-1.0.0"), "other", "1.0.0");
+
+        let workspace = temp.path().join("project");
+        write_manifest(&workspace.join("crates/my_crate"), "my_crate", "0.2.0");
+        write_manifest(
+            &workspace.join("target/package/my_crate"),
+            "my_crate",
+            "9.0.0",
+        );
+
+        let found = discover_crate("my_crate", &[registry], &[workspace]);
+        assert_eq!(found.len(), 4);
+
+        let latest = select_version(&found, None).unwrap();
+        assert_eq!(latest.version, SemVersion::new(0, 2, 0));
+        assert_eq!(latest.origin, CrateOrigin::Workspace);
+
+        let pinned = select_version(&found, Some("0.1.0")).unwrap();
+        assert_eq!(pinned.origin, CrateOrigin::Registry);
+
+        let beta = select_version(&found, Some("0.3.0-beta.1")).unwrap();
+        assert_eq!(beta.version.to_string(), "0.3.0-beta.1");
+
+        assert!(select_version(&found, Some("5.0.0")).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use super::{
+    discovery::{CrateOrigin, LocalCrateDir},
+    parser::{LocalCrate, LocalItem},
+};
+use crate::{
+    error_handling::{build_docs_url, build_item_docs_url},
+    html_parser::docs_parser::{categorize_items, generate_crate_summary},
+    item_index::{find_item, IndexedItem},
+    rustdoc_json::docs_builder::{
+        describe_docs, extract_code_examples, item_not_found, summarize_docs,
+    },
+};
+use rustacean_docs_core::{
+    models::docs::{CrateDocsResponse, CrateItem, ItemDocsResponse, Visibility},
+    Result,
+};
+use std::path::Path;
+use tracing::trace;
+use url::Url;
+
+/// Build crate documentation from locally parsed sources
+pub fn build_crate_documentation(
+    krate: &LocalCrate,
+    location: &LocalCrateDir,
+    docs_base: &str,
+    crate_name: &str,
+) -> Result<CrateDocsResponse> {
+    let version = location.version.to_string();
+    let description = krate.docs.as_deref().and_then(summarize_docs);
+
+    let mut items: Vec<CrateItem> = krate.items.iter().map(build_crate_item).collect();
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+
+    trace!(
+        crate_name = %crate_name,
+        root = %location.root.display(),
+        item_count = items.len(),
+        "Built crate documentation from local sources"
+    );
+
+    let summary = generate_crate_summary(&items, description);
+    let categories = categorize_items(&items);
+    let examples = krate
+        .docs
+        .as_deref()
+        .map(extract_code_examples)
+        .unwrap_or_default();
+    let docs_url = match location.origin {
+        CrateOrigin::Registry => Some(build_docs_url(docs_base, crate_name, &version)?),
+        CrateOrigin::Workspace => file_url(&krate.root_file),
+    };
+
+    Ok(CrateDocsResponse {
+        name: crate_name.to_string(),
+        version,
+        summary,
+        categories,
+        items,
+        examples,
+        docs_url,
+    })
+}
+
+/// Build documentation for a single item from locally parsed sources
+pub fn build_item_documentation(
+    krate: &LocalCrate,
+    location: &LocalCrateDir,
+    docs_base: &str,
+    crate_name: &str,
+    item_path: &str,
+) -> Result<ItemDocsResponse> {
+    let item = find_item(&krate.items, crate_name, item_path)
+        .ok_or_else(|| item_not_found(crate_name, item_path))?;
+
+    let html_path = item.html_path();
+    let docs = item.docs.as_deref().unwrap_or_default();
+    let docs_url = match location.origin {
+        CrateOrigin::Registry => Some(build_item_docs_url(
+            docs_base,
+            crate_name,
+            &location.version.to_string(),
+            &html_path,
+        )?),
+        CrateOrigin::Workspace => file_url(&item.source_file),
+    };
+
+    Ok(ItemDocsResponse {
+        crate_name: crate_name.to_string(),
+        item_path: html_path,
+        name: item.name.clone(),
+        kind: item.kind.clone(),
+        signature: item.signature.clone(),
+        description: describe_docs(docs),
+        examples: extract_code_examples(docs),
+        docs_url,
+        related_items: item.related_items.clone(),
+        associated_items: item.associated_items.clone(),
+        trait_impls: item.trait_impls.clone(),
+        required_items: item.required_items.clone(),
+        provided_items: item.provided_items.clone(),
+    })
+}
+
+fn build_crate_item(item: &LocalItem) -> CrateItem {
+    let path = item.html_path();
+
+    CrateItem {
+        name: item.name.clone(),
+        kind: item.kind.clone(),
+        summary: item.docs.as_deref().and_then(summarize_docs),
+        path: path.clone(),
+        visibility: Visibility::Public,
+        is_async: item.is_async,
+        signature: item.signature.clone(),
+        docs_path: Some(path),
+        deprecation: item.deprecation.clone(),
+    }
+}
+
+/// Workspace crates have no published docs, so link to the source file
+fn file_url(path: &Path) -> Option<Url> {
+    path.canonicalize()
+        .ok()
+        .and_then(|path| Url::from_file_path(path).ok())
+}
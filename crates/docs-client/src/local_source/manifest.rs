@@ -0,0 +1,422 @@
+use chrono::{DateTime, Utc};
+use rustacean_docs_core::{
+    models::metadata::{CrateMetadata, Dependency, DependencyKind, DownloadStats, VersionInfo},
+    ErrorBuilder, Result,
+};
+use semver::Version as SemVersion;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+use url::Url;
+
+/// A parsed `Cargo.toml`, with `workspace = true` fields resolved against the
+/// enclosing workspace manifest when there is one
+#[derive(Debug, Clone)]
+pub struct LocalManifest {
+    manifest: Table,
+    workspace_package: Table,
+    workspace_dependencies: Table,
+}
+
+impl LocalManifest {
+    /// Read and parse a package manifest
+    pub fn load(path: &Path) -> Result<Self> {
+        let manifest = read_table(path)?;
+        let workspace = find_workspace_manifest(path);
+
+        let workspace_section = |key: &str| {
+            workspace
+                .as_ref()
+                .and_then(|ws| ws.get("workspace"))
+                .and_then(|ws| ws.get(key))
+                .and_then(Value::as_table)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        Ok(Self {
+            workspace_package: workspace_section("package"),
+            workspace_dependencies: workspace_section("dependencies"),
+            manifest,
+        })
+    }
+
+    /// A `[package]` field, following `field.workspace = true` inheritance
+    fn package_field(&self, key: &str) -> Option<&Value> {
+        let value = self.manifest.get("package")?.get(key)?;
+        if is_workspace_inherited(value) {
+            self.workspace_package.get(key)
+        } else {
+            Some(value)
+        }
+    }
+
+    fn package_string(&self, key: &str) -> Option<String> {
+        self.package_field(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    fn package_strings(&self, key: &str) -> Vec<String> {
+        self.package_field(key)
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn package_url(&self, key: &str) -> Option<Url> {
+        self.package_string(key).and_then(|s| Url::parse(&s).ok())
+    }
+
+    /// Package name
+    pub fn name(&self) -> Option<String> {
+        self.package_string("name")
+    }
+
+    /// Package version. Cargo defaults an omitted version to `0.0.0`.
+    pub fn version(&self) -> Option<SemVersion> {
+        match self.package_string("version") {
+            Some(version) => SemVersion::parse(&version).ok(),
+            None => Some(SemVersion::new(0, 0, 0)),
+        }
+    }
+
+    /// Library target source path relative to the package root
+    pub fn lib_path(&self) -> PathBuf {
+        self.manifest
+            .get("lib")
+            .and_then(|lib| lib.get("path"))
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("src/lib.rs"))
+    }
+
+    /// Whether the library target is a procedural macro crate
+    pub fn is_proc_macro(&self) -> bool {
+        self.manifest
+            .get("lib")
+            .and_then(|lib| lib.get("proc-macro").or_else(|| lib.get("proc_macro")))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Declared features
+    pub fn features(&self) -> HashMap<String, Vec<String>> {
+        self.manifest
+            .get("features")
+            .and_then(Value::as_table)
+            .map(|features| {
+                features
+                    .iter()
+                    .map(|(name, enables)| {
+                        let enables = enables
+                            .as_array()
+                            .map(|values| {
+                                values
+                                    .iter()
+                                    .filter_map(Value::as_str)
+                                    .map(str::to_string)
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (name.clone(), enables)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// All dependencies, including target-specific ones
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        let mut dependencies = Vec::new();
+
+        let mut collect = |table: &Table, target: Option<&str>| {
+            for (section, kind) in [
+                ("dependencies", DependencyKind::Normal),
+                ("dev-dependencies", DependencyKind::Dev),
+                ("dev_dependencies", DependencyKind::Dev),
+                ("build-dependencies", DependencyKind::Build),
+                ("build_dependencies", DependencyKind::Build),
+            ] {
+                let Some(deps) = table.get(section).and_then(Value::as_table) else {
+                    continue;
+                };
+                for (name, spec) in deps {
+                    dependencies.push(self.dependency(name, spec, target, kind.clone()));
+                }
+            }
+        };
+
+        collect(&self.manifest, None);
+
+        if let Some(targets) = self.manifest.get("target").and_then(Value::as_table) {
+            for (target, table) in targets {
+                if let Some(table) = table.as_table() {
+                    collect(table, Some(target));
+                }
+            }
+        }
+
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+        dependencies
+    }
+
+    fn dependency(
+        &self,
+        key: &str,
+        spec: &Value,
+        target: Option<&str>,
+        kind: DependencyKind,
+    ) -> Dependency {
+        let inherited = is_workspace_inherited(spec)
+            .then(|| self.workspace_dependencies.get(key))
+            .flatten();
+
+        let lookup = |field: &str| {
+            spec.get(field)
+                .or_else(|| inherited.and_then(|dep| dep.get(field)))
+        };
+
+        let version_req = match (spec, inherited) {
+            (Value::String(req), _) => req.clone(),
+            (_, Some(Value::String(req))) => req.clone(),
+            _ => lookup("version")
+                .and_then(Value::as_str)
+                .unwrap_or("*")
+                .to_string(),
+        };
+
+        // Features listed on an inheriting dependency are additive
+        let mut features: Vec<String> = [
+            spec.get("features"),
+            inherited.and_then(|d| d.get("features")),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+        features.dedup();
+
+        Dependency {
+            name: lookup("package")
+                .and_then(Value::as_str)
+                .unwrap_or(key)
+                .to_string(),
+            version_req,
+            features,
+            optional: lookup("optional").and_then(Value::as_bool).unwrap_or(false),
+            default_features: lookup("default-features")
+                .or_else(|| lookup("default_features"))
+                .and_then(Value::as_bool)
+                .unwrap_or(true),
+            target: target.map(str::to_string),
+            kind,
+        }
+    }
+
+    /// Convert the manifest into crate metadata.
+    ///
+    /// Download statistics are not available locally and are reported as zero.
+    pub fn to_metadata(
+        &self,
+        fallback_name: &str,
+        version: &SemVersion,
+        versions: Vec<VersionInfo>,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> CrateMetadata {
+        let mut dependencies = Vec::new();
+        let mut dev_dependencies = Vec::new();
+        let mut build_dependencies = Vec::new();
+        for dependency in self.dependencies() {
+            match dependency.kind {
+                DependencyKind::Normal => dependencies.push(dependency),
+                DependencyKind::Dev => dev_dependencies.push(dependency),
+                DependencyKind::Build => build_dependencies.push(dependency),
+            }
+        }
+
+        let created_at = versions.iter().map(|v| v.created_at).min();
+
+        CrateMetadata {
+            name: self.name().unwrap_or_else(|| fallback_name.to_string()),
+            version: version.to_string(),
+            description: self
+                .package_string("description")
+                .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+            license: self.package_string("license"),
+            repository: self.package_url("repository"),
+            homepage: self.package_url("homepage"),
+            documentation: self.package_url("documentation"),
+            authors: self.package_strings("authors"),
+            keywords: self.package_strings("keywords"),
+            categories: self.package_strings("categories"),
+            downloads: DownloadStats {
+                total: 0,
+                version: 0,
+                recent: 0,
+            },
+            versions,
+            dependencies,
+            dev_dependencies,
+            build_dependencies,
+            features: self.features(),
+            rust_version: self.package_string("rust-version"),
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+/// Read just the package name and version from a manifest, returning `None`
+/// for virtual workspace manifests and unparsable files
+pub fn read_package_identity(path: &Path) -> Option<(String, SemVersion)> {
+    let manifest = LocalManifest::load(path).ok()?;
+    Some((manifest.name()?, manifest.version()?))
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    let contents = fs::read_to_string(path)?;
+    contents.parse::<Table>().map_err(|e| {
+        ErrorBuilder::docs().parse_error(format!("Invalid manifest {}: {e}", path.display()))
+    })
+}
+
+fn is_workspace_inherited(value: &Value) -> bool {
+    value
+        .get("workspace")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Walk up from a package manifest to the closest manifest declaring `[workspace]`
+fn find_workspace_manifest(package_manifest: &Path) -> Option<Table> {
+    package_manifest
+        .parent()?
+        .ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|candidate| candidate.is_file())
+        .filter_map(|candidate| read_table(&candidate).ok())
+        .find(|table| table.contains_key("workspace"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_metadata_with_workspace_inheritance() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["crates/demo"]
+
+[workspace.package]
+version = "1.4.0"
+license = "MIT"
+repository = "https://github.com/example/demo"
+
+[workspace.dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#,
+        )
+        .unwrap();
+
+        let crate_dir = temp.path().join("crates/demo");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            r#"
+[package]
+name = "demo"
+version.workspace = true
+license.workspace = true
+repository.workspace = true
+description = """
+A demo
+crate."""
+keywords = ["demo", "example"]
+rust-version = "1.70"
+
+[lib]
+path = "src/demo.rs"
+
+[features]
+default = ["std"]
+std = []
+
+[dependencies]
+serde = { workspace = true, features = ["rc"], optional = true }
+tokio = "1"
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = { version = "0.2", default-features = false }
+"#,
+        )
+        .unwrap();
+
+        let manifest_path = crate_dir.join("Cargo.toml");
+        let (name, version) = read_package_identity(&manifest_path).unwrap();
+        assert_eq!(name, "demo");
+        assert_eq!(version, SemVersion::new(1, 4, 0));
+
+        let manifest = LocalManifest::load(&manifest_path).unwrap();
+        assert_eq!(manifest.lib_path(), PathBuf::from("src/demo.rs"));
+        assert!(!manifest.is_proc_macro());
+
+        let metadata = manifest.to_metadata("demo", &version, Vec::new(), None);
+        assert_eq!(metadata.version, "1.4.0");
+        assert_eq!(metadata.description.as_deref(), Some("A demo crate."));
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            metadata.repository.unwrap().as_str(),
+            "https://github.com/example/demo"
+        );
+        assert_eq!(metadata.rust_version.as_deref(), Some("1.70"));
+        assert_eq!(metadata.features["default"], vec!["std".to_string()]);
+
+        let serde = metadata
+            .dependencies
+            .iter()
+            .find(|d| d.name == "serde")
+            .unwrap();
+        assert_eq!(serde.version_req, "1.0");
+        assert!(serde.optional);
+        assert_eq!(serde.features, vec!["rc".to_string(), "derive".to_string()]);
+
+        let libc = metadata
+            .dependencies
+            .iter()
+            .find(|d| d.name == "libc")
+            .unwrap();
+        assert_eq!(libc.target.as_deref(), Some("cfg(unix)"));
+        assert!(!libc.default_features);
+
+        assert_eq!(metadata.dev_dependencies.len(), 1);
+        assert!(metadata.build_dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_virtual_manifest_has_no_identity() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("Cargo.toml");
+        fs::write(&path, "[workspace]\nmembers = []\n").unwrap();
+        assert!(read_package_identity(&path).is_none());
+    }
+}
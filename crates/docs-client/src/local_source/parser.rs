@@ -0,0 +1,1798 @@
+use crate::{
+    html_parser::impl_items::parse_trait_impl_header, item_index::IndexedItem,
+    rustdoc_json::docs_builder::summarize_docs,
+};
+use proc_macro2::{Delimiter, TokenTree};
+use rustacean_docs_core::{
+    models::docs::{AssociatedItem, AssociatedItemKind, Deprecation, ItemKind, TraitImpl},
+    ErrorBuilder, Result,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
+use syn::{
+    ext::IdentExt, punctuated::Punctuated, Attribute, Expr, Fields, ImplItem, Item, Lit, Meta,
+    TraitItem, UseTree, Visibility,
+};
+use tracing::{debug, warn};
+
+/// Maximum number of re-export hops followed when resolving a `use` path
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Placeholder field standing in for stripped private fields while rendering
+const PRIVATE_FIELDS_MARKER: &str = "__private_fields";
+
+/// Public API of a crate extracted from its sources
+#[derive(Debug, Clone)]
+pub struct LocalCrate {
+    /// Crate-level documentation (`//!` comments of the library root)
+    pub docs: Option<String>,
+    /// Every public item at the path where rustdoc would document it
+    pub items: Vec<LocalItem>,
+    /// Library entry point the crate was parsed from
+    pub root_file: PathBuf,
+}
+
+/// A public item of a locally parsed crate
+#[derive(Debug, Clone)]
+pub struct LocalItem {
+    /// Name under which the item is reachable (may differ for `pub use ... as`)
+    pub name: String,
+    /// Module path from the crate root, excluding the crate name
+    pub parents: Vec<String>,
+    /// Our item kind
+    pub kind: ItemKind,
+    /// docs.rs page prefix (`struct`, `fn`, ...), or `index` for modules
+    pub page: &'static str,
+    /// Raw markdown documentation
+    pub docs: Option<String>,
+    /// Rendered declaration without body
+    pub signature: Option<String>,
+    /// Whether the item is an `async fn`
+    pub is_async: bool,
+    /// `#[deprecated]` notice
+    pub deprecation: Option<Deprecation>,
+    /// Impl headers for types and traits, child names for modules
+    pub related_items: Vec<String>,
+    /// Public items of inherent impls (types only)
+    pub associated_items: Vec<AssociatedItem>,
+    /// Trait impls, including derives (types only)
+    pub trait_impls: Vec<TraitImpl>,
+    /// Items without a default (traits only)
+    pub required_items: Vec<AssociatedItem>,
+    /// Items with a default (traits only)
+    pub provided_items: Vec<AssociatedItem>,
+    /// Source file declaring the item
+    pub source_file: PathBuf,
+}
+
+impl IndexedItem for LocalItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parents(&self) -> &[String] {
+        &self.parents
+    }
+
+    fn page_kind(&self) -> &str {
+        self.page
+    }
+}
+
+/// Parse the public API of a crate starting from its library root file.
+///
+/// Module files are located the way rustc does (`foo.rs`, `foo/mod.rs`,
+/// `#[path]`), `#[cfg(test)]` and `#[doc(hidden)]` items are skipped, and
+/// `pub use` re-exports of local items are followed so items are reported at
+/// their shallowest public path.
+pub fn parse_crate(root_file: &Path) -> Result<LocalCrate> {
+    let contents = fs::read_to_string(root_file)?;
+    let file = syn::parse_file(&contents).map_err(|e| {
+        ErrorBuilder::docs().parse_error(format!("Failed to parse {}: {e}", root_file.display()))
+    })?;
+
+    let base_dir = root_file.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let docs = doc_string(&file.attrs, &base_dir);
+
+    let mut tree = ModuleTree::default();
+    tree.add_module(
+        None,
+        root_file.to_path_buf(),
+        base_dir,
+        docs.clone(),
+        file.items,
+        false,
+    );
+
+    let items = tree.documented_items();
+
+    debug!(
+        root_file = %root_file.display(),
+        module_count = tree.modules.len(),
+        item_count = items.len(),
+        "Parsed local crate sources"
+    );
+
+    Ok(LocalCrate {
+        docs,
+        items,
+        root_file: root_file.to_path_buf(),
+    })
+}
+
+/// Something a path can resolve to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Target {
+    /// A module, by index into the module table
+    Module(usize),
+    /// An item, by module index and position in that module
+    Item(usize, usize),
+}
+
+/// A single binding introduced by a (possibly nested) `use` declaration
+#[derive(Debug, Clone)]
+struct UseEntry {
+    segments: Vec<String>,
+    /// Name the entry is bound to, `None` for glob imports
+    binding: Option<String>,
+    external: bool,
+}
+
+struct Module {
+    parent: Option<usize>,
+    file: PathBuf,
+    docs: Option<String>,
+    items: Vec<Item>,
+    children: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+struct ModuleTree {
+    modules: Vec<Module>,
+}
+
+impl ModuleTree {
+    /// Register a module and recursively load its child modules
+    fn add_module(
+        &mut self,
+        parent: Option<usize>,
+        file: PathBuf,
+        child_dir: PathBuf,
+        docs: Option<String>,
+        items: Vec<Item>,
+        inline: bool,
+    ) -> usize {
+        let items = expand_item_macros(items);
+        let index = self.modules.len();
+        self.modules.push(Module {
+            parent,
+            file: file.clone(),
+            docs,
+            items: Vec::new(),
+            children: HashMap::new(),
+        });
+
+        for item in &items {
+            let Item::Mod(module) = item else {
+                continue;
+            };
+            let name = module.ident.unraw().to_string();
+            // Platform-specific duplicates (`#[cfg(unix)] mod sys;`): the first wins
+            if is_test_only(&module.attrs) || self.modules[index].children.contains_key(&name) {
+                continue;
+            }
+            if let Some(child) = self.load_child(index, module, &file, &child_dir, inline) {
+                self.modules[index].children.insert(name, child);
+            }
+        }
+
+        self.modules[index].items = items;
+        index
+    }
+
+    fn load_child(
+        &mut self,
+        parent: usize,
+        module: &syn::ItemMod,
+        file: &Path,
+        child_dir: &Path,
+        parent_inline: bool,
+    ) -> Option<usize> {
+        let name = module.ident.unraw().to_string();
+        let file_dir = file.parent().unwrap_or(Path::new("."));
+        let path_attr = path_attribute(&module.attrs);
+
+        if let Some((_, items)) = &module.content {
+            let nested_dir = child_dir.join(path_attr.as_deref().unwrap_or(&name));
+            let docs = doc_string(&module.attrs, file_dir);
+            return Some(self.add_module(
+                Some(parent),
+                file.to_path_buf(),
+                nested_dir,
+                docs,
+                items.clone(),
+                true,
+            ));
+        }
+
+        let candidates = match &path_attr {
+            Some(path) if parent_inline => vec![child_dir.join(path)],
+            Some(path) => vec![file_dir.join(path)],
+            None => vec![
+                child_dir.join(format!("{name}.rs")),
+                child_dir.join(&name).join("mod.rs"),
+            ],
+        };
+
+        let Some(module_file) = candidates.into_iter().find(|path| path.is_file()) else {
+            debug!(module = %name, parent_file = %file.display(), "Module file not found");
+            return None;
+        };
+
+        let parsed = match fs::read_to_string(&module_file)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| syn::parse_file(&contents).map_err(|e| e.to_string()))
+        {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                warn!(file = %module_file.display(), error = %e, "Failed to parse module file");
+                return None;
+            }
+        };
+
+        // Files loaded through `#[path]` or named `mod.rs` own their directory
+        let module_dir = module_file.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let nested_dir = if path_attr.is_some() || module_file.ends_with("mod.rs") {
+            module_dir.clone()
+        } else {
+            child_dir.join(&name)
+        };
+
+        let docs = join_docs(
+            doc_string(&module.attrs, file_dir),
+            doc_string(&parsed.attrs, &module_dir),
+        );
+
+        Some(self.add_module(
+            Some(parent),
+            module_file,
+            nested_dir,
+            docs,
+            parsed.items,
+            false,
+        ))
+    }
+
+    /// Resolve a name inside a module, following imports
+    fn lookup(
+        &self,
+        module: usize,
+        name: &str,
+        depth: usize,
+        visiting: &mut HashSet<(usize, String)>,
+    ) -> Vec<Target> {
+        let mut targets = Vec::new();
+        let current = &self.modules[module];
+
+        if let Some(&child) = current.children.get(name) {
+            targets.push(Target::Module(child));
+        }
+
+        for (index, item) in current.items.iter().enumerate() {
+            if !matches!(item, Item::Mod(_)) && item_name(item).as_deref() == Some(name) {
+                targets.push(Target::Item(module, index));
+            }
+        }
+
+        // `#[macro_export]` macros live at the crate root wherever they're defined
+        if module == 0 {
+            targets.extend(
+                self.exported_macros()
+                    .filter(|target| self.target_name(*target).as_deref() == Some(name)),
+            );
+        }
+
+        if !targets.is_empty()
+            || depth >= MAX_REEXPORT_DEPTH
+            || !visiting.insert((module, name.to_string()))
+        {
+            return targets;
+        }
+
+        let entries = self.use_entries(module, false);
+
+        for entry in entries
+            .iter()
+            .filter(|e| e.binding.as_deref() == Some(name))
+        {
+            targets.extend(self.resolve_path(module, entry, depth + 1, visiting));
+        }
+
+        if targets.is_empty() {
+            for entry in entries.iter().filter(|e| e.binding.is_none()) {
+                for target in self.resolve_path(module, entry, depth + 1, visiting) {
+                    if let Target::Module(glob_module) = target {
+                        if glob_module != module {
+                            targets.extend(self.lookup(glob_module, name, depth + 1, visiting));
+                        }
+                    }
+                }
+            }
+        }
+
+        visiting.remove(&(module, name.to_string()));
+
+        let mut unique = HashSet::new();
+        targets.retain(|target| unique.insert(*target));
+        targets
+    }
+
+    /// Resolve the path of a `use` entry from the module declaring it
+    fn resolve_path(
+        &self,
+        from: usize,
+        entry: &UseEntry,
+        depth: usize,
+        visiting: &mut HashSet<(usize, String)>,
+    ) -> Vec<Target> {
+        let segments = &entry.segments;
+        if entry.external || segments.is_empty() {
+            return Vec::new();
+        }
+
+        match segments[0].as_str() {
+            "crate" | "$crate" => self.resolve_in(0, &segments[1..], depth, visiting),
+            "self" => self.resolve_in(from, &segments[1..], depth, visiting),
+            "super" => self.resolve_in(from, segments, depth, visiting),
+            _ => {
+                let targets = self.resolve_in(from, segments, depth, visiting);
+                // 2015 edition paths are relative to the crate root
+                if targets.is_empty() && from != 0 {
+                    self.resolve_in(0, segments, depth, visiting)
+                } else {
+                    targets
+                }
+            }
+        }
+    }
+
+    fn resolve_in(
+        &self,
+        module: usize,
+        segments: &[String],
+        depth: usize,
+        visiting: &mut HashSet<(usize, String)>,
+    ) -> Vec<Target> {
+        let Some((last, prefix)) = segments.split_last() else {
+            return vec![Target::Module(module)];
+        };
+
+        let mut current = module;
+        for segment in prefix {
+            let next = match segment.as_str() {
+                "super" => self.modules[current].parent,
+                "self" => Some(current),
+                _ => self
+                    .lookup(current, segment, depth, visiting)
+                    .into_iter()
+                    .find_map(|target| match target {
+                        Target::Module(m) => Some(m),
+                        Target::Item(..) => None,
+                    }),
+            };
+            let Some(next) = next else {
+                return Vec::new();
+            };
+            current = next;
+        }
+
+        match last.as_str() {
+            "super" => self.modules[current]
+                .parent
+                .map(Target::Module)
+                .into_iter()
+                .collect(),
+            "self" => vec![Target::Module(current)],
+            _ => self.lookup(current, last, depth, visiting),
+        }
+    }
+
+    /// All bindings introduced by `use` declarations of a module
+    fn use_entries(&self, module: usize, public_only: bool) -> Vec<UseEntry> {
+        let mut entries = Vec::new();
+        for item in &self.modules[module].items {
+            if let Item::Use(item_use) = item {
+                if is_test_only(&item_use.attrs) || (public_only && !is_public(&item_use.vis)) {
+                    continue;
+                }
+                flatten_use(
+                    &item_use.tree,
+                    &mut Vec::new(),
+                    item_use.leading_colon.is_some(),
+                    &mut entries,
+                );
+            }
+        }
+        entries
+    }
+
+    fn exported_macros(&self) -> impl Iterator<Item = Target> + '_ {
+        self.modules.iter().enumerate().flat_map(|(m, module)| {
+            module
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    matches!(item, Item::Macro(mac)
+                        if mac.ident.is_some()
+                            && has_attr(&mac.attrs, "macro_export")
+                            && !is_test_only(&mac.attrs))
+                })
+                .map(move |(index, _)| Target::Item(m, index))
+        })
+    }
+
+    fn item(&self, target: Target) -> Option<&Item> {
+        match target {
+            Target::Item(module, index) => self.modules[module].items.get(index),
+            Target::Module(_) => None,
+        }
+    }
+
+    fn target_name(&self, target: Target) -> Option<String> {
+        self.item(target).and_then(item_name)
+    }
+
+    /// Walk the public module tree breadth-first from the crate root, so an
+    /// item re-exported in several places is reported at its shallowest path
+    fn public_targets(&self) -> Vec<(Target, String, Vec<String>)> {
+        let mut walk = PublicWalk::default();
+        walk.visited_modules.insert(0);
+        walk.queue.push_back((0, Vec::new()));
+
+        for target in self.exported_macros() {
+            let hidden = self.item(target).map(item_attrs).is_some_and(is_hidden);
+            if let (false, Some(name)) = (hidden, self.target_name(target)) {
+                walk.record(target, name, &[]);
+            }
+        }
+
+        while let Some((module, parents)) = walk.queue.pop_front() {
+            for (index, item) in self.modules[module].items.iter().enumerate() {
+                let attrs = item_attrs(item);
+                if is_test_only(attrs) || is_hidden(attrs) {
+                    continue;
+                }
+
+                match item {
+                    Item::Use(item_use) if is_public(&item_use.vis) => {
+                        let mut entries = Vec::new();
+                        flatten_use(
+                            &item_use.tree,
+                            &mut Vec::new(),
+                            item_use.leading_colon.is_some(),
+                            &mut entries,
+                        );
+
+                        for entry in entries {
+                            let targets = self.resolve_path(module, &entry, 0, &mut HashSet::new());
+                            match entry.binding {
+                                // Glob re-exports flatten the module into this one
+                                None => {
+                                    for target in targets {
+                                        if let Target::Module(glob_module) = target {
+                                            if walk.visited_modules.insert(glob_module) {
+                                                walk.queue
+                                                    .push_back((glob_module, parents.clone()));
+                                            }
+                                        }
+                                    }
+                                }
+                                Some(name) if name != "_" => {
+                                    for target in targets {
+                                        let hidden = self
+                                            .item(target)
+                                            .map(item_attrs)
+                                            .is_some_and(is_hidden);
+                                        if !hidden {
+                                            walk.record(target, name.clone(), &parents);
+                                        }
+                                    }
+                                }
+                                Some(_) => {}
+                            }
+                        }
+                    }
+                    Item::Mod(item_mod) if is_public(&item_mod.vis) => {
+                        let name = item_mod.ident.unraw().to_string();
+                        if let Some(&child) = self.modules[module].children.get(&name) {
+                            walk.record(Target::Module(child), name, &parents);
+                        }
+                    }
+                    Item::Use(_) | Item::Mod(_) | Item::Macro(_) | Item::Impl(_) => {}
+                    _ => {
+                        if let (Some(vis), Some(name)) = (item_visibility(item), item_name(item)) {
+                            if is_public(vis) {
+                                walk.record(Target::Item(module, index), name, &parents);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        walk.found
+    }
+
+    /// Build the documented item list with signatures and related items
+    fn documented_items(&self) -> Vec<LocalItem> {
+        let impls = self.collect_impls();
+
+        let mut items: Vec<LocalItem> = self
+            .public_targets()
+            .into_iter()
+            .filter_map(|(target, name, parents)| self.build_item(target, name, parents, &impls))
+            .collect();
+
+        // Modules list the names of their public children
+        let children: HashMap<Vec<String>, Vec<String>> =
+            items.iter().fold(HashMap::new(), |mut acc, item| {
+                acc.entry(item.parents.clone())
+                    .or_default()
+                    .push(item.name.clone());
+                acc
+            });
+        for item in items.iter_mut().filter(|i| i.kind == ItemKind::Module) {
+            let mut path = item.parents.clone();
+            path.push(item.name.clone());
+            item.related_items = children.get(&path).cloned().unwrap_or_default();
+        }
+
+        items
+    }
+
+    fn build_item(
+        &self,
+        target: Target,
+        name: String,
+        parents: Vec<String>,
+        impls: &ImplIndex,
+    ) -> Option<LocalItem> {
+        let (module, index) = match target {
+            Target::Module(module) => {
+                let module = &self.modules[module];
+                return Some(LocalItem {
+                    signature: Some(format!("pub mod {name}")),
+                    name,
+                    parents,
+                    kind: ItemKind::Module,
+                    page: "index",
+                    docs: module.docs.clone(),
+                    is_async: false,
+                    deprecation: None,
+                    related_items: Vec::new(),
+                    associated_items: Vec::new(),
+                    trait_impls: Vec::new(),
+                    required_items: Vec::new(),
+                    provided_items: Vec::new(),
+                    source_file: module.file.clone(),
+                });
+            }
+            Target::Item(module, index) => (module, index),
+        };
+
+        let source_file = self.modules[module].file.clone();
+        let base_dir = source_file.parent().unwrap_or(Path::new("."));
+        let item = &self.modules[module].items[index];
+        let docs = doc_string(item_attrs(item), base_dir);
+        let deprecation = deprecation(item_attrs(item));
+        let original_name = item_name(item)?;
+
+        let (kind, page, related_items) = match item {
+            Item::Fn(function) => {
+                if let Some((page, macro_name)) = proc_macro_kind(function) {
+                    let signature = match page {
+                        "derive" => format!("#[derive({macro_name})]"),
+                        "attr" => format!("#[{macro_name}]"),
+                        _ => format!("{macro_name}!() {{ /* proc-macro */ }}"),
+                    };
+                    return Some(LocalItem {
+                        name: macro_name,
+                        parents,
+                        kind: ItemKind::Macro,
+                        page,
+                        docs,
+                        signature: Some(signature),
+                        is_async: false,
+                        deprecation,
+                        related_items: Vec::new(),
+                        associated_items: Vec::new(),
+                        trait_impls: Vec::new(),
+                        required_items: Vec::new(),
+                        provided_items: Vec::new(),
+                        source_file,
+                    });
+                }
+                (ItemKind::Function, "fn", Vec::new())
+            }
+            Item::Struct(_) => (ItemKind::Struct, "struct", impls.for_type(&original_name)),
+            Item::Enum(_) => (ItemKind::Enum, "enum", impls.for_type(&original_name)),
+            Item::Union(_) => (ItemKind::Union, "union", impls.for_type(&original_name)),
+            Item::Trait(_) => (ItemKind::Trait, "trait", impls.for_trait(&original_name)),
+            Item::TraitAlias(_) => (ItemKind::Trait, "traitalias", Vec::new()),
+            Item::Type(_) => (ItemKind::TypeAlias, "type", Vec::new()),
+            Item::Const(_) => (ItemKind::Constant, "constant", Vec::new()),
+            Item::Static(_) => (ItemKind::Constant, "static", Vec::new()),
+            Item::Macro(_) => (ItemKind::Macro, "macro", Vec::new()),
+            _ => return None,
+        };
+
+        let (associated_items, trait_impls) = match item {
+            Item::Struct(_) | Item::Enum(_) | Item::Union(_) => (
+                impls.inherent_items(&original_name),
+                impls.trait_impls(&original_name),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+        let (required_items, provided_items) = match item {
+            Item::Trait(trait_item) => trait_items(trait_item, base_dir),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        Some(LocalItem {
+            signature: render_signature(item, &name),
+            is_async: matches!(item, Item::Fn(f) if f.sig.asyncness.is_some()),
+            deprecation,
+            name,
+            parents,
+            kind,
+            page,
+            docs,
+            related_items,
+            associated_items,
+            trait_impls,
+            required_items,
+            provided_items,
+            source_file,
+        })
+    }
+
+    fn collect_impls(&self) -> ImplIndex {
+        let mut index = ImplIndex::default();
+
+        for module in &self.modules {
+            let base_dir = module.file.parent().unwrap_or(Path::new("."));
+            for item in &module.items {
+                match item {
+                    Item::Impl(imp) if !is_test_only(&imp.attrs) => {
+                        let header = render_impl_header(imp);
+                        if let Some(type_name) = type_name(&imp.self_ty) {
+                            if imp.trait_.is_none() {
+                                index
+                                    .inherent
+                                    .entry(type_name.clone())
+                                    .or_default()
+                                    .extend(inherent_items(imp, base_dir));
+                            } else {
+                                let is_blanket = imp
+                                    .generics
+                                    .type_params()
+                                    .any(|param| param.ident.unraw() == type_name);
+                                if let Some(trait_impl) =
+                                    parse_trait_impl_header(&header, is_blanket, false)
+                                {
+                                    index
+                                        .trait_impls
+                                        .entry(type_name.clone())
+                                        .or_default()
+                                        .push(trait_impl);
+                                }
+                            }
+                            index
+                                .types
+                                .entry(type_name)
+                                .or_default()
+                                .push(header.clone());
+                        }
+                        if let Some((_, path, _)) = &imp.trait_ {
+                            if let Some(segment) = path.segments.last() {
+                                index
+                                    .traits
+                                    .entry(segment.ident.unraw().to_string())
+                                    .or_default()
+                                    .push(header);
+                            }
+                        }
+                    }
+                    Item::Struct(_) | Item::Enum(_) | Item::Union(_) => {
+                        let Some(name) = item_name(item) else {
+                            continue;
+                        };
+                        for derived in derived_traits(item_attrs(item)) {
+                            let header = format!("impl {derived} for {name}");
+                            if let Some(trait_impl) = parse_trait_impl_header(&header, false, false)
+                            {
+                                index
+                                    .trait_impls
+                                    .entry(name.clone())
+                                    .or_default()
+                                    .push(trait_impl);
+                            }
+                            index.types.entry(name.clone()).or_default().push(header);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        index
+    }
+}
+
+/// State of the breadth-first walk over the public module tree
+#[derive(Debug, Default)]
+struct PublicWalk {
+    found: Vec<(Target, String, Vec<String>)>,
+    seen_items: HashSet<Target>,
+    visited_modules: HashSet<usize>,
+    queue: VecDeque<(usize, Vec<String>)>,
+}
+
+impl PublicWalk {
+    fn record(&mut self, target: Target, name: String, parents: &[String]) {
+        match target {
+            Target::Module(module) => {
+                if !self.visited_modules.insert(module) {
+                    return;
+                }
+                let mut module_path = parents.to_vec();
+                module_path.push(name.clone());
+                self.queue.push_back((module, module_path));
+            }
+            Target::Item(..) => {
+                if !self.seen_items.insert(target) {
+                    return;
+                }
+            }
+        }
+        self.found.push((target, name, parents.to_vec()));
+    }
+}
+
+/// Impl headers grouped by implementing type and by implemented trait,
+/// plus the inherent items and trait impls of each type
+#[derive(Debug, Default)]
+struct ImplIndex {
+    types: HashMap<String, Vec<String>>,
+    traits: HashMap<String, Vec<String>>,
+    inherent: HashMap<String, Vec<AssociatedItem>>,
+    trait_impls: HashMap<String, Vec<TraitImpl>>,
+}
+
+impl ImplIndex {
+    fn for_type(&self, name: &str) -> Vec<String> {
+        sorted(self.types.get(name))
+    }
+
+    fn for_trait(&self, name: &str) -> Vec<String> {
+        sorted(self.traits.get(name))
+    }
+
+    fn inherent_items(&self, name: &str) -> Vec<AssociatedItem> {
+        self.inherent.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Trait impls of a type, explicit impls before blanket impls
+    fn trait_impls(&self, name: &str) -> Vec<TraitImpl> {
+        let mut impls = self.trait_impls.get(name).cloned().unwrap_or_default();
+        impls.sort_by(|a, b| (a.is_blanket, &a.trait_name).cmp(&(b.is_blanket, &b.trait_name)));
+        impls.dedup_by(|a, b| a.header == b.header);
+        impls
+    }
+}
+
+fn sorted(headers: Option<&Vec<String>>) -> Vec<String> {
+    let mut headers = headers.cloned().unwrap_or_default();
+    headers.sort();
+    headers.dedup();
+    headers
+}
+
+/// Splice in the contents of item-position macro invocations whose body is a
+/// list of items, such as `cfg_feature! { pub mod feature; }` wrappers
+fn expand_item_macros(items: Vec<Item>) -> Vec<Item> {
+    let mut expanded = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Item::Macro(mac) if mac.ident.is_none() && !is_test_only(&mac.attrs) => {
+                match syn::parse2::<syn::File>(mac.mac.tokens.clone()) {
+                    Ok(file) if !file.items.is_empty() => {
+                        expanded.extend(expand_item_macros(file.items))
+                    }
+                    _ => expanded.push(Item::Macro(mac)),
+                }
+            }
+            other => expanded.push(other),
+        }
+    }
+    expanded
+}
+
+fn flatten_use(tree: &UseTree, prefix: &mut Vec<String>, external: bool, out: &mut Vec<UseEntry>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.unraw().to_string());
+            flatten_use(&path.tree, prefix, external, out);
+            prefix.pop();
+        }
+        UseTree::Name(name) => {
+            let ident = name.ident.unraw().to_string();
+            let (segments, binding) = if ident == "self" {
+                (prefix.clone(), prefix.last().cloned())
+            } else {
+                let mut segments = prefix.clone();
+                segments.push(ident.clone());
+                (segments, Some(ident))
+            };
+            out.push(UseEntry {
+                segments,
+                binding,
+                external,
+            });
+        }
+        UseTree::Rename(rename) => {
+            let ident = rename.ident.unraw().to_string();
+            let mut segments = prefix.clone();
+            if ident != "self" {
+                segments.push(ident);
+            }
+            out.push(UseEntry {
+                segments,
+                binding: Some(rename.rename.unraw().to_string()),
+                external,
+            });
+        }
+        UseTree::Glob(_) => out.push(UseEntry {
+            segments: prefix.clone(),
+            binding: None,
+            external,
+        }),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_use(tree, prefix, external, out);
+            }
+        }
+    }
+}
+
+fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(i) => &i.attrs,
+        Item::Enum(i) => &i.attrs,
+        Item::ExternCrate(i) => &i.attrs,
+        Item::Fn(i) => &i.attrs,
+        Item::ForeignMod(i) => &i.attrs,
+        Item::Impl(i) => &i.attrs,
+        Item::Macro(i) => &i.attrs,
+        Item::Mod(i) => &i.attrs,
+        Item::Static(i) => &i.attrs,
+        Item::Struct(i) => &i.attrs,
+        Item::Trait(i) => &i.attrs,
+        Item::TraitAlias(i) => &i.attrs,
+        Item::Type(i) => &i.attrs,
+        Item::Union(i) => &i.attrs,
+        Item::Use(i) => &i.attrs,
+        _ => &[],
+    }
+}
+
+fn item_name(item: &Item) -> Option<String> {
+    let ident = match item {
+        Item::Const(i) => &i.ident,
+        Item::Enum(i) => &i.ident,
+        Item::Fn(i) => &i.sig.ident,
+        Item::Macro(i) => i.ident.as_ref()?,
+        Item::Mod(i) => &i.ident,
+        Item::Static(i) => &i.ident,
+        Item::Struct(i) => &i.ident,
+        Item::Trait(i) => &i.ident,
+        Item::TraitAlias(i) => &i.ident,
+        Item::Type(i) => &i.ident,
+        Item::Union(i) => &i.ident,
+        _ => return None,
+    };
+    Some(ident.unraw().to_string())
+}
+
+fn item_visibility(item: &Item) -> Option<&Visibility> {
+    match item {
+        Item::Const(i) => Some(&i.vis),
+        Item::Enum(i) => Some(&i.vis),
+        Item::Fn(i) => Some(&i.vis),
+        Item::Static(i) => Some(&i.vis),
+        Item::Struct(i) => Some(&i.vis),
+        Item::Trait(i) => Some(&i.vis),
+        Item::TraitAlias(i) => Some(&i.vis),
+        Item::Type(i) => Some(&i.vis),
+        Item::Union(i) => Some(&i.vis),
+        _ => None,
+    }
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+fn list_tokens(attr: &Attribute, name: &str) -> Option<String> {
+    match &attr.meta {
+        Meta::List(list) if attr.path().is_ident(name) => Some(list.tokens.to_string()),
+        _ => None,
+    }
+}
+
+fn is_hidden(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        list_tokens(attr, "doc")
+            .is_some_and(|tokens| tokens.split(',').any(|option| option.trim() == "hidden"))
+    })
+}
+
+fn is_test_only(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| list_tokens(attr, "cfg").is_some_and(|tokens| tokens.trim() == "test"))
+}
+
+/// Read `#[deprecated]`, `#[deprecated = "note"]` and
+/// `#[deprecated(since = "1.0", note = "...")]`
+fn deprecation(attrs: &[Attribute]) -> Option<Deprecation> {
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("deprecated"))?;
+    let mut deprecation = Deprecation::default();
+
+    match &attr.meta {
+        Meta::NameValue(nv) => deprecation.note = string_literal(&nv.value),
+        Meta::List(_) => {
+            // Malformed arguments still mark the item deprecated
+            let _ = attr.parse_nested_meta(|meta| {
+                let value = string_literal(&meta.value()?.parse::<Expr>()?);
+                if meta.path.is_ident("since") {
+                    deprecation.since = value;
+                } else if meta.path.is_ident("note") {
+                    deprecation.note = value;
+                }
+                Ok(())
+            });
+        }
+        Meta::Path(_) => {}
+    }
+    Some(deprecation)
+}
+
+fn string_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(nv) if attr.path().is_ident("path") => match &nv.value {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Str(s) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The docs.rs page kind and exposed name of a procedural macro function
+fn proc_macro_kind(function: &syn::ItemFn) -> Option<(&'static str, String)> {
+    let fn_name = function.sig.ident.unraw().to_string();
+    function.attrs.iter().find_map(|attr| {
+        if attr.path().is_ident("proc_macro_derive") {
+            let tokens = list_tokens(attr, "proc_macro_derive")?;
+            Some(("derive", tokens.split(',').next()?.trim().to_string()))
+        } else if attr.path().is_ident("proc_macro_attribute") {
+            Some(("attr", fn_name.clone()))
+        } else if attr.path().is_ident("proc_macro") {
+            Some(("macro", fn_name.clone()))
+        } else {
+            None
+        }
+    })
+}
+
+fn derived_traits(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .filter_map(|path| path.segments.last().map(|s| s.ident.unraw().to_string()))
+        .collect()
+}
+
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.unraw().to_string()),
+        syn::Type::Reference(reference) => type_name(&reference.elem),
+        syn::Type::Paren(paren) => type_name(&paren.elem),
+        syn::Type::Group(group) => type_name(&group.elem),
+        _ => None,
+    }
+}
+
+/// Collect `#[doc]` attributes into markdown, resolving `include_str!`
+/// relative to the directory of the declaring file
+fn doc_string(attrs: &[Attribute], base_dir: &Path) -> Option<String> {
+    let mut sections = Vec::new();
+    let mut comment_lines: Vec<String> = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        let Meta::NameValue(nv) = &attr.meta else {
+            continue;
+        };
+
+        match &nv.value {
+            Expr::Lit(lit) => {
+                if let Lit::Str(s) = &lit.lit {
+                    comment_lines.extend(s.value().split('\n').map(str::to_string));
+                }
+            }
+            Expr::Macro(mac) if mac.mac.path.is_ident("include_str") => {
+                let Ok(path) = mac.mac.parse_body::<syn::LitStr>() else {
+                    continue;
+                };
+                if let Ok(contents) = fs::read_to_string(base_dir.join(path.value())) {
+                    sections.push(unindent(&std::mem::take(&mut comment_lines)));
+                    sections.push(contents);
+                }
+            }
+            _ => {}
+        }
+    }
+    sections.push(unindent(&comment_lines));
+
+    let docs = sections
+        .iter()
+        .map(|s| s.trim_end())
+        .filter(|s| !s.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let docs = docs.trim_matches('\n').to_string();
+
+    if docs.trim().is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
+fn join_docs(outer: Option<String>, inner: Option<String>) -> Option<String> {
+    match (outer, inner) {
+        (Some(outer), Some(inner)) => Some(format!("{outer}\n{inner}")),
+        (outer, inner) => outer.or(inner),
+    }
+}
+
+/// Remove the common leading whitespace of doc comment lines
+fn unindent(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| {
+            let skip: usize = line
+                .chars()
+                .take(indent)
+                .take_while(|c| c.is_whitespace())
+                .map(char::len_utf8)
+                .sum();
+            &line[skip..]
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn unparse(item: Item) -> String {
+    prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item],
+    })
+    .trim()
+    .to_string()
+}
+
+/// Render an item declaration the way rustdoc shows it: attributes and
+/// bodies removed, private fields replaced by a marker
+fn render_signature(item: &Item, name: &str) -> Option<String> {
+    let signature = match item.clone() {
+        Item::Fn(mut function) => {
+            function.attrs.clear();
+            function.block.stmts.clear();
+            strip_empty_body(&unparse(Item::Fn(function)))
+        }
+        Item::Struct(mut item) => {
+            item.attrs.clear();
+            let stripped = strip_private_fields(&mut item.fields);
+            finish_private_fields(unparse(Item::Struct(item)), stripped)
+        }
+        Item::Union(mut item) => {
+            item.attrs.clear();
+            let mut fields = Fields::Named(item.fields.clone());
+            let stripped = strip_private_fields(&mut fields);
+            if let Fields::Named(named) = fields {
+                item.fields = named;
+            }
+            finish_private_fields(unparse(Item::Union(item)), stripped)
+        }
+        Item::Enum(mut item) => {
+            item.attrs.clear();
+            item.variants = item
+                .variants
+                .into_iter()
+                .filter(|variant| !is_hidden(&variant.attrs))
+                .map(|mut variant| {
+                    variant.attrs.clear();
+                    variant.fields.iter_mut().for_each(|f| f.attrs.clear());
+                    variant
+                })
+                .collect();
+            unparse(Item::Enum(item))
+        }
+        Item::Trait(mut item) => {
+            item.attrs.clear();
+            item.items = item
+                .items
+                .into_iter()
+                .filter(|trait_item| !is_hidden(trait_item_attrs(trait_item)))
+                .map(|trait_item| match trait_item {
+                    TraitItem::Fn(mut f) => {
+                        f.attrs.clear();
+                        f.default = None;
+                        f.semi_token = Some(Default::default());
+                        TraitItem::Fn(f)
+                    }
+                    TraitItem::Const(mut c) => {
+                        c.attrs.clear();
+                        c.default = None;
+                        TraitItem::Const(c)
+                    }
+                    TraitItem::Type(mut t) => {
+                        t.attrs.clear();
+                        TraitItem::Type(t)
+                    }
+                    other => other,
+                })
+                .collect();
+            unparse(Item::Trait(item))
+        }
+        Item::TraitAlias(mut item) => {
+            item.attrs.clear();
+            unparse(Item::TraitAlias(item))
+        }
+        Item::Type(mut item) => {
+            item.attrs.clear();
+            unparse(Item::Type(item))
+        }
+        Item::Const(mut item) => {
+            item.attrs.clear();
+            let rendered = unparse(Item::Const(item.clone()));
+            if rendered.contains('\n') {
+                item.expr = Box::new(placeholder_expr()?);
+                unparse(Item::Const(item))
+            } else {
+                rendered
+            }
+        }
+        Item::Static(mut item) => {
+            item.attrs.clear();
+            item.expr = Box::new(placeholder_expr()?);
+            unparse(Item::Static(item)).replace(" = _;", ";")
+        }
+        Item::Macro(item) => render_macro_rules(name, &item.mac.tokens),
+        _ => return None,
+    };
+
+    Some(signature)
+}
+
+fn trait_item_attrs(item: &TraitItem) -> &[Attribute] {
+    match item {
+        TraitItem::Const(i) => &i.attrs,
+        TraitItem::Fn(i) => &i.attrs,
+        TraitItem::Type(i) => &i.attrs,
+        TraitItem::Macro(i) => &i.attrs,
+        _ => &[],
+    }
+}
+
+fn placeholder_expr() -> Option<Expr> {
+    syn::parse_str("_").ok()
+}
+
+fn strip_empty_body(rendered: &str) -> String {
+    rendered
+        .strip_suffix("{}")
+        .unwrap_or(rendered)
+        .trim_end()
+        .to_string()
+}
+
+/// Drop non-public fields. Named fields are replaced by a marker that is
+/// rendered as `/* private fields */`, tuple fields become `_`.
+fn strip_private_fields(fields: &mut Fields) -> bool {
+    match fields {
+        Fields::Named(named) => {
+            let before = named.named.len();
+            named.named = named
+                .named
+                .iter()
+                .filter(|field| is_public(&field.vis) && !is_hidden(&field.attrs))
+                .cloned()
+                .map(|mut field| {
+                    field.attrs.clear();
+                    field
+                })
+                .collect();
+
+            let stripped = named.named.len() < before;
+            if stripped {
+                if let Ok(marker) = syn::parse_str::<syn::FieldsNamed>(&format!(
+                    "{{ {PRIVATE_FIELDS_MARKER}: () }}"
+                )) {
+                    named.named.extend(marker.named);
+                }
+            }
+            stripped
+        }
+        Fields::Unnamed(unnamed) => {
+            for field in unnamed.unnamed.iter_mut() {
+                field.attrs.clear();
+                if !is_public(&field.vis) {
+                    field.vis = Visibility::Inherited;
+                    if let Ok(ty) = syn::parse_str("_") {
+                        field.ty = ty;
+                    }
+                }
+            }
+            false
+        }
+        Fields::Unit => false,
+    }
+}
+
+fn finish_private_fields(rendered: String, stripped: bool) -> String {
+    if stripped {
+        rendered.replace(
+            &format!("{PRIVATE_FIELDS_MARKER}: (),"),
+            "/* private fields */",
+        )
+    } else {
+        rendered
+    }
+}
+
+fn render_impl_header(imp: &syn::ItemImpl) -> String {
+    let mut imp = imp.clone();
+    imp.attrs.clear();
+    imp.items.clear();
+    let rendered = strip_empty_body(&unparse(Item::Impl(imp)));
+    rendered
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(',')
+        .to_string()
+}
+
+/// Public, non-hidden items of an inherent impl block
+fn inherent_items(imp: &syn::ItemImpl, base_dir: &Path) -> Vec<AssociatedItem> {
+    imp.items
+        .iter()
+        .filter_map(|member| {
+            let (kind, vis, ident, attrs, is_async) = match member {
+                ImplItem::Fn(f) => (
+                    AssociatedItemKind::Method,
+                    &f.vis,
+                    &f.sig.ident,
+                    &f.attrs,
+                    f.sig.asyncness.is_some(),
+                ),
+                ImplItem::Const(c) => (
+                    AssociatedItemKind::Constant,
+                    &c.vis,
+                    &c.ident,
+                    &c.attrs,
+                    false,
+                ),
+                ImplItem::Type(t) => (AssociatedItemKind::Type, &t.vis, &t.ident, &t.attrs, false),
+                _ => return None,
+            };
+            if !is_public(vis) || is_hidden(attrs) {
+                return None;
+            }
+
+            let mut member = member.clone();
+            match &mut member {
+                ImplItem::Fn(f) => {
+                    f.attrs.clear();
+                    f.block.stmts.clear();
+                }
+                ImplItem::Const(c) => c.attrs.clear(),
+                ImplItem::Type(t) => t.attrs.clear(),
+                _ => {}
+            }
+            let mut container: syn::ItemImpl = syn::parse_str("impl __Member {}").ok()?;
+            container.items.push(member);
+
+            let name = ident.unraw().to_string();
+            Some(AssociatedItem {
+                signature: Some(render_member(Item::Impl(container))),
+                summary: doc_string(attrs, base_dir)
+                    .as_deref()
+                    .and_then(summarize_docs),
+                anchor: kind.anchor(&name),
+                is_async,
+                kind,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Split the non-hidden items of a trait into required items and items
+/// with a default
+fn trait_items(
+    trait_item: &syn::ItemTrait,
+    base_dir: &Path,
+) -> (Vec<AssociatedItem>, Vec<AssociatedItem>) {
+    let mut required = Vec::new();
+    let mut provided = Vec::new();
+
+    for member in &trait_item.items {
+        let attrs = trait_item_attrs(member);
+        if is_hidden(attrs) {
+            continue;
+        }
+        let (kind, ident, has_default, is_async) = match member {
+            TraitItem::Fn(f) => (
+                AssociatedItemKind::Method,
+                &f.sig.ident,
+                f.default.is_some(),
+                f.sig.asyncness.is_some(),
+            ),
+            TraitItem::Const(c) => (
+                AssociatedItemKind::Constant,
+                &c.ident,
+                c.default.is_some(),
+                false,
+            ),
+            TraitItem::Type(t) => (
+                AssociatedItemKind::Type,
+                &t.ident,
+                t.default.is_some(),
+                false,
+            ),
+            _ => continue,
+        };
+
+        let mut member = member.clone();
+        match &mut member {
+            TraitItem::Fn(f) => {
+                f.attrs.clear();
+                f.default = None;
+                f.semi_token = Some(Default::default());
+            }
+            TraitItem::Const(c) => {
+                c.attrs.clear();
+                c.default = None;
+            }
+            TraitItem::Type(t) => t.attrs.clear(),
+            _ => {}
+        }
+        let Ok(mut container) = syn::parse_str::<syn::ItemTrait>("trait __Member {}") else {
+            continue;
+        };
+        container.items.push(member);
+
+        let name = ident.unraw().to_string();
+        let anchor = match kind {
+            // rustdoc anchors required methods as `tymethod.<name>`
+            AssociatedItemKind::Method if !has_default => format!("tymethod.{name}"),
+            _ => kind.anchor(&name),
+        };
+        let associated = AssociatedItem {
+            signature: Some(render_member(Item::Trait(container))),
+            summary: doc_string(attrs, base_dir)
+                .as_deref()
+                .and_then(summarize_docs),
+            anchor,
+            is_async,
+            kind,
+            name,
+        };
+        if has_default {
+            provided.push(associated);
+        } else {
+            required.push(associated);
+        }
+    }
+
+    (required, provided)
+}
+
+/// Render the only member of a placeholder impl or trait block, dropping the
+/// block itself, the trailing `;` and an empty body
+fn render_member(container: Item) -> String {
+    let rendered = unparse(container);
+    let lines: Vec<&str> = rendered.lines().collect();
+    let body = lines
+        .get(1..lines.len().saturating_sub(1))
+        .unwrap_or_default()
+        .iter()
+        .map(|line| line.strip_prefix("    ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    strip_empty_body(body.trim().trim_end_matches(';'))
+}
+
+/// Render `macro_rules!` as its list of matchers, like rustdoc does
+fn render_macro_rules(name: &str, tokens: &proc_macro2::TokenStream) -> String {
+    let mut arms = Vec::new();
+    let mut expecting_matcher = true;
+
+    for token in tokens.clone() {
+        if let TokenTree::Group(group) = token {
+            if expecting_matcher {
+                let matcher = group.stream().to_string().replace("$ ", "$");
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    _ => ("(", ")"),
+                };
+                arms.push(format!("    {open}{matcher}{close} => {{ ... }};"));
+            }
+            expecting_matcher = !expecting_matcher;
+        }
+    }
+
+    format!("macro_rules! {name} {{\n{}\n}}", arms.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn sample_crate() -> (tempfile::TempDir, LocalCrate) {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+
+        write(
+            root,
+            "src/lib.rs",
+            r#"
+//! Demo crate.
+//!
+//! ```
+//! demo::run();
+//! ```
+
+pub mod sync;
+mod inner;
+cfg_extras! {
+    /// Extra helpers.
+    pub fn extra() {}
+}
+#[cfg(test)]
+mod tests;
+
+pub use inner::{Config, helpers::*};
+pub use sync::Mutex as Lock;
+
+/// Runs the thing.
+///
+/// More details.
+pub async fn run<T: Clone>(value: T) -> Result<T, Error> {
+    Ok(value)
+}
+
+/// Errors.
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// Not found.
+    NotFound(String),
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+#[doc(hidden)]
+pub fn hidden() {}
+
+fn private() {}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+"#,
+        );
+        write(
+            root,
+            "src/sync/mod.rs",
+            r#"
+//! Synchronization primitives.
+
+/// A mutex.
+pub struct Mutex<T> {
+    pub poisoned: bool,
+    inner: T,
+}
+
+impl<T> Mutex<T> {
+    pub fn new(inner: T) -> Self {
+        Self { poisoned: false, inner }
+    }
+}
+
+/// Lock guards.
+pub trait Guard {
+    /// The guarded value.
+    type Target;
+
+    fn get(&self) -> &Self::Target;
+
+    fn release(self) where Self: Sized {
+        drop(self)
+    }
+}
+
+#[macro_export]
+/// Lock a value.
+macro_rules! lock {
+    ($value:expr) => { $crate::sync::Mutex::new($value) };
+    () => { compile_error!("missing value") };
+}
+"#,
+        );
+        write(
+            root,
+            "src/inner.rs",
+            r#"
+/// Settings.
+#[deprecated = "tuple fields are going away"]
+pub struct Config(pub u32, String);
+
+pub const MAX: u32 = 10;
+
+pub mod helpers {
+    /// Helps.
+    #[deprecated(since = "0.2.0", note = "use `run`")]
+    pub fn help() {}
+}
+"#,
+        );
+        write(root, "src/tests.rs", "pub fn should_not_appear() {}");
+
+        let krate = parse_crate(&root.join("src/lib.rs")).unwrap();
+        (temp, krate)
+    }
+
+    fn find<'a>(krate: &'a LocalCrate, path: &str) -> &'a LocalItem {
+        krate
+            .items
+            .iter()
+            .find(|item| item.rust_path() == path)
+            .unwrap_or_else(|| panic!("missing {path}"))
+    }
+
+    #[test]
+    fn test_public_items_and_reexports() {
+        let (_temp, krate) = sample_crate();
+
+        assert!(krate.docs.as_deref().unwrap().starts_with("Demo crate."));
+
+        let paths: Vec<String> = krate.items.iter().map(|i| i.rust_path()).collect();
+        for expected in [
+            "run",
+            "Error",
+            "sync",
+            "Config",
+            "help",
+            "Lock",
+            "lock",
+            "sync::Guard",
+            "extra",
+        ] {
+            assert!(
+                paths.contains(&expected.to_string()),
+                "missing {expected}: {paths:?}"
+            );
+        }
+        // Re-exported at the root, which is shallower than `sync::Mutex`
+        assert!(!paths.contains(&"sync::Mutex".to_string()));
+        for absent in [
+            "hidden",
+            "private",
+            "should_not_appear",
+            "MAX",
+            "inner",
+            "helpers",
+        ] {
+            assert!(!paths.contains(&absent.to_string()), "unexpected {absent}");
+        }
+
+        assert_eq!(find(&krate, "lock").html_path(), "macro.lock.html");
+        assert_eq!(find(&krate, "sync").html_path(), "sync/index.html");
+        assert_eq!(
+            find(&krate, "sync::Guard").html_path(),
+            "sync/trait.Guard.html"
+        );
+    }
+
+    #[test]
+    fn test_signatures_and_docs() {
+        let (_temp, krate) = sample_crate();
+
+        let run = find(&krate, "run");
+        assert_eq!(run.kind, ItemKind::Function);
+        assert!(run.is_async);
+        assert_eq!(
+            run.signature.as_deref(),
+            Some("pub async fn run<T: Clone>(value: T) -> Result<T, Error>")
+        );
+        assert_eq!(
+            run.docs.as_deref(),
+            Some("Runs the thing.\n\nMore details.")
+        );
+
+        let lock = find(&krate, "Lock");
+        assert_eq!(lock.kind, ItemKind::Struct);
+        let signature = lock.signature.as_deref().unwrap();
+        assert!(signature.contains("pub poisoned: bool,"));
+        assert!(signature.contains("/* private fields */"));
+        assert!(!signature.contains("inner"));
+        assert_eq!(lock.related_items, vec!["impl<T> Mutex<T>".to_string()]);
+        assert_eq!(lock.associated_items.len(), 1);
+        assert_eq!(lock.associated_items[0].anchor, "method.new");
+        assert_eq!(
+            lock.associated_items[0].signature.as_deref(),
+            Some("pub fn new(inner: T) -> Self")
+        );
+
+        let config = find(&krate, "Config");
+        assert_eq!(
+            config.signature.as_deref(),
+            Some("pub struct Config(pub u32, _);")
+        );
+        assert_eq!(
+            config.deprecation,
+            Some(Deprecation {
+                since: None,
+                note: Some("tuple fields are going away".to_string()),
+            })
+        );
+        assert_eq!(
+            find(&krate, "help").deprecation,
+            Some(Deprecation {
+                since: Some("0.2.0".to_string()),
+                note: Some("use `run`".to_string()),
+            })
+        );
+        assert_eq!(run.deprecation, None);
+
+        let error = find(&krate, "Error");
+        assert!(!error
+            .signature
+            .as_deref()
+            .unwrap()
+            .contains("__Nonexhaustive"));
+        assert_eq!(
+            error.related_items,
+            vec![
+                "impl Clone for Error".to_string(),
+                "impl Debug for Error".to_string(),
+                "impl std::fmt::Display for Error".to_string(),
+            ]
+        );
+        let traits: Vec<&str> = error
+            .trait_impls
+            .iter()
+            .map(|i| i.trait_name.as_str())
+            .collect();
+        assert_eq!(traits, vec!["Clone", "Debug", "std::fmt::Display"]);
+
+        let guard = find(&krate, "sync::Guard");
+        let signature = guard.signature.as_deref().unwrap();
+        assert!(signature.contains("fn get(&self) -> &Self::Target;"));
+        assert!(!signature.contains("drop(self)"));
+        let required: Vec<&str> = guard
+            .required_items
+            .iter()
+            .map(|i| i.anchor.as_str())
+            .collect();
+        assert_eq!(required, vec!["associatedtype.Target", "tymethod.get"]);
+        assert_eq!(
+            guard.required_items[0].summary.as_deref(),
+            Some("The guarded value.")
+        );
+        assert_eq!(guard.provided_items.len(), 1);
+        assert_eq!(
+            guard.provided_items[0].signature.as_deref(),
+            Some("fn release(self)\nwhere\n    Self: Sized")
+        );
+
+        let lock_macro = find(&krate, "lock");
+        assert_eq!(lock_macro.docs.as_deref(), Some("Lock a value."));
+        assert_eq!(
+            lock_macro.signature.as_deref(),
+            Some("macro_rules! lock {\n    ($value : expr) => { ... };\n    () => { ... };\n}")
+        );
+
+        let sync = find(&krate, "sync");
+        assert_eq!(sync.docs.as_deref(), Some("Synchronization primitives."));
+        assert!(sync.related_items.contains(&"Guard".to_string()));
+    }
+
+    #[test]
+    fn test_proc_macro_functions() {
+        let temp = tempfile::tempdir().unwrap();
+        write(
+            temp.path(),
+            "src/lib.rs",
+            r#"
+/// Derive the thing.
+#[proc_macro_derive(Thing, attributes(thing))]
+pub fn derive_thing(input: TokenStream) -> TokenStream { input }
+
+#[proc_macro_attribute]
+pub fn route(attr: TokenStream, item: TokenStream) -> TokenStream { item }
+"#,
+        );
+
+        let krate = parse_crate(&temp.path().join("src/lib.rs")).unwrap();
+        let thing = find(&krate, "Thing");
+        assert_eq!(thing.kind, ItemKind::Macro);
+        assert_eq!(thing.html_path(), "derive.Thing.html");
+        assert_eq!(thing.signature.as_deref(), Some("#[derive(Thing)]"));
+
+        let route = find(&krate, "route");
+        assert_eq!(route.html_path(), "attr.route.html");
+    }
+}
@@ -0,0 +1,707 @@
+//! Resolve the crate versions a Cargo project builds against.
+//!
+//! Versions are read from `Cargo.lock` when there is one, otherwise each
+//! manifest requirement is resolved to the highest matching published version.
+//! The result can be installed as [`VersionPins`] so that documentation
+//! requests without an explicit version follow the project instead of `latest`.
+
+use crate::{
+    client::DocsClient,
+    endpoints::{metadata::select_version, MetadataService},
+    local_source::LocalManifest,
+};
+use rustacean_docs_core::{
+    models::{
+        metadata::{CrateMetadata, CrateMetadataRequest, DependencyKind},
+        project::{ProjectAnalysis, ResolvedDependency, UnresolvedDependency, VersionSource},
+    },
+    CrateName, ErrorBuilder, Result, Version,
+};
+use semver::{Version as SemVersion, VersionReq};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+use toml::{Table, Value};
+use tracing::{debug, warn};
+
+const MANIFEST_FILE: &str = "Cargo.toml";
+const LOCKFILE_FILE: &str = "Cargo.lock";
+
+/// The manifest and lockfile describing a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectFiles {
+    /// `Cargo.toml`, if there is one
+    pub manifest: Option<PathBuf>,
+    /// `Cargo.lock`, if there is one
+    pub lockfile: Option<PathBuf>,
+}
+
+impl ProjectFiles {
+    /// Find the project files for a path to a `Cargo.toml` or `Cargo.lock`.
+    ///
+    /// The lockfile of a workspace member lives at the workspace root, so it
+    /// is searched for in the manifest's ancestors.
+    pub fn locate(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Err(ErrorBuilder::config()
+                .invalid_field("path", format!("{} is not a file", path.display())));
+        }
+
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some(MANIFEST_FILE) => Ok(Self {
+                manifest: Some(path.to_path_buf()),
+                lockfile: path
+                    .parent()
+                    .into_iter()
+                    .flat_map(Path::ancestors)
+                    .map(|dir| dir.join(LOCKFILE_FILE))
+                    .find(|candidate| candidate.is_file()),
+            }),
+            Some(LOCKFILE_FILE) => Ok(Self {
+                manifest: path
+                    .parent()
+                    .map(|dir| dir.join(MANIFEST_FILE))
+                    .filter(|candidate| candidate.is_file()),
+                lockfile: Some(path.to_path_buf()),
+            }),
+            _ => Err(ErrorBuilder::config().invalid_field(
+                "path",
+                format!(
+                    "{} is not a {MANIFEST_FILE} or {LOCKFILE_FILE}",
+                    path.display()
+                ),
+            )),
+        }
+    }
+}
+
+/// A package recorded in `Cargo.lock`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: SemVersion,
+    /// `registry+...`, `sparse+...` or `git+...`; `None` for path packages
+    pub source: Option<String>,
+    /// Dependency entries: `name`, `name version` or `name version (source)`
+    pub dependencies: Vec<String>,
+}
+
+impl LockedPackage {
+    /// Whether the package was downloaded from a registry
+    pub fn is_registry(&self) -> bool {
+        self.source
+            .as_deref()
+            .is_some_and(|source| source.starts_with("registry+") || source.starts_with("sparse+"))
+    }
+}
+
+/// A parsed `Cargo.lock`
+#[derive(Debug, Clone, Default)]
+pub struct Lockfile {
+    packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    /// Read and parse a lockfile
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents).map_err(|e| {
+            ErrorBuilder::docs().parse_error(format!("Invalid lockfile {}: {e}", path.display()))
+        })
+    }
+
+    /// Parse lockfile contents
+    pub fn parse(contents: &str) -> Result<Self> {
+        let table = contents
+            .parse::<Table>()
+            .map_err(|e| ErrorBuilder::docs().parse_error(e.to_string()))?;
+
+        let packages = table
+            .get("package")
+            .and_then(Value::as_array)
+            .map(|packages| packages.iter().filter_map(locked_package).collect())
+            .unwrap_or_default();
+
+        Ok(Self { packages })
+    }
+
+    /// All locked packages
+    pub fn packages(&self) -> &[LockedPackage] {
+        &self.packages
+    }
+
+    /// Packages built from the project itself (workspace members and path
+    /// dependencies)
+    pub fn local_packages(&self) -> impl Iterator<Item = &LockedPackage> {
+        self.packages
+            .iter()
+            .filter(|package| package.source.is_none())
+    }
+
+    /// The package a dependency entry refers to
+    pub fn lookup(&self, entry: &str) -> Option<&LockedPackage> {
+        let mut parts = entry.split_whitespace();
+        let name = parts.next()?;
+        let version = parts.next().and_then(|v| SemVersion::parse(v).ok());
+        self.packages.iter().find(|package| {
+            package.name == name && version.as_ref().is_none_or(|v| *v == package.version)
+        })
+    }
+
+    /// The locked version of a crate satisfying a requirement, the highest
+    /// one when the crate is locked at several versions
+    pub fn version_for(
+        &self,
+        name: &str,
+        requirement: Option<&VersionReq>,
+    ) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .filter(|package| package.name == name)
+            .filter(|package| requirement.is_none_or(|req| req.matches(&package.version)))
+            .max_by(|a, b| a.version.cmp(&b.version))
+    }
+}
+
+fn locked_package(value: &Value) -> Option<LockedPackage> {
+    Some(LockedPackage {
+        name: value.get("name")?.as_str()?.to_string(),
+        version: SemVersion::parse(value.get("version")?.as_str()?).ok()?,
+        source: value
+            .get("source")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        dependencies: value
+            .get("dependencies")
+            .and_then(Value::as_array)
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// A direct dependency before its version is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DirectDependency {
+    name: String,
+    requirement: Option<String>,
+    kind: Option<DependencyKind>,
+    optional: bool,
+    /// Package the lockfile records for it
+    locked: Option<LockedPackage>,
+}
+
+/// The direct dependencies of a project.
+///
+/// A package manifest lists them with their requirements. A virtual manifest
+/// or a lone lockfile only tells which locked packages the workspace members
+/// depend on.
+fn direct_dependencies(
+    manifest: Option<&LocalManifest>,
+    lockfile: Option<&Lockfile>,
+) -> Vec<DirectDependency> {
+    if let Some(manifest) = manifest.filter(|manifest| manifest.name().is_some()) {
+        return manifest
+            .dependencies()
+            .into_iter()
+            .map(|dependency| {
+                let requirement = VersionReq::parse(&dependency.version_req).ok();
+                DirectDependency {
+                    locked: lockfile
+                        .and_then(|lock| lock.version_for(&dependency.name, requirement.as_ref()))
+                        .cloned(),
+                    name: dependency.name,
+                    requirement: Some(dependency.version_req),
+                    kind: Some(dependency.kind),
+                    optional: dependency.optional,
+                }
+            })
+            .collect();
+    }
+
+    let Some(lockfile) = lockfile else {
+        return Vec::new();
+    };
+
+    let mut seen = BTreeMap::new();
+    for entry in lockfile
+        .local_packages()
+        .flat_map(|package| &package.dependencies)
+    {
+        if let Some(package) = lockfile.lookup(entry) {
+            seen.entry((package.name.clone(), package.version.clone()))
+                .or_insert_with(|| package.clone());
+        }
+    }
+
+    seen.into_values()
+        .filter(|package| package.source.is_some())
+        .map(|package| DirectDependency {
+            name: package.name.clone(),
+            requirement: None,
+            kind: None,
+            optional: false,
+            locked: Some(package),
+        })
+        .collect()
+}
+
+/// Session-wide crate versions used when a request does not name one.
+///
+/// Clones share the same pins.
+#[derive(Debug, Clone, Default)]
+pub struct VersionPins {
+    pins: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl VersionPins {
+    /// Pinned version of a crate
+    pub fn get(&self, crate_name: &str) -> Option<String> {
+        let pins = self.pins.read().unwrap_or_else(|e| e.into_inner());
+        pins.get(crate_name)
+            .or_else(|| pins.get(&crate_name.replace('-', "_")))
+            .or_else(|| pins.get(&crate_name.replace('_', "-")))
+            .cloned()
+    }
+
+    /// Replace all pins with the versions of an analyzed project
+    pub fn pin_project(&self, analysis: &ProjectAnalysis) {
+        let mut pins = self.pins.write().unwrap_or_else(|e| e.into_inner());
+        *pins = analysis.versions.clone().into_iter().collect();
+    }
+
+    /// Remove all pins
+    pub fn clear(&self) {
+        self.pins.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Number of pinned crates
+    pub fn len(&self) -> usize {
+        self.pins.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no crate is pinned
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Service resolving the dependency versions of local Cargo projects
+pub struct ProjectService {
+    metadata: MetadataService,
+}
+
+impl ProjectService {
+    /// Create a new project service using the client's registry for
+    /// dependencies that are not locked
+    pub fn new(client: DocsClient) -> Self {
+        Self {
+            metadata: MetadataService::new(client),
+        }
+    }
+
+    /// Analyze the project a `Cargo.toml` or `Cargo.lock` belongs to
+    pub async fn analyze(&self, path: &Path) -> Result<ProjectAnalysis> {
+        let files = ProjectFiles::locate(path)?;
+        let manifest = files
+            .manifest
+            .as_deref()
+            .map(LocalManifest::load)
+            .transpose()?;
+        let lockfile = files.lockfile.as_deref().map(Lockfile::load).transpose()?;
+
+        debug!(
+            manifest = ?files.manifest,
+            lockfile = ?files.lockfile,
+            "Analyzing project"
+        );
+
+        // Every registry crate in the build, so transitive dependencies
+        // follow the lockfile too
+        let mut versions = BTreeMap::new();
+        for package in lockfile
+            .iter()
+            .flat_map(Lockfile::packages)
+            .filter(|package| package.is_registry())
+        {
+            versions
+                .entry(package.name.clone())
+                .and_modify(|version: &mut SemVersion| {
+                    if package.version > *version {
+                        *version = package.version.clone();
+                    }
+                })
+                .or_insert_with(|| package.version.clone());
+        }
+        let mut versions: BTreeMap<String, String> = versions
+            .into_iter()
+            .map(|(name, version)| (name, version.to_string()))
+            .collect();
+
+        let mut dependencies = Vec::new();
+        let mut unresolved = Vec::new();
+        for dependency in direct_dependencies(manifest.as_ref(), lockfile.as_ref()) {
+            match self.resolve(&dependency).await {
+                Ok(resolved) => {
+                    versions.insert(resolved.name.clone(), resolved.version.clone());
+                    dependencies.push(resolved);
+                }
+                Err(reason) => unresolved.push(UnresolvedDependency {
+                    name: dependency.name,
+                    requirement: dependency.requirement,
+                    reason,
+                }),
+            }
+        }
+
+        Ok(ProjectAnalysis {
+            manifest_path: files.manifest.map(|path| path.display().to_string()),
+            lockfile_path: files.lockfile.map(|path| path.display().to_string()),
+            package: manifest.and_then(|manifest| manifest.name()),
+            dependencies,
+            unresolved,
+            versions,
+        })
+    }
+
+    /// Determine the version of a direct dependency and describe it, or
+    /// explain why that is not possible
+    async fn resolve(
+        &self,
+        dependency: &DirectDependency,
+    ) -> std::result::Result<ResolvedDependency, String> {
+        let crate_name = CrateName::new(&dependency.name).map_err(|e| e.to_string())?;
+
+        let (version, source, metadata) = match &dependency.locked {
+            Some(locked) if !locked.is_registry() => {
+                return Err(match &locked.source {
+                    Some(source) => format!("not a registry dependency ({source})"),
+                    None => "path dependency".to_string(),
+                });
+            }
+            Some(locked) => {
+                let metadata = self.metadata_for(&crate_name, &locked.version).await;
+                (locked.version.clone(), VersionSource::Lockfile, metadata)
+            }
+            None => {
+                let requirement = dependency.requirement.as_deref().unwrap_or("*");
+                let requirement = VersionReq::parse(requirement)
+                    .map_err(|e| format!("invalid version requirement '{requirement}': {e}"))?;
+
+                let latest = self
+                    .metadata
+                    .get_crate_metadata(&CrateMetadataRequest::new(crate_name.clone()))
+                    .await
+                    .map_err(|e| format!("failed to look up published versions: {e}"))?;
+                let version = select_version(&latest.versions, &requirement)
+                    .ok_or_else(|| format!("no published version matches '{requirement}'"))?;
+
+                let metadata = if latest.version == version.to_string() {
+                    Some(latest)
+                } else {
+                    self.metadata_for(&crate_name, &version).await
+                };
+                (version, VersionSource::Registry, metadata)
+            }
+        };
+
+        let version_info = metadata.as_ref().and_then(|metadata| {
+            metadata
+                .versions
+                .iter()
+                .find(|info| info.num == version.to_string())
+        });
+        let latest_version = metadata.as_ref().and_then(|metadata| {
+            select_version(&metadata.versions, &VersionReq::STAR).filter(|latest| *latest > version)
+        });
+
+        Ok(ResolvedDependency {
+            name: dependency.name.clone(),
+            requirement: dependency.requirement.clone(),
+            version: version.to_string(),
+            source,
+            kind: dependency.kind.clone(),
+            optional: dependency.optional,
+            description: metadata.as_ref().and_then(|m| m.description.clone()),
+            rust_version: version_info
+                .and_then(|info| info.rust_version.clone())
+                .or_else(|| metadata.as_ref().and_then(|m| m.rust_version.clone())),
+            latest_version: latest_version.map(|version| version.to_string()),
+            yanked: version_info.is_some_and(|info| info.yanked),
+        })
+    }
+
+    /// Registry metadata for an exact version. The version is already known,
+    /// so failures only lose the description and are not fatal.
+    async fn metadata_for(
+        &self,
+        crate_name: &CrateName,
+        version: &SemVersion,
+    ) -> Option<CrateMetadata> {
+        let request = CrateMetadataRequest::with_version(
+            crate_name.clone(),
+            Version::new(version.to_string()).ok()?,
+        );
+        self.metadata
+            .get_crate_metadata(&request)
+            .await
+            .inspect_err(|e| {
+                warn!(
+                    crate_name = %crate_name,
+                    version = %version,
+                    error = %e,
+                    "Failed to fetch metadata for project dependency"
+                )
+            })
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "demo"
+version = "0.1.0"
+dependencies = [
+ "helper",
+ "serde 1.0.190",
+ "syn 2.0.38",
+]
+
+[[package]]
+name = "helper"
+version = "0.1.0"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.190"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "91d3c334ca1ee894a2c6f6ad698fe8c435b76d504b13d436f0685d648d6d96f7"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.38"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    fn write_project(dir: &Path) {
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            r#"
+[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0"
+syn = { version = "2", features = ["full"] }
+helper = { path = "helper" }
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join(LOCKFILE_FILE), LOCKFILE).unwrap();
+    }
+
+    #[test]
+    fn test_parse_lockfile() {
+        let lockfile = Lockfile::parse(LOCKFILE).unwrap();
+        assert_eq!(lockfile.packages().len(), 5);
+        assert_eq!(lockfile.local_packages().count(), 2);
+
+        let syn = lockfile.lookup("syn 2.0.38").unwrap();
+        assert!(syn.is_registry());
+        assert_eq!(syn.version, SemVersion::new(2, 0, 38));
+        assert!(!lockfile.lookup("helper").unwrap().is_registry());
+
+        let req = VersionReq::parse("1").unwrap();
+        assert_eq!(
+            lockfile.version_for("syn", Some(&req)).unwrap().version,
+            SemVersion::new(1, 0, 109)
+        );
+        assert_eq!(
+            lockfile.version_for("syn", None).unwrap().version,
+            SemVersion::new(2, 0, 38)
+        );
+        assert!(Lockfile::parse("not = [valid").is_err());
+    }
+
+    #[test]
+    fn test_locate_project_files() {
+        let temp = tempfile::tempdir().unwrap();
+        write_project(temp.path());
+        let member = temp.path().join("helper");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            member.join(MANIFEST_FILE),
+            "[package]\nname = \"helper\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        // Members share the workspace lockfile
+        let files = ProjectFiles::locate(&member.join(MANIFEST_FILE)).unwrap();
+        assert_eq!(files.lockfile, Some(temp.path().join(LOCKFILE_FILE)));
+
+        let files = ProjectFiles::locate(&temp.path().join(LOCKFILE_FILE)).unwrap();
+        assert_eq!(files.manifest, Some(temp.path().join(MANIFEST_FILE)));
+
+        assert!(ProjectFiles::locate(&temp.path().join("missing.toml")).is_err());
+        fs::write(temp.path().join("other.toml"), "").unwrap();
+        assert!(ProjectFiles::locate(&temp.path().join("other.toml")).is_err());
+    }
+
+    #[test]
+    fn test_direct_dependencies_follow_lockfile() {
+        let temp = tempfile::tempdir().unwrap();
+        write_project(temp.path());
+        let manifest = LocalManifest::load(&temp.path().join(MANIFEST_FILE)).unwrap();
+        let lockfile = Lockfile::load(&temp.path().join(LOCKFILE_FILE)).unwrap();
+
+        let direct = direct_dependencies(Some(&manifest), Some(&lockfile));
+        let locked: Vec<(&str, Option<String>)> = direct
+            .iter()
+            .map(|dep| {
+                (
+                    dep.name.as_str(),
+                    dep.locked.as_ref().map(|p| p.version.to_string()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            locked,
+            vec![
+                ("helper", Some("0.1.0".to_string())),
+                ("serde", Some("1.0.190".to_string())),
+                ("syn", Some("2.0.38".to_string())),
+            ]
+        );
+
+        // Without a package manifest the workspace members' entries are used
+        let direct = direct_dependencies(None, Some(&lockfile));
+        let names: Vec<String> = direct
+            .iter()
+            .map(|dep| format!("{}@{}", dep.name, dep.locked.as_ref().unwrap().version))
+            .collect();
+        assert_eq!(names, vec!["serde@1.0.190", "syn@1.0.109", "syn@2.0.38"]);
+        assert!(direct.iter().all(|dep| dep.kind.is_none()));
+    }
+
+    #[test]
+    fn test_version_pins() {
+        let pins = VersionPins::default();
+        assert!(pins.is_empty());
+
+        let analysis = ProjectAnalysis {
+            manifest_path: None,
+            lockfile_path: None,
+            package: None,
+            dependencies: vec![],
+            unresolved: vec![],
+            versions: BTreeMap::from([("serde_json".to_string(), "1.0.108".to_string())]),
+        };
+        let shared = pins.clone();
+        shared.pin_project(&analysis);
+
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins.get("serde_json").as_deref(), Some("1.0.108"));
+        assert_eq!(pins.get("serde-json").as_deref(), Some("1.0.108"));
+        assert_eq!(pins.get("serde"), None);
+
+        pins.clear();
+        assert!(shared.is_empty());
+    }
+
+    #[cfg(feature = "integration-tests")]
+    mod integration_tests {
+        use super::*;
+        use crate::registry::Registry;
+        use mockito::Server;
+
+        #[tokio::test]
+        async fn test_analyze_project() {
+            let mut server = Server::new_async().await;
+            let serde_index = r#"{"name":"serde","vers":"1.0.190","deps":[],"features":{},"yanked":false,"rust_version":"1.31"}
+{"name":"serde","vers":"1.0.200","deps":[],"features":{},"yanked":false}
+"#;
+            let anyhow_index = r#"{"name":"anyhow","vers":"1.0.70","deps":[],"features":{},"yanked":false}
+{"name":"anyhow","vers":"1.0.75","deps":[],"features":{},"yanked":false}
+{"name":"anyhow","vers":"2.0.0","deps":[],"features":{},"yanked":false}
+"#;
+            server
+                .mock("GET", "/se/rd/serde")
+                .with_body(serde_index)
+                .create_async()
+                .await;
+            server
+                .mock("GET", "/an/yh/anyhow")
+                .with_body(anyhow_index)
+                .create_async()
+                .await;
+            server
+                .mock("GET", "/3/s/syn")
+                .with_status(404)
+                .create_async()
+                .await;
+
+            let temp = tempfile::tempdir().unwrap();
+            write_project(temp.path());
+            let manifest = temp.path().join(MANIFEST_FILE);
+            let contents = fs::read_to_string(&manifest).unwrap();
+            fs::write(&manifest, format!("{contents}anyhow = \"1.0.72\"\n")).unwrap();
+
+            let client = DocsClient::test_client()
+                .unwrap()
+                .with_registry(Registry::new("test", server.url()).with_index_url(server.url()));
+            let analysis = ProjectService::new(client)
+                .analyze(&manifest)
+                .await
+                .unwrap();
+
+            assert_eq!(analysis.package.as_deref(), Some("demo"));
+            let names: Vec<&str> = analysis
+                .dependencies
+                .iter()
+                .map(|dep| dep.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["anyhow", "serde", "syn"]);
+
+            let anyhow = &analysis.dependencies[0];
+            assert_eq!(anyhow.version, "1.0.75");
+            assert_eq!(anyhow.source, VersionSource::Registry);
+            assert_eq!(anyhow.latest_version.as_deref(), Some("2.0.0"));
+
+            let serde = &analysis.dependencies[1];
+            assert_eq!(serde.version, "1.0.190");
+            assert_eq!(serde.source, VersionSource::Lockfile);
+            assert_eq!(serde.rust_version.as_deref(), Some("1.31"));
+            assert_eq!(serde.latest_version.as_deref(), Some("1.0.200"));
+
+            // Locked versions survive a failed metadata lookup
+            assert_eq!(analysis.dependencies[2].version, "2.0.38");
+
+            assert_eq!(analysis.unresolved.len(), 1);
+            assert_eq!(analysis.unresolved[0].name, "helper");
+            assert_eq!(analysis.version_of("syn"), Some("2.0.38"));
+            assert_eq!(analysis.version_of("anyhow"), Some("1.0.75"));
+        }
+    }
+}
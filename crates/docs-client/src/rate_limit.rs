@@ -0,0 +1,485 @@
+//! Client-side rate limiting of outbound requests
+//!
+//! Each upstream host gets a token bucket. crates.io asks crawlers to stay at
+//! about one request per second, so it is limited by default; other hosts are
+//! only limited when configured. Interactive requests are served before
+//! background work (prewarming, refreshes) waiting on the same host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::debug;
+
+/// Host whose limit applies to every host without one of its own
+pub const ANY_HOST: &str = "*";
+
+/// Who is waiting for a request slot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequestPriority {
+    /// A tool call someone is waiting on
+    #[default]
+    Interactive,
+    /// Work nobody is waiting on, which yields to interactive requests
+    Background,
+}
+
+/// Sustained rate and burst size of one host's token bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HostRateLimit {
+    /// Requests per second once the burst is spent
+    pub requests_per_second: f64,
+    /// Requests that may be sent back to back after an idle period
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+}
+
+fn default_burst() -> u32 {
+    1
+}
+
+impl HostRateLimit {
+    pub fn per_second(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            burst: default_burst(),
+        }
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+impl fmt::Display for HostRateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.requests_per_second, self.burst)
+    }
+}
+
+impl FromStr for HostRateLimit {
+    type Err = String;
+
+    /// Parse `rate` or `rate/burst`, e.g. `1` or `5/10`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rate, burst) = match s.trim().split_once('/') {
+            Some((rate, burst)) => (rate, Some(burst)),
+            None => (s.trim(), None),
+        };
+        let requests_per_second: f64 = rate
+            .trim()
+            .parse()
+            .ok()
+            .filter(|rate: &f64| rate.is_finite() && *rate > 0.0)
+            .ok_or_else(|| format!("Invalid rate limit '{s}': expected a positive rate"))?;
+        let burst = match burst {
+            Some(burst) => burst
+                .trim()
+                .parse()
+                .ok()
+                .filter(|burst| *burst > 0)
+                .ok_or_else(|| format!("Invalid rate limit '{s}': expected a positive burst"))?,
+            None => default_burst(),
+        };
+        Ok(Self {
+            requests_per_second,
+            burst,
+        })
+    }
+}
+
+/// Rate limits by host; [`ANY_HOST`] sets the limit of hosts not listed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub hosts: BTreeMap<String, HostRateLimit>,
+}
+
+impl RateLimitConfig {
+    /// No host is limited
+    pub fn unlimited() -> Self {
+        Self {
+            hosts: BTreeMap::new(),
+        }
+    }
+
+    /// Limit `host`, or every unlisted host when it is [`ANY_HOST`]
+    pub fn with_host(mut self, host: impl Into<String>, limit: HostRateLimit) -> Self {
+        self.hosts.insert(host.into(), limit);
+        self
+    }
+
+    /// The limit applying to `host`, if any
+    pub fn limit_for(&self, host: &str) -> Option<HostRateLimit> {
+        self.hosts
+            .get(host)
+            .or_else(|| self.hosts.get(ANY_HOST))
+            .copied()
+    }
+
+    /// Parse a comma-separated list of `host=rate[/burst]` entries, e.g.
+    /// `crates.io=1,docs.rs=5/10,*=20`
+    pub fn parse_list(list: &str) -> Result<BTreeMap<String, HostRateLimit>, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (host, limit) = entry.split_once('=').ok_or_else(|| {
+                    format!("Invalid rate limit '{entry}': expected host=rate[/burst]")
+                })?;
+                Ok((host.trim().to_string(), limit.parse()?))
+            })
+            .collect()
+    }
+}
+
+impl Default for RateLimitConfig {
+    /// crates.io's crawler policy: one request per second
+    fn default() -> Self {
+        Self::unlimited().with_host("crates.io", HostRateLimit::per_second(1.0))
+    }
+}
+
+/// Queue and wait statistics of one host's limiter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitStats {
+    pub host: String,
+    pub limit: Option<HostRateLimit>,
+    /// Requests currently waiting for a slot
+    pub queue_depth: usize,
+    /// Of those, how many are background requests
+    pub background_waiting: usize,
+    /// Requests granted a slot
+    pub requests: u64,
+    /// Requests that had to wait for their slot
+    pub delayed: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+}
+
+impl RateLimitStats {
+    /// Average wait over all requests, delayed or not
+    pub fn mean_wait(&self) -> Duration {
+        match self.requests {
+            0 => Duration::ZERO,
+            requests => self.total_wait / requests as u32,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    interactive_waiting: usize,
+    background_waiting: usize,
+    stats: RateLimitStats,
+}
+
+impl BucketState {
+    fn waiting_mut(&mut self, priority: RequestPriority) -> &mut usize {
+        match priority {
+            RequestPriority::Interactive => &mut self.interactive_waiting,
+            RequestPriority::Background => &mut self.background_waiting,
+        }
+    }
+}
+
+/// A request counted as waiting for a token of `bucket`; dropping it, as
+/// when the waiting future is cancelled, takes it off the count again
+struct Waiting<'a> {
+    bucket: &'a TokenBucket,
+    priority: RequestPriority,
+}
+
+impl<'a> Waiting<'a> {
+    fn enter(bucket: &'a TokenBucket, state: &mut BucketState, priority: RequestPriority) -> Self {
+        *state.waiting_mut(priority) += 1;
+        Self { bucket, priority }
+    }
+
+    /// Stop waiting, with the bucket's state already locked
+    fn leave(self, state: &mut BucketState) {
+        *state.waiting_mut(self.priority) -= 1;
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        *self.bucket.lock().waiting_mut(self.priority) -= 1;
+    }
+}
+
+/// Token bucket of one host
+#[derive(Debug)]
+struct TokenBucket {
+    limit: Option<HostRateLimit>,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    fn new(host: &str, limit: Option<HostRateLimit>) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: limit.map_or(0.0, |limit| f64::from(limit.burst)),
+                refilled_at: Instant::now(),
+                interactive_waiting: 0,
+                background_waiting: 0,
+                stats: RateLimitStats {
+                    host: host.to_string(),
+                    limit,
+                    ..Default::default()
+                },
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for a token, returning how long that took
+    async fn acquire(&self, priority: RequestPriority) -> Duration {
+        let started = Instant::now();
+        let Some(limit) = self.limit else {
+            self.record(Duration::ZERO);
+            return Duration::ZERO;
+        };
+        let interval = Duration::from_secs_f64(1.0 / limit.requests_per_second);
+
+        let mut waiting: Option<Waiting<'_>> = None;
+        let mut queued = false;
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                let now = Instant::now();
+                let refill =
+                    now.duration_since(state.refilled_at).as_secs_f64() * limit.requests_per_second;
+                state.tokens = (state.tokens + refill).min(f64::from(limit.burst));
+                state.refilled_at = now;
+
+                let yields =
+                    priority == RequestPriority::Background && state.interactive_waiting > 0;
+                // Tolerate rounding in the refill arithmetic
+                if state.tokens >= 1.0 - 1e-9 && !yields {
+                    state.tokens = (state.tokens - 1.0).max(0.0);
+                    if let Some(waiting) = waiting.take() {
+                        waiting.leave(&mut state);
+                    }
+                    break;
+                }
+
+                if !queued {
+                    queued = true;
+                    waiting = Some(Waiting::enter(self, &mut state, priority));
+                }
+                if yields {
+                    // Let the interactive requests take the next tokens first
+                    interval
+                } else {
+                    interval.mul_f64(1.0 - state.tokens)
+                }
+            };
+            sleep(wait).await;
+        }
+
+        let waited = if queued {
+            started.elapsed()
+        } else {
+            Duration::ZERO
+        };
+        self.record(waited);
+        if !waited.is_zero() {
+            debug!(
+                host = %self.lock().stats.host,
+                waited_ms = waited.as_millis(),
+                ?priority,
+                "Request delayed by rate limit"
+            );
+        }
+        waited
+    }
+
+    fn record(&self, waited: Duration) {
+        let mut state = self.lock();
+        state.stats.requests += 1;
+        if !waited.is_zero() {
+            state.stats.delayed += 1;
+            state.stats.total_wait += waited;
+            state.stats.max_wait = state.stats.max_wait.max(waited);
+        }
+    }
+
+    fn stats(&self) -> RateLimitStats {
+        let state = self.lock();
+        RateLimitStats {
+            queue_depth: state.interactive_waiting + state.background_waiting,
+            background_waiting: state.background_waiting,
+            ..state.stats.clone()
+        }
+    }
+}
+
+/// Token buckets of every upstream host, shared by all clones of a client
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Arc<TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until a request to `host` may be sent, returning how long that
+    /// took
+    pub async fn acquire(&self, host: &str, priority: RequestPriority) -> Duration {
+        let bucket =
+            {
+                let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+                Arc::clone(buckets.entry(host.to_string()).or_insert_with(|| {
+                    Arc::new(TokenBucket::new(host, self.config.limit_for(host)))
+                }))
+            };
+        bucket.acquire(priority).await
+    }
+
+    /// Statistics of every host contacted so far, by host
+    pub fn stats(&self) -> Vec<RateLimitStats> {
+        let buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<_> = buckets.values().map(|bucket| bucket.stats()).collect();
+        stats.sort_by(|a, b| a.host.cmp(&b.host));
+        stats
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate_limits() {
+        let hosts = RateLimitConfig::parse_list("crates.io=1, docs.rs=5/10,*=0.5").unwrap();
+        assert_eq!(hosts["crates.io"], HostRateLimit::per_second(1.0));
+        assert_eq!(
+            hosts["docs.rs"],
+            HostRateLimit::per_second(5.0).with_burst(10)
+        );
+
+        let config = RateLimitConfig { hosts };
+        assert_eq!(
+            config.limit_for("static.crates.io"),
+            Some(HostRateLimit::per_second(0.5))
+        );
+        assert_eq!(RateLimitConfig::default().limit_for("docs.rs"), None);
+
+        assert!(RateLimitConfig::parse_list("crates.io").is_err());
+        assert!(RateLimitConfig::parse_list("crates.io=0").is_err());
+        assert!(RateLimitConfig::parse_list("crates.io=1/0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_spaced_by_rate() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig::unlimited().with_host("crates.io", HostRateLimit::per_second(20.0)),
+        );
+
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter
+                .acquire("crates.io", RequestPriority::Interactive)
+                .await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(95));
+
+        // Unlimited hosts never wait
+        assert!(limiter
+            .acquire("docs.rs", RequestPriority::Interactive)
+            .await
+            .is_zero());
+
+        let stats = limiter.stats();
+        assert_eq!(stats[0].host, "crates.io");
+        assert_eq!((stats[0].requests, stats[0].delayed), (3, 2));
+        assert!(stats[0].max_wait >= Duration::from_millis(45));
+        assert_eq!(stats[0].queue_depth, 0);
+        assert_eq!(stats[1].limit, None);
+    }
+
+    #[tokio::test]
+    async fn test_interactive_requests_jump_ahead_of_background() {
+        let limiter = Arc::new(RateLimiter::new(
+            RateLimitConfig::unlimited().with_host("crates.io", HostRateLimit::per_second(10.0)),
+        ));
+        limiter
+            .acquire("crates.io", RequestPriority::Interactive)
+            .await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (name, priority) in [
+            ("background", RequestPriority::Background),
+            ("interactive", RequestPriority::Interactive),
+        ] {
+            let limiter = Arc::clone(&limiter);
+            let order = Arc::clone(&order);
+            tasks.push(tokio::spawn(async move {
+                limiter.acquire("crates.io", priority).await;
+                order.lock().unwrap().push(name);
+            }));
+            tokio::task::yield_now().await;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stats = &limiter.stats()[0];
+        assert_eq!((stats.queue_depth, stats.background_waiting), (2, 1));
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["interactive", "background"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiters_leave_the_queue() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig::unlimited().with_host("crates.io", HostRateLimit::per_second(20.0)),
+        );
+        limiter
+            .acquire("crates.io", RequestPriority::Interactive)
+            .await;
+
+        // Dropped while waiting for the next token
+        let pending = tokio::time::timeout(
+            Duration::from_millis(5),
+            limiter.acquire("crates.io", RequestPriority::Interactive),
+        )
+        .await;
+        assert!(pending.is_err());
+        assert_eq!(limiter.stats()[0].queue_depth, 0);
+
+        // Background requests no longer yield to the cancelled request
+        let background = tokio::time::timeout(
+            Duration::from_secs(1),
+            limiter.acquire("crates.io", RequestPriority::Background),
+        )
+        .await;
+        assert!(background.is_ok());
+        assert_eq!(limiter.stats()[0].queue_depth, 0);
+    }
+}
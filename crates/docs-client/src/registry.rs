@@ -0,0 +1,312 @@
+use rustacean_docs_core::{ErrorBuilder, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+use url::Url;
+
+/// Name under which crates.io is registered
+pub const DEFAULT_REGISTRY: &str = "crates-io";
+
+/// Public documentation host for crates.io crates
+pub const DOCS_RS_URL: &str = "https://docs.rs";
+
+/// Endpoints and credentials for a Cargo registry and its rustdoc host.
+///
+/// The web API is expected to be crates.io compatible (`/api/v1/crates/...`)
+/// and the docs host to lay pages out like docs.rs
+/// (`/{crate}/{version}/{crate}/...`). Registries without a web API can still
+/// answer metadata requests from their sparse index.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registry {
+    /// Name used to select the registry in requests
+    pub name: String,
+    /// Sparse index base URL, with or without the `sparse+` prefix
+    #[serde(default)]
+    pub index_url: Option<String>,
+    /// Web API base URL
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Rustdoc host base URL
+    pub docs_url: String,
+    /// Bearer token sent with every request to this registry
+    #[serde(default, skip_serializing)]
+    pub token: Option<String>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("name", &self.name)
+            .field("index_url", &self.index_url)
+            .field("api_url", &self.api_url)
+            .field("docs_url", &self.docs_url)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::crates_io()
+    }
+}
+
+impl Registry {
+    /// The public crates.io registry with docs.rs
+    pub fn crates_io() -> Self {
+        Self {
+            name: DEFAULT_REGISTRY.to_string(),
+            index_url: Some("https://index.crates.io".to_string()),
+            api_url: Some("https://crates.io".to_string()),
+            docs_url: DOCS_RS_URL.to_string(),
+            token: None,
+        }
+    }
+
+    /// Create a registry that only serves documentation
+    pub fn new(name: impl Into<String>, docs_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            index_url: None,
+            api_url: None,
+            docs_url: docs_url.into(),
+            token: None,
+        }
+    }
+
+    /// Set the sparse index base URL
+    pub fn with_index_url(mut self, index_url: impl Into<String>) -> Self {
+        self.index_url = Some(index_url.into());
+        self
+    }
+
+    /// Set the web API base URL
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = Some(api_url.into());
+        self
+    }
+
+    /// Set the bearer token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Check that every configured URL parses
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(ErrorBuilder::config().missing_field("registry.name"));
+        }
+
+        let urls = [
+            ("docs_url", Some(self.docs_url.as_str())),
+            ("api_url", self.api_url.as_deref()),
+            ("index_url", self.index_url.as_deref().map(strip_sparse)),
+        ];
+        for (field, url) in urls {
+            if let Some(url) = url {
+                Url::parse(url).map_err(|e| {
+                    ErrorBuilder::config()
+                        .invalid_field(format!("registries.{}.{field}", self.name), e.to_string())
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Documentation base URL without a trailing slash
+    pub fn docs_base(&self) -> &str {
+        self.docs_url.trim_end_matches('/')
+    }
+
+    /// Full URL of a web API path, if the registry has a web API
+    pub fn api_endpoint(&self, path: &str) -> Option<String> {
+        self.api_url
+            .as_deref()
+            .map(|base| format!("{}{path}", base.trim_end_matches('/')))
+    }
+
+    /// Full URL of a crate's sparse index file, if the registry has an index
+    pub fn index_endpoint(&self, crate_name: &str) -> Option<String> {
+        self.index_url.as_deref().map(|base| {
+            format!(
+                "{}/{}",
+                strip_sparse(base).trim_end_matches('/'),
+                index_path(crate_name)
+            )
+        })
+    }
+}
+
+/// Named registries a client may be pointed at per request
+#[derive(Debug, Clone, Default)]
+pub struct RegistrySet {
+    registries: HashMap<String, Registry>,
+}
+
+impl RegistrySet {
+    /// Add or replace a registry
+    pub fn insert(&mut self, registry: Registry) {
+        self.registries.insert(registry.name.clone(), registry);
+    }
+
+    /// Look up a registry by name
+    pub fn get(&self, name: &str) -> Option<&Registry> {
+        self.registries.get(name)
+    }
+
+    /// Iterate over the registered registries
+    pub fn iter(&self) -> impl Iterator<Item = &Registry> {
+        self.registries.values()
+    }
+
+    /// Registered names, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.registries.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl FromIterator<Registry> for RegistrySet {
+    fn from_iter<I: IntoIterator<Item = Registry>>(iter: I) -> Self {
+        let mut set = Self::default();
+        for registry in iter {
+            set.insert(registry);
+        }
+        set
+    }
+}
+
+fn strip_sparse(url: &str) -> &str {
+    url.strip_prefix("sparse+").unwrap_or(url)
+}
+
+/// Path of a crate's file within a sparse index, following Cargo's layout
+pub fn index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// One published version as recorded in a sparse index file
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub deps: Vec<IndexDependency>,
+    #[serde(default)]
+    pub features: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub features2: Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    /// Publish time, recorded by newer registries only
+    #[serde(default)]
+    pub pubtime: Option<String>,
+}
+
+impl IndexEntry {
+    /// Features from both the `features` and `features2` maps
+    pub fn all_features(&self) -> HashMap<String, Vec<String>> {
+        let mut features = self.features.clone();
+        if let Some(extra) = &self.features2 {
+            features.extend(extra.clone());
+        }
+        features
+    }
+}
+
+/// A dependency as recorded in a sparse index file
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexDependency {
+    pub name: String,
+    pub req: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default = "default_true")]
+    pub default_features: bool,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Real package name when the dependency is renamed
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Parse a sparse index file, one JSON object per line
+pub fn parse_index_file(contents: &str) -> Result<Vec<IndexEntry>> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                ErrorBuilder::docs().parse_error(format!("Invalid sparse index entry: {e}"))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_path_layout() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("cc"), "2/cc");
+        assert_eq!(index_path("syn"), "3/s/syn");
+        assert_eq!(index_path("Serde"), "se/rd/serde");
+    }
+
+    #[test]
+    fn test_registry_endpoints() {
+        let registry = Registry::new("internal", "https://docs.example.com/")
+            .with_api_url("https://crates.example.com/")
+            .with_index_url("sparse+https://index.example.com/")
+            .with_token("secret");
+        registry.validate().unwrap();
+
+        assert_eq!(registry.docs_base(), "https://docs.example.com");
+        assert_eq!(
+            registry.api_endpoint("/api/v1/crates/tokio").unwrap(),
+            "https://crates.example.com/api/v1/crates/tokio"
+        );
+        assert_eq!(
+            registry.index_endpoint("tokio").unwrap(),
+            "https://index.example.com/to/ki/tokio"
+        );
+        assert!(!format!("{registry:?}").contains("secret"));
+
+        let docs_only = Registry::new("mirror", "https://docs.example.com");
+        assert!(docs_only.api_endpoint("/api/v1/crates").is_none());
+        assert!(docs_only.index_endpoint("tokio").is_none());
+        assert!(Registry::new("bad", "not a url").validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_index_file() {
+        let contents = r#"{"name":"demo","vers":"0.1.0","deps":[{"name":"serde","req":"^1","features":["derive"],"optional":false,"default_features":true,"target":null,"kind":"normal"}],"cksum":"00","features":{"std":[]},"yanked":false}
+{"name":"demo","vers":"0.2.0","deps":[],"cksum":"00","features":{},"features2":{"serde":["dep:serde"]},"yanked":true,"rust_version":"1.70"}
+"#;
+        let entries = parse_index_file(contents).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].deps[0].req, "^1");
+        assert!(entries[1].yanked);
+        assert!(entries[1].all_features().contains_key("serde"));
+    }
+}
@@ -5,10 +5,11 @@
 //! - Circuit breaker pattern to prevent cascade failures
 //! - Configurable retry policies for different error types
 
-use rustacean_docs_core::{error::ErrorContext, Result};
+use rustacean_docs_core::{DocsError, Error, NetworkError, Result};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
 use tracing::{debug, warn};
 
@@ -97,30 +98,22 @@ impl CircuitBreaker {
             failure_count: AtomicUsize::new(0),
             success_count: AtomicUsize::new(0),
             last_failure_time: AtomicU64::new(0),
-            last_state_change: AtomicU64::new(
-                Instant::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64,
-            ),
+            last_state_change: AtomicU64::new(now_millis()),
         }
     }
 
     /// Check if a request should be allowed through the circuit
     pub fn should_allow_request(&self) -> bool {
         let current_state = self.get_state();
-        let now = Instant::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = now_millis();
 
         match current_state {
             CircuitState::Closed => true,
             CircuitState::Open => {
                 // Check if we should transition to half-open
                 let last_change = self.last_state_change.load(Ordering::Relaxed);
-                let time_since_open = Duration::from_millis(now - last_change);
-                
+                let time_since_open = Duration::from_millis(now.saturating_sub(last_change));
+
                 if time_since_open >= self.config.recovery_timeout {
                     self.transition_to_half_open();
                     true
@@ -135,7 +128,7 @@ impl CircuitBreaker {
     /// Record a successful request
     pub fn record_success(&self) {
         let current_state = self.get_state();
-        
+
         match current_state {
             CircuitState::Closed => {
                 // Reset failure count on success
@@ -155,15 +148,12 @@ impl CircuitBreaker {
 
     /// Record a failed request
     pub fn record_failure(&self) {
-        let now = Instant::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
-        
+        let now = now_millis();
+
         self.last_failure_time.store(now, Ordering::Relaxed);
-        
+
         let current_state = self.get_state();
-        
+
         match current_state {
             CircuitState::Closed => {
                 let failure_count = self.failure_count.fetch_add(1, Ordering::Relaxed) + 1;
@@ -205,31 +195,31 @@ impl CircuitBreaker {
 
     fn transition_to_open(&self) {
         debug!("Circuit breaker transitioning to OPEN state");
-        self.state.store(CircuitState::Open as usize, Ordering::Relaxed);
+        self.state
+            .store(CircuitState::Open as usize, Ordering::Relaxed);
         self.success_count.store(0, Ordering::Relaxed);
         self.update_state_change_time();
     }
 
     fn transition_to_half_open(&self) {
         debug!("Circuit breaker transitioning to HALF_OPEN state");
-        self.state.store(CircuitState::HalfOpen as usize, Ordering::Relaxed);
+        self.state
+            .store(CircuitState::HalfOpen as usize, Ordering::Relaxed);
         self.success_count.store(0, Ordering::Relaxed);
         self.update_state_change_time();
     }
 
     fn transition_to_closed(&self) {
         debug!("Circuit breaker transitioning to CLOSED state");
-        self.state.store(CircuitState::Closed as usize, Ordering::Relaxed);
+        self.state
+            .store(CircuitState::Closed as usize, Ordering::Relaxed);
         self.failure_count.store(0, Ordering::Relaxed);
         self.success_count.store(0, Ordering::Relaxed);
         self.update_state_change_time();
     }
 
     fn update_state_change_time(&self) {
-        let now = Instant::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = now_millis();
         self.last_state_change.store(now, Ordering::Relaxed);
     }
 }
@@ -281,10 +271,8 @@ impl RetryPolicy {
             // Check circuit breaker before attempting request
             if !self.circuit_breaker.should_allow_request() {
                 warn!("Circuit breaker is OPEN, rejecting request");
-                return Err(rustacean_docs_core::error::ErrorBuilder::network().http_request(
-                    "Circuit breaker is open - service unavailable", 
-                    Some(503)
-                ));
+                return Err(rustacean_docs_core::error::ErrorBuilder::network()
+                    .http_request("Circuit breaker is open - service unavailable", Some(503)));
             }
 
             debug!(
@@ -312,19 +300,19 @@ impl RetryPolicy {
 
                     // Don't retry on the last attempt
                     if attempt + 1 >= self.retry_config.max_attempts {
-                        return Err(rustacean_docs_core::error::ErrorBuilder::network().http_request(
-                            format!("Operation failed after {} attempts: {}", 
-                                   self.retry_config.max_attempts, error),
-                            None
-                        ));
+                        return Err(rustacean_docs_core::error::ErrorBuilder::network()
+                            .http_request(
+                                format!(
+                                    "Operation failed after {} attempts: {}",
+                                    self.retry_config.max_attempts, error
+                                ),
+                                None,
+                            ));
                     }
 
                     // Calculate delay with exponential backoff
                     let delay = self.calculate_delay(attempt);
-                    debug!(
-                        delay_ms = delay.as_millis(),
-                        "Waiting before retry"
-                    );
+                    debug!(delay_ms = delay.as_millis(), "Waiting before retry");
                     sleep(delay).await;
                 }
                 Err(_timeout) => {
@@ -353,10 +341,97 @@ impl RetryPolicy {
 
         // This should never be reached due to the loop logic above
         Err(rustacean_docs_core::error::ErrorBuilder::internal(
-            "Retry loop completed unexpectedly"
+            "Retry loop completed unexpectedly",
         ))
     }
 
+    /// Execute an HTTP request with retry logic and circuit breaker protection.
+    ///
+    /// Unlike [`execute`](Self::execute), only errors accepted by
+    /// [`should_retry_error`] are retried, and the last error is returned
+    /// unchanged. A rate-limited response is retried after its `Retry-After`
+    /// delay, or not at all when that is longer than `max_delay`. Errors that
+    /// show the host answered, such as a 404, and rate-limited responses do
+    /// not count as breaker failures.
+    pub async fn execute_request<F, Fut, T>(&self, operation: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        self.execute_throttled_request(|| async {}, operation).await
+    }
+
+    /// Like [`execute_request`](Self::execute_request), awaiting `wait_turn`
+    /// before every attempt. The wait is outside the attempt timeout, so time
+    /// spent queued behind a local rate limiter neither times the attempt out
+    /// nor counts against the host.
+    pub async fn execute_throttled_request<W, WFut, F, Fut, T>(
+        &self,
+        wait_turn: W,
+        operation: F,
+    ) -> Result<T>
+    where
+        W: Fn() -> WFut,
+        WFut: std::future::Future,
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            if !self.circuit_breaker.should_allow_request() {
+                warn!("Circuit breaker is OPEN, rejecting request");
+                return Err(rustacean_docs_core::error::ErrorBuilder::network()
+                    .http_request("Circuit breaker is open - service unavailable", Some(503)));
+            }
+
+            wait_turn().await;
+            let error =
+                match tokio::time::timeout(self.retry_config.attempt_timeout, operation()).await {
+                    Ok(Ok(success)) => {
+                        self.circuit_breaker.record_success();
+                        return Ok(success);
+                    }
+                    Ok(Err(error)) if !should_retry_error(&error) => {
+                        if is_upstream_answer(&error) {
+                            self.circuit_breaker.record_success();
+                        }
+                        return Err(error);
+                    }
+                    Ok(Err(error)) => error,
+                    Err(_timeout) => rustacean_docs_core::error::ErrorBuilder::network().timeout(),
+                };
+            // A 429 means the host is up and asking us to slow down
+            if !is_rate_limited(&error) {
+                self.circuit_breaker.record_failure();
+            }
+
+            attempt += 1;
+            if attempt >= self.retry_config.max_attempts {
+                return Err(error);
+            }
+
+            let delay = match retry_after(&error) {
+                Some(delay) if delay > self.retry_config.max_delay => {
+                    warn!(
+                        retry_after_secs = delay.as_secs(),
+                        "Rate limited for longer than the maximum retry delay, giving up"
+                    );
+                    return Err(error);
+                }
+                Some(delay) => delay,
+                None => self.calculate_delay(attempt - 1),
+            };
+            warn!(
+                attempt,
+                max_attempts = self.retry_config.max_attempts,
+                delay_ms = delay.as_millis(),
+                error = %error,
+                "Request failed, retrying"
+            );
+            sleep(delay).await;
+        }
+    }
+
     /// Get circuit breaker statistics
     pub fn circuit_breaker_stats(&self) -> CircuitBreakerStats {
         self.circuit_breaker.stats()
@@ -370,7 +445,8 @@ impl RetryPolicy {
     /// Calculate delay for the given attempt using exponential backoff
     fn calculate_delay(&self, attempt: usize) -> Duration {
         let delay_ms = (self.retry_config.base_delay.as_millis() as f64
-            * self.retry_config.backoff_multiplier.powi(attempt as i32)) as u64;
+            * self.retry_config.backoff_multiplier.powi(attempt as i32))
+            as u64;
 
         let mut delay = Duration::from_millis(delay_ms);
 
@@ -397,22 +473,119 @@ impl Default for RetryPolicy {
     }
 }
 
+/// Retry policies with one circuit breaker per upstream host, so that an
+/// outage of one host (say index.crates.io) does not reject requests to the
+/// others
+#[derive(Debug)]
+pub struct HostRetryPolicies {
+    retry_config: RetryConfig,
+    circuit_breaker_config: CircuitBreakerConfig,
+    policies: Mutex<HashMap<String, Arc<RetryPolicy>>>,
+}
+
+impl HostRetryPolicies {
+    pub fn new(retry_config: RetryConfig, circuit_breaker_config: CircuitBreakerConfig) -> Self {
+        Self {
+            retry_config,
+            circuit_breaker_config,
+            policies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The policy for `host`, created on first use
+    pub fn policy_for(&self, host: &str) -> Arc<RetryPolicy> {
+        let mut policies = self.policies.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(policies.entry(host.to_string()).or_insert_with(|| {
+            Arc::new(RetryPolicy::with_config(
+                self.retry_config.clone(),
+                self.circuit_breaker_config.clone(),
+            ))
+        }))
+    }
+
+    /// Circuit breaker statistics of every host contacted so far, by host
+    pub fn circuit_breaker_stats(&self) -> Vec<(String, CircuitBreakerStats)> {
+        let policies = self.policies.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<_> = policies
+            .iter()
+            .map(|(host, policy)| (host.clone(), policy.circuit_breaker_stats()))
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
+    }
+}
+
+impl Default for HostRetryPolicies {
+    fn default() -> Self {
+        Self::new(RetryConfig::default(), CircuitBreakerConfig::default())
+    }
+}
+
+/// Current time in unix milliseconds
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Whether `error` is a rate-limited response
+fn is_rate_limited(error: &Error) -> bool {
+    match error {
+        Error::Network(NetworkError::RateLimit { .. }) => true,
+        Error::Shared(error) => is_rate_limited(error),
+        _ => false,
+    }
+}
+
+/// Whether `error` stands for an answer from the upstream host, such as a
+/// 404; errors raised locally, like an unparsable body, say nothing about
+/// the host's health
+fn is_upstream_answer(error: &Error) -> bool {
+    match error {
+        Error::Network(NetworkError::HttpRequest {
+            status: Some(status),
+            ..
+        }) => *status != 429,
+        Error::Docs(
+            DocsError::CrateNotFound { .. }
+            | DocsError::ItemNotFound { .. }
+            | DocsError::VersionNotFound { .. },
+        ) => true,
+        Error::Shared(error) => is_upstream_answer(error),
+        _ => false,
+    }
+}
+
+/// How long a rate-limited error asks callers to wait
+fn retry_after(error: &Error) -> Option<Duration> {
+    match error {
+        Error::Network(NetworkError::RateLimit {
+            retry_after: Some(secs),
+        }) => Some(Duration::from_secs(*secs)),
+        Error::Shared(error) => retry_after(error),
+        _ => None,
+    }
+}
+
 /// Determines if an error should be retried
 pub fn should_retry_error(error: &rustacean_docs_core::Error) -> bool {
     match error {
-        rustacean_docs_core::Error::Network(network_err) => {
-            network_err.is_recoverable()
+        rustacean_docs_core::Error::Network(network_err) => match network_err {
+            // A request that failed without a response: connection reset, refused, ...
+            NetworkError::HttpRequest { status: None, .. } => true,
+            NetworkError::Reqwest(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => network_err.is_recoverable() || network_err.is_temporary(),
         },
         rustacean_docs_core::Error::Docs(_) => false,
-        rustacean_docs_core::Error::Cache(cache_err) => {
-            cache_err.is_recoverable()
-        },
+        rustacean_docs_core::Error::Cache(cache_err) => cache_err.is_recoverable(),
         rustacean_docs_core::Error::Config(_) => false,
         rustacean_docs_core::Error::Protocol(_) => false,
         rustacean_docs_core::Error::Serialization(_) => false,
         rustacean_docs_core::Error::UrlParse(_) => false,
         rustacean_docs_core::Error::Io(_) => true,
         rustacean_docs_core::Error::Internal(_) => false,
+        rustacean_docs_core::Error::Shared(shared_err) => should_retry_error(shared_err),
     }
 }
 
@@ -447,7 +620,7 @@ mod tests {
         let breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
         assert_eq!(breaker.get_state(), CircuitState::Closed);
         assert!(breaker.should_allow_request());
-        
+
         let stats = breaker.stats();
         assert_eq!(stats.state, CircuitState::Closed);
         assert_eq!(stats.failure_count, 0);
@@ -523,7 +696,7 @@ mod tests {
             ..Default::default()
         };
         let policy = RetryPolicy::with_config(config, CircuitBreakerConfig::default());
-        
+
         let counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = Arc::clone(&counter);
 
@@ -553,7 +726,7 @@ mod tests {
             ..Default::default()
         };
         let policy = RetryPolicy::with_config(config, CircuitBreakerConfig::default());
-        
+
         let counter = Arc::new(AtomicUsize::new(0));
         let counter_clone = Arc::clone(&counter);
 
@@ -573,11 +746,13 @@ mod tests {
     #[test]
     fn test_should_retry_error() {
         // Should retry network errors
-        let network_error = rustacean_docs_core::error::ErrorBuilder::network().http_request("Connection failed", None);
+        let network_error = rustacean_docs_core::error::ErrorBuilder::network()
+            .http_request("Connection failed", None);
         assert!(should_retry_error(&network_error));
 
         // Should retry server errors
-        let server_error = rustacean_docs_core::error::ErrorBuilder::network().http_request("Internal server error", Some(500));
+        let server_error = rustacean_docs_core::error::ErrorBuilder::network()
+            .http_request("Internal server error", Some(500));
         assert!(should_retry_error(&server_error));
 
         // Should retry rate limiting
@@ -585,18 +760,235 @@ mod tests {
         assert!(should_retry_error(&rate_limit_error));
 
         // Should not retry client errors (except specific ones)
-        let client_error = rustacean_docs_core::error::ErrorBuilder::network().http_request("Not found", Some(404));
+        let client_error = rustacean_docs_core::error::ErrorBuilder::network()
+            .http_request("Not found", Some(404));
         assert!(!should_retry_error(&client_error));
 
         // Should not retry parsing errors
-        let parse_error = rustacean_docs_core::error::ErrorBuilder::docs().parse_error("Invalid JSON");
+        let parse_error =
+            rustacean_docs_core::error::ErrorBuilder::docs().parse_error("Invalid JSON");
         assert!(!should_retry_error(&parse_error));
 
-        // Should not retry validation errors  
-        let validation_error = rustacean_docs_core::error::ErrorBuilder::protocol().invalid_input("test_tool", "Invalid input");
+        // Should not retry validation errors
+        let validation_error = rustacean_docs_core::error::ErrorBuilder::protocol()
+            .invalid_input("test_tool", "Invalid input");
         assert!(!should_retry_error(&validation_error));
     }
 
+    #[tokio::test]
+    async fn test_execute_request_retries_only_retryable_errors() {
+        let config = RetryConfig {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+        let policy = RetryPolicy::with_config(config, CircuitBreakerConfig::default());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let result = policy
+            .execute_request(|| {
+                let counter = Arc::clone(&counter);
+                async move {
+                    match counter.fetch_add(1, Ordering::Relaxed) {
+                        0 | 1 => Err(rustacean_docs_core::error::ErrorBuilder::network()
+                            .http_request("Server error", Some(502))),
+                        _ => Ok(42),
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(counter.load(Ordering::Relaxed), 3);
+
+        counter.store(0, Ordering::Relaxed);
+        let result: Result<i32> = policy
+            .execute_request(|| {
+                let counter = Arc::clone(&counter);
+                async move {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    Err(rustacean_docs_core::error::ErrorBuilder::network()
+                        .http_request("Not found", Some(404)))
+                }
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::Network(NetworkError::HttpRequest {
+                status: Some(404),
+                ..
+            }))
+        ));
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert_eq!(policy.circuit_breaker_stats().failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_request_honors_retry_after() {
+        let config = RetryConfig {
+            base_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let policy = RetryPolicy::with_config(config, CircuitBreakerConfig::default());
+
+        // Retry-After replaces the backoff delay
+        let counter = Arc::new(AtomicUsize::new(0));
+        let result =
+            policy
+                .execute_request(|| {
+                    let counter = Arc::clone(&counter);
+                    async move {
+                        match counter.fetch_add(1, Ordering::Relaxed) {
+                            0 => Err(rustacean_docs_core::error::ErrorBuilder::network()
+                                .rate_limit(Some(0))),
+                            _ => Ok(()),
+                        }
+                    }
+                })
+                .await;
+        assert!(result.is_ok());
+        assert_eq!(counter.load(Ordering::Relaxed), 2);
+
+        // Waits longer than max_delay are not worth blocking a caller on
+        counter.store(0, Ordering::Relaxed);
+        let result: Result<()> = policy
+            .execute_request(|| {
+                let counter = Arc::clone(&counter);
+                async move {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    Err(rustacean_docs_core::error::ErrorBuilder::network().rate_limit(Some(60)))
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_responses_do_not_open_breaker() {
+        let policy = RetryPolicy::with_config(
+            RetryConfig {
+                max_attempts: 3,
+                ..Default::default()
+            },
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        let result: Result<()> = policy
+            .execute_request(|| async {
+                Err(rustacean_docs_core::error::ErrorBuilder::network().rate_limit(Some(0)))
+            })
+            .await;
+        assert!(result.is_err());
+        let stats = policy.circuit_breaker_stats();
+        assert_eq!(stats.state, CircuitState::Closed);
+        assert_eq!(stats.failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_local_errors_do_not_close_half_open_breaker() {
+        let policy = RetryPolicy::with_config(
+            RetryConfig {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                recovery_timeout: Duration::ZERO,
+                success_threshold: 1,
+                ..Default::default()
+            },
+        );
+        let result: Result<()> = policy
+            .execute_request(|| async {
+                Err(rustacean_docs_core::error::ErrorBuilder::network().timeout())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(policy.circuit_breaker_stats().state, CircuitState::Open);
+
+        // The breaker lets the probe through half-open, but a body that does
+        // not parse is no evidence the host has recovered
+        let result: Result<()> = policy
+            .execute_request(|| async {
+                Err(rustacean_docs_core::error::ErrorBuilder::docs().parse_error("bad json"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(policy.circuit_breaker_stats().state, CircuitState::HalfOpen);
+
+        // A 404 is a real answer from the host
+        let result: Result<()> = policy
+            .execute_request(|| async {
+                Err(rustacean_docs_core::error::ErrorBuilder::network()
+                    .http_request("HTTP 404", Some(404)))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(policy.circuit_breaker_stats().state, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_throttle_wait_is_not_timed() {
+        let policy = RetryPolicy::with_config(
+            RetryConfig {
+                max_attempts: 1,
+                attempt_timeout: Duration::from_millis(20),
+                ..Default::default()
+            },
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        let result = policy
+            .execute_throttled_request(|| sleep(Duration::from_millis(60)), || async { Ok(42) })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(policy.circuit_breaker_stats().failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_host_retry_policies_have_separate_breakers() {
+        let policies = HostRetryPolicies::new(
+            RetryConfig {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        );
+
+        let result: Result<()> = policies
+            .policy_for("static.crates.io")
+            .execute_request(|| async {
+                Err(rustacean_docs_core::error::ErrorBuilder::network().timeout())
+            })
+            .await;
+        assert!(result.is_err());
+
+        assert!(!policies
+            .policy_for("static.crates.io")
+            .circuit_breaker
+            .should_allow_request());
+        assert!(policies
+            .policy_for("docs.rs")
+            .execute_request(|| async { Ok(()) })
+            .await
+            .is_ok());
+
+        let stats = policies.circuit_breaker_stats();
+        assert_eq!(stats[0].0, "docs.rs");
+        assert_eq!(stats[0].1.state, CircuitState::Closed);
+        assert_eq!(stats[1].0, "static.crates.io");
+        assert_eq!(stats[1].1.state, CircuitState::Open);
+    }
+
     #[test]
     fn test_calculate_delay() {
         let config = RetryConfig {
@@ -624,4 +1016,4 @@ mod tests {
         let large_delay = policy.calculate_delay(10);
         assert_eq!(large_delay, Duration::from_secs(5));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,166 @@
+//! Conditional revalidation of upstream responses
+//!
+//! Successful text responses that carry an `ETag` or `Last-Modified`
+//! validator are kept in a [`ResponseStore`]. The next request for the same
+//! URL sends `If-None-Match`/`If-Modified-Since`, and a `304 Not Modified`
+//! answer is served from the stored body, so an unchanged docs page or
+//! crates.io document is never downloaded twice.
+
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    RequestBuilder, Response, ResponseBuilderExt, StatusCode,
+};
+use rustacean_docs_cache::Cache;
+use rustacean_docs_core::{Error, ErrorBuilder, NetworkError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+/// Storage for revalidatable responses, keyed by URL
+#[derive(Clone)]
+pub struct ResponseStore {
+    cache: Arc<dyn Cache<Key = String, Value = StoredResponse, Error = anyhow::Error>>,
+}
+
+impl ResponseStore {
+    pub fn new<C>(cache: C) -> Self
+    where
+        C: Cache<Key = String, Value = StoredResponse, Error = anyhow::Error> + 'static,
+    {
+        Self {
+            cache: Arc::new(cache),
+        }
+    }
+
+    pub(crate) async fn get(&self, url: &str) -> anyhow::Result<Option<StoredResponse>> {
+        self.cache.get(&url.to_string()).await
+    }
+
+    pub(crate) async fn insert(&self, url: &str, stored: StoredResponse) -> anyhow::Result<()> {
+        self.cache.insert(url.to_string(), stored).await
+    }
+}
+
+impl fmt::Debug for ResponseStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseStore").finish_non_exhaustive()
+    }
+}
+
+/// A response body with the validators needed to revalidate it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub body: String,
+    pub content_type: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl StoredResponse {
+    /// Read `response` into a stored copy, returning `None` for its body
+    /// when it has no validators or is not text
+    pub(crate) async fn read(response: Response) -> Result<(Vec<u8>, Option<Self>)> {
+        let header_value = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let content_type = header_value(header::CONTENT_TYPE);
+        let etag = header_value(header::ETAG);
+        let last_modified = header_value(header::LAST_MODIFIED);
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::from(NetworkError::from(e)))?
+            .to_vec();
+        if etag.is_none() && last_modified.is_none() {
+            return Ok((body, None));
+        }
+
+        let stored = String::from_utf8(body.clone()).ok().map(|body| Self {
+            body,
+            content_type,
+            etag,
+            last_modified,
+        });
+        Ok((body, stored))
+    }
+
+    /// Ask the server to answer `304 Not Modified` if this copy is current
+    pub(crate) fn conditional(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+
+    /// A successful response to `url` with this body
+    pub(crate) fn to_response(&self, url: &str) -> Result<Response> {
+        let mut headers = HeaderMap::new();
+        if let Some(content_type) = &self.content_type {
+            if let Ok(value) = HeaderValue::from_str(content_type) {
+                headers.insert(header::CONTENT_TYPE, value);
+            }
+        }
+        build_response(url, StatusCode::OK, headers, self.body.clone().into_bytes())
+    }
+}
+
+/// A response to `url` with the given status, headers and body
+pub(crate) fn build_response(
+    url: &str,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+) -> Result<Response> {
+    let mut builder = http::Response::builder()
+        .status(status)
+        .url(Url::parse(url)?);
+    if let Some(builder_headers) = builder.headers_mut() {
+        builder_headers.extend(headers);
+    }
+    let response = builder
+        .body(body)
+        .map_err(|e| ErrorBuilder::internal(format!("Failed to rebuild response: {e}")))?;
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stored_response_round_trip() {
+        let stored = StoredResponse {
+            body: r#"{"name":"serde"}"#.to_string(),
+            content_type: Some("application/json".to_string()),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+
+        let response = stored
+            .to_response("https://crates.io/api/v1/crates/serde")
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.url().as_str(),
+            "https://crates.io/api/v1/crates/serde"
+        );
+        let (body, copy) = StoredResponse::read(response).await.unwrap();
+        assert_eq!(body, stored.body.as_bytes());
+        // Rebuilt responses carry no validators of their own
+        assert_eq!(copy, None);
+
+        let request = stored.conditional(reqwest::Client::new().get("https://crates.io/"));
+        let request = request.build().unwrap();
+        assert_eq!(request.headers()[header::IF_NONE_MATCH], "\"abc\"");
+        assert!(request.headers().get(header::IF_MODIFIED_SINCE).is_none());
+    }
+}
@@ -0,0 +1,252 @@
+pub mod docs_builder;
+pub mod model;
+pub mod render;
+
+use crate::client::DocsClient;
+use rustacean_docs_cache::{Cache, MemoryCache};
+use rustacean_docs_core::{
+    error::NetworkError,
+    models::docs::{CrateDocsRequest, CrateDocsResponse, ItemDocsRequest, ItemDocsResponse},
+    Error, ErrorBuilder, Result,
+};
+use std::{io::Read, sync::Arc};
+use tracing::{debug, trace};
+
+pub use docs_builder::{build_crate_documentation, build_item_documentation};
+pub use model::{DocumentedItem, Id, RawItem, RustdocCrate};
+
+/// Magic number at the start of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Number of decoded crates kept in memory. Decoded rustdoc JSON can be
+/// tens of megabytes, so only a handful are retained for follow-up item lookups.
+const DECODED_CRATE_CACHE_CAPACITY: usize = 4;
+
+/// Structured documentation source backed by the rustdoc JSON that docs.rs
+/// publishes for recent builds (`/crate/{name}/{version}/json`).
+pub struct RustdocJsonSource {
+    client: DocsClient,
+    decoded_cache: MemoryCache<String, Arc<RustdocCrate>>,
+}
+
+impl RustdocJsonSource {
+    /// Create a new rustdoc JSON source using the given HTTP client
+    pub fn new(client: DocsClient) -> Self {
+        Self {
+            client,
+            decoded_cache: MemoryCache::new(DECODED_CRATE_CACHE_CAPACITY),
+        }
+    }
+
+    /// Download and decode the rustdoc JSON for a crate release.
+    ///
+    /// Returns `Ok(None)` when docs.rs has no JSON for the release (older
+    /// builds, failed builds, or crates that were never documented).
+    pub async fn fetch_crate(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<Option<Arc<RustdocCrate>>> {
+        let cache_key = format!("{crate_name}@{version}");
+        if let Ok(Some(krate)) = self.decoded_cache.get(&cache_key).await {
+            trace!(crate_name = %crate_name, version = %version, "Decoded rustdoc JSON cache hit");
+            return Ok(Some(krate));
+        }
+
+        let path = format!("/crate/{crate_name}/{version}/json");
+        let bytes = match self.client.get_bytes(&path).await {
+            Ok(bytes) => bytes,
+            Err(Error::Network(NetworkError::HttpRequest {
+                status: Some(404), ..
+            })) => {
+                debug!(
+                    crate_name = %crate_name,
+                    version = %version,
+                    "No rustdoc JSON published for this release"
+                );
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let krate = Arc::new(decode_rustdoc_json(&bytes)?);
+
+        debug!(
+            crate_name = %crate_name,
+            version = ?krate.crate_version,
+            format_version = krate.format_version,
+            item_count = krate.index.len(),
+            "Decoded rustdoc JSON"
+        );
+
+        let _ = self.decoded_cache.insert(cache_key, krate.clone()).await;
+        Ok(Some(krate))
+    }
+
+    /// Build crate documentation from rustdoc JSON, if available
+    pub async fn get_crate_docs(
+        &self,
+        request: &CrateDocsRequest,
+    ) -> Result<Option<CrateDocsResponse>> {
+        let version = request.version.as_ref().map(|v| v.as_str().to_string());
+        let Some(krate) = self
+            .fetch_crate(
+                request.crate_name.as_str(),
+                version.as_deref().unwrap_or("latest"),
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        build_crate_documentation(
+            &krate,
+            self.client.base_url(),
+            request.crate_name.as_str(),
+            &version,
+        )
+        .map(Some)
+    }
+
+    /// Build item documentation from rustdoc JSON, if available
+    pub async fn get_item_docs(
+        &self,
+        request: &ItemDocsRequest,
+    ) -> Result<Option<ItemDocsResponse>> {
+        let version = request.version.as_ref().map(|v| v.as_str().to_string());
+        let Some(krate) = self
+            .fetch_crate(
+                request.crate_name.as_str(),
+                version.as_deref().unwrap_or("latest"),
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        build_item_documentation(
+            &krate,
+            self.client.base_url(),
+            request.crate_name.as_str(),
+            request.item_path.as_str(),
+            &version,
+        )
+        .map(Some)
+    }
+}
+
+/// Decode a rustdoc JSON document, transparently handling zstd compression
+pub fn decode_rustdoc_json(bytes: &[u8]) -> Result<RustdocCrate> {
+    let json = if bytes.starts_with(&ZSTD_MAGIC) {
+        let mut decoder = ruzstd::decoding::StreamingDecoder::new(bytes).map_err(|e| {
+            ErrorBuilder::docs().parse_error(format!("Invalid zstd rustdoc JSON: {e}"))
+        })?;
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed).map_err(|e| {
+            ErrorBuilder::docs().parse_error(format!("Failed to decompress rustdoc JSON: {e}"))
+        })?;
+        decompressed
+    } else {
+        bytes.to_vec()
+    };
+
+    serde_json::from_slice(&json)
+        .map_err(|e| ErrorBuilder::docs().parse_error(format!("Invalid rustdoc JSON: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+    use serde_json::json;
+
+    fn sample_json() -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "root": 0,
+            "crate_version": "1.0.0",
+            "format_version": 45,
+            "index": {
+                "0": {"name": "demo", "visibility": "public", "docs": "Demo.",
+                      "inner": {"module": {"is_crate": true, "items": []}}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_plain_json() {
+        let krate = decode_rustdoc_json(&sample_json()).unwrap();
+        assert_eq!(krate.crate_version.as_deref(), Some("1.0.0"));
+        assert_eq!(krate.format_version, 45);
+        assert!(krate.root_item().is_some());
+    }
+
+    #[test]
+    fn test_decode_zstd_json() {
+        let compressed = compress_to_vec(&sample_json()[..], CompressionLevel::Fastest);
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+
+        let krate = decode_rustdoc_json(&compressed).unwrap();
+        assert_eq!(krate.crate_version.as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn test_decode_invalid_input() {
+        assert!(decode_rustdoc_json(b"<html>not json</html>").is_err());
+        assert!(decode_rustdoc_json(&[0x28, 0xB5, 0x2F, 0xFD, 0, 0]).is_err());
+    }
+
+    #[cfg(feature = "integration-tests")]
+    mod integration_tests {
+        use super::*;
+        use mockito::Server;
+        use rustacean_docs_core::CrateName;
+
+        #[tokio::test]
+        async fn test_fetch_crate_decodes_zstd_response() {
+            let mut server = Server::new_async().await;
+            let mock = server
+                .mock("GET", "/crate/demo/1.0.0/json")
+                .with_status(200)
+                .with_header("content-type", "application/zstd")
+                .with_body(compress_to_vec(
+                    &sample_json()[..],
+                    CompressionLevel::Fastest,
+                ))
+                .create_async()
+                .await;
+
+            let client = DocsClient::test_client()
+                .unwrap()
+                .with_base_url(server.url());
+            let source = RustdocJsonSource::new(client);
+
+            let krate = source.fetch_crate("demo", "1.0.0").await.unwrap();
+            assert!(krate.is_some());
+
+            // Second lookup is served from the decoded crate cache
+            let again = source.fetch_crate("demo", "1.0.0").await.unwrap();
+            assert!(again.is_some());
+
+            mock.expect(1).assert_async().await;
+        }
+
+        #[tokio::test]
+        async fn test_missing_json_returns_none() {
+            let mut server = Server::new_async().await;
+            let _mock = server
+                .mock("GET", "/crate/demo/latest/json")
+                .with_status(404)
+                .create_async()
+                .await;
+
+            let client = DocsClient::test_client()
+                .unwrap()
+                .with_base_url(server.url());
+            let source = RustdocJsonSource::new(client);
+
+            let request = CrateDocsRequest::new(CrateName::new("demo").unwrap());
+            assert!(source.get_crate_docs(&request).await.unwrap().is_none());
+        }
+    }
+}
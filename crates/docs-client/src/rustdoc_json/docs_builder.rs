@@ -0,0 +1,648 @@
+use super::{
+    model::{field, flag, ids, DocumentedItem, Id, RawItem, RustdocCrate},
+    render::{
+        render_assoc_item, render_impl_bounds, render_impl_header, render_item, render_path,
+        render_visibility,
+    },
+};
+use crate::{
+    error_handling::{build_docs_url, build_item_docs_url},
+    html_parser::docs_parser::{categorize_items, generate_crate_summary},
+    item_index::{find_item, IndexedItem},
+};
+use regex::Regex;
+use rustacean_docs_core::{
+    models::docs::{
+        AssociatedItem, AssociatedItemKind, CodeExample, CrateDocsResponse, CrateItem,
+        ItemDocsResponse, ItemKind, TraitImpl, Visibility,
+    },
+    resolve_version, CrateName, ErrorBuilder, ItemPath, Result,
+};
+use std::sync::OnceLock;
+use tracing::trace;
+
+/// Build comprehensive crate documentation from a rustdoc JSON document
+pub fn build_crate_documentation(
+    krate: &RustdocCrate,
+    docs_base: &str,
+    crate_name: &str,
+    version: &Option<String>,
+) -> Result<CrateDocsResponse> {
+    let root = krate
+        .root_item()
+        .ok_or_else(|| ErrorBuilder::docs().parse_error("rustdoc JSON has no root module"))?;
+
+    let actual_version = resolve_version(krate.crate_version.clone().or_else(|| version.clone()));
+    let description = root.docs.as_deref().and_then(summarize_docs);
+
+    let mut items: Vec<CrateItem> = krate
+        .documented_items()
+        .iter()
+        .filter_map(|documented| build_crate_item(krate, documented))
+        .collect();
+
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+
+    trace!(
+        crate_name = %crate_name,
+        format_version = krate.format_version,
+        item_count = items.len(),
+        "Built crate documentation from rustdoc JSON"
+    );
+
+    let summary = generate_crate_summary(&items, description);
+    let categories = categorize_items(&items);
+    let examples = root
+        .docs
+        .as_deref()
+        .map(extract_code_examples)
+        .unwrap_or_default();
+    let docs_url = Some(build_docs_url(docs_base, crate_name, &actual_version)?);
+
+    Ok(CrateDocsResponse {
+        name: crate_name.to_string(),
+        version: actual_version,
+        summary,
+        categories,
+        items,
+        examples,
+        docs_url,
+    })
+}
+
+/// Build documentation for a single item from a rustdoc JSON document
+pub fn build_item_documentation(
+    krate: &RustdocCrate,
+    docs_base: &str,
+    crate_name: &str,
+    item_path: &str,
+    version: &Option<String>,
+) -> Result<ItemDocsResponse> {
+    let items = krate.documented_items();
+    let documented = find_item(&items, crate_name, item_path)
+        .ok_or_else(|| item_not_found(crate_name, item_path))?;
+    let item = krate
+        .item(&documented.id)
+        .ok_or_else(|| item_not_found(crate_name, item_path))?;
+
+    let actual_version = resolve_version(krate.crate_version.clone().or_else(|| version.clone()));
+    let html_path = documented.html_path();
+    let docs = item.docs.as_deref().unwrap_or_default();
+
+    let related_items = match documented.kind {
+        ItemKind::Module => ids(&item.body()["items"])
+            .iter()
+            .filter_map(|id| krate.item(id))
+            .filter_map(|child| child.name.clone())
+            .collect(),
+        ItemKind::Trait => impl_headers(krate, &item.body()["implementations"]),
+        ItemKind::Struct | ItemKind::Enum | ItemKind::Union => {
+            impl_headers(krate, &item.body()["impls"])
+        }
+        _ => Vec::new(),
+    };
+
+    let (associated_items, trait_impls) = match documented.kind {
+        ItemKind::Struct | ItemKind::Enum | ItemKind::Union => {
+            let impl_ids = ids(&item.body()["impls"]);
+            (
+                inherent_items(krate, &impl_ids),
+                trait_impls(krate, &impl_ids),
+            )
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+    let (required_items, provided_items) = match documented.kind {
+        ItemKind::Trait => trait_items(krate, &ids(&item.body()["items"])),
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    Ok(ItemDocsResponse {
+        crate_name: crate_name.to_string(),
+        item_path: html_path.clone(),
+        name: documented.name.clone(),
+        kind: documented.kind.clone(),
+        signature: render_item(krate, item, &documented.name),
+        description: describe_docs(docs),
+        examples: extract_code_examples(docs),
+        docs_url: Some(build_item_docs_url(
+            docs_base,
+            crate_name,
+            &actual_version,
+            &html_path,
+        )?),
+        related_items,
+        associated_items,
+        trait_impls,
+        required_items,
+        provided_items,
+    })
+}
+
+fn build_crate_item(krate: &RustdocCrate, documented: &DocumentedItem) -> Option<CrateItem> {
+    let item = krate.item(&documented.id)?;
+    let path = documented.html_path();
+
+    Some(CrateItem {
+        name: documented.name.clone(),
+        kind: documented.kind.clone(),
+        summary: item.docs.as_deref().and_then(summarize_docs),
+        path: path.clone(),
+        visibility: item.visibility(),
+        is_async: item.is_async(),
+        signature: render_item(krate, item, &documented.name),
+        docs_path: Some(path),
+        deprecation: item.deprecation.clone(),
+    })
+}
+
+/// Render the headers of non-synthetic, non-blanket impls
+fn impl_headers(krate: &RustdocCrate, impl_ids: &serde_json::Value) -> Vec<String> {
+    let mut headers: Vec<String> = ids(impl_ids)
+        .iter()
+        .filter_map(|id: &Id| krate.item(id))
+        .filter(|imp| {
+            let body = imp.body();
+            !flag(body, &["is_synthetic", "synthetic"]) && body["blanket_impl"].is_null()
+        })
+        .filter_map(render_impl_header)
+        .collect();
+    headers.sort();
+    headers.dedup();
+    headers
+}
+
+/// Collect the public methods, constants and types of inherent impls
+fn inherent_items(krate: &RustdocCrate, impl_ids: &[Id]) -> Vec<AssociatedItem> {
+    impl_ids
+        .iter()
+        .filter_map(|id| krate.item(id))
+        .filter(|imp| field(imp.body(), &["trait"]).is_null())
+        .flat_map(|imp| ids(&imp.body()["items"]))
+        .filter_map(|id| krate.item(&id))
+        .filter(|item| item.visibility() == Visibility::Public)
+        .filter_map(|item| associated_item(item, &render_visibility(&item.visibility)))
+        .collect()
+}
+
+/// Describe every trait impl, explicit impls first, then blanket and auto impls
+fn trait_impls(krate: &RustdocCrate, impl_ids: &[Id]) -> Vec<TraitImpl> {
+    let mut impls: Vec<TraitImpl> = impl_ids
+        .iter()
+        .filter_map(|id| krate.item(id))
+        .filter_map(|imp| {
+            let body = imp.body();
+            let trait_path = field(body, &["trait"]);
+            if trait_path.is_null() {
+                return None;
+            }
+            Some(TraitImpl {
+                trait_name: render_path(trait_path),
+                header: render_impl_header(imp)?,
+                bounds: render_impl_bounds(imp),
+                is_blanket: !body["blanket_impl"].is_null(),
+                is_auto: flag(body, &["is_synthetic", "synthetic"]),
+            })
+        })
+        .collect();
+    impls.sort_by(|a, b| {
+        (a.is_auto, a.is_blanket, &a.trait_name).cmp(&(b.is_auto, b.is_blanket, &b.trait_name))
+    });
+    impls.dedup_by(|a, b| a.header == b.header);
+    impls
+}
+
+/// Split trait items into required items and items with a default
+fn trait_items(
+    krate: &RustdocCrate,
+    item_ids: &[Id],
+) -> (Vec<AssociatedItem>, Vec<AssociatedItem>) {
+    let mut required = Vec::new();
+    let mut provided = Vec::new();
+
+    for item in item_ids.iter().filter_map(|id| krate.item(id)) {
+        let body = item.body();
+        let has_default = match item.kind_tag() {
+            Some("function") => flag(body, &["has_body"]),
+            Some("assoc_const") => !field(body, &["value", "default"]).is_null(),
+            Some("assoc_type") => !field(body, &["type", "default"]).is_null(),
+            _ => false,
+        };
+        match associated_item(item, "") {
+            Some(associated) if has_default => provided.push(associated),
+            Some(mut associated) => {
+                // rustdoc anchors required methods as `tymethod.<name>`
+                if associated.kind == AssociatedItemKind::Method {
+                    associated.anchor = format!("tymethod.{}", associated.name);
+                }
+                required.push(associated);
+            }
+            None => {}
+        }
+    }
+
+    (required, provided)
+}
+
+fn associated_item(item: &RawItem, visibility: &str) -> Option<AssociatedItem> {
+    let kind = match item.kind_tag()? {
+        "function" => AssociatedItemKind::Method,
+        "assoc_const" => AssociatedItemKind::Constant,
+        "assoc_type" => AssociatedItemKind::Type,
+        _ => return None,
+    };
+    let name = item.name.clone()?;
+
+    Some(AssociatedItem {
+        signature: render_assoc_item(item, &name, visibility),
+        summary: item.docs.as_deref().and_then(summarize_docs),
+        anchor: kind.anchor(&name),
+        is_async: item.is_async(),
+        kind,
+        name,
+    })
+}
+
+pub(crate) fn item_not_found(crate_name: &str, item_path: &str) -> rustacean_docs_core::Error {
+    match (CrateName::new(crate_name), ItemPath::new(item_path)) {
+        (Ok(crate_name), Ok(item_path)) => {
+            ErrorBuilder::docs().item_not_found(crate_name, item_path)
+        }
+        _ => ErrorBuilder::docs().parse_error(format!(
+            "Item '{item_path}' not found in crate '{crate_name}'"
+        )),
+    }
+}
+
+fn markdown_link_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\[([^\[\]]+)\](?:\([^)]*\)|\[[^\]]*\])?").unwrap())
+}
+
+/// Strip markdown link syntax, keeping the link text
+fn strip_markdown_links(text: &str) -> String {
+    markdown_link_regex().replace_all(text, "$1").to_string()
+}
+
+/// Return the first prose paragraph of a doc comment, flattened to one line.
+/// Headings, code blocks and README badge rows are skipped.
+pub(crate) fn summarize_docs(docs: &str) -> Option<String> {
+    let paragraph = docs.split("\n\n").map(str::trim).find(|p| {
+        !p.is_empty()
+            && !p.starts_with("```")
+            && !p.starts_with('#')
+            && !p.starts_with("[![")
+            && !p.starts_with("![")
+    })?;
+
+    let summary = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(strip_markdown_links(&summary))
+}
+
+/// Return the documentation prose with code blocks removed
+pub(crate) fn describe_docs(docs: &str) -> Option<String> {
+    let mut prose = Vec::new();
+    let mut in_code = false;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if !in_code {
+            prose.push(line);
+        }
+    }
+
+    let description = strip_markdown_links(prose.join("\n").trim());
+    let description = description
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// Extract fenced code blocks from a doc comment.
+///
+/// Untagged blocks are Rust doctests; hidden doctest lines (`# ...`) are
+/// dropped and `ignore`/`no_run`/`compile_fail` blocks are not runnable.
+pub(crate) fn extract_code_examples(docs: &str) -> Vec<CodeExample> {
+    let mut examples = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        let is_fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+
+        match current.take() {
+            Some((info, lines)) if is_fence => {
+                if let Some(example) = build_code_example(&info, &lines, &heading, examples.len()) {
+                    examples.push(example);
+                }
+            }
+            Some((info, mut lines)) => {
+                lines.push(line);
+                current = Some((info, lines));
+            }
+            None if is_fence => {
+                let info = trimmed.trim_start_matches(['`', '~']).trim().to_string();
+                current = Some((info, Vec::new()));
+            }
+            None => {
+                if let Some(title) = trimmed.strip_prefix('#') {
+                    heading = Some(title.trim_start_matches('#').trim().to_string());
+                }
+            }
+        }
+    }
+
+    examples
+}
+
+fn build_code_example(
+    info: &str,
+    lines: &[&str],
+    heading: &Option<String>,
+    index: usize,
+) -> Option<CodeExample> {
+    const RUST_ATTRIBUTES: &[&str] = &[
+        "rust",
+        "ignore",
+        "no_run",
+        "compile_fail",
+        "should_panic",
+        "test_harness",
+        "standalone_crate",
+    ];
+
+    let attributes: Vec<&str> = info
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect();
+    let language = attributes
+        .iter()
+        .find(|a| !RUST_ATTRIBUTES.contains(a) && !a.starts_with("edition"))
+        .map(|a| a.to_string())
+        .unwrap_or_else(|| "rust".to_string());
+    let is_rust = language == "rust";
+
+    let code = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !is_rust || !(trimmed == "#" || trimmed.starts_with("# "))
+        })
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+
+    let is_runnable = is_rust
+        && !attributes
+            .iter()
+            .any(|a| matches!(*a, "ignore" | "no_run" | "compile_fail"));
+
+    Some(CodeExample {
+        title: heading
+            .clone()
+            .filter(|h| !h.is_empty())
+            .or_else(|| Some(format!("Example {}", index + 1))),
+        code: code.to_string(),
+        language,
+        is_runnable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DOCS_RS_URL;
+    use rustacean_docs_core::models::docs::Visibility;
+    use serde_json::json;
+
+    fn sample_crate() -> RustdocCrate {
+        serde_json::from_value(json!({
+            "root": 0,
+            "crate_version": "0.3.1",
+            "format_version": 45,
+            "index": {
+                "0": {"name": "demo", "visibility": "public",
+                      "docs": "A [demo](https://example.com) crate.\n\n# Examples\n\n```\n# use demo::run;\nrun().await;\n```",
+                      "inner": {"module": {"is_crate": true, "items": [1, 2, 3]}}},
+                "1": {"name": "run", "visibility": "public", "docs": "Runs the thing.\n\nMore details here.",
+                      "inner": {"function": {
+                          "sig": {"inputs": [], "output": null, "is_c_variadic": false},
+                          "generics": {"params": [], "where_predicates": []},
+                          "header": {"is_const": false, "is_unsafe": false, "is_async": true, "abi": "Rust"},
+                          "has_body": true}}},
+                "2": {"name": "Config", "visibility": "public", "docs": "Settings for [`run`].",
+                      "inner": {"struct": {"kind": "unit",
+                          "generics": {"params": [], "where_predicates": []}, "impls": [4, 5, 8, 9]}}},
+                "3": {"name": "Handler", "visibility": "public", "docs": null,
+                      "deprecation": {"since": "0.3.0", "note": "use `run` instead"},
+                      "inner": {"trait": {"is_auto": false, "is_unsafe": false, "items": [10, 11],
+                          "generics": {"params": [], "where_predicates": []}, "bounds": [],
+                          "implementations": []}}},
+                "4": {"name": null, "visibility": "default",
+                      "inner": {"impl": {"generics": {"params": [], "where_predicates": []},
+                          "trait": {"path": "Clone", "id": 99, "args": null},
+                          "for": {"resolved_path": {"path": "Config", "id": 2, "args": null}},
+                          "items": [], "is_synthetic": false, "blanket_impl": null}}},
+                "5": {"name": null, "visibility": "default",
+                      "inner": {"impl": {"generics": {"params": [], "where_predicates": []},
+                          "trait": null,
+                          "for": {"resolved_path": {"path": "Config", "id": 2, "args": null}},
+                          "items": [6, 7], "is_synthetic": false, "blanket_impl": null}}},
+                "6": {"name": "load", "visibility": "public", "docs": "Load the settings.\n\nReads the file.",
+                      "inner": {"function": {
+                          "sig": {"inputs": [["path", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"primitive": "str"}}}]],
+                                  "output": {"generic": "Self"}, "is_c_variadic": false},
+                          "generics": {"params": [], "where_predicates": []},
+                          "header": {"is_const": false, "is_unsafe": false, "is_async": true, "abi": "Rust"},
+                          "has_body": true}}},
+                "7": {"name": "PORT", "visibility": "public", "docs": null,
+                      "inner": {"assoc_const": {"type": {"primitive": "u16"}, "value": "8080"}}},
+                "8": {"name": null, "visibility": "default",
+                      "inner": {"impl": {"generics": {"params": [], "where_predicates": []},
+                          "trait": {"path": "Send", "id": 98, "args": null},
+                          "for": {"resolved_path": {"path": "Config", "id": 2, "args": null}},
+                          "items": [], "is_synthetic": true, "blanket_impl": null}}},
+                "9": {"name": null, "visibility": "default",
+                      "inner": {"impl": {"generics": {"params": [{"name": "T", "kind": {"type": {
+                              "bounds": [{"trait_bound": {"trait": {"path": "Sized", "id": 97, "args": null},
+                                  "generic_params": [], "modifier": "maybe"}}],
+                              "default": null, "is_synthetic": false}}}], "where_predicates": []},
+                          "trait": {"path": "Any", "id": 96, "args": null},
+                          "for": {"generic": "T"},
+                          "items": [], "is_synthetic": false, "blanket_impl": {"generic": "T"}}}},
+                "10": {"name": "handle", "visibility": "default", "docs": "Handle one request.",
+                      "inner": {"function": {
+                          "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}]],
+                                  "output": null, "is_c_variadic": false},
+                          "generics": {"params": [], "where_predicates": []},
+                          "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+                          "has_body": false}}},
+                "11": {"name": "name", "visibility": "default", "docs": null,
+                      "inner": {"function": {
+                          "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}]],
+                                  "output": {"primitive": "str"}, "is_c_variadic": false},
+                          "generics": {"params": [], "where_predicates": []},
+                          "header": {"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"},
+                          "has_body": true}}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_crate_documentation() {
+        let krate = sample_crate();
+        let docs = build_crate_documentation(&krate, DOCS_RS_URL, "demo", &None).unwrap();
+
+        assert_eq!(docs.version, "0.3.1");
+        assert_eq!(docs.summary.description.as_deref(), Some("A demo crate."));
+        assert_eq!(docs.summary.function_count, 1);
+        assert_eq!(docs.summary.struct_count, 1);
+        assert_eq!(docs.summary.trait_count, 1);
+        assert_eq!(docs.examples.len(), 1);
+        assert_eq!(docs.examples[0].code, "run().await;");
+
+        let run = docs.items.iter().find(|i| i.name == "run").unwrap();
+        assert_eq!(run.kind, ItemKind::Function);
+        assert_eq!(run.visibility, Visibility::Public);
+        assert!(run.is_async);
+        assert_eq!(run.signature.as_deref(), Some("pub async fn run()"));
+        assert_eq!(run.path, "fn.run.html");
+        assert_eq!(run.summary.as_deref(), Some("Runs the thing."));
+        assert_eq!(run.deprecation, None);
+
+        let handler = docs.items.iter().find(|i| i.name == "Handler").unwrap();
+        let deprecation = handler.deprecation.as_ref().unwrap();
+        assert_eq!(deprecation.since.as_deref(), Some("0.3.0"));
+        assert_eq!(deprecation.note.as_deref(), Some("use `run` instead"));
+    }
+
+    #[test]
+    fn test_build_item_documentation() {
+        let krate = sample_crate();
+        let docs = build_item_documentation(&krate, DOCS_RS_URL, "demo", "Config", &None).unwrap();
+
+        assert_eq!(docs.name, "Config");
+        assert_eq!(docs.kind, ItemKind::Struct);
+        assert_eq!(docs.item_path, "struct.Config.html");
+        assert_eq!(docs.signature.as_deref(), Some("pub struct Config;"));
+        assert_eq!(docs.description.as_deref(), Some("Settings for `run`."));
+        assert_eq!(
+            docs.related_items,
+            vec![
+                "impl Clone for Config".to_string(),
+                "impl Config".to_string()
+            ]
+        );
+        assert_eq!(
+            docs.docs_url.unwrap().as_str(),
+            "https://docs.rs/demo/0.3.1/demo/struct.Config.html"
+        );
+
+        assert_eq!(docs.associated_items.len(), 2);
+        let load = &docs.associated_items[0];
+        assert_eq!(load.kind, AssociatedItemKind::Method);
+        assert_eq!(load.anchor, "method.load");
+        assert_eq!(load.summary.as_deref(), Some("Load the settings."));
+        assert_eq!(
+            load.signature.as_deref(),
+            Some("pub async fn load(path: &str) -> Self")
+        );
+        assert!(load.is_async);
+        let port = &docs.associated_items[1];
+        assert_eq!(port.anchor, "associatedconstant.PORT");
+        assert_eq!(
+            port.signature.as_deref(),
+            Some("pub const PORT: u16 = 8080")
+        );
+
+        let traits: Vec<(&str, bool, bool)> = docs
+            .trait_impls
+            .iter()
+            .map(|i| (i.trait_name.as_str(), i.is_blanket, i.is_auto))
+            .collect();
+        assert_eq!(
+            traits,
+            vec![
+                ("Clone", false, false),
+                ("Any", true, false),
+                ("Send", false, true)
+            ]
+        );
+        assert_eq!(docs.trait_impls[1].bounds, vec!["T: ?Sized".to_string()]);
+        assert!(docs.required_items.is_empty());
+
+        assert!(build_item_documentation(&krate, DOCS_RS_URL, "demo", "Missing", &None).is_err());
+    }
+
+    #[test]
+    fn test_build_trait_item_documentation() {
+        let krate = sample_crate();
+        let docs = build_item_documentation(&krate, DOCS_RS_URL, "demo", "Handler", &None).unwrap();
+
+        assert_eq!(docs.required_items.len(), 1);
+        assert_eq!(docs.required_items[0].name, "handle");
+        assert_eq!(docs.required_items[0].anchor, "tymethod.handle");
+        assert_eq!(
+            docs.required_items[0].signature.as_deref(),
+            Some("fn handle(&self)")
+        );
+        assert_eq!(docs.provided_items.len(), 1);
+        assert_eq!(docs.provided_items[0].anchor, "method.name");
+        assert!(docs.trait_impls.is_empty());
+        assert_eq!(
+            docs.signature.as_deref(),
+            Some(
+                "pub trait Handler {\n    fn handle(&self);\n    fn name(&self) -> str { ... }\n}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_code_examples() {
+        let docs = "# Usage\n\n```rust,no_run\nlet x = 1;\n```\n\n```toml\n[dependencies]\n```\n";
+        let examples = extract_code_examples(docs);
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].title.as_deref(), Some("Usage"));
+        assert_eq!(examples[0].language, "rust");
+        assert!(!examples[0].is_runnable);
+        assert_eq!(examples[1].language, "toml");
+        assert!(!examples[1].is_runnable);
+    }
+
+    #[test]
+    fn test_summarize_and_describe_docs() {
+        let docs = "First line\nwraps here.\n\n```\ncode();\n```\n\nSecond [`Para`].";
+        assert_eq!(
+            summarize_docs(docs).as_deref(),
+            Some("First line wraps here.")
+        );
+        assert_eq!(
+            describe_docs(docs).as_deref(),
+            Some("First line\nwraps here.\n\nSecond `Para`.")
+        );
+        assert_eq!(describe_docs("```\nonly code\n```"), None);
+
+        let readme = "[![github]](https://github.com/x/y)&ensp;[![docs-rs]](https://docs.rs/y)\n\nReal summary.";
+        assert_eq!(summarize_docs(readme).as_deref(), Some("Real summary."));
+    }
+}
@@ -0,0 +1,421 @@
+use crate::item_index::IndexedItem;
+use rustacean_docs_core::models::docs::{Deprecation, ItemKind, Visibility};
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Identifier of an item inside a rustdoc JSON document.
+///
+/// Older format versions use strings (`"0:42:1234"`), newer ones use plain
+/// integers, so both are normalized to a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Id(pub String);
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IdVisitor;
+
+        impl de::Visitor<'_> for IdVisitor {
+            type Value = Id;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a rustdoc item id (string or integer)")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Id, E> {
+                Ok(Id(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Id, E> {
+                Ok(Id(v.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Id, E> {
+                Ok(Id(v.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(IdVisitor)
+    }
+}
+
+impl Id {
+    /// Parse an id embedded in an arbitrary JSON value
+    pub fn from_value(value: &Value) -> Option<Id> {
+        match value {
+            Value::String(s) => Some(Id(s.clone())),
+            Value::Number(n) => Some(Id(n.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Top-level rustdoc JSON document.
+///
+/// Only the fields we rely on are modelled; item bodies are kept as raw JSON
+/// so that minor format changes between rustdoc releases don't break decoding.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RustdocCrate {
+    /// Id of the crate root module
+    pub root: Id,
+    /// Version of the crate the JSON was generated for
+    #[serde(default)]
+    pub crate_version: Option<String>,
+    /// All local items, keyed by id
+    pub index: HashMap<Id, RawItem>,
+    /// rustdoc JSON format version
+    #[serde(default)]
+    pub format_version: u32,
+}
+
+/// A single entry of the rustdoc JSON item index
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawItem {
+    /// Item name (absent for impls and glob imports)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Declared visibility
+    #[serde(default)]
+    pub visibility: Value,
+    /// Raw markdown documentation
+    #[serde(default)]
+    pub docs: Option<String>,
+    /// `#[deprecated]` notice (`{"since": ..., "note": ...}`)
+    #[serde(default)]
+    pub deprecation: Option<Deprecation>,
+    /// Kind-specific body, externally tagged (`{"function": {...}}`)
+    pub inner: Value,
+}
+
+impl RawItem {
+    /// The rustdoc kind tag of this item (`"struct"`, `"function"`, ...)
+    pub fn kind_tag(&self) -> Option<&str> {
+        match &self.inner {
+            Value::Object(map) => map.keys().next().map(|k| k.as_str()),
+            Value::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The kind-specific body of this item
+    pub fn body(&self) -> &Value {
+        match &self.inner {
+            Value::Object(map) => map.values().next().unwrap_or(&Value::Null),
+            _ => &Value::Null,
+        }
+    }
+
+    /// Map the rustdoc kind onto our item kinds, if it is a documentable item
+    pub fn item_kind(&self) -> Option<ItemKind> {
+        kind_from_tag(self.kind_tag()?)
+    }
+
+    /// Map rustdoc visibility onto our visibility levels
+    pub fn visibility(&self) -> Visibility {
+        match &self.visibility {
+            Value::String(s) if s == "crate" => Visibility::Crate,
+            Value::Object(map) => {
+                let path = map
+                    .get("restricted")
+                    .and_then(|r| r.get("path"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                if path == "self" || path == "::self" {
+                    Visibility::Private
+                } else {
+                    Visibility::Module
+                }
+            }
+            // "public" and "default" (trait items, variants) are both reachable
+            _ => Visibility::Public,
+        }
+    }
+
+    /// Whether the item is an `async fn`
+    pub fn is_async(&self) -> bool {
+        self.kind_tag() == Some("function")
+            && flag(&self.body()["header"], &["is_async", "async_", "async"])
+    }
+}
+
+/// Map a rustdoc kind tag onto an [`ItemKind`]
+pub fn kind_from_tag(tag: &str) -> Option<ItemKind> {
+    match tag {
+        "module" => Some(ItemKind::Module),
+        "struct" => Some(ItemKind::Struct),
+        "enum" => Some(ItemKind::Enum),
+        "union" => Some(ItemKind::Union),
+        "trait" | "trait_alias" => Some(ItemKind::Trait),
+        "function" => Some(ItemKind::Function),
+        "macro" | "proc_macro" => Some(ItemKind::Macro),
+        "constant" | "static" => Some(ItemKind::Constant),
+        "type_alias" | "typedef" => Some(ItemKind::TypeAlias),
+        _ => None,
+    }
+}
+
+/// Read a boolean flag that has been renamed across format versions
+pub(crate) fn flag(value: &Value, names: &[&str]) -> bool {
+    names
+        .iter()
+        .find_map(|name| value.get(name).and_then(Value::as_bool))
+        .unwrap_or(false)
+}
+
+/// Read a field that has been renamed across format versions
+pub(crate) fn field<'a>(value: &'a Value, names: &[&str]) -> &'a Value {
+    names
+        .iter()
+        .find_map(|name| value.get(name).filter(|v| !v.is_null()))
+        .unwrap_or(&Value::Null)
+}
+
+/// Read a list of item ids from a JSON array
+pub(crate) fn ids(value: &Value) -> Vec<Id> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(Id::from_value).collect())
+        .unwrap_or_default()
+}
+
+/// An item reachable from the crate root, at the path where rustdoc documents it
+#[derive(Debug, Clone)]
+pub struct DocumentedItem {
+    /// Id of the item in the index
+    pub id: Id,
+    /// Name under which the item is reachable (may differ for `pub use ... as`)
+    pub name: String,
+    /// Module path from the crate root, excluding the crate name
+    pub parents: Vec<String>,
+    /// Our item kind
+    pub kind: ItemKind,
+    /// docs.rs page prefix (`struct`, `fn`, ...), or `index` for modules
+    pub page: &'static str,
+}
+
+impl IndexedItem for DocumentedItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parents(&self) -> &[String] {
+        &self.parents
+    }
+
+    fn page_kind(&self) -> &str {
+        self.page
+    }
+}
+
+/// The docs.rs page prefix for a rustdoc item
+fn page_kind(item: &RawItem) -> &'static str {
+    match item.kind_tag().unwrap_or_default() {
+        "module" => "index",
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "trait_alias" => "traitalias",
+        "function" => "fn",
+        "constant" => "constant",
+        "static" => "static",
+        "type_alias" | "typedef" => "type",
+        "proc_macro" => match item.body()["kind"].as_str() {
+            Some("attr") => "attr",
+            Some("derive") => "derive",
+            _ => "macro",
+        },
+        _ => "macro",
+    }
+}
+
+impl RustdocCrate {
+    /// Look up an item by id
+    pub fn item(&self, id: &Id) -> Option<&RawItem> {
+        self.index.get(id)
+    }
+
+    /// The crate root module
+    pub fn root_item(&self) -> Option<&RawItem> {
+        self.item(&self.root)
+    }
+
+    /// Walk the public module tree from the crate root and return every
+    /// documentable item at the path rustdoc renders it.
+    ///
+    /// Modules are visited breadth-first so an item re-exported in several
+    /// places is reported at its shallowest path. Re-exports (`pub use`) are
+    /// followed when the target is local to the crate.
+    pub fn documented_items(&self) -> Vec<DocumentedItem> {
+        let mut items = Vec::new();
+        let mut seen_items = HashSet::new();
+        let mut visited_modules = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        visited_modules.insert(self.root.clone());
+        queue.push_back((self.root.clone(), Vec::<String>::new()));
+
+        while let Some((module_id, parents)) = queue.pop_front() {
+            let Some(module) = self.item(&module_id) else {
+                continue;
+            };
+
+            for child_id in ids(&module.body()["items"]) {
+                let Some(child) = self.item(&child_id) else {
+                    continue;
+                };
+
+                let (target_id, name) = match child.kind_tag() {
+                    Some("use") | Some("import") => {
+                        let body = child.body();
+                        let Some(target_id) = Id::from_value(&body["id"]) else {
+                            continue;
+                        };
+                        let Some(target) = self.item(&target_id) else {
+                            // Re-export of an item from another crate
+                            continue;
+                        };
+
+                        if flag(body, &["is_glob", "glob"]) {
+                            if target.kind_tag() == Some("module")
+                                && visited_modules.insert(target_id.clone())
+                            {
+                                queue.push_back((target_id, parents.clone()));
+                            }
+                            continue;
+                        }
+
+                        let name = body["name"]
+                            .as_str()
+                            .map(str::to_string)
+                            .or_else(|| target.name.clone());
+                        (target_id, name)
+                    }
+                    _ => (child_id.clone(), child.name.clone()),
+                };
+
+                let (Some(target), Some(name)) = (self.item(&target_id), name) else {
+                    continue;
+                };
+                let Some(kind) = target.item_kind() else {
+                    continue;
+                };
+
+                if kind == ItemKind::Module {
+                    if !visited_modules.insert(target_id.clone()) {
+                        continue;
+                    }
+                    let mut module_path = parents.clone();
+                    module_path.push(name.clone());
+                    queue.push_back((target_id.clone(), module_path));
+                } else if !seen_items.insert(target_id.clone()) {
+                    continue;
+                }
+
+                items.push(DocumentedItem {
+                    id: target_id,
+                    name,
+                    parents: parents.clone(),
+                    kind,
+                    page: page_kind(target),
+                });
+            }
+        }
+
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item_index::find_item;
+    use serde_json::json;
+
+    fn sample_crate() -> RustdocCrate {
+        serde_json::from_value(json!({
+            "root": 0,
+            "crate_version": "1.2.3",
+            "format_version": 45,
+            "index": {
+                "0": {"name": "demo", "visibility": "public", "docs": "Demo crate",
+                      "inner": {"module": {"is_crate": true, "items": [1, 2, 4, 5]}}},
+                "1": {"name": "Widget", "visibility": "public", "docs": null,
+                      "inner": {"struct": {"kind": "unit", "generics": {"params": [], "where_predicates": []}, "impls": []}}},
+                "2": {"name": "sync", "visibility": "public",
+                      "inner": {"module": {"is_crate": false, "items": [3]}}},
+                "3": {"name": "Mutex", "visibility": "public",
+                      "inner": {"struct": {"kind": "unit", "generics": {"params": [], "where_predicates": []}, "impls": []}}},
+                "4": {"name": null, "visibility": "public",
+                      "inner": {"use": {"source": "sync::Mutex", "name": "Lock", "id": 3, "is_glob": false}}},
+                "5": {"name": "run", "visibility": {"restricted": {"parent": 0, "path": "::demo"}},
+                      "inner": {"function": {"header": {"is_async": true}}}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_id_accepts_strings_and_integers() {
+        let string_id: Id = serde_json::from_value(json!("0:1:2")).unwrap();
+        let int_id: Id = serde_json::from_value(json!(42)).unwrap();
+        assert_eq!(string_id, Id("0:1:2".to_string()));
+        assert_eq!(int_id, Id("42".to_string()));
+    }
+
+    #[test]
+    fn test_documented_items_walks_modules_and_reexports() {
+        let krate = sample_crate();
+        let items = krate.documented_items();
+
+        let paths: Vec<String> = items.iter().map(|item| item.rust_path()).collect();
+        assert!(paths.contains(&"Widget".to_string()));
+        assert!(paths.contains(&"sync".to_string()));
+        assert!(paths.contains(&"run".to_string()));
+        // Mutex is re-exported at the root as `Lock`, which is shallower
+        assert!(paths.contains(&"Lock".to_string()));
+        assert!(!paths.contains(&"sync::Mutex".to_string()));
+    }
+
+    #[test]
+    fn test_html_paths_follow_docs_rs_layout() {
+        let krate = sample_crate();
+        let items = krate.documented_items();
+        let html: Vec<String> = items.iter().map(|item| item.html_path()).collect();
+
+        assert!(html.contains(&"struct.Widget.html".to_string()));
+        assert!(html.contains(&"sync/index.html".to_string()));
+        assert!(html.contains(&"fn.run.html".to_string()));
+    }
+
+    #[test]
+    fn test_item_kind_visibility_and_async() {
+        let krate = sample_crate();
+        let run = krate.item(&Id("5".to_string())).unwrap();
+        assert_eq!(run.item_kind(), Some(ItemKind::Function));
+        assert_eq!(run.visibility(), Visibility::Module);
+        assert!(run.is_async());
+
+        let widget = krate.item(&Id("1".to_string())).unwrap();
+        assert_eq!(widget.visibility(), Visibility::Public);
+        assert!(!widget.is_async());
+    }
+
+    #[test]
+    fn test_find_item() {
+        let krate = sample_crate();
+        let items = krate.documented_items();
+
+        let by_html = find_item(&items, "demo", "struct.Widget.html").unwrap();
+        assert_eq!(by_html.name, "Widget");
+
+        let by_path = find_item(&items, "demo", "demo::Lock").unwrap();
+        assert_eq!(by_path.name, "Lock");
+
+        let by_name = find_item(&items, "demo", "widget").unwrap();
+        assert_eq!(by_name.name, "Widget");
+
+        assert!(find_item(&items, "demo", "Missing").is_none());
+    }
+}
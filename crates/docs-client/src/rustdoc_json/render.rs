@@ -0,0 +1,930 @@
+//! Render rustdoc JSON types and item declarations back into Rust source form.
+//!
+//! The renderer works on raw `serde_json::Value`s and reads renamed fields
+//! through [`field`]/[`flag`], so it tolerates the small schema differences
+//! between rustdoc JSON format versions.
+
+use super::model::{field, flag, ids, Id, RawItem, RustdocCrate};
+use serde_json::Value;
+
+/// Render a rustdoc `Type` as Rust source
+pub fn render_type(ty: &Value) -> String {
+    let (tag, body) = match ty {
+        Value::String(s) if s == "infer" => return "_".to_string(),
+        Value::Object(map) => match map.iter().next() {
+            Some((tag, body)) => (tag.as_str(), body),
+            None => return "_".to_string(),
+        },
+        _ => return "_".to_string(),
+    };
+
+    match tag {
+        "resolved_path" => render_path(body),
+        "generic" | "primitive" => body.as_str().unwrap_or("_").to_string(),
+        "tuple" => {
+            let elements: Vec<String> = body
+                .as_array()
+                .map(|types| types.iter().map(render_type).collect())
+                .unwrap_or_default();
+            match elements.len() {
+                1 => format!("({},)", elements[0]),
+                _ => format!("({})", elements.join(", ")),
+            }
+        }
+        "slice" => format!("[{}]", render_type(body)),
+        "array" => format!(
+            "[{}; {}]",
+            render_type(&body["type"]),
+            body["len"].as_str().unwrap_or("_")
+        ),
+        "pat" => render_type(&body["type"]),
+        "impl_trait" => format!("impl {}", render_bounds(body)),
+        "dyn_trait" => {
+            let mut parts: Vec<String> = body["traits"]
+                .as_array()
+                .map(|traits| traits.iter().map(render_poly_trait).collect())
+                .unwrap_or_default();
+            if let Some(lifetime) = body["lifetime"].as_str() {
+                parts.push(lifetime.to_string());
+            }
+            format!("dyn {}", parts.join(" + "))
+        }
+        "raw_pointer" => format!(
+            "*{} {}",
+            if flag(body, &["is_mutable", "mutable"]) {
+                "mut"
+            } else {
+                "const"
+            },
+            render_type(&body["type"])
+        ),
+        "borrowed_ref" => {
+            let mut out = "&".to_string();
+            if let Some(lifetime) = body["lifetime"].as_str() {
+                out.push_str(lifetime);
+                out.push(' ');
+            }
+            if flag(body, &["is_mutable", "mutable"]) {
+                out.push_str("mut ");
+            }
+            out.push_str(&render_type(&body["type"]));
+            out
+        }
+        "qualified_path" => {
+            let self_type = render_type(&body["self_type"]);
+            let name = body["name"].as_str().unwrap_or("_");
+            let args = render_generic_args(&body["args"]);
+            match body.get("trait").filter(|t| !t.is_null()) {
+                Some(trait_path) => {
+                    format!("<{self_type} as {}>::{name}{args}", render_path(trait_path))
+                }
+                None => format!("{self_type}::{name}{args}"),
+            }
+        }
+        "function_pointer" => {
+            let mut out = render_higher_ranked(&body["generic_params"]);
+            out.push_str(&render_header(&body["header"]));
+            out.push_str("fn");
+            out.push_str(&render_fn_decl(field(body, &["sig", "decl"]), false));
+            out
+        }
+        _ => "_".to_string(),
+    }
+}
+
+/// Render a resolved path (`std::vec::Vec<T>`) with its generic arguments
+pub fn render_path(path: &Value) -> String {
+    let name = field(path, &["path", "name"]).as_str().unwrap_or("_");
+    format!("{name}{}", render_generic_args(&path["args"]))
+}
+
+/// Render generic arguments (`<T, Item = U>` or `(A) -> B`)
+fn render_generic_args(args: &Value) -> String {
+    let Value::Object(map) = args else {
+        return String::new();
+    };
+
+    if let Some(angle) = map.get("angle_bracketed") {
+        let mut parts: Vec<String> = angle["args"]
+            .as_array()
+            .map(|args| args.iter().map(render_generic_arg).collect())
+            .unwrap_or_default();
+
+        if let Some(constraints) = field(angle, &["constraints", "bindings"]).as_array() {
+            parts.extend(constraints.iter().map(render_constraint));
+        }
+
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", parts.join(", "))
+        }
+    } else if let Some(paren) = map.get("parenthesized") {
+        let inputs: Vec<String> = paren["inputs"]
+            .as_array()
+            .map(|inputs| inputs.iter().map(render_type).collect())
+            .unwrap_or_default();
+        let mut out = format!("({})", inputs.join(", "));
+        if !paren["output"].is_null() {
+            out.push_str(" -> ");
+            out.push_str(&render_type(&paren["output"]));
+        }
+        out
+    } else {
+        "(..)".to_string()
+    }
+}
+
+fn render_generic_arg(arg: &Value) -> String {
+    match arg {
+        Value::Object(map) => match map.iter().next() {
+            Some((tag, body)) if tag == "lifetime" => body.as_str().unwrap_or("'_").to_string(),
+            Some((tag, body)) if tag == "type" => render_type(body),
+            Some((tag, body)) if tag == "const" => body["expr"].as_str().unwrap_or("_").to_string(),
+            _ => "_".to_string(),
+        },
+        _ => "_".to_string(),
+    }
+}
+
+fn render_constraint(constraint: &Value) -> String {
+    let name = format!(
+        "{}{}",
+        constraint["name"].as_str().unwrap_or("_"),
+        render_generic_args(&constraint["args"])
+    );
+
+    match &constraint["binding"] {
+        Value::Object(map) if map.contains_key("equality") => {
+            let term = &map["equality"];
+            let rendered = if let Some(ty) = term.get("type") {
+                render_type(ty)
+            } else if let Some(constant) = term.get("constant") {
+                constant["expr"].as_str().unwrap_or("_").to_string()
+            } else {
+                render_type(term)
+            };
+            format!("{name} = {rendered}")
+        }
+        Value::Object(map) if map.contains_key("constraint") => {
+            format!("{name}: {}", render_bounds(&map["constraint"]))
+        }
+        _ => name,
+    }
+}
+
+fn render_poly_trait(poly: &Value) -> String {
+    format!(
+        "{}{}",
+        render_higher_ranked(&poly["generic_params"]),
+        render_path(&poly["trait"])
+    )
+}
+
+/// Render `for<'a> ` for higher-ranked bounds
+fn render_higher_ranked(params: &Value) -> String {
+    let params: Vec<String> = params
+        .as_array()
+        .map(|params| params.iter().filter_map(render_generic_param).collect())
+        .unwrap_or_default();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("for<{}> ", params.join(", "))
+    }
+}
+
+/// Render a list of generic bounds joined with `+`
+fn render_bounds(bounds: &Value) -> String {
+    bounds
+        .as_array()
+        .map(|bounds| {
+            bounds
+                .iter()
+                .map(render_bound)
+                .filter(|b| !b.is_empty())
+                .collect::<Vec<_>>()
+                .join(" + ")
+        })
+        .unwrap_or_default()
+}
+
+fn render_bound(bound: &Value) -> String {
+    let Value::Object(map) = bound else {
+        return String::new();
+    };
+
+    if let Some(trait_bound) = map.get("trait_bound") {
+        let modifier = match trait_bound["modifier"].as_str() {
+            Some("maybe") => "?",
+            Some("maybe_const") => "~const ",
+            _ => "",
+        };
+        format!(
+            "{modifier}{}{}",
+            render_higher_ranked(&trait_bound["generic_params"]),
+            render_path(&trait_bound["trait"])
+        )
+    } else if let Some(lifetime) = map.get("outlives") {
+        lifetime.as_str().unwrap_or_default().to_string()
+    } else if let Some(captures) = map.get("use") {
+        let captures: Vec<String> = captures
+            .as_array()
+            .map(|captures| {
+                captures
+                    .iter()
+                    .map(|c| match c {
+                        Value::String(s) => s.clone(),
+                        Value::Object(m) => m
+                            .values()
+                            .next()
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                        _ => String::new(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        format!("use<{}>", captures.join(", "))
+    } else {
+        String::new()
+    }
+}
+
+/// Render a single generic parameter definition, skipping synthetic ones
+/// (the desugared `impl Trait` arguments)
+fn render_generic_param(param: &Value) -> Option<String> {
+    let name = param["name"].as_str()?;
+    let Value::Object(kind) = &param["kind"] else {
+        return Some(name.to_string());
+    };
+    let (tag, body) = kind.iter().next()?;
+
+    match tag.as_str() {
+        "lifetime" => {
+            let outlives: Vec<&str> = body["outlives"]
+                .as_array()
+                .map(|o| o.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            if outlives.is_empty() {
+                Some(name.to_string())
+            } else {
+                Some(format!("{name}: {}", outlives.join(" + ")))
+            }
+        }
+        "type" => {
+            if flag(body, &["is_synthetic", "synthetic"]) {
+                return None;
+            }
+            let mut out = name.to_string();
+            let bounds = render_bounds(&body["bounds"]);
+            if !bounds.is_empty() {
+                out.push_str(": ");
+                out.push_str(&bounds);
+            }
+            if !body["default"].is_null() {
+                out.push_str(" = ");
+                out.push_str(&render_type(&body["default"]));
+            }
+            Some(out)
+        }
+        "const" => {
+            let mut out = format!("const {name}: {}", render_type(&body["type"]));
+            if let Some(default) = body["default"].as_str() {
+                out.push_str(" = ");
+                out.push_str(default);
+            }
+            Some(out)
+        }
+        _ => Some(name.to_string()),
+    }
+}
+
+/// Render `<...>` and the `where` clause of a `Generics` block
+fn render_generics(generics: &Value) -> (String, String) {
+    let params: Vec<String> = generics["params"]
+        .as_array()
+        .map(|params| params.iter().filter_map(render_generic_param).collect())
+        .unwrap_or_default();
+    let params = if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    };
+
+    let predicates: Vec<String> = generics["where_predicates"]
+        .as_array()
+        .map(|predicates| {
+            predicates
+                .iter()
+                .filter_map(render_where_predicate)
+                .collect()
+        })
+        .unwrap_or_default();
+    let where_clause = if predicates.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", predicates.join(", "))
+    };
+
+    (params, where_clause)
+}
+
+fn render_where_predicate(predicate: &Value) -> Option<String> {
+    let Value::Object(map) = predicate else {
+        return None;
+    };
+    let (tag, body) = map.iter().next()?;
+
+    match tag.as_str() {
+        "bound_predicate" => Some(format!(
+            "{}{}: {}",
+            render_higher_ranked(&body["generic_params"]),
+            render_type(&body["type"]),
+            render_bounds(&body["bounds"])
+        )),
+        "lifetime_predicate" => {
+            let outlives: Vec<&str> = body["outlives"]
+                .as_array()
+                .map(|o| o.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            Some(format!(
+                "{}: {}",
+                body["lifetime"].as_str().unwrap_or("'_"),
+                outlives.join(" + ")
+            ))
+        }
+        "eq_predicate" => Some(format!(
+            "{} == {}",
+            render_type(&body["lhs"]),
+            render_generic_arg(&body["rhs"])
+        )),
+        _ => None,
+    }
+}
+
+/// Render the visibility prefix of an item (`pub `, `pub(crate) `, ...)
+pub fn render_visibility(visibility: &Value) -> String {
+    match visibility {
+        Value::String(s) if s == "public" => "pub ".to_string(),
+        Value::String(s) if s == "crate" => "pub(crate) ".to_string(),
+        Value::Object(map) => {
+            let path = map
+                .get("restricted")
+                .and_then(|r| r.get("path"))
+                .and_then(Value::as_str)
+                .unwrap_or("crate");
+            match path {
+                "self" | "::self" => String::new(),
+                "crate" | "::crate" => "pub(crate) ".to_string(),
+                "super" | "::super" => "pub(super) ".to_string(),
+                path => format!("pub(in {}) ", path.trim_start_matches("::")),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// Render `const async unsafe extern "C" ` qualifiers of a function header
+fn render_header(header: &Value) -> String {
+    let mut out = String::new();
+    if flag(header, &["is_const", "const_", "const"]) {
+        out.push_str("const ");
+    }
+    if flag(header, &["is_async", "async_", "async"]) {
+        out.push_str("async ");
+    }
+    if flag(header, &["is_unsafe", "unsafe_", "unsafe"]) {
+        out.push_str("unsafe ");
+    }
+    match &header["abi"] {
+        Value::String(abi) if abi == "Rust" => {}
+        Value::String(abi) => out.push_str(&format!("extern \"{abi}\" ")),
+        Value::Object(map) => {
+            if let Some((abi, _)) = map.iter().next() {
+                let abi = match abi.as_str() {
+                    "Other" => map["Other"].as_str().unwrap_or("C"),
+                    other => other,
+                };
+                out.push_str(&format!("extern \"{abi}\" "));
+            }
+        }
+        _ => {}
+    }
+    out
+}
+
+/// Render a parameter list and return type (`(&self, key: &K) -> Option<&V>`)
+fn render_fn_decl(sig: &Value, named: bool) -> String {
+    let inputs: Vec<String> = sig["inputs"]
+        .as_array()
+        .map(|inputs| {
+            inputs
+                .iter()
+                .map(|input| {
+                    let name = input[0].as_str().unwrap_or("_");
+                    let ty = &input[1];
+                    if name == "self" {
+                        render_self_param(ty)
+                    } else if named {
+                        format!("{name}: {}", render_type(ty))
+                    } else {
+                        render_type(ty)
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut out = format!("({})", inputs.join(", "));
+    if flag(sig, &["is_c_variadic", "c_variadic"]) {
+        out.insert_str(out.len() - 1, ", ...");
+    }
+    if !sig["output"].is_null() {
+        out.push_str(" -> ");
+        out.push_str(&render_type(&sig["output"]));
+    }
+    out
+}
+
+/// Render the `self` receiver in its idiomatic short form where possible
+fn render_self_param(ty: &Value) -> String {
+    let is_self = |t: &Value| t.get("generic").and_then(Value::as_str) == Some("Self");
+
+    if is_self(ty) {
+        return "self".to_string();
+    }
+    if let Some(reference) = ty.get("borrowed_ref") {
+        if is_self(&reference["type"]) {
+            let mut out = "&".to_string();
+            if let Some(lifetime) = reference["lifetime"].as_str() {
+                out.push_str(lifetime);
+                out.push(' ');
+            }
+            if flag(reference, &["is_mutable", "mutable"]) {
+                out.push_str("mut ");
+            }
+            out.push_str("self");
+            return out;
+        }
+    }
+    format!("self: {}", render_type(ty))
+}
+
+/// Render a function or method signature
+pub fn render_function(name: &str, visibility: &str, function: &Value) -> String {
+    let (params, where_clause) = render_generics(&function["generics"]);
+    format!(
+        "{visibility}{}fn {name}{params}{}{where_clause}",
+        render_header(&function["header"]),
+        render_fn_decl(field(function, &["sig", "decl"]), true)
+    )
+}
+
+/// Render the declaration of any documentable item
+pub fn render_item(krate: &RustdocCrate, item: &RawItem, name: &str) -> Option<String> {
+    let visibility = render_visibility(&item.visibility);
+    let body = item.body();
+
+    let signature = match item.kind_tag()? {
+        "function" => render_function(name, &visibility, body),
+        "module" => format!("{visibility}mod {name}"),
+        "struct" => render_struct(krate, name, &visibility, body),
+        "union" => {
+            let (params, where_clause) = render_generics(&body["generics"]);
+            let fields = render_fields(
+                krate,
+                &ids(&body["fields"]),
+                flag(body, &["has_stripped_fields", "fields_stripped"]),
+            );
+            format!("{visibility}union {name}{params}{where_clause} {fields}")
+        }
+        "enum" => render_enum(krate, name, &visibility, body),
+        "trait" => render_trait(krate, name, &visibility, body),
+        "trait_alias" => {
+            let (params, where_clause) = render_generics(&body["generics"]);
+            format!(
+                "{visibility}trait {name}{params} = {}{where_clause};",
+                render_bounds(&body["params"])
+            )
+        }
+        "type_alias" | "typedef" => {
+            let (params, where_clause) = render_generics(&body["generics"]);
+            format!(
+                "{visibility}type {name}{params}{where_clause} = {};",
+                render_type(&body["type"])
+            )
+        }
+        "constant" => {
+            let expr = body
+                .get("const")
+                .map(|c| &c["expr"])
+                .unwrap_or(&body["expr"])
+                .as_str()
+                .unwrap_or("_");
+            format!(
+                "{visibility}const {name}: {} = {expr};",
+                render_type(&body["type"])
+            )
+        }
+        "static" => format!(
+            "{visibility}static {}{name}: {};",
+            if flag(body, &["is_mutable", "mutable"]) {
+                "mut "
+            } else {
+                ""
+            },
+            render_type(&body["type"])
+        ),
+        "macro" => body.as_str().unwrap_or_default().trim().to_string(),
+        "proc_macro" => match body["kind"].as_str() {
+            Some("derive") => format!("#[derive({name})]"),
+            Some("attr") => format!("#[{name}]"),
+            _ => format!("{name}!() {{ /* proc-macro */ }}"),
+        },
+        _ => return None,
+    };
+
+    Some(signature)
+}
+
+fn render_struct(krate: &RustdocCrate, name: &str, visibility: &str, body: &Value) -> String {
+    let (params, where_clause) = render_generics(&body["generics"]);
+    let header = format!("{visibility}struct {name}{params}");
+
+    match &body["kind"] {
+        Value::Object(kind) if kind.contains_key("plain") => {
+            let plain = &kind["plain"];
+            let fields = render_fields(
+                krate,
+                &ids(&plain["fields"]),
+                flag(plain, &["has_stripped_fields", "fields_stripped"]),
+            );
+            format!("{header}{where_clause} {fields}")
+        }
+        Value::Object(kind) if kind.contains_key("tuple") => format!(
+            "{header}({}){where_clause};",
+            render_tuple_fields(krate, &kind["tuple"])
+        ),
+        _ => format!("{header}{where_clause};"),
+    }
+}
+
+fn render_enum(krate: &RustdocCrate, name: &str, visibility: &str, body: &Value) -> String {
+    let (params, where_clause) = render_generics(&body["generics"]);
+    let mut out = format!("{visibility}enum {name}{params}{where_clause} {{\n");
+
+    for variant in ids(&body["variants"])
+        .iter()
+        .filter_map(|id| krate.item(id))
+    {
+        let variant_name = variant.name.as_deref().unwrap_or("_");
+        let variant_body = variant.body();
+        let rendered = match &variant_body["kind"] {
+            Value::Object(kind) if kind.contains_key("tuple") => format!(
+                "{variant_name}({})",
+                render_tuple_fields(krate, &kind["tuple"])
+            ),
+            Value::Object(kind) if kind.contains_key("struct") => {
+                let fields = &kind["struct"];
+                format!(
+                    "{variant_name} {}",
+                    render_inline_fields(
+                        krate,
+                        &ids(&fields["fields"]),
+                        flag(fields, &["has_stripped_fields", "fields_stripped"]),
+                    )
+                )
+            }
+            _ => variant_name.to_string(),
+        };
+        let discriminant = variant_body["discriminant"]["expr"]
+            .as_str()
+            .map(|expr| format!(" = {expr}"))
+            .unwrap_or_default();
+        out.push_str(&format!("    {rendered}{discriminant},\n"));
+    }
+
+    if flag(body, &["has_stripped_variants", "variants_stripped"]) {
+        out.push_str("    // some variants omitted\n");
+    }
+    out.push('}');
+    out
+}
+
+fn render_trait(krate: &RustdocCrate, name: &str, visibility: &str, body: &Value) -> String {
+    let (params, where_clause) = render_generics(&body["generics"]);
+    let mut out = visibility.to_string();
+    if flag(body, &["is_unsafe"]) {
+        out.push_str("unsafe ");
+    }
+    if flag(body, &["is_auto"]) {
+        out.push_str("auto ");
+    }
+    out.push_str(&format!("trait {name}{params}"));
+
+    let supertraits = render_bounds(&body["bounds"]);
+    if !supertraits.is_empty() {
+        out.push_str(": ");
+        out.push_str(&supertraits);
+    }
+    out.push_str(&where_clause);
+    out.push_str(" {\n");
+
+    for item in ids(&body["items"]).iter().filter_map(|id| krate.item(id)) {
+        let item_name = item.name.as_deref().unwrap_or("_");
+        let Some(rendered) = render_assoc_item(item, item_name, "") else {
+            continue;
+        };
+        let terminator = if item.kind_tag() == Some("function") && flag(item.body(), &["has_body"])
+        {
+            " { ... }"
+        } else {
+            ";"
+        };
+        out.push_str(&format!("    {rendered}{terminator}\n"));
+    }
+
+    out.push('}');
+    out
+}
+
+/// Render the declaration of a method, associated constant or associated type,
+/// without a trailing `;` or body
+pub fn render_assoc_item(item: &RawItem, name: &str, visibility: &str) -> Option<String> {
+    let body = item.body();
+
+    let rendered = match item.kind_tag()? {
+        "function" => render_function(name, visibility, body),
+        "assoc_type" => {
+            let (params, where_clause) = render_generics(&body["generics"]);
+            let mut line = format!("{visibility}type {name}{params}");
+            let bounds = render_bounds(&body["bounds"]);
+            if !bounds.is_empty() {
+                line.push_str(": ");
+                line.push_str(&bounds);
+            }
+            line.push_str(&where_clause);
+            let default = field(body, &["type", "default"]);
+            if !default.is_null() {
+                line.push_str(" = ");
+                line.push_str(&render_type(default));
+            }
+            line
+        }
+        "assoc_const" => {
+            let mut line = format!("{visibility}const {name}: {}", render_type(&body["type"]));
+            if let Some(value) = field(body, &["value", "default"]).as_str() {
+                line.push_str(" = ");
+                line.push_str(value);
+            }
+            line
+        }
+        _ => return None,
+    };
+
+    Some(rendered)
+}
+
+/// Render `{ pub a: A, /* private fields */ }` for plain structs and unions
+fn render_fields(krate: &RustdocCrate, fields: &[Id], stripped: bool) -> String {
+    let mut out = "{\n".to_string();
+    for field in fields.iter().filter_map(|id| krate.item(id)) {
+        out.push_str(&format!(
+            "    {}{}: {},\n",
+            render_visibility(&field.visibility),
+            field.name.as_deref().unwrap_or("_"),
+            render_type(field.body())
+        ));
+    }
+    if stripped {
+        out.push_str("    /* private fields */\n");
+    }
+    out.push('}');
+    out
+}
+
+/// Render `{ a: A, b: B }` on a single line for struct-like enum variants
+fn render_inline_fields(krate: &RustdocCrate, fields: &[Id], stripped: bool) -> String {
+    let mut parts: Vec<String> = fields
+        .iter()
+        .filter_map(|id| krate.item(id))
+        .map(|field| {
+            format!(
+                "{}: {}",
+                field.name.as_deref().unwrap_or("_"),
+                render_type(field.body())
+            )
+        })
+        .collect();
+    if stripped {
+        parts.push("..".to_string());
+    }
+    format!("{{ {} }}", parts.join(", "))
+}
+
+/// Render tuple fields, where stripped (private) fields are `null`
+fn render_tuple_fields(krate: &RustdocCrate, fields: &Value) -> String {
+    fields
+        .as_array()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|field| {
+                    Id::from_value(field)
+                        .and_then(|id| krate.item(&id))
+                        .map(|item| {
+                            format!(
+                                "{}{}",
+                                render_visibility(&item.visibility),
+                                render_type(item.body())
+                            )
+                        })
+                        .unwrap_or_else(|| "_".to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
+
+/// Render the header of an impl block (`impl<T: Clone> Clone for Wrapper<T>`)
+pub fn render_impl_header(item: &RawItem) -> Option<String> {
+    let body = item.body();
+    let (params, where_clause) = render_generics(&body["generics"]);
+    let negative = if flag(body, &["is_negative", "negative"]) {
+        "!"
+    } else {
+        ""
+    };
+    let for_type = render_type(&body["for"]);
+
+    match body.get("trait").filter(|t| !t.is_null()) {
+        Some(trait_path) => Some(format!(
+            "impl{params} {negative}{} for {for_type}{where_clause}",
+            render_path(trait_path)
+        )),
+        None => Some(format!("impl{params} {for_type}{where_clause}")),
+    }
+}
+
+/// Render the bounded generic parameters and where predicates of an impl block
+pub fn render_impl_bounds(item: &RawItem) -> Vec<String> {
+    let generics = &item.body()["generics"];
+    let params = generics["params"]
+        .as_array()
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(render_generic_param)
+                .filter(|param| param.contains(": "))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let predicates = generics["where_predicates"]
+        .as_array()
+        .map(|predicates| {
+            predicates
+                .iter()
+                .filter_map(render_where_predicate)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    params.into_iter().chain(predicates).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(name: &str, args: Value) -> Value {
+        json!({"resolved_path": {"path": name, "id": 1, "args": args}})
+    }
+
+    #[test]
+    fn test_render_basic_types() {
+        assert_eq!(render_type(&json!({"primitive": "u8"})), "u8");
+        assert_eq!(render_type(&json!({"generic": "T"})), "T");
+        assert_eq!(render_type(&json!({"tuple": []})), "()");
+        assert_eq!(render_type(&json!({"slice": {"primitive": "u8"}})), "[u8]");
+        assert_eq!(
+            render_type(&json!({"array": {"type": {"primitive": "u8"}, "len": "4"}})),
+            "[u8; 4]"
+        );
+        assert_eq!(
+            render_type(
+                &json!({"borrowed_ref": {"lifetime": "'a", "is_mutable": true, "type": {"primitive": "str"}}})
+            ),
+            "&'a mut str"
+        );
+        assert_eq!(
+            render_type(&json!({"raw_pointer": {"mutable": false, "type": {"primitive": "u8"}}})),
+            "*const u8"
+        );
+    }
+
+    #[test]
+    fn test_render_paths_with_generic_args() {
+        let vec_of_t = path(
+            "Vec",
+            json!({"angle_bracketed": {"args": [{"type": {"generic": "T"}}], "constraints": []}}),
+        );
+        assert_eq!(render_type(&vec_of_t), "Vec<T>");
+
+        let iter = json!({"impl_trait": [{"trait_bound": {
+            "trait": {"path": "Iterator", "id": 2, "args": {"angle_bracketed": {"args": [], "constraints": [
+                {"name": "Item", "args": null, "binding": {"equality": {"type": {"primitive": "u32"}}}}
+            ]}}},
+            "generic_params": [],
+            "modifier": "none"
+        }}]});
+        assert_eq!(render_type(&iter), "impl Iterator<Item = u32>");
+
+        let qualified = json!({"qualified_path": {
+            "name": "Output", "args": null,
+            "self_type": {"generic": "F"},
+            "trait": {"path": "Future", "id": 3, "args": null}
+        }});
+        assert_eq!(render_type(&qualified), "<F as Future>::Output");
+    }
+
+    #[test]
+    fn test_render_function_signature() {
+        let function = json!({
+            "sig": {
+                "inputs": [
+                    ["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}],
+                    ["future", {"generic": "F"}]
+                ],
+                "output": path("JoinHandle", json!({"angle_bracketed": {"args": [{"type": {"generic": "T"}}], "constraints": []}})),
+                "is_c_variadic": false
+            },
+            "generics": {
+                "params": [{"name": "F", "kind": {"type": {"bounds": [{"trait_bound": {"trait": {"path": "Future", "id": 3, "args": null}, "generic_params": [], "modifier": "none"}}], "default": null, "is_synthetic": false}}}],
+                "where_predicates": [{"bound_predicate": {"type": {"generic": "F"}, "bounds": [{"trait_bound": {"trait": {"path": "Send", "id": 4, "args": null}, "generic_params": [], "modifier": "none"}}], "generic_params": []}}]
+            },
+            "header": {"is_const": false, "is_unsafe": false, "is_async": true, "abi": "Rust"},
+            "has_body": true
+        });
+
+        assert_eq!(
+            render_function("spawn", "pub ", &function),
+            "pub async fn spawn<F: Future>(&self, future: F) -> JoinHandle<T> where F: Send"
+        );
+    }
+
+    #[test]
+    fn test_render_visibility() {
+        assert_eq!(render_visibility(&json!("public")), "pub ");
+        assert_eq!(render_visibility(&json!("crate")), "pub(crate) ");
+        assert_eq!(render_visibility(&json!("default")), "");
+        assert_eq!(
+            render_visibility(&json!({"restricted": {"parent": 0, "path": "::a::b"}})),
+            "pub(in a::b) "
+        );
+    }
+
+    #[test]
+    fn test_render_items() {
+        let krate: RustdocCrate = serde_json::from_value(json!({
+            "root": 0,
+            "format_version": 45,
+            "index": {
+                "0": {"name": "demo", "visibility": "public", "inner": {"module": {"items": [1]}}},
+                "1": {"name": "Point", "visibility": "public", "inner": {"struct": {
+                    "kind": {"plain": {"fields": [2], "has_stripped_fields": true}},
+                    "generics": {"params": [], "where_predicates": []}, "impls": []}}},
+                "2": {"name": "x", "visibility": "public", "inner": {"struct_field": {"primitive": "i32"}}},
+                "3": {"name": "Shape", "visibility": "public", "inner": {"enum": {
+                    "generics": {"params": [], "where_predicates": []},
+                    "variants": [4, 5], "has_stripped_variants": false, "impls": []}}},
+                "4": {"name": "Empty", "visibility": "default", "inner": {"variant": {"kind": "plain", "discriminant": null}}},
+                "5": {"name": "Circle", "visibility": "default", "inner": {"variant": {"kind": {"tuple": [6]}, "discriminant": null}}},
+                "6": {"name": "0", "visibility": "default", "inner": {"struct_field": {"primitive": "f64"}}},
+                "7": {"name": "MAX", "visibility": "public", "inner": {"constant": {"type": {"primitive": "u32"}, "const": {"expr": "10", "value": "10", "is_literal": true}}}}
+            }
+        }))
+        .unwrap();
+
+        let point = krate.item(&Id("1".into())).unwrap();
+        assert_eq!(
+            render_item(&krate, point, "Point").unwrap(),
+            "pub struct Point {\n    pub x: i32,\n    /* private fields */\n}"
+        );
+
+        let shape = krate.item(&Id("3".into())).unwrap();
+        assert_eq!(
+            render_item(&krate, shape, "Shape").unwrap(),
+            "pub enum Shape {\n    Empty,\n    Circle(f64),\n}"
+        );
+
+        let max = krate.item(&Id("7".into())).unwrap();
+        assert_eq!(
+            render_item(&krate, max, "MAX").unwrap(),
+            "pub const MAX: u32 = 10;"
+        );
+    }
+}
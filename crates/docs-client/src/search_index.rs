@@ -0,0 +1,533 @@
+//! In-process search over the items of a single crate.
+//!
+//! The index is built from a [`CrateDocsResponse`] and answers two kinds of
+//! queries: free text, matched against item names, paths and summaries, and
+//! type signatures (`fn(&str) -> Result<_, _>`), matched structurally against
+//! the declarations of functions. Results are ranked so that exact name
+//! matches come before partial and documentation matches.
+
+pub mod callables;
+pub mod signature;
+
+use rustacean_docs_core::models::{
+    docs::{CrateDocsResponse, CrateItem, ItemKind},
+    search::{ItemSearchHit, ItemSearchMode, ItemSearchRequest, ItemSearchResponse},
+};
+use signature::FnSignature;
+use std::collections::HashSet;
+
+pub use callables::CrateCallables;
+pub use signature::{SignatureQuery, TypePattern};
+
+/// Words too common in documentation to narrow a search
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it", "of",
+    "on", "or", "that", "the", "this", "to", "with",
+];
+
+/// Ranked search over a crate's items
+#[derive(Debug, Clone)]
+pub struct ItemSearchIndex {
+    crate_name: String,
+    version: String,
+    entries: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    item: CrateItem,
+    /// Path from the crate root (`sync::Mutex`)
+    rust_path: String,
+    /// Page relative to the crate root (`sync/struct.Mutex.html`)
+    docs_path: String,
+    lower_name: String,
+    lower_path: String,
+    /// Name split on `_` and case changes, lowercased
+    name_words: Vec<String>,
+    /// Words of the summary, lowercased
+    doc_words: HashSet<String>,
+    lower_signature: Option<String>,
+    signature: Option<FnSignature>,
+}
+
+impl ItemSearchIndex {
+    /// Index the items of a crate's documentation
+    pub fn from_docs(docs: &CrateDocsResponse) -> Self {
+        Self::from_items(&docs.name, &docs.version, &docs.items)
+    }
+
+    /// Index a list of items of one crate release.
+    ///
+    /// Methods are recognised by their kind and located by the page of the
+    /// type or trait declaring them (`struct.Bytes.html#method.slice`).
+    pub fn from_items(crate_name: &str, version: &str, items: &[CrateItem]) -> Self {
+        let entries = items
+            .iter()
+            .map(|item| {
+                let docs_path = item.docs_path.clone().unwrap_or_else(|| item.path.clone());
+                let rust_path = rust_path(&docs_path, &item.name, item.kind == ItemKind::Method);
+                let signature = parse_signature(item, &docs_path);
+
+                IndexEntry {
+                    lower_name: item.name.to_lowercase(),
+                    lower_path: rust_path.to_lowercase(),
+                    name_words: split_identifier(&item.name),
+                    doc_words: item.summary.as_deref().map(words).unwrap_or_default(),
+                    lower_signature: item.signature.as_ref().map(|s| s.to_lowercase()),
+                    signature,
+                    rust_path,
+                    docs_path,
+                    item: item.clone(),
+                }
+            })
+            .collect();
+
+        Self {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            entries,
+        }
+    }
+
+    /// Number of indexed items
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index holds no items
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Rank the indexed items against a query.
+    ///
+    /// Queries containing `->` or starting with `fn(` or `(` are read as type
+    /// signatures; anything else is a text query in which every term must
+    /// match the item's name, path or summary.
+    pub fn search(&self, request: &ItemSearchRequest) -> ItemSearchResponse {
+        let query = request.query.trim();
+        let signature_query = SignatureQuery::looks_like_signature(query)
+            .then(|| SignatureQuery::parse(query))
+            .flatten();
+
+        let (mode, ranked) = match &signature_query {
+            Some(signature_query) => (
+                ItemSearchMode::Signature,
+                self.rank(request.kind.as_ref(), |entry| {
+                    signature_query.score(entry.signature.as_ref()?)
+                }),
+            ),
+            None => {
+                let terms = text_terms(query);
+                (
+                    ItemSearchMode::Text,
+                    self.rank(request.kind.as_ref(), |entry| {
+                        entry.text_score(query, &terms)
+                    }),
+                )
+            }
+        };
+
+        let total = ranked.len();
+        ItemSearchResponse {
+            crate_name: self.crate_name.clone(),
+            version: self.version.clone(),
+            query: request.query.clone(),
+            mode,
+            results: ranked.into_iter().take(request.limit()).collect(),
+            total,
+        }
+    }
+
+    /// Every function and method matching a signature query, best first
+    pub fn search_signature(&self, query: &SignatureQuery) -> Vec<ItemSearchHit> {
+        self.rank(None, |entry| query.score(entry.signature.as_ref()?))
+    }
+
+    fn rank<F>(&self, kind: Option<&ItemKind>, score: F) -> Vec<ItemSearchHit>
+    where
+        F: Fn(&IndexEntry) -> Option<u32>,
+    {
+        let mut scored: Vec<(u32, &IndexEntry)> = self
+            .entries
+            .iter()
+            .filter(|entry| kind.is_none_or(|k| *k == entry.item.kind))
+            .filter_map(|entry| Some((score(entry)?, entry)))
+            .collect();
+
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| a.depth().cmp(&b.depth()))
+                .then_with(|| a.rust_path.cmp(&b.rust_path))
+        });
+
+        scored
+            .into_iter()
+            .map(|(score, entry)| ItemSearchHit {
+                name: entry.item.name.clone(),
+                path: entry.rust_path.clone(),
+                kind: entry.item.kind.clone(),
+                summary: entry.item.summary.clone(),
+                signature: entry.item.signature.clone(),
+                docs_path: entry.docs_path.clone(),
+                score,
+            })
+            .collect()
+    }
+}
+
+impl IndexEntry {
+    fn depth(&self) -> usize {
+        self.rust_path.matches("::").count()
+    }
+
+    fn text_score(&self, query: &str, terms: &[String]) -> Option<u32> {
+        if terms.is_empty() {
+            return None;
+        }
+
+        let mut score = terms
+            .iter()
+            .try_fold(0, |score, term| self.term_score(term).map(|s| score + s))?;
+
+        // `sync::Mutex` style queries favour items whose path ends with it
+        let query = query.to_lowercase();
+        if query.contains("::") {
+            let query = query.trim_start_matches("::");
+            if self.lower_path == query || self.lower_path.ends_with(&format!("::{query}")) {
+                score += 50;
+            }
+        }
+
+        Some(score)
+    }
+
+    fn term_score(&self, term: &str) -> Option<u32> {
+        let score =
+            if self.lower_name == term {
+                100
+            } else if self.lower_name.starts_with(term) {
+                60
+            } else if self.name_words.iter().any(|word| word == term) {
+                40
+            } else if self.lower_name.contains(term) {
+                25
+            } else if self.lower_path.split("::").any(|segment| {
+                segment == term || split_identifier(segment).iter().any(|w| w == term)
+            }) {
+                15
+            } else if self.doc_words.contains(term) {
+                10
+            } else if self
+                .lower_signature
+                .as_deref()
+                .is_some_and(|signature| signature.contains(term))
+            {
+                5
+            } else {
+                return None;
+            };
+        Some(score)
+    }
+}
+
+/// Parse the declaration of a function or method.
+///
+/// `Self` in a method resolves to the type whose page declares it, or stays
+/// generic, bounded by the trait, for methods declared by a trait.
+fn parse_signature(item: &CrateItem, docs_path: &str) -> Option<FnSignature> {
+    let signature = FnSignature::parse(item.signature.as_deref()?)?;
+    match item.kind {
+        ItemKind::Function => Some(signature),
+        ItemKind::Method => {
+            let (page_kind, owner) = page_item(docs_path)?;
+            let owner = TypePattern::Path {
+                name: owner.to_string(),
+                args: Vec::new(),
+            };
+            Some(if page_kind == "trait" {
+                signature.with_self_bound(owner)
+            } else {
+                signature.with_self_type(&owner)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Kind prefix and name of the item a docs page documents
+/// (`sync/struct.Mutex.html#method.lock` gives `("struct", "Mutex")`)
+fn page_item(docs_path: &str) -> Option<(&str, &str)> {
+    let page = docs_path.split('#').next()?;
+    let file = page.rsplit('/').next()?;
+    file.strip_suffix(".html")?.split_once('.')
+}
+
+/// Rust path of an item from its docs page (`sync/struct.Mutex.html` becomes
+/// `sync::Mutex`, `sync/index.html` becomes `sync`). Members are appended to
+/// the path of their page (`sync::Mutex::lock`).
+pub(crate) fn rust_path(docs_path: &str, name: &str, is_member: bool) -> String {
+    let (page, fragment) = match docs_path.split_once('#') {
+        Some((page, fragment)) => (page, Some(fragment)),
+        None => (docs_path, None),
+    };
+    let page = page.trim_start_matches('/');
+    let mut segments: Vec<&str> = page.split('/').filter(|s| !s.is_empty()).collect();
+
+    match segments.pop() {
+        Some("index.html") => {}
+        Some(file) if file.ends_with(".html") => {
+            let stem = file.trim_end_matches(".html");
+            let item = stem.split_once('.').map_or(stem, |(_, item)| item);
+            segments.push(item);
+        }
+        Some(other) => segments.push(other),
+        None => {}
+    }
+
+    if is_member || fragment.is_some() {
+        segments.push(name);
+    }
+
+    if segments.is_empty() {
+        name.to_string()
+    } else {
+        segments.join("::")
+    }
+}
+
+/// Split `HashMap` / `read_to_string` into lowercase words
+fn split_identifier(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+
+    for c in identifier.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Lowercase terms of a text query, without stopwords
+fn text_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .filter(|term| !STOPWORDS.contains(&term.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustacean_docs_core::{
+        models::docs::{CrateCategories, CrateSummary, Visibility},
+        CrateName,
+    };
+
+    fn item(
+        name: &str,
+        kind: ItemKind,
+        path: &str,
+        summary: &str,
+        signature: Option<&str>,
+    ) -> CrateItem {
+        CrateItem {
+            name: name.to_string(),
+            kind,
+            summary: Some(summary.to_string()),
+            path: path.to_string(),
+            visibility: Visibility::Public,
+            is_async: false,
+            signature: signature.map(str::to_string),
+            docs_path: Some(path.to_string()),
+            deprecation: None,
+        }
+    }
+
+    fn sample_index() -> ItemSearchIndex {
+        ItemSearchIndex::from_docs(&CrateDocsResponse {
+            name: "demo".to_string(),
+            version: "1.0.0".to_string(),
+            summary: CrateSummary {
+                description: None,
+                module_count: 1,
+                struct_count: 2,
+                trait_count: 0,
+                function_count: 3,
+                enum_count: 0,
+                features: vec![],
+            },
+            categories: CrateCategories {
+                core_types: vec![],
+                traits: vec![],
+                modules: vec![],
+                functions: vec![],
+                macros: vec![],
+                constants: vec![],
+            },
+            items: vec![
+                item(
+                    "sync",
+                    ItemKind::Module,
+                    "sync/index.html",
+                    "Synchronization primitives",
+                    None,
+                ),
+                item(
+                    "Mutex",
+                    ItemKind::Struct,
+                    "sync/struct.Mutex.html",
+                    "An asynchronous mutual exclusion lock",
+                    Some("pub struct Mutex<T: ?Sized>"),
+                ),
+                item(
+                    "MutexGuard",
+                    ItemKind::Struct,
+                    "sync/struct.MutexGuard.html",
+                    "A handle to a held Mutex",
+                    None,
+                ),
+                item(
+                    "parse_config",
+                    ItemKind::Function,
+                    "fn.parse_config.html",
+                    "Parse the configuration from a string",
+                    Some("pub fn parse_config(input: &str) -> Result<Config, Error>"),
+                ),
+                item(
+                    "read_to_string",
+                    ItemKind::Function,
+                    "fs/fn.read_to_string.html",
+                    "Read a file into a string",
+                    Some(
+                        "pub async fn read_to_string(path: impl AsRef<Path>) -> io::Result<String>",
+                    ),
+                ),
+                item(
+                    "spawn",
+                    ItemKind::Function,
+                    "fn.spawn.html",
+                    "Spawn a new asynchronous task",
+                    Some("pub fn spawn<F: Future>(future: F) -> JoinHandle<F::Output>"),
+                ),
+            ],
+            examples: Vec::new(),
+            docs_url: None,
+        })
+    }
+
+    fn search(index: &ItemSearchIndex, query: &str) -> ItemSearchResponse {
+        index.search(&ItemSearchRequest::new(
+            CrateName::new("demo").unwrap(),
+            query,
+        ))
+    }
+
+    #[test]
+    fn test_rust_path_and_identifier_words() {
+        assert_eq!(
+            rust_path("sync/struct.Mutex.html", "Mutex", false),
+            "sync::Mutex"
+        );
+        assert_eq!(rust_path("sync/index.html", "sync", false), "sync");
+        assert_eq!(rust_path("fn.spawn.html", "spawn", false), "spawn");
+        assert_eq!(
+            rust_path("sync/struct.Mutex.html#method.lock", "lock", true),
+            "sync::Mutex::lock"
+        );
+        assert_eq!(
+            page_item("sync/trait.Lock.html#tymethod.lock"),
+            Some(("trait", "Lock"))
+        );
+        assert_eq!(split_identifier("MutexGuard"), vec!["mutex", "guard"]);
+        assert_eq!(
+            split_identifier("read_to_string"),
+            vec!["read", "to", "string"]
+        );
+        assert_eq!(split_identifier("HTTPClient"), vec!["httpclient"]);
+    }
+
+    #[test]
+    fn test_text_search_ranking() {
+        let index = sample_index();
+        assert_eq!(index.len(), 6);
+
+        let response = search(&index, "mutex");
+        assert_eq!(response.mode, ItemSearchMode::Text);
+        assert_eq!(response.total, 2);
+        assert_eq!(response.results[0].path, "sync::Mutex");
+        assert_eq!(response.results[0].docs_path, "sync/struct.Mutex.html");
+        assert_eq!(response.results[1].name, "MutexGuard");
+
+        // Every term must match, stopwords are ignored
+        let response = search(&index, "read a string");
+        assert_eq!(response.results[0].name, "read_to_string");
+        assert_eq!(response.total, 1);
+
+        let response = search(&index, "asynchronous");
+        assert_eq!(response.total, 2);
+        assert!(search(&index, "nonexistent").results.is_empty());
+
+        let response = search(&index, "sync::MutexGuard");
+        assert_eq!(response.results[0].name, "MutexGuard");
+    }
+
+    #[test]
+    fn test_kind_filter_and_limit() {
+        let index = sample_index();
+        let request = ItemSearchRequest::new(CrateName::new("demo").unwrap(), "mutex")
+            .with_kind(ItemKind::Module);
+        assert!(index.search(&request).results.is_empty());
+
+        let request =
+            ItemSearchRequest::new(CrateName::new("demo").unwrap(), "string").with_limit(1);
+        let response = index.search(&request);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.total, 2);
+    }
+
+    #[test]
+    fn test_signature_search() {
+        let index = sample_index();
+
+        let response = search(&index, "fn(&str) -> Result<_, _>");
+        assert_eq!(response.mode, ItemSearchMode::Signature);
+        assert_eq!(response.total, 2);
+        assert_eq!(response.results[0].name, "parse_config");
+        // `impl AsRef<Path>` accepts a `&str`, but less specifically
+        assert_eq!(response.results[1].name, "read_to_string");
+        assert!(response.results[0].score > response.results[1].score);
+
+        // `io::Result<T>` is a Result
+        let response = search(&index, "(&str) -> Result<String>");
+        assert_eq!(response.results[0].name, "read_to_string");
+
+        let response = search(&index, "-> JoinHandle<_>");
+        assert_eq!(response.results[0].name, "spawn");
+    }
+}
@@ -0,0 +1,358 @@
+//! The functions and methods of a crate release, gathered for signature search.
+//!
+//! Crate docs only list free functions, so the methods of each type and trait
+//! are collected from their item docs. Common conversion traits implemented by
+//! a type (`From`, `TryFrom`, `FromStr`, ...) are added as methods too, so a
+//! search for `Vec<u8> -> Bytes` finds `impl From<Vec<u8>> for Bytes`.
+
+use rustacean_docs_core::models::docs::{
+    AssociatedItemKind, CrateDocsResponse, CrateItem, ItemDocsResponse, ItemKind, TraitImpl,
+    Visibility,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ItemSearchIndex;
+
+/// Functions and methods of one crate release
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CrateCallables {
+    /// Crate name
+    pub crate_name: String,
+    /// Version of the documentation the items were read from
+    pub version: String,
+    /// Free functions, then methods located on their type's or trait's page
+    pub items: Vec<CrateItem>,
+}
+
+impl CrateCallables {
+    /// Start from the free functions of a crate
+    pub fn new(docs: &CrateDocsResponse) -> Self {
+        Self {
+            crate_name: docs.name.clone(),
+            version: docs.version.clone(),
+            items: docs
+                .items
+                .iter()
+                .filter(|item| item.kind == ItemKind::Function && item.signature.is_some())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Items whose docs list methods: types and traits
+    pub fn is_method_container(item: &CrateItem) -> bool {
+        matches!(
+            item.kind,
+            ItemKind::Struct | ItemKind::Enum | ItemKind::Union | ItemKind::Trait
+        )
+    }
+
+    /// Add the methods documented on a type or trait page.
+    ///
+    /// `page` is the docs path of the item (`struct.Bytes.html`); methods are
+    /// addressed by their anchor on that page.
+    pub fn add_item_docs(&mut self, page: &str, docs: &ItemDocsResponse) {
+        let page = page.split('#').next().unwrap_or(page);
+        let members = docs
+            .associated_items
+            .iter()
+            .chain(&docs.required_items)
+            .chain(&docs.provided_items)
+            .filter(|member| member.kind == AssociatedItemKind::Method);
+
+        for member in members {
+            let Some(signature) = &member.signature else {
+                continue;
+            };
+            let path = format!("{page}#{}", member.anchor);
+            self.items.push(CrateItem {
+                name: member.name.clone(),
+                kind: ItemKind::Method,
+                summary: member.summary.clone(),
+                path: path.clone(),
+                visibility: Visibility::Public,
+                is_async: member.is_async,
+                signature: Some(signature.clone()),
+                docs_path: Some(path),
+                deprecation: None,
+            });
+        }
+
+        for trait_impl in docs
+            .trait_impls
+            .iter()
+            .filter(|t| !t.is_blanket && !t.is_auto)
+        {
+            if let Some(item) = conversion_method(page, trait_impl) {
+                self.items.push(item);
+            }
+        }
+    }
+
+    /// Build a search index over the collected items
+    pub fn index(&self) -> ItemSearchIndex {
+        ItemSearchIndex::from_items(&self.crate_name, &self.version, &self.items)
+    }
+}
+
+/// The method a conversion trait impl provides, written out for the
+/// implementing type (`impl From<Vec<u8>> for Bytes` gives
+/// `fn from(value: Vec<u8>) -> Bytes`)
+fn conversion_method(page: &str, trait_impl: &TraitImpl) -> Option<CrateItem> {
+    let header = trait_impl.header.trim();
+    let rest = header.strip_prefix("unsafe ").unwrap_or(header);
+    let rest = rest.strip_prefix("impl")?;
+
+    let (params, rest) = match rest.strip_prefix('<') {
+        Some(inner) => {
+            let end = closing_angle(inner)?;
+            (&inner[..end], &inner[end + 1..])
+        }
+        None => ("", rest),
+    };
+    let (declaration, where_clause) = match rest.split_once(" where ") {
+        Some((declaration, where_clause)) => (declaration, Some(where_clause)),
+        None => (rest, None),
+    };
+    let (_, self_type) = declaration.trim().split_once(" for ")?;
+    let self_type = self_type.trim();
+
+    let (trait_path, trait_args) = match trait_impl.trait_name.split_once('<') {
+        Some((path, args)) => (path, args.strip_suffix('>')?),
+        None => (trait_impl.trait_name.as_str(), ""),
+    };
+    let trait_name = trait_path.rsplit("::").next()?.trim();
+
+    let (name, params_list, output) = match trait_name {
+        "From" => (
+            "from",
+            format!("value: {trait_args}"),
+            self_type.to_string(),
+        ),
+        "TryFrom" => (
+            "try_from",
+            format!("value: {trait_args}"),
+            format!("Result<{self_type}, _>"),
+        ),
+        "FromStr" => (
+            "from_str",
+            "s: &str".to_string(),
+            format!("Result<{self_type}, _>"),
+        ),
+        "FromIterator" => (
+            "from_iter",
+            format!("iter: impl IntoIterator<Item = {trait_args}>"),
+            self_type.to_string(),
+        ),
+        "AsRef" => (
+            "as_ref",
+            format!("self: &{self_type}"),
+            format!("&{trait_args}"),
+        ),
+        "Default" => ("default", String::new(), self_type.to_string()),
+        _ => return None,
+    };
+
+    let generics = if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{params}>")
+    };
+    let mut signature = format!("fn {name}{generics}({params_list}) -> {output}");
+    if let Some(where_clause) = where_clause {
+        signature.push_str(" where ");
+        signature.push_str(where_clause);
+    }
+
+    Some(CrateItem {
+        name: name.to_string(),
+        kind: ItemKind::Method,
+        summary: Some(format!("Provided by `{header}`")),
+        path: page.to_string(),
+        visibility: Visibility::Public,
+        is_async: false,
+        signature: Some(signature),
+        docs_path: Some(page.to_string()),
+        deprecation: None,
+    })
+}
+
+/// Byte index of the `>` closing an already opened `<`
+fn closing_angle(text: &str) -> Option<usize> {
+    let mut depth = 1usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if text[..index].ends_with('-') => {}
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_index::SignatureQuery;
+    use rustacean_docs_core::models::docs::AssociatedItem;
+
+    fn method(name: &str, signature: &str, anchor_prefix: &str) -> AssociatedItem {
+        AssociatedItem {
+            name: name.to_string(),
+            kind: AssociatedItemKind::Method,
+            signature: Some(signature.to_string()),
+            summary: None,
+            anchor: format!("{anchor_prefix}.{name}"),
+            is_async: false,
+        }
+    }
+
+    fn trait_impl(trait_name: &str, header: &str) -> TraitImpl {
+        TraitImpl {
+            trait_name: trait_name.to_string(),
+            header: header.to_string(),
+            bounds: vec![],
+            is_blanket: false,
+            is_auto: false,
+        }
+    }
+
+    fn item_docs(name: &str, kind: ItemKind) -> ItemDocsResponse {
+        ItemDocsResponse {
+            crate_name: "bytes".to_string(),
+            item_path: name.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: None,
+            description: None,
+            examples: vec![],
+            docs_url: None,
+            related_items: vec![],
+            associated_items: vec![],
+            trait_impls: vec![],
+            required_items: vec![],
+            provided_items: vec![],
+        }
+    }
+
+    fn sample_callables() -> CrateCallables {
+        let mut callables = CrateCallables {
+            crate_name: "bytes".to_string(),
+            version: "1.5.0".to_string(),
+            items: vec![],
+        };
+
+        let mut bytes = item_docs("Bytes", ItemKind::Struct);
+        bytes.associated_items = vec![
+            method("len", "pub const fn len(&self) -> usize", "method"),
+            method(
+                "copy_from_slice",
+                "pub fn copy_from_slice(data: &[u8]) -> Self",
+                "method",
+            ),
+        ];
+        bytes.trait_impls = vec![
+            trait_impl("From<Vec<u8>>", "impl From<Vec<u8>> for Bytes"),
+            trait_impl("From<&'static str>", "impl From<&'static str> for Bytes"),
+            trait_impl("Clone", "impl Clone for Bytes"),
+        ];
+        callables.add_item_docs("struct.Bytes.html", &bytes);
+
+        let mut buf = item_docs("Buf", ItemKind::Trait);
+        buf.required_items = vec![method(
+            "remaining",
+            "fn remaining(&self) -> usize",
+            "tymethod",
+        )];
+        buf.provided_items = vec![method(
+            "copy_to_bytes",
+            "fn copy_to_bytes(&mut self, len: usize) -> Bytes",
+            "method",
+        )];
+        callables.add_item_docs("buf/trait.Buf.html", &buf);
+
+        callables
+    }
+
+    #[test]
+    fn test_collect_methods_and_conversions() {
+        let callables = sample_callables();
+        let names: Vec<&str> = callables.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "len",
+                "copy_from_slice",
+                "from",
+                "from",
+                "remaining",
+                "copy_to_bytes"
+            ]
+        );
+        assert_eq!(
+            callables.items[0].docs_path.as_deref(),
+            Some("struct.Bytes.html#method.len")
+        );
+        assert_eq!(
+            callables.items[2].signature.as_deref(),
+            Some("fn from(value: Vec<u8>) -> Bytes")
+        );
+    }
+
+    #[test]
+    fn test_conversion_method_generics() {
+        let item = conversion_method(
+            "struct.Wrapper.html",
+            &trait_impl(
+                "FromIterator<T>",
+                "impl<T> FromIterator<T> for Wrapper<T> where T: Clone",
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            item.signature.as_deref(),
+            Some("fn from_iter<T>(iter: impl IntoIterator<Item = T>) -> Wrapper<T> where T: Clone")
+        );
+        assert!(conversion_method(
+            "struct.Wrapper.html",
+            &trait_impl("Debug", "impl Debug for Wrapper")
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_signature_search_over_methods() {
+        let index = sample_callables().index();
+
+        let query = SignatureQuery::parse("Vec<u8> -> Bytes").unwrap();
+        let hits = index.search_signature(&query);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "Bytes::from");
+        assert_eq!(hits[0].docs_path, "struct.Bytes.html");
+
+        // `Self` resolves to the type, so `&Bytes` matches the receiver
+        let query = SignatureQuery::parse("&Bytes -> usize").unwrap();
+        let hits = index.search_signature(&query);
+        assert_eq!(hits[0].path, "Bytes::len");
+
+        // Trait methods take any implementor as `Self`, scored lower than
+        // the concrete receiver
+        assert!(hits.iter().any(|hit| hit.path == "buf::Buf::remaining"));
+        assert!(hits[0].score > hits.last().unwrap().score);
+
+        let query = SignatureQuery::parse("-> Bytes").unwrap();
+        let paths: Vec<String> = index
+            .search_signature(&query)
+            .into_iter()
+            .map(|hit| hit.path)
+            .collect();
+        assert!(paths.contains(&"Bytes::copy_from_slice".to_string()));
+        assert!(paths.contains(&"buf::Buf::copy_to_bytes".to_string()));
+    }
+}
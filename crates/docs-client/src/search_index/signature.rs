@@ -0,0 +1,1149 @@
+//! Structural type patterns for searching function signatures.
+//!
+//! Rendered declarations (`pub fn get<K: Hash>(&self, key: &K) -> Option<&V>`)
+//! and user queries (`fn(&str) -> Result<_, _>`) are parsed into the same
+//! [`TypePattern`] trees. Types are compared by their last path segment, so
+//! `io::Result<T>` and `std::io::Result<T>` are the same type, while `_` and
+//! generic parameters match any type.
+
+use std::collections::{HashMap, HashSet};
+
+/// A type, reduced to what matters when comparing signatures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypePattern {
+    /// `_`, matches any type
+    Wildcard,
+    /// A generic parameter of the function, or a single capital letter in a query
+    Generic(String),
+    /// `&T` / `&mut T`
+    Reference {
+        mutable: bool,
+        inner: Box<TypePattern>,
+    },
+    /// `*const T` / `*mut T`
+    Pointer(Box<TypePattern>),
+    /// `[T]`
+    Slice(Box<TypePattern>),
+    /// `[T; N]`
+    Array(Box<TypePattern>),
+    /// `(A, B)`, `()` when empty
+    Tuple(Vec<TypePattern>),
+    /// A named type by its last path segment, with its generic arguments
+    Path {
+        name: String,
+        args: Vec<TypePattern>,
+    },
+    /// `impl A + B` or `dyn A + B`
+    Bounds(Vec<TypePattern>),
+    /// `fn(A) -> B` and the `Fn(A) -> B` trait sugar
+    Function {
+        inputs: Vec<TypePattern>,
+        output: Box<TypePattern>,
+    },
+    /// `!`
+    Never,
+}
+
+impl TypePattern {
+    /// The unit type `()`
+    pub fn unit() -> Self {
+        TypePattern::Tuple(Vec::new())
+    }
+
+    /// Parse a single type. Single capital letters are read as generic
+    /// parameters, the convention used in queries.
+    pub fn parse(text: &str) -> Option<Self> {
+        let tokens = tokenize(text);
+        let mut parser = Parser::new(&tokens, HashSet::new(), true);
+        let pattern = parser.parse_type()?;
+        parser.at_end().then_some(pattern)
+    }
+
+    /// How closely `candidate` matches this pattern, or `None` if it doesn't.
+    ///
+    /// Every concrete type constructor matched adds to the score; wildcards,
+    /// generic parameters and `impl Trait` arguments match anything but add
+    /// nothing.
+    pub fn unify(&self, candidate: &TypePattern) -> Option<u32> {
+        self.unify_in(candidate, &mut Bindings::default())
+    }
+
+    fn unify_in(&self, candidate: &TypePattern, bindings: &mut Bindings<'_>) -> Option<u32> {
+        use TypePattern::*;
+
+        match (self, candidate) {
+            (Wildcard, _) => Some(0),
+            (Generic(name), _) => bindings.bind_query(name, candidate),
+            (_, Generic(name)) => bindings.bind_candidate(name, self),
+            (_, Bounds(offered)) => bounds_score(self, offered, bindings),
+            (
+                Reference { mutable, inner },
+                Reference {
+                    mutable: offered_mutable,
+                    inner: offered_inner,
+                },
+            ) => {
+                if *mutable && !offered_mutable {
+                    return None;
+                }
+                inner.unify_in(offered_inner, bindings).map(|s| s + 1)
+            }
+            (Pointer(a), Pointer(b)) | (Slice(a), Slice(b)) | (Array(a), Array(b)) => {
+                a.unify_in(b, bindings).map(|s| s + 1)
+            }
+            (Tuple(a), Tuple(b)) if a.len() == b.len() => unify_all(a, b, bindings).map(|s| s + 1),
+            (
+                Path { name, args },
+                Path {
+                    name: offered_name,
+                    args: offered_args,
+                },
+            ) if name == offered_name => {
+                // Aliases such as `io::Result<T>` take fewer arguments than the
+                // type they stand for, so only the common prefix is compared
+                let len = args.len().min(offered_args.len());
+                unify_all(&args[..len], &offered_args[..len], bindings).map(|s| s + 2)
+            }
+            (
+                Function { inputs, output },
+                Function {
+                    inputs: offered_inputs,
+                    output: offered_output,
+                },
+            ) if inputs.len() == offered_inputs.len() => {
+                let inputs = unify_all(inputs, offered_inputs, bindings)?;
+                Some(inputs + output.unify_in(offered_output, bindings)? + 1)
+            }
+            (Never, Never) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Replace every bare `Self` with `replacement`
+    fn replace_self(&mut self, replacement: &TypePattern) {
+        use TypePattern::*;
+
+        match self {
+            Path { name, args } if name == "Self" && args.is_empty() => {
+                *self = replacement.clone();
+            }
+            Path { args: types, .. } | Tuple(types) | Bounds(types) => {
+                types.iter_mut().for_each(|t| t.replace_self(replacement));
+            }
+            Reference { inner, .. } | Pointer(inner) | Slice(inner) | Array(inner) => {
+                inner.replace_self(replacement)
+            }
+            Function { inputs, output } => {
+                inputs.iter_mut().for_each(|t| t.replace_self(replacement));
+                output.replace_self(replacement);
+            }
+            Wildcard | Generic(_) | Never => {}
+        }
+    }
+}
+
+fn unify_all(
+    wanted: &[TypePattern],
+    offered: &[TypePattern],
+    bindings: &mut Bindings<'_>,
+) -> Option<u32> {
+    wanted.iter().zip(offered).try_fold(0, |score, (w, o)| {
+        w.unify_in(o, bindings).map(|s| score + s)
+    })
+}
+
+/// How well `wanted` fills a generic or `impl Trait` parameter with the given
+/// trait bounds.
+///
+/// An `impl Trait` pattern must name traits found among the bounds and earns a
+/// point for each. Whether a concrete type implements the bounds isn't known
+/// here, so concrete types are accepted without credit.
+fn bounds_score(
+    wanted: &TypePattern,
+    offered: &[TypePattern],
+    bindings: &mut Bindings<'_>,
+) -> Option<u32> {
+    let TypePattern::Bounds(wanted) = wanted else {
+        return Some(0);
+    };
+
+    let mut score = 1;
+    for bound in wanted {
+        let (best, trial) = offered
+            .iter()
+            .filter_map(|o| {
+                let mut trial = bindings.clone();
+                bound.unify_in(o, &mut trial).map(|s| (s, trial))
+            })
+            .max_by_key(|(s, _)| *s)?;
+        score += best;
+        *bindings = trial;
+    }
+    Some(score)
+}
+
+/// Generic parameters bound while matching a query against one signature.
+///
+/// A generic parameter matches any type the first time it is seen, and only
+/// that same type afterwards, which is what makes `Vec<T> -> T` reject
+/// `fn len(v: Vec<u8>) -> usize`.
+#[derive(Debug, Clone, Default)]
+struct Bindings<'a> {
+    /// Trait bounds of the candidate's generic parameters
+    bounds: Option<&'a HashMap<String, Vec<TypePattern>>>,
+    /// Query generics and the candidate types they stand for
+    query: HashMap<String, TypePattern>,
+    /// Candidate generics and the query types they stand for
+    candidate: HashMap<String, TypePattern>,
+}
+
+impl<'a> Bindings<'a> {
+    fn bind_query(&mut self, name: &str, candidate: &TypePattern) -> Option<u32> {
+        match self.query.get(name) {
+            Some(bound) => (bound == candidate).then_some(1),
+            None => {
+                self.query.insert(name.to_string(), candidate.clone());
+                Some(0)
+            }
+        }
+    }
+
+    fn bind_candidate(&mut self, name: &str, wanted: &TypePattern) -> Option<u32> {
+        if let Some(bound) = self.candidate.get(name) {
+            return (bound == wanted).then_some(1);
+        }
+        self.candidate.insert(name.to_string(), wanted.clone());
+
+        let bounds: &'a [TypePattern] = self
+            .bounds
+            .and_then(|bounds| bounds.get(name))
+            .map_or(&[], Vec::as_slice);
+        bounds_score(wanted, bounds, self)
+    }
+}
+
+/// Parameters and return type of a function or method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FnSignature {
+    /// Names of the generic type parameters
+    pub generics: Vec<String>,
+    /// Trait bounds of the generic parameters, from the parameter list and
+    /// the `where` clause
+    pub bounds: HashMap<String, Vec<TypePattern>>,
+    /// The `self` parameter of a method
+    pub receiver: Option<TypePattern>,
+    /// Types of the remaining parameters
+    pub inputs: Vec<TypePattern>,
+    /// Return type, `()` when omitted
+    pub output: TypePattern,
+}
+
+impl FnSignature {
+    /// Parse a rendered declaration such as
+    /// `pub async fn get<K: Hash>(&self, key: &K) -> Option<&V>`
+    pub fn parse(declaration: &str) -> Option<Self> {
+        let tokens = tokenize(declaration);
+        let fn_index = tokens.iter().position(|t| t.is_ident("fn"))?;
+        let rest = &tokens[fn_index + 1..];
+        if !matches!(rest.first(), Some(Token::Ident(_))) {
+            return None;
+        }
+
+        let mut generics = Vec::new();
+        let mut params: &[Token] = &[];
+        let mut position = 1;
+        if rest.get(position) == Some(&Token::Punct('<')) {
+            let end = matching_close(rest, position)?;
+            params = &rest[position + 1..end];
+            generics = generic_param_names(params);
+            position = end + 1;
+        }
+        if rest.get(position) != Some(&Token::Punct('(')) {
+            return None;
+        }
+
+        let names: HashSet<String> = generics.iter().cloned().collect();
+        let mut parser = Parser::new(&rest[position + 1..], names.clone(), false);
+        let (receiver, inputs) = parser.parse_params()?;
+        let output = if parser.eat(&Token::Arrow) {
+            parser.parse_type()?
+        } else {
+            TypePattern::unit()
+        };
+
+        let mut bounds: HashMap<String, Vec<TypePattern>> = HashMap::new();
+        let where_clause = if parser.eat_ident("where") {
+            &parser.tokens[parser.position..]
+        } else {
+            &[]
+        };
+        for predicate in split_top_level(params)
+            .into_iter()
+            .chain(split_top_level(where_clause))
+        {
+            // Only `T: Bound` predicates constrain a parameter directly
+            let [Token::Ident(name), Token::Punct(':'), bound_tokens @ ..] = predicate else {
+                continue;
+            };
+            if !names.contains(name) {
+                continue;
+            }
+            let mut bound_parser = Parser::new(bound_tokens, names.clone(), false);
+            if let Some(parsed) = bound_parser.parse_bounds() {
+                bounds.entry(name.clone()).or_default().extend(parsed);
+            }
+        }
+
+        Some(Self {
+            generics,
+            bounds,
+            receiver,
+            inputs,
+            output,
+        })
+    }
+
+    /// Resolve `Self` to the implementing type, for inherent methods and
+    /// trait impls
+    pub fn with_self_type(mut self, self_type: &TypePattern) -> Self {
+        self.replace_self(self_type);
+        self
+    }
+
+    /// Treat `Self` as a generic parameter bounded by `bound`, for the
+    /// methods declared by a trait
+    pub fn with_self_bound(mut self, bound: TypePattern) -> Self {
+        self.replace_self(&TypePattern::Generic("Self".to_string()));
+        self.generics.push("Self".to_string());
+        self.bounds
+            .entry("Self".to_string())
+            .or_default()
+            .push(bound);
+        self
+    }
+
+    /// The receiver followed by the other parameters
+    pub fn params(&self) -> impl Iterator<Item = &TypePattern> {
+        self.receiver.iter().chain(&self.inputs)
+    }
+
+    fn replace_self(&mut self, replacement: &TypePattern) {
+        for pattern in self
+            .receiver
+            .iter_mut()
+            .chain(&mut self.inputs)
+            .chain(std::iter::once(&mut self.output))
+            .chain(self.bounds.values_mut().flatten())
+        {
+            pattern.replace_self(replacement);
+        }
+    }
+}
+
+/// A signature query: parameter types and, optionally, the return type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureQuery {
+    pub inputs: Vec<TypePattern>,
+    pub output: Option<TypePattern>,
+}
+
+impl SignatureQuery {
+    /// Whether a free-text query should be read as a signature
+    pub fn looks_like_signature(query: &str) -> bool {
+        let query = query.trim();
+        query.contains("->")
+            || query.starts_with('(')
+            || query.strip_prefix("fn").is_some_and(|rest| {
+                let rest = rest.trim_start();
+                rest.starts_with('(')
+            })
+    }
+
+    /// Parse `fn(&str, usize) -> Vec<u8>`, `(&str) -> Result<_, _>`,
+    /// `&str -> String` or `-> Bytes`
+    pub fn parse(query: &str) -> Option<Self> {
+        let tokens = tokenize(query);
+        let tokens = match tokens.first() {
+            Some(t) if t.is_ident("fn") => &tokens[1..],
+            _ => &tokens[..],
+        };
+
+        let arrow = top_level_position(tokens, &Token::Arrow);
+        let (lhs, rhs) = match arrow {
+            Some(index) => (&tokens[..index], Some(&tokens[index + 1..])),
+            None => (tokens, None),
+        };
+
+        // `(A, B)` on the left is a parameter list, not a tuple
+        let lhs = match (lhs.first(), lhs.last()) {
+            (Some(Token::Punct('(')), Some(Token::Punct(')')))
+                if matching_close(lhs, 0) == Some(lhs.len() - 1) =>
+            {
+                &lhs[1..lhs.len() - 1]
+            }
+            _ => lhs,
+        };
+
+        let mut parser = Parser::new(lhs, HashSet::new(), true);
+        let mut inputs = Vec::new();
+        while !parser.at_end() {
+            inputs.push(parser.parse_type()?);
+            if !parser.eat(&Token::Punct(',')) && !parser.at_end() {
+                return None;
+            }
+        }
+
+        let output = match rhs {
+            Some(rhs) if !rhs.is_empty() => {
+                let mut parser = Parser::new(rhs, HashSet::new(), true);
+                let output = parser.parse_type()?;
+                if !parser.at_end() {
+                    return None;
+                }
+                Some(output)
+            }
+            _ => None,
+        };
+
+        if inputs.is_empty() && output.is_none() {
+            return None;
+        }
+        Some(Self { inputs, output })
+    }
+
+    /// How closely a function matches this query, or `None` if it doesn't.
+    ///
+    /// Each queried parameter must match a different parameter of the
+    /// function, the receiver included, in any order; unmatched extra
+    /// parameters lower the score. Generic parameters on either side must bind
+    /// consistently across all positions.
+    pub fn score(&self, candidate: &FnSignature) -> Option<u32> {
+        let params: Vec<&TypePattern> = candidate.params().collect();
+        if self.inputs.len() > params.len() {
+            return None;
+        }
+
+        let mut bindings = Bindings {
+            bounds: Some(&candidate.bounds),
+            ..Bindings::default()
+        };
+        let mut used = vec![false; params.len()];
+        let mut specificity = 0;
+        for wanted in &self.inputs {
+            let mut best: Option<(usize, u32, Bindings<'_>)> = None;
+            for (index, offered) in params.iter().enumerate() {
+                if used[index] {
+                    continue;
+                }
+                let mut trial = bindings.clone();
+                if let Some(score) = wanted.unify_in(offered, &mut trial) {
+                    if best.as_ref().is_none_or(|(_, b, _)| score > *b) {
+                        best = Some((index, score, trial));
+                    }
+                }
+            }
+
+            let (index, score, trial) = best?;
+            used[index] = true;
+            specificity += score;
+            bindings = trial;
+        }
+
+        if let Some(output) = &self.output {
+            specificity += output.unify_in(&candidate.output, &mut bindings)?;
+        }
+
+        let extra = (params.len() - self.inputs.len()) as u32;
+        Some((100 + specificity * 10).saturating_sub(extra * 5))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Lifetime,
+    Arrow,
+    PathSep,
+    Punct(char),
+}
+
+impl Token {
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self, Token::Ident(ident) if ident == name)
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '\'' => {
+                while chars
+                    .peek()
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    chars.next();
+                }
+                tokens.push(Token::Lifetime);
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                tokens.push(Token::PathSep);
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    ident.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+
+    tokens
+}
+
+/// Index of the bracket closing the one at `open`
+fn matching_close(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('<' | '(' | '[') => depth += 1,
+            Token::Punct('>' | ')' | ']') => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index of the first occurrence of `wanted` outside any brackets
+fn top_level_position(tokens: &[Token], wanted: &Token) -> Option<usize> {
+    let mut depth = 0i32;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('<' | '(' | '[') => depth += 1,
+            Token::Punct('>' | ')' | ']') => depth -= 1,
+            token if depth == 0 && token == wanted => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a token list on the commas outside any brackets
+fn split_top_level(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('<' | '(' | '[') => depth += 1,
+            Token::Punct('>' | ')' | ']') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                parts.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+    parts
+}
+
+/// Names of the type and const parameters in a `<...>` generics list
+fn generic_param_names(tokens: &[Token]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0i32;
+    let mut expecting_name = true;
+
+    for token in tokens {
+        match token {
+            Token::Punct('<' | '(' | '[') => depth += 1,
+            Token::Punct('>' | ')' | ']') => depth -= 1,
+            Token::Punct(',') if depth == 0 => expecting_name = true,
+            // `const N: usize` is named by the identifier after `const`
+            Token::Ident(ident) if depth == 0 && expecting_name && ident == "const" => {}
+            Token::Ident(ident) if depth == 0 && expecting_name => {
+                names.push(ident.clone());
+                expecting_name = false;
+            }
+            _ if depth == 0 => expecting_name = false,
+            _ => {}
+        }
+    }
+
+    names
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    generics: HashSet<String>,
+    /// Read unknown single capital letters as generic parameters
+    letters_are_generic: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token], generics: HashSet<String>, letters_are_generic: bool) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            generics,
+            letters_are_generic,
+        }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if self.peek().is_some_and(|t| t.is_ident(name)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// Parse `self`-aware parameters up to and including the closing `)`
+    fn parse_params(&mut self) -> Option<(Option<TypePattern>, Vec<TypePattern>)> {
+        let mut receiver = None;
+        let mut inputs = Vec::new();
+
+        loop {
+            if self.eat(&Token::Punct(')')) {
+                return Some((receiver, inputs));
+            }
+
+            if let Some(self_type) = self.parse_receiver() {
+                receiver = Some(self_type);
+            } else {
+                self.skip_pattern()?;
+                inputs.push(self.parse_type()?);
+            }
+
+            if !self.eat(&Token::Punct(',')) && self.peek() != Some(&Token::Punct(')')) {
+                return None;
+            }
+        }
+    }
+
+    /// `self`, `mut self`, `&self`, `&'a mut self` or `self: Type`
+    fn parse_receiver(&mut self) -> Option<TypePattern> {
+        let start = self.position;
+        let self_type = || TypePattern::Path {
+            name: "Self".to_string(),
+            args: Vec::new(),
+        };
+
+        let reference = self.eat(&Token::Punct('&'));
+        if reference {
+            self.eat(&Token::Lifetime);
+        }
+        let mutable = self.eat_ident("mut");
+        if !self.eat_ident("self") {
+            self.position = start;
+            return None;
+        }
+
+        if reference {
+            return Some(TypePattern::Reference {
+                mutable,
+                inner: Box::new(self_type()),
+            });
+        }
+        if self.eat(&Token::Punct(':')) {
+            return self.parse_type();
+        }
+        Some(self_type())
+    }
+
+    /// Skip a parameter pattern and its `:`
+    fn skip_pattern(&mut self) -> Option<()> {
+        let mut depth = 0i32;
+        loop {
+            match self.next()? {
+                Token::Punct('<' | '(' | '[') => depth += 1,
+                Token::Punct('>' | ')' | ']') => depth -= 1,
+                Token::Punct(':') if depth == 0 => return Some(()),
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Option<TypePattern> {
+        match self.next()? {
+            Token::Punct('&') => {
+                self.eat(&Token::Lifetime);
+                let mutable = self.eat_ident("mut");
+                Some(TypePattern::Reference {
+                    mutable,
+                    inner: Box::new(self.parse_type()?),
+                })
+            }
+            Token::Punct('*') => {
+                if !self.eat_ident("const") {
+                    self.eat_ident("mut");
+                }
+                Some(TypePattern::Pointer(Box::new(self.parse_type()?)))
+            }
+            Token::Punct('[') => {
+                let inner = Box::new(self.parse_type()?);
+                if self.eat(&Token::Punct(']')) {
+                    return Some(TypePattern::Slice(inner));
+                }
+                if !self.eat(&Token::Punct(';')) {
+                    return None;
+                }
+                // Skip the length expression
+                let mut depth = 0i32;
+                loop {
+                    match self.next()? {
+                        Token::Punct('[' | '(' | '{') => depth += 1,
+                        Token::Punct(']') if depth == 0 => break,
+                        Token::Punct(']' | ')' | '}') => depth -= 1,
+                        _ => {}
+                    }
+                }
+                Some(TypePattern::Array(inner))
+            }
+            Token::Punct('(') => {
+                let mut elements = Vec::new();
+                let mut trailing_comma = false;
+                while !self.eat(&Token::Punct(')')) {
+                    elements.push(self.parse_type()?);
+                    trailing_comma = self.eat(&Token::Punct(','));
+                    if !trailing_comma && self.peek() != Some(&Token::Punct(')')) {
+                        return None;
+                    }
+                }
+                if elements.len() == 1 && !trailing_comma {
+                    return elements.pop();
+                }
+                Some(TypePattern::Tuple(elements))
+            }
+            Token::Punct('!') => Some(TypePattern::Never),
+            Token::Punct('<') => self.parse_qualified_path(),
+            Token::Ident(ident) => match ident.as_str() {
+                "_" => Some(TypePattern::Wildcard),
+                "impl" | "dyn" => self.parse_bounds().map(TypePattern::Bounds),
+                "fn" => self.parse_fn_pointer(),
+                "unsafe" | "extern" => {
+                    // `unsafe extern "C" fn(...)`
+                    while !self.peek()?.is_ident("fn") {
+                        self.next();
+                    }
+                    self.next();
+                    self.parse_fn_pointer()
+                }
+                _ => self.parse_path(ident.clone()),
+            },
+            Token::PathSep => match self.next()? {
+                Token::Ident(ident) => self.parse_path(ident.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// `fn(A, B) -> C`, after the `fn` keyword
+    fn parse_fn_pointer(&mut self) -> Option<TypePattern> {
+        if !self.eat(&Token::Punct('(')) {
+            return None;
+        }
+        let (_, inputs) = self.parse_fn_pointer_params()?;
+        let output = if self.eat(&Token::Arrow) {
+            self.parse_type()?
+        } else {
+            TypePattern::unit()
+        };
+        Some(TypePattern::Function {
+            inputs,
+            output: Box::new(output),
+        })
+    }
+
+    /// Function pointer and `Fn` sugar parameters may be bare types or named
+    fn parse_fn_pointer_params(&mut self) -> Option<(Option<TypePattern>, Vec<TypePattern>)> {
+        let mut inputs = Vec::new();
+        loop {
+            if self.eat(&Token::Punct(')')) {
+                return Some((None, inputs));
+            }
+            if matches!(self.peek(), Some(Token::Ident(_)))
+                && self.tokens.get(self.position + 1) == Some(&Token::Punct(':'))
+            {
+                self.position += 2;
+            }
+            inputs.push(self.parse_type()?);
+            if !self.eat(&Token::Punct(',')) && self.peek() != Some(&Token::Punct(')')) {
+                return None;
+            }
+        }
+    }
+
+    /// `<T as Trait>::Name`, after the opening `<`
+    fn parse_qualified_path(&mut self) -> Option<TypePattern> {
+        let mut depth = 1usize;
+        while depth > 0 {
+            match self.next()? {
+                Token::Punct('<') => depth += 1,
+                Token::Punct('>') => depth -= 1,
+                _ => {}
+            }
+        }
+        if !self.eat(&Token::PathSep) {
+            return None;
+        }
+        match self.next()? {
+            Token::Ident(ident) => self.parse_path(ident.clone()),
+            _ => None,
+        }
+    }
+
+    /// A path starting with `first`, reduced to its last segment
+    fn parse_path(&mut self, first: String) -> Option<TypePattern> {
+        let mut name = first;
+        let mut segments = 1;
+        let mut args = Vec::new();
+
+        loop {
+            if self.peek() == Some(&Token::Punct('<')) {
+                self.next();
+                args = self.parse_generic_args()?;
+            } else if self.peek() == Some(&Token::Punct('('))
+                && matches!(name.as_str(), "Fn" | "FnMut" | "FnOnce")
+            {
+                self.next();
+                let (_, inputs) = self.parse_fn_pointer_params()?;
+                let output = if self.eat(&Token::Arrow) {
+                    self.parse_type()?
+                } else {
+                    TypePattern::unit()
+                };
+                return Some(TypePattern::Function {
+                    inputs,
+                    output: Box::new(output),
+                });
+            }
+
+            if self.eat(&Token::PathSep) {
+                match self.next()? {
+                    Token::Ident(ident) => {
+                        name = ident.clone();
+                        segments += 1;
+                        args.clear();
+                    }
+                    Token::Punct('<') => args = self.parse_generic_args()?,
+                    _ => return None,
+                }
+                continue;
+            }
+            break;
+        }
+
+        let is_generic = segments == 1
+            && args.is_empty()
+            && (self.generics.contains(&name)
+                || (self.letters_are_generic
+                    && name.len() == 1
+                    && name.chars().all(|c| c.is_ascii_uppercase())));
+        if is_generic {
+            return Some(TypePattern::Generic(name));
+        }
+
+        Some(TypePattern::Path { name, args })
+    }
+
+    /// Generic arguments after the opening `<`; lifetimes are dropped and
+    /// associated type constraints (`Item = T`) keep their type
+    fn parse_generic_args(&mut self) -> Option<Vec<TypePattern>> {
+        let mut args = Vec::new();
+        loop {
+            if self.eat(&Token::Punct('>')) {
+                return Some(args);
+            }
+            if self.eat(&Token::Lifetime) {
+                // Lifetime argument
+            } else if matches!(self.peek(), Some(Token::Ident(_)))
+                && matches!(
+                    self.tokens.get(self.position + 1),
+                    Some(Token::Punct('=' | ':'))
+                )
+            {
+                self.position += 1;
+                if self.next()? == &Token::Punct('=') {
+                    args.push(self.parse_type()?);
+                } else {
+                    args.push(TypePattern::Bounds(self.parse_bounds()?));
+                }
+            } else if self.peek() == Some(&Token::Punct('{')) {
+                // Const generic expression
+                let mut depth = 0i32;
+                loop {
+                    match self.next()? {
+                        Token::Punct('{') => depth += 1,
+                        Token::Punct('}') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                args.push(TypePattern::Wildcard);
+            } else {
+                args.push(self.parse_type()?);
+            }
+            if !self.eat(&Token::Punct(',')) && self.peek() != Some(&Token::Punct('>')) {
+                return None;
+            }
+        }
+    }
+
+    /// `A + B + 'a + ?Sized`, keeping the trait bounds
+    fn parse_bounds(&mut self) -> Option<Vec<TypePattern>> {
+        let mut bounds = Vec::new();
+        loop {
+            if self.eat(&Token::Lifetime) {
+                // Lifetime bound
+            } else {
+                let maybe = self.eat(&Token::Punct('?'));
+                if self.eat_ident("for") {
+                    // Higher-ranked `for<'a>` prefix
+                    self.next();
+                    while self.eat(&Token::Lifetime) || self.eat(&Token::Punct(',')) {}
+                    self.next();
+                }
+                let bound = self.parse_type()?;
+                if !maybe {
+                    bounds.push(bound);
+                }
+            }
+            if !self.eat(&Token::Punct('+')) {
+                return Some(bounds);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(name: &str, args: Vec<TypePattern>) -> TypePattern {
+        TypePattern::Path {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(
+            TypePattern::parse("&'a mut [u8]").unwrap(),
+            TypePattern::Reference {
+                mutable: true,
+                inner: Box::new(TypePattern::Slice(Box::new(path("u8", vec![])))),
+            }
+        );
+        assert_eq!(
+            TypePattern::parse("std::io::Result<Vec<T>>").unwrap(),
+            path(
+                "Result",
+                vec![path("Vec", vec![TypePattern::Generic("T".to_string())])]
+            )
+        );
+        assert_eq!(
+            TypePattern::parse("impl Iterator<Item = u8> + Send + 'static").unwrap(),
+            TypePattern::Bounds(vec![
+                path("Iterator", vec![path("u8", vec![])]),
+                path("Send", vec![]),
+            ])
+        );
+        assert_eq!(
+            TypePattern::parse("Box<dyn Fn(&str) -> bool>").unwrap(),
+            path(
+                "Box",
+                vec![TypePattern::Bounds(vec![TypePattern::Function {
+                    inputs: vec![TypePattern::parse("&str").unwrap()],
+                    output: Box::new(path("bool", vec![])),
+                }])]
+            )
+        );
+        assert_eq!(
+            TypePattern::parse("<T as Deref>::Target").unwrap(),
+            path("Target", vec![])
+        );
+        assert_eq!(TypePattern::parse("()").unwrap(), TypePattern::unit());
+        assert!(TypePattern::parse("Vec<").is_none());
+    }
+
+    #[test]
+    fn test_parse_fn_signature() {
+        let signature = FnSignature::parse(
+            "pub async fn get<'a, K: Hash + Eq, const N: usize>(&'a self, key: &K, (a, b): (u8, u8)) -> Option<&'a V> where K: Clone",
+        )
+        .unwrap();
+        assert_eq!(signature.generics, vec!["K".to_string(), "N".to_string()]);
+        assert!(matches!(
+            signature.receiver,
+            Some(TypePattern::Reference { mutable: false, .. })
+        ));
+        assert_eq!(signature.inputs.len(), 2);
+        assert_eq!(
+            signature.inputs[0],
+            TypePattern::Reference {
+                mutable: false,
+                inner: Box::new(TypePattern::Generic("K".to_string())),
+            }
+        );
+        assert_eq!(
+            signature.output,
+            path(
+                "Option",
+                vec![TypePattern::Reference {
+                    mutable: false,
+                    inner: Box::new(path("V", vec![])),
+                }]
+            )
+        );
+
+        let unit = FnSignature::parse("fn run(self: Box<Self>)").unwrap();
+        assert!(unit.inputs.is_empty());
+        assert_eq!(unit.output, TypePattern::unit());
+        assert!(FnSignature::parse("pub struct Config;").is_none());
+    }
+
+    #[test]
+    fn test_signature_query() {
+        assert!(SignatureQuery::looks_like_signature(
+            "fn(&str) -> Result<_, _>"
+        ));
+        assert!(SignatureQuery::looks_like_signature("-> Bytes"));
+        assert!(!SignatureQuery::looks_like_signature("spawn blocking"));
+        assert!(!SignatureQuery::looks_like_signature("fnmut"));
+
+        let query = SignatureQuery::parse("fn(&str) -> Result<_, _>").unwrap();
+        let parse =
+            FnSignature::parse("pub fn parse(input: &str) -> Result<Config, Error>").unwrap();
+        let alias = FnSignature::parse("pub fn load(path: &str) -> io::Result<Config>").unwrap();
+        let generic =
+            FnSignature::parse("pub fn from<S: AsRef<str>>(s: S) -> Result<Self>").unwrap();
+        let extra = FnSignature::parse(
+            "pub fn parse_with(input: &str, strict: bool) -> Result<Config, Error>",
+        )
+        .unwrap();
+        let wrong = FnSignature::parse("pub fn len(input: &str) -> usize").unwrap();
+
+        let exact = query.score(&parse).unwrap();
+        assert_eq!(query.score(&alias), Some(exact));
+        assert!(query.score(&generic).unwrap() < exact);
+        assert!(query.score(&extra).unwrap() < exact);
+        assert_eq!(query.score(&wrong), None);
+
+        let output_only = SignatureQuery::parse("-> Vec<u8>").unwrap();
+        assert!(output_only.inputs.is_empty());
+        assert!(output_only
+            .score(&FnSignature::parse("fn to_vec(&self) -> Vec<u8>").unwrap())
+            .is_some());
+
+        let unordered = SignatureQuery::parse("usize, &str").unwrap();
+        assert!(unordered
+            .score(&FnSignature::parse("fn pad(s: &str, width: usize) -> String").unwrap())
+            .is_some());
+        assert!(SignatureQuery::parse("fn(").is_none());
+    }
+
+    #[test]
+    fn test_generic_bindings_and_bounds() {
+        let signature =
+            FnSignature::parse("pub fn read<R>(reader: R) -> Vec<u8> where R: io::Read + Send")
+                .unwrap();
+        assert_eq!(
+            signature.bounds["R"],
+            vec![path("Read", vec![]), path("Send", vec![])]
+        );
+
+        // Query generics must bind to the same type everywhere
+        let first = SignatureQuery::parse("Vec<T> -> T").unwrap();
+        assert!(first
+            .score(&FnSignature::parse("fn first(v: Vec<u8>) -> u8").unwrap())
+            .is_some());
+        assert!(first
+            .score(&FnSignature::parse("fn len(v: Vec<u8>) -> usize").unwrap())
+            .is_none());
+
+        // ...and so must the candidate's
+        let pair = FnSignature::parse("fn pair<T>(a: T, b: T) -> (T, T)").unwrap();
+        assert!(SignatureQuery::parse("u8, u8")
+            .unwrap()
+            .score(&pair)
+            .is_some());
+        assert!(SignatureQuery::parse("u8, String")
+            .unwrap()
+            .score(&pair)
+            .is_none());
+
+        // `impl Trait` queries must name a bound of the parameter, and rank
+        // above concrete types that merely might satisfy it
+        let by_bound = SignatureQuery::parse("impl Read -> Vec<u8>").unwrap();
+        let concrete = SignatureQuery::parse("File -> Vec<u8>").unwrap();
+        let bound_score = by_bound.score(&signature).unwrap();
+        assert!(bound_score > concrete.score(&signature).unwrap());
+        assert!(SignatureQuery::parse("impl Write -> Vec<u8>")
+            .unwrap()
+            .score(&signature)
+            .is_none());
+
+        // `Self` of a trait method is a generic bounded by the trait
+        let method = FnSignature::parse("fn remaining(&self) -> usize")
+            .unwrap()
+            .with_self_bound(path("Buf", vec![]));
+        assert_eq!(method.bounds["Self"], vec![path("Buf", vec![])]);
+        assert!(SignatureQuery::parse("&impl Buf -> usize")
+            .unwrap()
+            .score(&method)
+            .is_some());
+    }
+}
@@ -1,23 +1,52 @@
 //! Common utilities for integration tests
 
 use crate::*;
-use async_trait::async_trait;
 use chrono::Utc;
-use rustacean_docs_cache::{Cache, DiskCache, MemoryCache, TieredCache, WriteStrategy};
+use rustacean_docs_cache::{DiskCache, MemoryCache, TieredCache, WriteStrategy};
+use rustacean_docs_client::{
+    fixtures::{FIXTURE_DIR_ENV, FIXTURE_MODE_ENV},
+    ClientConfig, FixtureConfig, FixtureMode,
+};
+use rustacean_docs_mcp_server::tools::cache_backend::MemoryCacheWrapper;
+use std::path::PathBuf;
 use url::Url;
 
 type ServerCache = MemoryCache<String, Value>;
 
+/// Recorded upstream responses the integration tests replay
+pub const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// Fixture settings for the integration tests: the checked-in fixtures are
+/// replayed unless `RUSTACEAN_DOCS_FIXTURE_MODE` asks for something else
+pub fn fixture_config() -> FixtureConfig {
+    let mode = std::env::var(FIXTURE_MODE_ENV)
+        .map(|mode| mode.parse().expect("Invalid fixture mode"))
+        .unwrap_or(FixtureMode::Replay);
+    let dir = std::env::var_os(FIXTURE_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(FIXTURE_DIR));
+    FixtureConfig { mode, dir }
+}
+
+/// Create a client that replays the recorded fixtures by default
+pub fn create_test_client() -> DocsClient {
+    DocsClient::with_config(ClientConfig {
+        fixtures: Some(fixture_config()),
+        ..Default::default()
+    })
+    .expect("Failed to create DocsClient")
+}
+
 /// Create a test environment with standard settings
 pub async fn create_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let cache = Arc::new(RwLock::new(MemoryCache::new(100)));
     (client, cache)
 }
 
 /// Create a test environment with short TTL for expiration testing
 pub async fn create_short_ttl_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let cache = Arc::new(RwLock::new(MemoryCache::new(10)));
     (client, cache)
 }
@@ -31,48 +60,6 @@ pub fn create_tiered_cache(
     let memory_cache = MemoryCache::new(memory_capacity);
     let disk_cache = DiskCache::new(cache_dir);
 
-    // Wrap memory cache to match error type
-    struct MemoryCacheWrapper(MemoryCache<String, Value>);
-
-    #[async_trait]
-    impl Cache for MemoryCacheWrapper {
-        type Key = String;
-        type Value = Value;
-        type Error = anyhow::Error;
-
-        async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
-            self.0
-                .get(key)
-                .await
-                .map_err(|_| anyhow::anyhow!("Memory cache error"))
-        }
-
-        async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
-            self.0
-                .insert(key, value)
-                .await
-                .map_err(|_| anyhow::anyhow!("Memory cache error"))
-        }
-
-        async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
-            self.0
-                .remove(key)
-                .await
-                .map_err(|_| anyhow::anyhow!("Memory cache error"))
-        }
-
-        async fn clear(&self) -> Result<(), Self::Error> {
-            self.0
-                .clear()
-                .await
-                .map_err(|_| anyhow::anyhow!("Memory cache error"))
-        }
-
-        fn stats(&self) -> rustacean_docs_cache::CacheStats {
-            self.0.stats()
-        }
-    }
-
     TieredCache::new(
         vec![
             Box::new(MemoryCacheWrapper(memory_cache)),
@@ -3,7 +3,7 @@
 //! These tests verify cache hit/miss scenarios, TTL behavior, and
 //! integration between different components using the cache.
 
-use integration_tests::common::create_tiered_cache;
+use integration_tests::common::{create_test_client, create_tiered_cache};
 use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 use rustacean_docs_mcp_server::tools::{search::SearchTool, ToolHandler};
@@ -17,7 +17,7 @@ type ServerCache = TieredCache<String, Value>;
 
 /// Create a test environment with shorter TTL for testing
 async fn create_cache_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     let cache = Arc::new(RwLock::new(
@@ -28,7 +28,7 @@ async fn create_cache_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>
 
 /// Create a standard test environment
 async fn create_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     let cache = Arc::new(RwLock::new(create_tiered_cache(100, &temp_dir)));
@@ -179,7 +179,7 @@ async fn test_cache_key_uniqueness() {
     let tool = SearchTool::new();
 
     // Create different cache entries that should have unique keys
-    let test_cases = vec![
+    let test_cases = [
         (
             "search_crate:test1:10",
             json!({"query": "test1", "limit": 10}),
@@ -3,7 +3,7 @@
 //! Tests the complete documentation retrieval and caching workflow,
 //! including crate docs, item docs, and version-specific requests.
 
-use integration_tests::common::create_tiered_cache;
+use integration_tests::common::{create_test_client, create_tiered_cache};
 use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 use rustacean_docs_mcp_server::tools::{
@@ -18,7 +18,7 @@ type ServerCache = TieredCache<String, Value>;
 
 /// Create test environment optimized for documentation caching
 async fn create_docs_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     // Documentation cache: 500 entries
@@ -88,7 +88,7 @@ fn create_mock_crate_docs(crate_name: &str, version: &str) -> Value {
 
 /// Create mock item documentation response
 fn create_mock_item_docs(crate_name: &str, item_path: &str) -> Value {
-    let item_name = item_path.split('/').last().unwrap_or(item_path);
+    let item_name = item_path.split('/').next_back().unwrap_or(item_path);
     json!({
         "crate_name": crate_name,
         "item_path": item_path,
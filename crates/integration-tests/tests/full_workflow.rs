@@ -3,7 +3,7 @@
 //! These tests verify the full search workflow with cache integration,
 //! simulating real-world usage patterns and edge cases.
 
-use integration_tests::common::create_tiered_cache;
+use integration_tests::common::{create_test_client, create_tiered_cache};
 use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 use rustacean_docs_mcp_server::tools::{search::SearchTool, ToolHandler};
@@ -16,7 +16,7 @@ type ServerCache = TieredCache<String, Value>;
 
 /// Create test environment with realistic cache settings
 async fn create_realistic_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     // Use realistic cache settings: 1000 entries
@@ -459,7 +459,7 @@ async fn test_workflow_performance_characteristics() {
 
 #[tokio::test]
 async fn test_workflow_cache_capacity_management() {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     // Create cache with very small capacity for testing eviction
     let temp_dir = std::env::temp_dir().join(format!(
         "rustacean_docs_test_small_{}",
@@ -3,7 +3,7 @@
 //! These tests verify that our tools correctly implement the MCP protocol
 //! specifications and provide the expected interfaces.
 
-use integration_tests::common::create_tiered_cache;
+use integration_tests::common::{create_test_client, create_tiered_cache};
 use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 use rustacean_docs_mcp_server::tools::{search::SearchTool, ToolHandler};
@@ -515,7 +515,7 @@ async fn test_tool_json_schema_validation() {
 }
 
 async fn create_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     let cache = Arc::new(RwLock::new(create_tiered_cache(100, &temp_dir)));
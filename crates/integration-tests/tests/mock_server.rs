@@ -0,0 +1,102 @@
+//! Offline integration tests against the local mock server
+//!
+//! These tests drive the MCP tools through a [`MockServer`] serving the
+//! bundled fixture tree, so they exercise real HTTP round trips and the
+//! docs.rs parsers without touching the network.
+
+use integration_tests::common::create_tiered_cache;
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::DocsClient;
+use rustacean_docs_mcp_server::tools::{
+    crate_docs::CrateDocsTool, item_docs::ItemDocsTool, metadata::CrateMetadataTool,
+    search::SearchTool, ToolHandler,
+};
+use rustacean_docs_mock_server::{bundled_fixtures, MockServer};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+type ServerCache = TieredCache<String, Value>;
+
+fn create_cache() -> Arc<RwLock<ServerCache>> {
+    let temp_dir =
+        std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
+    Arc::new(RwLock::new(create_tiered_cache(100, &temp_dir)))
+}
+
+/// Start a mock server on the bundled fixtures with a client pointed at it
+async fn create_mock_environment() -> (MockServer, Arc<DocsClient>, Arc<RwLock<ServerCache>>) {
+    let server = MockServer::start(bundled_fixtures())
+        .await
+        .expect("Failed to start mock server");
+    let client = Arc::new(server.client().expect("Failed to create DocsClient"));
+    (server, client, create_cache())
+}
+
+#[tokio::test]
+async fn test_search_and_metadata_from_mock_api() {
+    let (_server, client, cache) = create_mock_environment().await;
+
+    let search = SearchTool::new()
+        .execute(json!({ "query": "itoa", "limit": 5 }), &client, &cache)
+        .await
+        .expect("Search should succeed against the mock server");
+    assert_eq!(search["total"], 1);
+    assert_eq!(search["results"][0]["name"], "itoa");
+    assert_eq!(search["results"][0]["version"], "1.0.11");
+
+    let metadata = CrateMetadataTool::new()
+        .execute(json!({ "crate_name": "itoa" }), &client, &cache)
+        .await
+        .expect("Metadata should succeed against the mock server");
+    let metadata = &metadata["metadata"];
+    assert_eq!(metadata["summary"]["version"], "1.0.11");
+    assert_eq!(metadata["versions"]["count"], 2);
+    assert_eq!(metadata["dependencies"]["list"][0]["name"], "no-panic");
+}
+
+#[tokio::test]
+async fn test_crate_and_item_docs_from_mock_pages() {
+    let (server, client, cache) = create_mock_environment().await;
+
+    let docs = CrateDocsTool::new()
+        .execute(json!({ "crate_name": "itoa" }), &client, &cache)
+        .await
+        .expect("Crate docs should succeed against the mock server");
+    assert_eq!(docs["version"], "1.0.11");
+    assert_eq!(docs["categories"]["core_types"], json!(["Buffer"]));
+    assert_eq!(docs["categories"]["traits"], json!(["Integer"]));
+    assert!(docs["docs_url"].as_str().unwrap().starts_with(server.url()));
+
+    let item = ItemDocsTool::new()
+        .execute(
+            json!({
+                "crate_name": "itoa",
+                "item_path": "itoa/struct.Buffer.html",
+                "version": "1.0.11"
+            }),
+            &client,
+            &cache,
+        )
+        .await
+        .expect("Item docs should succeed against the mock server");
+    assert_eq!(item["name"], "Buffer");
+    assert_eq!(item["kind"], "Struct");
+    let methods: Vec<_> = item["associated_items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|method| method["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(methods, ["new", "format"]);
+}
+
+#[tokio::test]
+async fn test_unknown_crate_is_not_found() {
+    let (_server, client, cache) = create_mock_environment().await;
+
+    let result = CrateMetadataTool::new()
+        .execute(json!({ "crate_name": "not-a-fixture" }), &client, &cache)
+        .await;
+    assert!(result.is_err(), "Missing fixtures should surface as errors");
+}
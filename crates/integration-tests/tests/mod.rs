@@ -7,12 +7,14 @@
 //! - Cache hit/miss scenarios and TTL behavior  
 //! - MCP protocol compliance and schema validation
 //! - Full workflow testing with cache integration
+//! - Offline tool runs against the local mock server
 
 // Import all integration test modules
 mod cache_integration;
 mod docs_workflow;
 mod full_workflow;
 mod mcp_protocol;
+mod mock_server;
 mod search_flow;
 
 // Re-export commonly used test utilities
@@ -4,7 +4,7 @@
 //! through client API calls to cache integration.
 
 use chrono::Utc;
-use integration_tests::common::create_tiered_cache;
+use integration_tests::common::{create_test_client, create_tiered_cache};
 use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 use rustacean_docs_core::models::search::CrateSearchResult;
@@ -18,7 +18,7 @@ type ServerCache = TieredCache<String, Value>;
 
 /// Helper function to create test environment
 async fn create_test_environment() -> (DocsClient, Arc<RwLock<ServerCache>>) {
-    let client = DocsClient::new().expect("Failed to create DocsClient");
+    let client = create_test_client();
     let temp_dir =
         std::env::temp_dir().join(format!("rustacean_docs_test_{}", rand::random::<u64>()));
     let cache = Arc::new(RwLock::new(create_tiered_cache(100, &temp_dir)));
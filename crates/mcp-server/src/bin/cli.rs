@@ -1,7 +1,9 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use rustacean_docs_mcp_server::tools::PrewarmInput;
 use rustacean_docs_mcp_server::{Config, RustaceanDocsHandler};
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(
@@ -39,6 +41,45 @@ enum Commands {
         #[arg(help = "Tool name")]
         tool: String,
     },
+
+    #[command(about = "Export, import or prewarm the response cache")]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    #[command(about = "Write all live cache entries to a snapshot file")]
+    Export {
+        #[arg(help = "Snapshot file to write")]
+        file: PathBuf,
+    },
+
+    #[command(about = "Load cache entries from a snapshot file")]
+    Import {
+        #[arg(help = "Snapshot file to read")]
+        file: PathBuf,
+    },
+
+    #[command(about = "Fetch docs, overviews, item pages and metadata into the cache")]
+    Prewarm {
+        #[arg(help = "Crates to prewarm, as name or name@version")]
+        crates: Vec<String>,
+
+        #[arg(long, help = "Cargo.lock whose registry packages are prewarmed")]
+        lockfile: Option<PathBuf>,
+
+        #[arg(long, help = "Configured registry to fetch from")]
+        registry: Option<String>,
+
+        #[arg(long, help = "Item pages to fetch per crate [default: 20]")]
+        max_items: Option<usize>,
+
+        #[arg(long, help = "Pages fetched at once [default: 4]")]
+        concurrency: Option<usize>,
+    },
 }
 
 #[derive(Clone, Copy, clap::ValueEnum)]
@@ -75,6 +116,26 @@ async fn main() -> Result<()> {
         Commands::List => list_tools(&handler, cli.format),
         Commands::Run { tool, params } => run_tool(&handler, &tool, params, cli.format).await,
         Commands::Schema { tool } => show_schema(&handler, &tool, cli.format),
+        Commands::Cache { action } => match action {
+            CacheAction::Export { file } => export_cache(&handler, &file, cli.format).await,
+            CacheAction::Import { file } => import_cache(&handler, &file, cli.format).await,
+            CacheAction::Prewarm {
+                crates,
+                lockfile,
+                registry,
+                max_items,
+                concurrency,
+            } => {
+                let input = PrewarmInput {
+                    crates,
+                    lockfile: lockfile.map(|path| path.display().to_string()),
+                    registry,
+                    max_items,
+                    concurrency,
+                };
+                prewarm_cache(&handler, input, cli.format).await
+            }
+        },
     }
 }
 
@@ -168,3 +229,110 @@ fn show_schema(
 
     Ok(())
 }
+
+async fn export_cache(
+    handler: &RustaceanDocsHandler,
+    file: &Path,
+    format: OutputFormat,
+) -> Result<()> {
+    let exported = handler.export_cache(file).await?;
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "exported": exported,
+            });
+            println!("{}", serde_json::to_string(&json)?);
+        }
+        OutputFormat::Pretty => {
+            println!("Exported {exported} cache entries to {}", file.display());
+        }
+        OutputFormat::Raw => {
+            println!("{exported}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_cache(
+    handler: &RustaceanDocsHandler,
+    file: &Path,
+    format: OutputFormat,
+) -> Result<()> {
+    let report = handler.import_cache(file).await?;
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "imported": report.imported,
+                "expired": report.expired,
+            });
+            println!("{}", serde_json::to_string(&json)?);
+        }
+        OutputFormat::Pretty => {
+            println!(
+                "Imported {} cache entries from {} ({} already expired)",
+                report.imported,
+                file.display(),
+                report.expired
+            );
+        }
+        OutputFormat::Raw => {
+            println!("{}", report.imported);
+        }
+    }
+
+    Ok(())
+}
+
+async fn prewarm_cache(
+    handler: &RustaceanDocsHandler,
+    input: PrewarmInput,
+    format: OutputFormat,
+) -> Result<()> {
+    let report = handler
+        .prewarm_cache(input, |done, total, warmed| {
+            // Progress goes to stderr so the report on stdout stays parseable
+            if let OutputFormat::Pretty = format {
+                let version = warmed.version.as_deref().unwrap_or("latest");
+                eprintln!(
+                    "[{done}/{total}] {} {version}: {} pages, {} failed",
+                    warmed.crate_name,
+                    warmed.pages,
+                    warmed.failures.len()
+                );
+            }
+        })
+        .await?;
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string(&report)?);
+        }
+        OutputFormat::Pretty => {
+            for warmed in &report.crates {
+                for failure in &warmed.failures {
+                    println!(
+                        "  {} {}: {}",
+                        warmed.crate_name, failure.page, failure.error
+                    );
+                }
+            }
+            println!(
+                "Prewarmed {} crates: {} pages cached, {} failed ({} ms)",
+                report.crates.len(),
+                report.pages,
+                report.failed_pages,
+                report.duration_ms
+            );
+        }
+        OutputFormat::Raw => {
+            println!("{}", report.pages);
+        }
+    }
+
+    Ok(())
+}
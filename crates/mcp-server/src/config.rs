@@ -1,6 +1,18 @@
 use anyhow::Result;
+use rustacean_docs_cache::EvictionPolicy;
+use rustacean_docs_client::{
+    fixtures::{DEFAULT_FIXTURE_DIR, FIXTURE_DIR_ENV, FIXTURE_MODE_ENV},
+    DocsSourceMode, FixtureConfig, FixtureMode, HostRateLimit, LocalDocsConfig, RateLimitConfig,
+    Registry, RegistrySet, RetryConfig, DEFAULT_REGISTRY,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -17,15 +29,249 @@ pub struct ServerSettings {
     pub description: String,
     pub bind_address: String,
     pub port: u16,
+    #[serde(default)]
+    pub transport: Transport,
+}
+
+/// Transport used by the server binary to talk to MCP clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// JSON-RPC over stdin/stdout, for clients that spawn the server as a child process
+    Stdio,
+    /// Streamable HTTP/SSE server listening on `bind_address:port`
+    #[default]
+    Http,
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transport::Stdio => write!(f, "stdio"),
+            Transport::Http => write!(f, "http"),
+        }
+    }
+}
+
+impl FromStr for Transport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "stdio" => Ok(Transport::Stdio),
+            "http" => Ok(Transport::Http),
+            other => Err(anyhow::anyhow!(
+                "Invalid transport: {} (expected 'stdio' or 'http')",
+                other
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientSettings {
     pub user_agent: String,
     pub timeout_secs: u64,
+    /// Times a failed request is retried before giving up
     pub max_retries: u32,
+    /// Delay before the first retry; later retries back off exponentially
     pub retry_delay_ms: u64,
+    /// Request rate limits by upstream host; `*` applies to unlisted hosts
+    #[serde(default = "default_rate_limits")]
+    pub rate_limits: BTreeMap<String, HostRateLimit>,
     pub base_url: String,
+    /// crates.io web API base URL
+    #[serde(default = "default_crates_io_api_url")]
+    pub crates_io_api_url: String,
+    /// crates.io sparse index base URL
+    #[serde(default = "default_crates_io_index_url")]
+    pub crates_io_index_url: String,
+    /// Allow plain HTTP upstreams, e.g. a local mock server
+    #[serde(default)]
+    pub allow_http: bool,
+    /// Whether docs and metadata come from local sources, docs.rs/crates.io, or both
+    #[serde(default)]
+    pub docs_source: DocsSourceMode,
+    /// Registry source directories (defaults to `$CARGO_HOME/registry/src`)
+    #[serde(default)]
+    pub local_registry_paths: Vec<PathBuf>,
+    /// Workspace or project directories searched for local packages
+    #[serde(default)]
+    pub local_workspace_paths: Vec<PathBuf>,
+    /// Registry used when a request does not name one (defaults to crates.io)
+    #[serde(default)]
+    pub default_registry: Option<String>,
+    /// Additional registries that requests may select by name
+    #[serde(default)]
+    pub registries: Vec<Registry>,
+    /// Whether upstream responses are recorded to or replayed from `fixture_dir`
+    #[serde(default)]
+    pub fixture_mode: FixtureMode,
+    #[serde(default = "default_fixture_dir")]
+    pub fixture_dir: PathBuf,
+}
+
+fn default_crates_io_api_url() -> String {
+    Registry::crates_io().api_url.unwrap_or_default()
+}
+
+fn default_crates_io_index_url() -> String {
+    Registry::crates_io().index_url.unwrap_or_default()
+}
+
+fn default_fixture_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_FIXTURE_DIR)
+}
+
+fn default_rate_limits() -> BTreeMap<String, HostRateLimit> {
+    RateLimitConfig::default().hosts
+}
+
+impl ClientSettings {
+    /// Search paths for the local documentation source
+    pub fn local_docs_config(&self) -> LocalDocsConfig {
+        let mut config = LocalDocsConfig::default();
+        if !self.local_registry_paths.is_empty() {
+            config.registry_paths = self.local_registry_paths.clone();
+        }
+        config.workspace_paths = self.local_workspace_paths.clone();
+        config
+    }
+
+    /// Retry behavior for outbound requests
+    pub fn retry_config(&self) -> RetryConfig {
+        RetryConfig {
+            max_attempts: self.max_retries as usize + 1,
+            base_delay: Duration::from_millis(self.retry_delay_ms),
+            ..Default::default()
+        }
+    }
+
+    /// Per-host rate limits for outbound requests
+    pub fn rate_limit_config(&self) -> RateLimitConfig {
+        RateLimitConfig {
+            hosts: self.rate_limits.clone(),
+        }
+    }
+
+    /// Recording or replay of upstream responses
+    pub fn fixture_config(&self) -> FixtureConfig {
+        FixtureConfig {
+            mode: self.fixture_mode,
+            dir: self.fixture_dir.clone(),
+        }
+    }
+
+    /// Name of the registry used when a request does not name one
+    pub fn default_registry_name(&self) -> &str {
+        self.default_registry.as_deref().unwrap_or(DEFAULT_REGISTRY)
+    }
+
+    /// crates.io, documented at `base_url`, plus the configured registries.
+    /// A configured `crates-io` entry replaces the built-in one.
+    pub fn registry_set(&self) -> RegistrySet {
+        let crates_io = Registry {
+            docs_url: self.base_url.clone(),
+            api_url: Some(self.crates_io_api_url.clone()),
+            index_url: Some(self.crates_io_index_url.clone()),
+            ..Registry::crates_io()
+        };
+        std::iter::once(crates_io)
+            .chain(self.registries.iter().cloned())
+            .collect()
+    }
+
+    /// Apply `RUSTACEAN_DOCS_REGISTRY_<NAME>_<FIELD>` variables, where field is
+    /// one of `DOCS_URL`, `API_URL`, `INDEX_URL` or `TOKEN`. Names are lowercased
+    /// with `_` read as `-`, matching Cargo's `CARGO_REGISTRIES_<NAME>_*`.
+    fn apply_registry_env(&mut self, vars: impl IntoIterator<Item = (String, String)>) {
+        const FIELDS: [&str; 4] = ["_DOCS_URL", "_API_URL", "_INDEX_URL", "_TOKEN"];
+
+        for (key, value) in vars {
+            let Some(rest) = key.strip_prefix("RUSTACEAN_DOCS_REGISTRY_") else {
+                continue;
+            };
+            let Some((name, field)) = FIELDS
+                .iter()
+                .find_map(|field| rest.strip_suffix(field).map(|name| (name, *field)))
+            else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+
+            let name = name.to_lowercase().replace('_', "-");
+            let index = match self.registries.iter().position(|r| r.name == name) {
+                Some(index) => index,
+                None => {
+                    self.registries.push(Registry::new(name, String::new()));
+                    self.registries.len() - 1
+                }
+            };
+            let registry = &mut self.registries[index];
+
+            match field {
+                "_DOCS_URL" => registry.docs_url = value,
+                "_API_URL" => registry.api_url = Some(value),
+                "_INDEX_URL" => registry.index_url = Some(value),
+                _ => registry.token = Some(value),
+            }
+        }
+    }
+
+    /// Apply [`FIXTURE_MODE_ENV`] and [`FIXTURE_DIR_ENV`] from `vars`
+    fn apply_fixture_env(
+        &mut self,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+    ) -> Result<()> {
+        for (key, value) in vars {
+            if key == FIXTURE_MODE_ENV {
+                let mode = value
+                    .to_str()
+                    .ok_or_else(|| anyhow::anyhow!("{FIXTURE_MODE_ENV} is not valid UTF-8"))?;
+                self.fixture_mode = mode.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            } else if key == FIXTURE_DIR_ENV {
+                self.fixture_dir = PathBuf::from(value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Storage used for the persistent cache layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskBackend {
+    /// Content-addressed cacache store, one file per value
+    #[default]
+    Cacache,
+    /// A single SQLite file
+    Sqlite,
+}
+
+impl fmt::Display for DiskBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiskBackend::Cacache => write!(f, "cacache"),
+            DiskBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl FromStr for DiskBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "cacache" => Ok(DiskBackend::Cacache),
+            "sqlite" => Ok(DiskBackend::Sqlite),
+            other => Err(anyhow::anyhow!(
+                "Invalid cache backend: {} (expected 'cacache' or 'sqlite')",
+                other
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +281,27 @@ pub struct CacheSettings {
     pub disk_enabled: bool,
     pub disk_max_size_mb: u64,
     pub disk_ttl_secs: u64,
+    /// Which disk entries are evicted first once `disk_max_size_mb` is exceeded
+    #[serde(default)]
+    pub disk_eviction_policy: EvictionPolicy,
+    /// Seconds between disk cache garbage collection passes (0 disables them)
+    #[serde(default = "default_disk_gc_interval_secs")]
+    pub disk_gc_interval_secs: u64,
+    /// Storage behind the persistent cache layer
+    #[serde(default)]
+    pub disk_backend: DiskBackend,
+    /// Seconds upstream responses are kept for conditional revalidation
+    /// (0 disables revalidation)
+    #[serde(default = "default_response_ttl_secs")]
+    pub response_ttl_secs: u64,
+}
+
+fn default_disk_gc_interval_secs() -> u64 {
+    CacheSettings::default().disk_gc_interval_secs
+}
+
+fn default_response_ttl_secs() -> u64 {
+    CacheSettings::default().response_ttl_secs
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +318,7 @@ impl Default for ServerSettings {
             description: "MCP server for Rust documentation access".to_string(),
             bind_address: "127.0.0.1".to_string(),
             port: 8080,
+            transport: Transport::default(),
         }
     }
 }
@@ -62,7 +330,18 @@ impl Default for ClientSettings {
             timeout_secs: 30,
             max_retries: 3,
             retry_delay_ms: 1000,
+            rate_limits: default_rate_limits(),
             base_url: "https://docs.rs".to_string(),
+            crates_io_api_url: default_crates_io_api_url(),
+            crates_io_index_url: default_crates_io_index_url(),
+            allow_http: false,
+            docs_source: DocsSourceMode::default(),
+            local_registry_paths: Vec::new(),
+            local_workspace_paths: Vec::new(),
+            default_registry: None,
+            registries: Vec::new(),
+            fixture_mode: FixtureMode::default(),
+            fixture_dir: default_fixture_dir(),
         }
     }
 }
@@ -75,6 +354,10 @@ impl Default for CacheSettings {
             disk_enabled: true,
             disk_max_size_mb: 500,
             disk_ttl_secs: 86400, // 24 hours
+            disk_eviction_policy: EvictionPolicy::Lru,
+            disk_gc_interval_secs: 3600, // 1 hour
+            disk_backend: DiskBackend::Cacache,
+            response_ttl_secs: 604800, // 7 days
         }
     }
 }
@@ -112,6 +395,9 @@ impl Config {
         if let Ok(port) = env::var("RUSTACEAN_DOCS_PORT") {
             self.server.port = port.parse()?;
         }
+        if let Ok(transport) = env::var("RUSTACEAN_DOCS_TRANSPORT") {
+            self.server.transport = transport.parse()?;
+        }
 
         // Client settings
         if let Ok(user_agent) = env::var("RUSTACEAN_DOCS_USER_AGENT") {
@@ -120,9 +406,44 @@ impl Config {
         if let Ok(timeout) = env::var("RUSTACEAN_DOCS_CLIENT_TIMEOUT") {
             self.client.timeout_secs = timeout.parse()?;
         }
+        if let Ok(max_retries) = env::var("RUSTACEAN_DOCS_MAX_RETRIES") {
+            self.client.max_retries = max_retries.parse()?;
+        }
+        if let Ok(delay) = env::var("RUSTACEAN_DOCS_RETRY_DELAY_MS") {
+            self.client.retry_delay_ms = delay.parse()?;
+        }
+        if let Ok(rate_limits) = env::var("RUSTACEAN_DOCS_RATE_LIMITS") {
+            self.client.rate_limits =
+                RateLimitConfig::parse_list(&rate_limits).map_err(|e| anyhow::anyhow!(e))?;
+        }
         if let Ok(base_url) = env::var("RUSTACEAN_DOCS_BASE_URL") {
             self.client.base_url = base_url;
         }
+        if let Ok(api_url) = env::var("RUSTACEAN_DOCS_CRATES_IO_API_URL") {
+            self.client.crates_io_api_url = api_url;
+        }
+        if let Ok(index_url) = env::var("RUSTACEAN_DOCS_CRATES_IO_INDEX_URL") {
+            self.client.crates_io_index_url = index_url;
+        }
+        if let Ok(allow_http) = env::var("RUSTACEAN_DOCS_ALLOW_HTTP") {
+            self.client.allow_http = allow_http.parse()?;
+        }
+        if let Ok(docs_source) = env::var("RUSTACEAN_DOCS_SOURCE") {
+            self.client.docs_source = docs_source
+                .parse()
+                .map_err(|e: String| anyhow::anyhow!(e))?;
+        }
+        if let Some(paths) = env::var_os("RUSTACEAN_DOCS_LOCAL_REGISTRY") {
+            self.client.local_registry_paths = env::split_paths(&paths).collect();
+        }
+        if let Some(paths) = env::var_os("RUSTACEAN_DOCS_LOCAL_WORKSPACES") {
+            self.client.local_workspace_paths = env::split_paths(&paths).collect();
+        }
+        if let Ok(registry) = env::var("RUSTACEAN_DOCS_DEFAULT_REGISTRY") {
+            self.client.default_registry = Some(registry);
+        }
+        self.client.apply_registry_env(env::vars());
+        self.client.apply_fixture_env(env::vars_os())?;
 
         // Cache settings
         if let Ok(max_entries) = env::var("RUSTACEAN_DOCS_CACHE_MAX_ENTRIES") {
@@ -134,6 +455,22 @@ impl Config {
         if let Ok(disk_enabled) = env::var("RUSTACEAN_DOCS_CACHE_DISK_ENABLED") {
             self.cache.disk_enabled = disk_enabled.parse()?;
         }
+        if let Ok(max_size) = env::var("RUSTACEAN_DOCS_CACHE_DISK_MAX_SIZE_MB") {
+            self.cache.disk_max_size_mb = max_size.parse()?;
+        }
+        if let Ok(policy) = env::var("RUSTACEAN_DOCS_CACHE_EVICTION_POLICY") {
+            self.cache.disk_eviction_policy =
+                policy.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+        }
+        if let Ok(interval) = env::var("RUSTACEAN_DOCS_CACHE_GC_INTERVAL") {
+            self.cache.disk_gc_interval_secs = interval.parse()?;
+        }
+        if let Ok(backend) = env::var("RUSTACEAN_DOCS_CACHE_BACKEND") {
+            self.cache.disk_backend = backend.parse()?;
+        }
+        if let Ok(ttl) = env::var("RUSTACEAN_DOCS_CACHE_RESPONSE_TTL") {
+            self.cache.response_ttl_secs = ttl.parse()?;
+        }
 
         // Logging settings
         if let Ok(level) = env::var("RUSTACEAN_DOCS_LOG_LEVEL") {
@@ -151,8 +488,13 @@ impl Config {
         if self.server.name.is_empty() {
             return Err(anyhow::anyhow!("Server name cannot be empty"));
         }
-        if self.server.port == 0 {
-            return Err(anyhow::anyhow!("Server port must be greater than 0"));
+        if self.server.transport == Transport::Http {
+            if self.server.bind_address.is_empty() {
+                return Err(anyhow::anyhow!("Bind address cannot be empty"));
+            }
+            if self.server.port == 0 {
+                return Err(anyhow::anyhow!("Server port must be greater than 0"));
+            }
         }
 
         // Validate client settings
@@ -162,11 +504,29 @@ impl Config {
         if self.client.timeout_secs == 0 {
             return Err(anyhow::anyhow!("Client timeout must be greater than 0"));
         }
+        let registries = self.client.registry_set();
+        for registry in registries.iter() {
+            registry.validate().map_err(|e| anyhow::anyhow!(e))?;
+        }
+        if registries
+            .get(self.client.default_registry_name())
+            .is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "Default registry '{}' is not configured",
+                self.client.default_registry_name()
+            ));
+        }
 
         // Validate cache settings
         if self.cache.memory_max_entries == 0 {
             return Err(anyhow::anyhow!("Cache max entries must be greater than 0"));
         }
+        if self.cache.disk_max_size_mb == 0 {
+            return Err(anyhow::anyhow!(
+                "Disk cache max size must be greater than 0"
+            ));
+        }
 
         // Validate logging settings
         match self.logging.level.as_str() {
@@ -177,3 +537,236 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_from_str() {
+        assert_eq!("stdio".parse::<Transport>().unwrap(), Transport::Stdio);
+        assert_eq!("HTTP".parse::<Transport>().unwrap(), Transport::Http);
+        assert_eq!(" http ".parse::<Transport>().unwrap(), Transport::Http);
+        assert!("websocket".parse::<Transport>().is_err());
+    }
+
+    #[test]
+    fn test_transport_defaults_to_http() {
+        let config = Config::default();
+        assert_eq!(config.server.transport, Transport::Http);
+        assert_eq!(config.server.transport.to_string(), "http");
+    }
+
+    #[test]
+    fn test_validate_ignores_port_for_stdio() {
+        let mut config = Config::default();
+        config.server.port = 0;
+        assert!(config.validate().is_err());
+
+        config.server.transport = Transport::Stdio;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_server_settings_deserialize_without_transport() {
+        let settings: ServerSettings = serde_json::from_value(serde_json::json!({
+            "name": "rustacean-docs-mcp",
+            "version": "0.1.0",
+            "description": "test",
+            "bind_address": "0.0.0.0",
+            "port": 8000
+        }))
+        .unwrap();
+        assert_eq!(settings.transport, Transport::Http);
+    }
+
+    #[test]
+    fn test_client_settings_local_docs() {
+        let settings: ClientSettings = serde_json::from_value(serde_json::json!({
+            "user_agent": "test",
+            "timeout_secs": 10,
+            "max_retries": 1,
+            "retry_delay_ms": 100,
+            "base_url": "https://docs.rs",
+            "docs_source": "local-first",
+            "local_workspace_paths": ["/work/project"]
+        }))
+        .unwrap();
+        assert_eq!(settings.docs_source, DocsSourceMode::LocalFirst);
+
+        let local = settings.local_docs_config();
+        assert_eq!(local.workspace_paths, vec![PathBuf::from("/work/project")]);
+        assert_eq!(
+            local.registry_paths,
+            LocalDocsConfig::default().registry_paths
+        );
+
+        assert_eq!(
+            ClientSettings::default().docs_source,
+            DocsSourceMode::RemoteOnly
+        );
+    }
+
+    #[test]
+    fn test_client_settings_retry_config() {
+        let mut settings = ClientSettings::default();
+        let retry = settings.retry_config();
+        assert_eq!(retry.max_attempts, 4);
+        assert_eq!(retry.base_delay, Duration::from_millis(1000));
+
+        settings.max_retries = 0;
+        settings.retry_delay_ms = 100;
+        let retry = settings.retry_config();
+        assert_eq!(retry.max_attempts, 1);
+        assert_eq!(retry.base_delay, Duration::from_millis(100));
+        assert_eq!(retry.max_delay, RetryConfig::default().max_delay);
+    }
+
+    #[test]
+    fn test_client_settings_rate_limits() {
+        let mut json = serde_json::json!({
+            "user_agent": "test",
+            "timeout_secs": 10,
+            "max_retries": 1,
+            "retry_delay_ms": 100,
+            "base_url": "https://docs.rs"
+        });
+        let settings: ClientSettings = serde_json::from_value(json.clone()).unwrap();
+        let limits = settings.rate_limit_config();
+        assert_eq!(
+            limits.limit_for("crates.io"),
+            Some(HostRateLimit::per_second(1.0))
+        );
+        assert_eq!(limits.limit_for("docs.rs"), None);
+
+        json["rate_limits"] = serde_json::json!({
+            "docs.rs": {"requests_per_second": 5.0, "burst": 10},
+            "*": {"requests_per_second": 20.0}
+        });
+        let settings: ClientSettings = serde_json::from_value(json).unwrap();
+        let limits = settings.rate_limit_config();
+        assert_eq!(
+            limits.limit_for("crates.io"),
+            Some(HostRateLimit::per_second(20.0))
+        );
+        assert_eq!(
+            limits.limit_for("docs.rs"),
+            Some(HostRateLimit::per_second(5.0).with_burst(10))
+        );
+
+        // RUSTACEAN_DOCS_RATE_LIMITS lists the same limits inline
+        assert_eq!(
+            RateLimitConfig::parse_list("docs.rs=5/10, *=20").unwrap(),
+            settings.rate_limits
+        );
+        assert!(RateLimitConfig::parse_list("docs.rs").is_err());
+    }
+
+    #[test]
+    fn test_client_settings_fixture_mode() {
+        let mut settings = ClientSettings::default();
+        assert_eq!(
+            settings.fixture_config(),
+            FixtureConfig {
+                mode: FixtureMode::Off,
+                dir: PathBuf::from("fixtures"),
+            }
+        );
+
+        settings
+            .apply_fixture_env([
+                (FIXTURE_MODE_ENV.into(), "replay".into()),
+                (FIXTURE_DIR_ENV.into(), "/tmp/fixtures".into()),
+                ("RUSTACEAN_DOCS_SOURCE".into(), "local-first".into()),
+            ])
+            .unwrap();
+        assert_eq!(
+            settings.fixture_config(),
+            FixtureConfig::replay("/tmp/fixtures")
+        );
+
+        assert!(settings
+            .apply_fixture_env([(FIXTURE_MODE_ENV.into(), "rewind".into())])
+            .is_err());
+    }
+
+    #[test]
+    fn test_registry_settings_from_env() {
+        let mut config = Config::default();
+        config.client.apply_registry_env([
+            (
+                "RUSTACEAN_DOCS_REGISTRY_MY_CORP_DOCS_URL".to_string(),
+                "https://docs.corp.example".to_string(),
+            ),
+            (
+                "RUSTACEAN_DOCS_REGISTRY_MY_CORP_INDEX_URL".to_string(),
+                "sparse+https://index.corp.example/".to_string(),
+            ),
+            (
+                "RUSTACEAN_DOCS_REGISTRY_MY_CORP_TOKEN".to_string(),
+                "secret".to_string(),
+            ),
+            (
+                "RUSTACEAN_DOCS_LOCAL_REGISTRY".to_string(),
+                "/tmp".to_string(),
+            ),
+        ]);
+
+        assert_eq!(config.client.registries.len(), 1);
+        let registry = &config.client.registries[0];
+        assert_eq!(registry.name, "my-corp");
+        assert_eq!(registry.docs_url, "https://docs.corp.example");
+        assert_eq!(registry.token.as_deref(), Some("secret"));
+        assert!(registry.api_url.is_none());
+
+        config.client.default_registry = Some("my-corp".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config
+                .client
+                .registry_set()
+                .get("crates-io")
+                .unwrap()
+                .docs_url,
+            "https://docs.rs"
+        );
+
+        config.client.default_registry = Some("missing".to_string());
+        assert!(config.validate().is_err());
+
+        config.client.default_registry = None;
+        config.client.registries[0].docs_url = String::new();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_cache_settings_deserialize_without_eviction_settings() {
+        let settings: CacheSettings = serde_json::from_value(serde_json::json!({
+            "memory_max_entries": 100,
+            "memory_ttl_secs": 60,
+            "disk_enabled": true,
+            "disk_max_size_mb": 10,
+            "disk_ttl_secs": 600
+        }))
+        .unwrap();
+        assert_eq!(settings.disk_eviction_policy, EvictionPolicy::Lru);
+        assert_eq!(settings.disk_gc_interval_secs, 3600);
+        assert_eq!(settings.disk_backend, DiskBackend::Cacache);
+        assert_eq!(settings.response_ttl_secs, 604800);
+
+        let settings: CacheSettings = serde_json::from_value(serde_json::json!({
+            "memory_max_entries": 100,
+            "memory_ttl_secs": 60,
+            "disk_enabled": true,
+            "disk_max_size_mb": 10,
+            "disk_ttl_secs": 600,
+            "disk_eviction_policy": "lfu",
+            "disk_gc_interval_secs": 0,
+            "disk_backend": "sqlite"
+        }))
+        .unwrap();
+        assert_eq!(settings.disk_eviction_policy, EvictionPolicy::Lfu);
+        assert_eq!(settings.disk_gc_interval_secs, 0);
+        assert_eq!(settings.disk_backend, DiskBackend::Sqlite);
+    }
+}
@@ -3,7 +3,7 @@ pub mod mcp_handler;
 pub mod server;
 pub mod tools;
 
-pub use config::Config;
+pub use config::{Config, Transport};
 pub use mcp_handler::{RustaceanDocsHandler, ToolInfo};
 pub use server::{McpServer, ServerConfig};
 pub use tools::ToolHandler;
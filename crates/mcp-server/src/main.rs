@@ -1,25 +1,64 @@
 use anyhow::Result;
+use clap::Parser;
 
 use rust_mcp_sdk::{
-    mcp_server::{hyper_server, HyperServerOptions},
+    mcp_server::{hyper_server, server_runtime, HyperServerOptions},
     schema::{
         Implementation, InitializeResult, ServerCapabilities, ServerCapabilitiesTools,
         LATEST_PROTOCOL_VERSION,
     },
+    McpServer, StdioTransport, TransportOptions,
 };
 
-use rustacean_docs_mcp_server::{Config, RustaceanDocsHandler};
+use rustacean_docs_mcp_server::{Config, RustaceanDocsHandler, Transport};
 use std::time::Duration;
+use tracing::info;
+
+#[derive(Parser)]
+#[command(
+    name = "rustacean-docs-server",
+    about = "MCP server for accessing Rust documentation",
+    version
+)]
+struct Args {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "Transport used to talk to MCP clients [env: RUSTACEAN_DOCS_TRANSPORT]"
+    )]
+    transport: Option<Transport>,
+
+    #[arg(long, help = "HTTP bind address [env: RUSTACEAN_DOCS_BIND_ADDRESS]")]
+    host: Option<String>,
+
+    #[arg(short, long, help = "HTTP port [env: RUSTACEAN_DOCS_PORT]")]
+    port: Option<u16>,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
+    let args = Args::parse();
+
+    // Initialize tracing. Logs always go to stderr so that stdout stays
+    // reserved for JSON-RPC messages when running over stdio.
     tracing_subscriber::fmt()
         .with_env_filter("rustacean_docs=debug,info")
+        .with_writer(std::io::stderr)
         .init();
 
-    // Load server configuration
-    let config = Config::load()?;
+    // Load server configuration, letting command line flags take precedence
+    let mut config = Config::load()?;
+    if let Some(transport) = args.transport {
+        config.server.transport = transport;
+    }
+    if let Some(host) = args.host {
+        config.server.bind_address = host;
+    }
+    if let Some(port) = args.port {
+        config.server.port = port;
+    }
+    config.validate()?;
 
     // Create server details for MCP initialization
     let server_details = InitializeResult {
@@ -36,30 +75,46 @@ async fn main() -> Result<()> {
         protocol_version: LATEST_PROTOCOL_VERSION.into(),
     };
 
-    // // Create stdio transport
-    // let transport = rust_mcp_sdk::StdioTransport::new(Default::default())
-    //     .map_err(|e| anyhow::anyhow!("Failed to create transport: {}", e))?;
+    let transport = config.server.transport;
+    let host = config.server.bind_address.clone();
+    let port = config.server.port;
 
     // Create our handler
     let handler = RustaceanDocsHandler::new(config).await?;
 
-    // // Create and start the MCP server
-    // let server = server_runtime::create_server(server_details, transport, handler);
-    let server = hyper_server::create_server(
-        server_details,
-        handler,
-        HyperServerOptions {
-            host: "0.0.0.0".to_string(),
-            port: 8000,
-            ping_interval: Duration::from_secs(5),
-            ..Default::default()
-        },
-    );
-    // // eprintln!("Starting Rustacean Docs MCP Server...");
-    server
-        .start()
-        .await
-        .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+    match transport {
+        Transport::Stdio => {
+            let transport = StdioTransport::new(TransportOptions::default())
+                .map_err(|e| anyhow::anyhow!("Failed to create transport: {}", e))?;
+
+            info!("Starting Rustacean Docs MCP Server over stdio");
+            let server = server_runtime::create_server(server_details, transport, handler);
+            server
+                .start()
+                .await
+                .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+        }
+        Transport::Http => {
+            info!(
+                "Starting Rustacean Docs MCP Server on http://{}:{}",
+                host, port
+            );
+            let server = hyper_server::create_server(
+                server_details,
+                handler,
+                HyperServerOptions {
+                    host,
+                    port,
+                    ping_interval: Duration::from_secs(5),
+                    ..Default::default()
+                },
+            );
+            server
+                .start()
+                .await
+                .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+        }
+    }
 
     Ok(())
 }
@@ -9,17 +9,21 @@ use rust_mcp_sdk::{
     McpServer,
 };
 use serde_json::Value;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info};
 
-use rustacean_docs_cache::{Cache, DiskCache, MemoryCache, TieredCache, WriteStrategy};
+use rustacean_docs_cache::{ImportReport, Snapshot, TieredCache};
 use rustacean_docs_client::DocsClient;
 
 use crate::config::Config;
 use crate::tools::{
-    CacheInfoTool, CacheStatsTool, ClearCacheTool, CrateDocsTool, CrateMetadataTool,
-    CrateOverviewTool, ItemDocsTool, RecentReleasesTool, SearchTool, ToolHandler,
+    cache_backend::build_server_cache, AnalyzeProjectTool, CacheInfoTool, CacheStatsTool,
+    ClearCacheTool, ClientFactory, CrateDocsTool, CrateMetadataTool, CrateOverviewTool,
+    DiffCrateVersionsTool, FindBySignatureTool, ItemDocsTool, PrewarmCacheTool, PrewarmInput,
+    PrewarmReport, PrewarmedCrate, RecentReleasesTool, SearchItemsTool, SearchTool, ToolHandler,
 };
 
 type ServerCache = TieredCache<String, Value>;
@@ -34,13 +38,23 @@ pub struct RustaceanDocsHandler {
     cache: Arc<RwLock<ServerCache>>,
     #[allow(dead_code)]
     config: Config,
+    /// Background disk cache garbage collection, stopped with the handler
+    gc_tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for RustaceanDocsHandler {
+    fn drop(&mut self) {
+        for gc_task in &self.gc_tasks {
+            gc_task.abort();
+        }
+    }
 }
 
 impl RustaceanDocsHandler {
     pub async fn new(config: Config) -> Result<Self> {
         config.validate()?;
 
-        let client = Arc::new(DocsClient::new()?);
+        let client = ClientFactory::from_settings(&config.client)?;
 
         // Create cache directory if it doesn't exist
         let cache_dir = dirs::cache_dir()
@@ -50,65 +64,12 @@ impl RustaceanDocsHandler {
         if !cache_dir.exists() {
             tokio::fs::create_dir_all(&cache_dir).await?;
         }
+        let (client, response_gc_task) =
+            ClientFactory::with_response_store(client, &config.cache, &cache_dir);
+        let client = Arc::new(client);
 
-        let _memory_ttl = std::time::Duration::from_secs(config.cache.memory_ttl_secs);
-        let _disk_ttl = std::time::Duration::from_secs(config.cache.disk_ttl_secs);
-        let _disk_max_size = config.cache.disk_max_size_mb * 1024 * 1024; // Convert MB to bytes
-
-        // Create individual cache layers
-        let memory_cache = MemoryCache::new(config.cache.memory_max_entries);
-        let disk_cache = DiskCache::new(&cache_dir);
-
-        // Wrap them to match the expected error type
-        struct MemoryCacheWrapper(MemoryCache<String, Value>);
-
-        #[async_trait::async_trait]
-        impl Cache for MemoryCacheWrapper {
-            type Key = String;
-            type Value = Value;
-            type Error = anyhow::Error;
-
-            async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
-                self.0
-                    .get(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
-                self.0
-                    .insert(key, value)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
-                self.0
-                    .remove(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn clear(&self) -> Result<(), Self::Error> {
-                self.0
-                    .clear()
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            fn stats(&self) -> rustacean_docs_cache::CacheStats {
-                self.0.stats()
-            }
-        }
-
-        // Create tiered cache with both layers
-        let cache = Arc::new(RwLock::new(TieredCache::new(
-            vec![
-                Box::new(MemoryCacheWrapper(memory_cache)),
-                Box::new(disk_cache),
-            ],
-            WriteStrategy::WriteThrough,
-        )));
+        let (cache, gc_task) = build_server_cache(&config.cache, &cache_dir)?;
+        let cache = Arc::new(RwLock::new(cache));
 
         info!(
             "Initialized Rustacean Docs MCP Handler: {} v{}",
@@ -119,6 +80,7 @@ impl RustaceanDocsHandler {
             client,
             cache,
             config,
+            gc_tasks: gc_task.into_iter().chain(response_gc_task).collect(),
         })
     }
 
@@ -152,6 +114,38 @@ impl RustaceanDocsHandler {
                     .unwrap(),
                 annotations: None,
             },
+            Tool {
+                name: "search_items".to_string(),
+                description: Some(SearchItemsTool::new().description().to_string()),
+                input_schema: serde_json::from_value(SearchItemsTool::new().parameters_schema())
+                    .unwrap(),
+                annotations: None,
+            },
+            Tool {
+                name: "find_by_signature".to_string(),
+                description: Some(FindBySignatureTool::new().description().to_string()),
+                input_schema: serde_json::from_value(
+                    FindBySignatureTool::new().parameters_schema(),
+                )
+                .unwrap(),
+                annotations: None,
+            },
+            Tool {
+                name: "analyze_project".to_string(),
+                description: Some(AnalyzeProjectTool::new().description().to_string()),
+                input_schema: serde_json::from_value(AnalyzeProjectTool::new().parameters_schema())
+                    .unwrap(),
+                annotations: None,
+            },
+            Tool {
+                name: "diff_crate_versions".to_string(),
+                description: Some(DiffCrateVersionsTool::new().description().to_string()),
+                input_schema: serde_json::from_value(
+                    DiffCrateVersionsTool::new().parameters_schema(),
+                )
+                .unwrap(),
+                annotations: None,
+            },
             Tool {
                 name: "get_crate_metadata".to_string(),
                 description: Some(CrateMetadataTool::new().description().to_string()),
@@ -187,6 +181,13 @@ impl RustaceanDocsHandler {
                     .unwrap(),
                 annotations: None,
             },
+            Tool {
+                name: "prewarm_cache".to_string(),
+                description: Some(PrewarmCacheTool::new().description().to_string()),
+                input_schema: serde_json::from_value(PrewarmCacheTool::new().parameters_schema())
+                    .unwrap(),
+                annotations: None,
+            },
         ]
     }
 
@@ -194,6 +195,44 @@ impl RustaceanDocsHandler {
         self.execute_tool(tool_name, params).await
     }
 
+    /// Write every live cache entry, with its TTL and tags, to a snapshot file
+    pub async fn export_cache(&self, path: &Path) -> Result<usize> {
+        let snapshot = self.cache.read().await.export_snapshot().await?;
+        snapshot.write_to(path).await?;
+        info!(
+            "Exported {} cache entries to {}",
+            snapshot.entries.len(),
+            path.display()
+        );
+        Ok(snapshot.entries.len())
+    }
+
+    /// Load the entries of a snapshot file written by [`Self::export_cache`]
+    pub async fn import_cache(&self, path: &Path) -> Result<ImportReport> {
+        let snapshot = Snapshot::read_from(path).await?;
+        let report = self.cache.read().await.import_snapshot(snapshot).await?;
+        info!(
+            "Imported {} cache entries from {} ({} expired)",
+            report.imported,
+            path.display(),
+            report.expired
+        );
+        Ok(report)
+    }
+
+    /// Fetch the pages of the crates in `input` into the cache, calling
+    /// `progress` with the crates done, the crates in total and the outcome
+    /// of the crate that just finished
+    pub async fn prewarm_cache(
+        &self,
+        input: PrewarmInput,
+        progress: impl FnMut(usize, usize, &PrewarmedCrate),
+    ) -> Result<PrewarmReport> {
+        PrewarmCacheTool::new()
+            .prewarm(input, &self.client, &self.cache, progress)
+            .await
+    }
+
     pub fn get_available_tools(&self) -> Vec<ToolInfo> {
         vec![
             ToolInfo {
@@ -212,6 +251,22 @@ impl RustaceanDocsHandler {
                 name: "get_item_docs".to_string(),
                 description: ItemDocsTool::new().description().to_string(),
             },
+            ToolInfo {
+                name: "search_items".to_string(),
+                description: SearchItemsTool::new().description().to_string(),
+            },
+            ToolInfo {
+                name: "find_by_signature".to_string(),
+                description: FindBySignatureTool::new().description().to_string(),
+            },
+            ToolInfo {
+                name: "analyze_project".to_string(),
+                description: AnalyzeProjectTool::new().description().to_string(),
+            },
+            ToolInfo {
+                name: "diff_crate_versions".to_string(),
+                description: DiffCrateVersionsTool::new().description().to_string(),
+            },
             ToolInfo {
                 name: "get_crate_metadata".to_string(),
                 description: CrateMetadataTool::new().description().to_string(),
@@ -232,6 +287,10 @@ impl RustaceanDocsHandler {
                 name: "cache_info".to_string(),
                 description: CacheInfoTool::new().description().to_string(),
             },
+            ToolInfo {
+                name: "prewarm_cache".to_string(),
+                description: PrewarmCacheTool::new().description().to_string(),
+            },
         ]
     }
 
@@ -241,11 +300,16 @@ impl RustaceanDocsHandler {
             "get_crate_docs" => CrateDocsTool::new().parameters_schema(),
             "get_crate_overview" => CrateOverviewTool::new().parameters_schema(),
             "get_item_docs" => ItemDocsTool::new().parameters_schema(),
+            "search_items" => SearchItemsTool::new().parameters_schema(),
+            "find_by_signature" => FindBySignatureTool::new().parameters_schema(),
+            "analyze_project" => AnalyzeProjectTool::new().parameters_schema(),
+            "diff_crate_versions" => DiffCrateVersionsTool::new().parameters_schema(),
             "get_crate_metadata" => CrateMetadataTool::new().parameters_schema(),
             "list_recent_releases" => RecentReleasesTool::new().parameters_schema(),
             "get_cache_stats" => CacheStatsTool::new().parameters_schema(),
             "clear_cache" => ClearCacheTool::new().parameters_schema(),
             "cache_info" => CacheInfoTool::new().parameters_schema(),
+            "prewarm_cache" => PrewarmCacheTool::new().parameters_schema(),
             _ => return Err(anyhow::anyhow!("Unknown tool: {}", tool_name)),
         };
         Ok(schema)
@@ -273,6 +337,26 @@ impl RustaceanDocsHandler {
                     .execute(params, &self.client, &self.cache)
                     .await
             }
+            "search_items" => {
+                SearchItemsTool::new()
+                    .execute(params, &self.client, &self.cache)
+                    .await
+            }
+            "find_by_signature" => {
+                FindBySignatureTool::new()
+                    .execute(params, &self.client, &self.cache)
+                    .await
+            }
+            "analyze_project" => {
+                AnalyzeProjectTool::new()
+                    .execute(params, &self.client, &self.cache)
+                    .await
+            }
+            "diff_crate_versions" => {
+                DiffCrateVersionsTool::new()
+                    .execute(params, &self.client, &self.cache)
+                    .await
+            }
             "get_crate_metadata" => {
                 CrateMetadataTool::new()
                     .execute(params, &self.client, &self.cache)
@@ -298,6 +382,11 @@ impl RustaceanDocsHandler {
                     .execute(params, &self.client, &self.cache)
                     .await
             }
+            "prewarm_cache" => {
+                PrewarmCacheTool::new()
+                    .execute(params, &self.client, &self.cache)
+                    .await
+            }
             _ => {
                 return Err(anyhow::anyhow!("Unknown tool: {}", tool_name));
             }
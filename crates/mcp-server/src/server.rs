@@ -1,17 +1,16 @@
 use anyhow::Result;
-use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{debug, info};
 
-use rustacean_docs_cache::{Cache, DiskCache, MemoryCache, TieredCache, WriteStrategy};
+use rustacean_docs_cache::{Cache, TieredCache};
 use rustacean_docs_client::DocsClient;
 
 use crate::config::Config;
-use crate::tools::ToolHandler;
+use crate::tools::{cache_backend::build_server_cache, ClientFactory, ToolHandler};
 
 // Type alias for our specific cache implementation
 type ServerCache = TieredCache<String, Value>;
@@ -21,6 +20,16 @@ pub struct McpServer {
     client: Arc<DocsClient>,
     cache: Arc<RwLock<ServerCache>>,
     config: Config,
+    /// Background disk cache garbage collection, stopped with the server
+    gc_tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for McpServer {
+    fn drop(&mut self) {
+        for gc_task in &self.gc_tasks {
+            gc_task.abort();
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,7 +57,7 @@ impl McpServer {
     pub async fn new(config: Config) -> Result<Self> {
         config.validate()?;
 
-        let client = Arc::new(DocsClient::new()?);
+        let client = ClientFactory::from_settings(&config.client)?;
 
         // Create cache directory if it doesn't exist
         let cache_dir = dirs::cache_dir()
@@ -58,71 +67,19 @@ impl McpServer {
         if !cache_dir.exists() {
             tokio::fs::create_dir_all(&cache_dir).await?;
         }
+        let (client, response_gc_task) =
+            ClientFactory::with_response_store(client, &config.cache, &cache_dir);
+        let client = Arc::new(client);
 
-        let _memory_ttl = Duration::from_secs(config.cache.memory_ttl_secs);
-        let _disk_ttl = Duration::from_secs(config.cache.disk_ttl_secs);
-        let _disk_max_size = config.cache.disk_max_size_mb * 1024 * 1024; // Convert MB to bytes
-
-        // Create individual cache layers
-        let memory_cache = MemoryCache::new(config.cache.memory_max_entries);
-        let disk_cache = DiskCache::new(&cache_dir);
-
-        // Wrap memory cache to match error type
-        struct MemoryCacheWrapper(MemoryCache<String, Value>);
-
-        #[async_trait]
-        impl Cache for MemoryCacheWrapper {
-            type Key = String;
-            type Value = Value;
-            type Error = anyhow::Error;
-
-            async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
-                self.0
-                    .get(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
-                self.0
-                    .insert(key, value)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
-                self.0
-                    .remove(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn clear(&self) -> Result<(), Self::Error> {
-                self.0
-                    .clear()
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            fn stats(&self) -> rustacean_docs_cache::CacheStats {
-                self.0.stats()
-            }
-        }
-
-        // Create tiered cache
-        let cache = Arc::new(RwLock::new(TieredCache::new(
-            vec![
-                Box::new(MemoryCacheWrapper(memory_cache)),
-                Box::new(disk_cache),
-            ],
-            WriteStrategy::WriteThrough,
-        )));
+        let (cache, gc_task) = build_server_cache(&config.cache, &cache_dir)?;
+        let cache = Arc::new(RwLock::new(cache));
 
         let mut server = Self {
             tools: HashMap::new(),
             client,
             cache,
             config,
+            gc_tasks: gc_task.into_iter().chain(response_gc_task).collect(),
         };
 
         server.register_default_tools()?;
@@ -170,6 +127,30 @@ impl McpServer {
         // Register the item docs tool
         self.register_tool("get_item_docs", Box::new(crate::tools::ItemDocsTool::new()))?;
 
+        // Register the item search tool
+        self.register_tool(
+            "search_items",
+            Box::new(crate::tools::SearchItemsTool::new()),
+        )?;
+
+        // Register the signature search tool
+        self.register_tool(
+            "find_by_signature",
+            Box::new(crate::tools::FindBySignatureTool::new()),
+        )?;
+
+        // Register the project analysis tool
+        self.register_tool(
+            "analyze_project",
+            Box::new(crate::tools::AnalyzeProjectTool::new()),
+        )?;
+
+        // Register the version diff tool
+        self.register_tool(
+            "diff_crate_versions",
+            Box::new(crate::tools::DiffCrateVersionsTool::new()),
+        )?;
+
         // Register the metadata tool
         self.register_tool(
             "get_crate_metadata",
@@ -189,6 +170,10 @@ impl McpServer {
         )?;
         self.register_tool("clear_cache", Box::new(crate::tools::ClearCacheTool::new()))?;
         self.register_tool("cache_info", Box::new(crate::tools::CacheInfoTool::new()))?;
+        self.register_tool(
+            "prewarm_cache",
+            Box::new(crate::tools::PrewarmCacheTool::new()),
+        )?;
 
         info!("Registered {} tools", self.tools.len());
         Ok(())
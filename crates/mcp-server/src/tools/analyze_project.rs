@@ -0,0 +1,269 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::{DocsClient, ProjectService};
+use rustacean_docs_core::{Error, ErrorBuilder};
+
+use crate::tools::{
+    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolHandler, ToolInput,
+};
+
+// Type alias for our specific cache implementation
+type ServerCache = TieredCache<String, Value>;
+
+/// Input parameters for the analyze_project tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzeProjectToolInput {
+    /// Path to the project's `Cargo.toml` or `Cargo.lock`
+    pub path: String,
+    /// Configured registry to resolve unlocked dependencies against
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Pin the resolved versions for later documentation requests (default: true)
+    #[serde(default)]
+    pub pin: Option<bool>,
+}
+
+impl ToolInput for AnalyzeProjectToolInput {
+    fn validate(&self) -> Result<(), Error> {
+        if self.path.trim().is_empty() {
+            return Err(
+                ErrorBuilder::protocol().invalid_input("analyze_project", "path cannot be empty")
+            );
+        }
+        ParameterValidator::validate_registry(&self.registry, "analyze_project")?;
+        Ok(())
+    }
+
+    fn cache_key(&self, tool_name: &str) -> String {
+        format!("{}:{}", tool_name, self.path)
+    }
+}
+
+impl AnalyzeProjectToolInput {
+    /// Whether the resolved versions should be pinned
+    pub fn pin(&self) -> bool {
+        self.pin.unwrap_or(true)
+    }
+}
+
+/// Tool that resolves the dependency versions of a local Cargo project and
+/// pins them for the rest of the session
+pub struct AnalyzeProjectTool;
+
+impl AnalyzeProjectTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AnalyzeProjectTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for AnalyzeProjectTool {
+    async fn execute(
+        &self,
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Value> {
+        debug!("Executing analyze_project tool with params: {}", params);
+
+        let input: AnalyzeProjectToolInput =
+            serde_json::from_value(params.clone()).map_err(|e| {
+                anyhow::anyhow!(
+                    "{}: {}",
+                    ErrorHandler::parameter_parsing_context("analyze_project"),
+                    e
+                )
+            })?;
+
+        // The files on disk change as the project evolves, so the analysis
+        // is never cached; the registry lookups behind it are
+        CacheStrategy::execute_with_cache(
+            "analyze_project",
+            params,
+            input,
+            CacheConfig::disabled(),
+            client,
+            cache,
+            |input, client| async move {
+                let service = ProjectService::new(client.for_registry(input.registry.as_deref())?);
+                let analysis = service.analyze(Path::new(&input.path)).await?;
+
+                if input.pin() {
+                    client.version_pins().pin_project(&analysis);
+                }
+
+                debug!(
+                    path = %input.path,
+                    dependencies = analysis.dependencies.len(),
+                    unresolved = analysis.unresolved.len(),
+                    versions = analysis.versions.len(),
+                    pinned = input.pin(),
+                    "Project analyzed"
+                );
+
+                let mut response = serde_json::to_value(&analysis)?;
+                response["pinned"] = json!(input.pin());
+                Ok(response)
+            },
+        )
+        .await
+    }
+
+    fn description(&self) -> &str {
+        "Analyze a local Cargo project from its Cargo.toml or Cargo.lock. Reports the exact version of every direct dependency (from the lockfile, or the highest published version matching the manifest requirement), whether newer versions exist, and the versions of all locked crates. By default the versions are pinned, so later get_crate_docs and get_item_docs calls without a version return the documentation of the versions the project actually uses."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the project's Cargo.toml or Cargo.lock",
+                    "examples": ["/home/me/project/Cargo.toml", "./Cargo.lock"]
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to resolve unlocked dependencies against instead of the default"
+                },
+                "pin": {
+                    "type": "boolean",
+                    "description": "Use the resolved versions for later documentation requests that omit a version",
+                    "default": true
+                }
+            },
+            "required": ["path"],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::crate_docs::CrateDocsTool;
+    use crate::tools::test_support::create_test_cache;
+    use rustacean_docs_cache::Cache;
+    use rustacean_docs_client::Registry;
+
+    #[test]
+    fn test_analyze_project_input_validation() {
+        let input = AnalyzeProjectToolInput {
+            path: "Cargo.lock".to_string(),
+            registry: None,
+            pin: None,
+        };
+        assert!(input.validate().is_ok());
+        assert!(input.pin());
+
+        let empty = AnalyzeProjectToolInput {
+            path: " ".to_string(),
+            ..input.clone()
+        };
+        assert!(empty.validate().is_err());
+
+        let bad_registry = AnalyzeProjectToolInput {
+            registry: Some("not a registry".to_string()),
+            ..input
+        };
+        assert!(bad_registry.validate().is_err());
+    }
+
+    #[test]
+    fn test_parameters_schema() {
+        let schema = AnalyzeProjectTool::new().parameters_schema();
+        assert_eq!(schema["required"], json!(["path"]));
+        assert_eq!(schema["properties"]["pin"]["default"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_lockfile_pins_crate_docs() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_dir = temp.path().to_path_buf();
+        let lockfile = project_dir.join("Cargo.lock");
+        std::fs::write(
+            &lockfile,
+            r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["demo"]
+
+[[package]]
+name = "demo"
+version = "0.9.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        // Metadata lookups fail fast; the lockfile alone decides the versions
+        let registry =
+            Registry::new("offline", "https://127.0.0.1:9").with_api_url("https://127.0.0.1:9");
+        let client = Arc::new(DocsClient::new().unwrap().with_registry(registry));
+        let (_cache_dir, cache) = create_test_cache();
+
+        let result = AnalyzeProjectTool::new()
+            .execute(
+                json!({ "path": lockfile.display().to_string() }),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["dependencies"][0]["name"], "demo");
+        assert_eq!(result["dependencies"][0]["version"], "0.9.1");
+        assert_eq!(result["dependencies"][0]["source"], "lockfile");
+        assert_eq!(result["pinned"], true);
+        assert_eq!(client.version_pins().get("demo").as_deref(), Some("0.9.1"));
+
+        // Crate docs without a version now resolve to the pinned release
+        let docs = json!({ "name": "demo", "version": "0.9.1" });
+        cache
+            .read()
+            .await
+            .insert("get_crate_docs:demo:0.9.1".to_string(), docs.clone())
+            .await
+            .unwrap();
+        let result = CrateDocsTool::new()
+            .execute(json!({ "crate_name": "demo" }), &client, &cache)
+            .await
+            .unwrap();
+        assert_eq!(result, docs);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_rejects_other_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_dir = temp.path().to_path_buf();
+        let other = project_dir.join("notes.txt");
+        std::fs::write(&other, "").unwrap();
+
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+        let result = AnalyzeProjectTool::new()
+            .execute(
+                json!({ "path": other.display().to_string() }),
+                &client,
+                &cache,
+            )
+            .await;
+        assert!(result.is_err());
+        assert!(client.version_pins().is_empty());
+    }
+}
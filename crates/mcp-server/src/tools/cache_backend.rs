@@ -0,0 +1,159 @@
+//! The tiered cache behind the server: a memory layer in front of the
+//! configured disk backend
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use rustacean_docs_cache::{
+    Cache, CacheEntry, CacheStats, DiskCache, EntryFilter, MemoryCache, SqliteCache, TieredCache,
+    WriteStrategy,
+};
+
+use crate::config::{CacheSettings, DiskBackend};
+
+type ServerCache = TieredCache<String, Value>;
+
+/// Build the server cache in `cache_dir` from `settings`, returning it with
+/// the disk layer's garbage collection task when one is configured
+pub fn build_server_cache(
+    settings: &CacheSettings,
+    cache_dir: &Path,
+) -> Result<(ServerCache, Option<JoinHandle<()>>)> {
+    let memory_ttl = Duration::from_secs(settings.memory_ttl_secs);
+    let disk_ttl = Duration::from_secs(settings.disk_ttl_secs);
+    let disk_max_size = settings.disk_max_size_mb * 1024 * 1024; // Convert MB to bytes
+
+    // Create individual cache layers
+    let memory_cache = MemoryCache::new(settings.memory_max_entries).with_ttl(memory_ttl);
+    let gc_interval = (settings.disk_gc_interval_secs > 0)
+        .then(|| Duration::from_secs(settings.disk_gc_interval_secs));
+    let (disk_cache, gc_task): (Box<dyn Cache<Key = String, Value = Value, Error = _>>, _) =
+        match settings.disk_backend {
+            DiskBackend::Cacache => {
+                let disk_cache = DiskCache::new(cache_dir)
+                    .with_ttl(disk_ttl)
+                    .with_max_size(disk_max_size)
+                    .with_eviction_policy(settings.disk_eviction_policy);
+                let gc_task = gc_interval.map(|interval| disk_cache.spawn_gc(interval));
+                (Box::new(disk_cache), gc_task)
+            }
+            DiskBackend::Sqlite => {
+                let disk_cache = SqliteCache::open(cache_dir.join("cache.sqlite"))?
+                    .with_ttl(disk_ttl)
+                    .with_max_size(disk_max_size)
+                    .with_eviction_policy(settings.disk_eviction_policy);
+                let gc_task = gc_interval.map(|interval| disk_cache.spawn_gc(interval));
+                (Box::new(disk_cache), gc_task)
+            }
+        };
+
+    let cache = TieredCache::new(
+        vec![Box::new(MemoryCacheWrapper(memory_cache)), disk_cache],
+        WriteStrategy::WriteThrough,
+    );
+    Ok((cache, gc_task))
+}
+
+/// Memory layer with its errors turned into `anyhow::Error` like the disk
+/// layers'; every `Cache` method is forwarded so the layer behaves exactly
+/// like the wrapped cache
+pub struct MemoryCacheWrapper(pub MemoryCache<String, Value>);
+
+#[async_trait]
+impl Cache for MemoryCacheWrapper {
+    type Key = String;
+    type Value = Value;
+    type Error = anyhow::Error;
+
+    async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
+        self.0
+            .get(key)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+        self.0
+            .insert(key, value)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn get_with_ttl(
+        &self,
+        key: &Self::Key,
+    ) -> Result<Option<(Self::Value, Option<Duration>)>, Self::Error> {
+        self.0
+            .get_with_ttl(key)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn insert_with_ttl(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+    ) -> Result<(), Self::Error> {
+        self.0
+            .insert_with_ttl(key, value, ttl)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn insert_tagged(
+        &self,
+        key: Self::Key,
+        value: Self::Value,
+        ttl: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), Self::Error> {
+        self.0
+            .insert_tagged(key, value, ttl, tags)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn remove_matching(
+        &self,
+        filter: &EntryFilter<'_, Self::Key>,
+    ) -> Result<Vec<Self::Key>, Self::Error> {
+        self.0
+            .remove_matching(filter)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn entries(&self) -> Result<Vec<CacheEntry<Self::Key, Self::Value>>, Self::Error> {
+        self.0
+            .entries()
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
+        self.0
+            .remove(key)
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    async fn clear(&self) -> Result<(), Self::Error> {
+        self.0
+            .clear()
+            .await
+            .map_err(|_| anyhow::anyhow!("Memory cache error"))
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.0.stats()
+    }
+
+    fn layer_name(&self) -> &str {
+        self.0.layer_name()
+    }
+}
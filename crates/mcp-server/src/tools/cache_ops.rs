@@ -1,18 +1,109 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
-use rustacean_docs_cache::{Cache, CacheStats, TieredCache};
-use rustacean_docs_client::DocsClient;
+use rustacean_docs_cache::{
+    Cache, CacheScope, CacheStats, LatencyHistogram, MetricsSnapshot, OutcomeCounts, TieredCache,
+};
+use rustacean_docs_client::{DocsClient, RateLimitStats};
+use rustacean_docs_core::ErrorBuilder;
 
-use crate::tools::ToolHandler;
+use crate::tools::{ParameterValidator, ToolHandler};
 
 // Type alias for our specific cache implementation
 type ServerCache = TieredCache<String, Value>;
 
+/// Lookup counts of one layer, tool or namespace
+fn format_outcomes(outcomes: &OutcomeCounts) -> Value {
+    json!({
+        "hits": outcomes.hits,
+        "misses": outcomes.misses,
+        "stale": outcomes.stale,
+        "errors": outcomes.errors,
+        "hit_rate": format!("{:.2}%", outcomes.hit_rate() * 100.0)
+    })
+}
+
+/// Read latency summary in microseconds
+fn format_latency(histogram: &LatencyHistogram) -> Value {
+    let micros = |quantile: f64| histogram.quantile(quantile).as_micros() as u64;
+    json!({
+        "count": histogram.count(),
+        "mean_us": histogram.mean().as_micros() as u64,
+        "p50_us": micros(0.5),
+        "p95_us": micros(0.95),
+        "p99_us": micros(0.99),
+        "max_us": histogram.max().as_micros() as u64,
+        "buckets": histogram.buckets().into_iter().map(|(bound, count)| json!({
+            "le_us": bound.map(|bound| bound.as_micros() as u64),
+            "count": count
+        })).collect::<Vec<_>>()
+    })
+}
+
+/// Statistics broken down by layer, tool and key namespace
+fn format_breakdown(layer_stats: &[(String, CacheStats)], metrics: &MetricsSnapshot) -> Value {
+    let layers: Vec<Value> = metrics
+        .layers
+        .iter()
+        .zip(layer_stats)
+        .map(|(layer, (_, stats))| {
+            json!({
+                "name": layer.name,
+                "entries": stats.size,
+                "capacity": stats.capacity,
+                "bytes": stats.bytes,
+                "max_bytes": stats.max_bytes,
+                "evictions": stats.evictions,
+                "reads": format_outcomes(&layer.outcomes),
+                "read_latency": format_latency(&layer.read_latency)
+            })
+        })
+        .collect();
+    let by_name = |counts: &std::collections::BTreeMap<String, OutcomeCounts>| {
+        counts
+            .iter()
+            .map(|(name, outcomes)| (name.clone(), format_outcomes(outcomes)))
+            .collect::<serde_json::Map<_, _>>()
+    };
+
+    json!({
+        "layers": layers,
+        "read_latency": format_latency(&metrics.read_latency),
+        "tools": by_name(&metrics.tools),
+        "namespaces": by_name(&metrics.namespaces)
+    })
+}
+
+/// Queue depth and wait times of the outbound rate limiter, by host
+fn format_rate_limits(stats: &[RateLimitStats]) -> Value {
+    stats
+        .iter()
+        .map(|host| {
+            (
+                host.host.clone(),
+                json!({
+                    "limit": host.limit.map(|limit| json!({
+                        "requests_per_second": limit.requests_per_second,
+                        "burst": limit.burst
+                    })),
+                    "queue_depth": host.queue_depth,
+                    "background_waiting": host.background_waiting,
+                    "requests": host.requests,
+                    "delayed": host.delayed,
+                    "mean_wait_ms": host.mean_wait().as_millis() as u64,
+                    "max_wait_ms": host.max_wait.as_millis() as u64
+                }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
 /// Tool for retrieving comprehensive cache statistics
 pub struct CacheStatsTool;
 
@@ -30,7 +121,12 @@ impl Default for CacheStatsTool {
 
 impl CacheStatsTool {
     /// Format cache statistics into a comprehensive response
-    fn format_cache_stats(&self, stats: &CacheStats) -> Value {
+    fn format_cache_stats(
+        &self,
+        stats: &CacheStats,
+        breakdown: Value,
+        rate_limits: Value,
+    ) -> Value {
         json!({
             "summary": {
                 "total_requests": stats.hits + stats.misses,
@@ -43,10 +139,16 @@ impl CacheStatsTool {
                 "entries": stats.size,
                 "capacity": stats.capacity,
                 "utilization": format!("{:.1}%", stats.utilization()),
+                "disk_bytes": stats.bytes,
+                "disk_max_bytes": stats.max_bytes,
+                "disk_utilization": format!("{:.1}%", stats.byte_utilization()),
+                "evictions": stats.evictions,
                 "hits": stats.hits,
                 "misses": stats.misses,
                 "hit_rate": format!("{:.2}%", stats.hit_rate())
             },
+            "breakdown": breakdown,
+            "rate_limits": rate_limits,
             "analysis": {
                 "efficiency": self.get_efficiency_analysis(stats),
                 "recommendations": self.get_recommendations(stats)
@@ -100,6 +202,9 @@ impl CacheStatsTool {
 
         if utilization > 95.0 {
             recommendations.push("Cache is near capacity - consider increasing size");
+        } else if stats.byte_utilization() > 95.0 {
+            recommendations
+                .push("Disk cache is near its size limit - consider increasing disk_max_size_mb");
         } else if utilization < 20.0 && stats.size > 0 {
             recommendations.push("Cache utilization is low - consider reducing size");
         }
@@ -121,16 +226,21 @@ impl ToolHandler for CacheStatsTool {
     async fn execute(
         &self,
         _params: Value,
-        _client: &Arc<DocsClient>,
+        client: &Arc<DocsClient>,
         cache: &Arc<RwLock<ServerCache>>,
     ) -> Result<Value> {
         debug!("Retrieving cache statistics");
 
         let cache_guard = cache.read().await;
         let stats = cache_guard.stats();
+        let breakdown = format_breakdown(
+            &cache_guard.layer_stats(),
+            &cache_guard.metrics().snapshot(),
+        );
         drop(cache_guard);
 
-        let formatted_stats = self.format_cache_stats(&stats);
+        let rate_limits = format_rate_limits(&client.rate_limit_stats());
+        let formatted_stats = self.format_cache_stats(&stats, breakdown, rate_limits);
 
         info!(
             total_requests = stats.hits + stats.misses,
@@ -143,7 +253,7 @@ impl ToolHandler for CacheStatsTool {
     }
 
     fn description(&self) -> &str {
-        "Get comprehensive cache statistics including hit rates, utilization, and performance analysis"
+        "Get comprehensive cache statistics including hit rates, utilization, and performance analysis, broken down by cache layer, tool and key namespace with hit/miss/stale/error counts and read latency histograms, plus the outbound rate limiter's queue depth and wait times per host"
     }
 
     fn parameters_schema(&self) -> Value {
@@ -156,6 +266,63 @@ impl ToolHandler for CacheStatsTool {
     }
 }
 
+/// Optional scope of a `clear_cache` call; everything is cleared when empty
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClearCacheInput {
+    /// Clear entries about this crate
+    pub crate_name: Option<String>,
+    /// Narrow `crate_name` to one version, e.g. `1.38` for every `1.38.x`
+    pub version: Option<String>,
+    /// Clear entries cached by this tool
+    pub tool: Option<String>,
+    /// Clear entries holding this kind of data: docs, metadata, search or releases
+    pub kind: Option<String>,
+    /// Clear entries carrying this exact tag
+    pub tag: Option<String>,
+    /// Clear entries whose cache key matches this glob (`*` and `?`)
+    pub key_pattern: Option<String>,
+}
+
+impl ClearCacheInput {
+    /// The scope selected by the parameters; at most one may be given, with
+    /// `version` only alongside `crate_name`
+    pub fn scope(self) -> Result<CacheScope> {
+        if self.version.is_some() && self.crate_name.is_none() {
+            return Err(ErrorBuilder::protocol()
+                .invalid_input("clear_cache", "version requires crate_name")
+                .into());
+        }
+
+        let mut scopes = Vec::new();
+        if let Some(crate_name) = self.crate_name {
+            ParameterValidator::validate_crate_name(&crate_name, "clear_cache")?;
+            scopes.push(match self.version {
+                Some(version) => CacheScope::Version {
+                    crate_name,
+                    version,
+                },
+                None => CacheScope::Crate(crate_name),
+            });
+        }
+        scopes.extend(self.tool.map(CacheScope::Tool));
+        scopes.extend(self.kind.map(CacheScope::Kind));
+        scopes.extend(self.tag.map(CacheScope::Tag));
+        scopes.extend(self.key_pattern.map(CacheScope::KeyGlob));
+
+        match scopes.len() {
+            0 => Ok(CacheScope::All),
+            1 => Ok(scopes.remove(0)),
+            _ => Err(ErrorBuilder::protocol()
+                .invalid_input(
+                    "clear_cache",
+                    "specify only one of crate_name (with optional version), tool, kind, tag or key_pattern",
+                )
+                .into()),
+        }
+    }
+}
+
 /// Tool for clearing cache data
 pub struct ClearCacheTool;
 
@@ -175,40 +342,66 @@ impl Default for ClearCacheTool {
 impl ToolHandler for ClearCacheTool {
     async fn execute(
         &self,
-        _params: Value,
+        params: Value,
         _client: &Arc<DocsClient>,
         cache: &Arc<RwLock<ServerCache>>,
     ) -> Result<Value> {
-        debug!("Clearing cache");
+        let input: ClearCacheInput = if params.is_null() {
+            ClearCacheInput::default()
+        } else {
+            serde_json::from_value(params)?
+        };
+        let scope = input.scope()?;
+        debug!(scope = %scope, "Clearing cache");
 
         let cache_guard = cache.write().await;
-        let stats_before = cache_guard.stats();
-
-        cache_guard.clear().await?;
-
-        let _stats_after = cache_guard.stats();
+        let items_cleared = cache_guard.invalidate(&scope).await?;
         drop(cache_guard);
 
-        info!(
-            items_cleared = stats_before.size,
-            "Cache cleared successfully"
-        );
+        info!(items_cleared, scope = %scope, "Cache cleared successfully");
 
         Ok(json!({
             "success": true,
-            "items_cleared": stats_before.size,
-            "message": format!("Successfully cleared {} items from cache", stats_before.size)
+            "items_cleared": items_cleared,
+            "scope": scope.to_string(),
+            "message": format!("Successfully cleared {items_cleared} items ({scope}) from cache")
         }))
     }
 
     fn description(&self) -> &str {
-        "Clear all cached data from the cache"
+        "Clear cached data: everything, or only the entries for a crate, crate version, tool, data kind, tag or cache key pattern"
     }
 
     fn parameters_schema(&self) -> Value {
         json!({
             "type": "object",
-            "properties": {},
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Only clear entries about this crate"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "With crate_name, only clear entries for this version; \"1.38\" covers every 1.38.x release"
+                },
+                "tool": {
+                    "type": "string",
+                    "description": "Only clear entries cached by this tool, e.g. \"search_crate\""
+                },
+                "kind": {
+                    "type": "string",
+                    "enum": ["docs", "metadata", "search", "releases"],
+                    "description": "Only clear entries holding this kind of data"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Only clear entries carrying this exact tag, e.g. \"crate:tokio@1.38.0\""
+                },
+                "key_pattern": {
+                    "type": "string",
+                    "description": "Only clear entries whose cache key matches this glob, where * matches any characters and ? one character"
+                }
+            },
             "required": [],
             "additionalProperties": false
         })
@@ -235,13 +428,17 @@ impl ToolHandler for CacheInfoTool {
     async fn execute(
         &self,
         _params: Value,
-        _client: &Arc<DocsClient>,
+        client: &Arc<DocsClient>,
         cache: &Arc<RwLock<ServerCache>>,
     ) -> Result<Value> {
         debug!("Retrieving cache information");
 
         let cache_guard = cache.read().await;
         let stats = cache_guard.stats();
+        let breakdown = format_breakdown(
+            &cache_guard.layer_stats(),
+            &cache_guard.metrics().snapshot(),
+        );
         drop(cache_guard);
 
         Ok(json!({
@@ -249,9 +446,17 @@ impl ToolHandler for CacheInfoTool {
             "current_size": stats.size,
             "capacity": stats.capacity,
             "utilization_percent": stats.utilization(),
+            "disk_bytes": stats.bytes,
+            "disk_max_bytes": stats.max_bytes,
+            "disk_utilization_percent": stats.byte_utilization(),
+            "evictions": stats.evictions,
             "hit_rate_percent": stats.hit_rate(),
             "total_operations": stats.hits + stats.misses,
-            "status": if stats.size == 0 { "empty" } else { "active" }
+            "status": if stats.size == 0 { "empty" } else { "active" },
+            "layers": breakdown["layers"],
+            "tools": breakdown["tools"],
+            "namespaces": breakdown["namespaces"],
+            "rate_limits": format_rate_limits(&client.rate_limit_stats())
         }))
     }
 
@@ -321,6 +526,7 @@ mod tests {
             misses: 10,
             size: 50,
             capacity: 100,
+            ..Default::default()
         };
         assert_eq!(tool.assess_performance(&excellent_stats), "Excellent");
 
@@ -329,6 +535,7 @@ mod tests {
             misses: 80,
             size: 10,
             capacity: 100,
+            ..Default::default()
         };
         assert_eq!(tool.assess_performance(&poor_stats), "Poor");
     }
@@ -378,9 +585,172 @@ mod tests {
         assert_eq!(response["cache_type"], "TieredCache");
         assert!(response["current_size"].is_number());
         assert!(response["capacity"].is_number());
+        assert_eq!(response["disk_bytes"], 0);
         assert!(response["status"].is_string());
     }
 
+    #[tokio::test]
+    async fn test_cache_stats_breakdown_by_layer_and_tool() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache_dir = temp.path().to_path_buf();
+        let disk = rustacean_docs_cache::DiskCache::new(&cache_dir);
+        let cache = Arc::new(RwLock::new(ServerCache::new(
+            vec![Box::new(disk)],
+            rustacean_docs_cache::WriteStrategy::WriteThrough,
+        )));
+        {
+            let cache_guard = cache.read().await;
+            cache_guard
+                .get(&"search_crate:async".to_string())
+                .await
+                .unwrap();
+            cache_guard.metrics().record_lookup(
+                "search_crate",
+                "search_crate:async",
+                rustacean_docs_cache::CacheOutcome::Miss,
+            );
+        }
+
+        let client = Arc::new(DocsClient::new().unwrap());
+        let response = CacheStatsTool::new()
+            .execute(json!({}), &client, &cache)
+            .await
+            .unwrap();
+        let breakdown = &response["breakdown"];
+        assert_eq!(breakdown["layers"][0]["name"], "disk");
+        assert_eq!(breakdown["layers"][0]["reads"]["misses"], 1);
+        assert_eq!(breakdown["layers"][0]["read_latency"]["count"], 1);
+        assert_eq!(breakdown["tools"]["search_crate"]["misses"], 1);
+        assert_eq!(breakdown["namespaces"]["search_crate"]["hit_rate"], "0.00%");
+
+        let info = CacheInfoTool::new()
+            .execute(json!({}), &client, &cache)
+            .await
+            .unwrap();
+        assert_eq!(info["layers"], breakdown["layers"]);
+        assert_eq!(info["tools"], breakdown["tools"]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats_report_rate_limits() {
+        let config = rustacean_docs_client::ClientConfig {
+            retry: rustacean_docs_client::RetryConfig {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            rate_limits: rustacean_docs_client::RateLimitConfig::unlimited().with_host(
+                "127.0.0.1",
+                rustacean_docs_client::HostRateLimit::per_second(50.0).with_burst(2),
+            ),
+            ..Default::default()
+        };
+        let registry = rustacean_docs_client::Registry::new("offline", "https://127.0.0.1:9")
+            .with_api_url("https://127.0.0.1:9");
+        let client = Arc::new(
+            DocsClient::with_config(config)
+                .unwrap()
+                .with_registry(registry),
+        );
+        for _ in 0..3 {
+            assert!(client.get_api("/api/v1/crates", "test").await.is_err());
+        }
+
+        let response = CacheStatsTool::new()
+            .execute(json!({}), &client, &create_test_cache().await)
+            .await
+            .unwrap();
+        let host = &response["rate_limits"]["127.0.0.1"];
+        assert_eq!(host["limit"]["burst"], 2);
+        assert_eq!(host["requests"], 3);
+        assert_eq!(host["delayed"], 1);
+        assert_eq!(host["queue_depth"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_info_reports_disk_usage() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache_dir = temp.path().to_path_buf();
+        let disk = rustacean_docs_cache::DiskCache::new(&cache_dir).with_max_size(1024);
+        let cache = Arc::new(RwLock::new(ServerCache::new(
+            vec![Box::new(disk)],
+            rustacean_docs_cache::WriteStrategy::WriteThrough,
+        )));
+        cache
+            .read()
+            .await
+            .insert("key".to_string(), json!("value"))
+            .await
+            .unwrap();
+
+        let client = Arc::new(DocsClient::new().unwrap());
+        let response = CacheInfoTool::new()
+            .execute(json!({}), &client, &cache)
+            .await
+            .unwrap();
+        assert_eq!(response["disk_bytes"], 24);
+        assert_eq!(response["disk_max_bytes"], 1024);
+    }
+
+    #[test]
+    fn test_clear_cache_scope_parameters() {
+        let scope = |params: Value| serde_json::from_value::<ClearCacheInput>(params)?.scope();
+
+        assert_eq!(scope(json!({})).unwrap(), CacheScope::All);
+        assert_eq!(
+            scope(json!({"crate_name": "tokio", "version": "1.38"})).unwrap(),
+            CacheScope::Version {
+                crate_name: "tokio".to_string(),
+                version: "1.38".to_string()
+            }
+        );
+        assert_eq!(
+            scope(json!({"key_pattern": "get_crate_docs:*"})).unwrap(),
+            CacheScope::KeyGlob("get_crate_docs:*".to_string())
+        );
+        assert!(scope(json!({"version": "1.38"})).is_err());
+        assert!(scope(json!({"crate_name": "tokio", "tool": "search_crate"})).is_err());
+        assert!(scope(json!({"crate": "tokio"})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_clear_cache_tool_scoped_execution() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache_dir = temp.path().to_path_buf();
+        let disk = rustacean_docs_cache::DiskCache::new(&cache_dir);
+        let cache = Arc::new(RwLock::new(ServerCache::new(
+            vec![Box::new(disk)],
+            rustacean_docs_cache::WriteStrategy::WriteThrough,
+        )));
+        for (key, tags) in [
+            ("get_crate_docs:tokio:latest", vec!["crate:tokio"]),
+            ("get_crate_docs:serde:latest", vec!["crate:serde"]),
+        ] {
+            let tags = tags.into_iter().map(String::from).collect();
+            cache
+                .read()
+                .await
+                .insert_tagged(key.to_string(), json!("docs"), None, tags)
+                .await
+                .unwrap();
+        }
+
+        let client = Arc::new(DocsClient::new().unwrap());
+        let response = ClearCacheTool::new()
+            .execute(json!({"crate_name": "tokio"}), &client, &cache)
+            .await
+            .unwrap();
+        assert_eq!(response["items_cleared"], 1);
+        assert_eq!(response["scope"], "crate:tokio");
+
+        let remaining = cache.read().await;
+        assert!(remaining
+            .get(&"get_crate_docs:serde:latest".to_string())
+            .await
+            .unwrap()
+            .is_some());
+        drop(remaining);
+    }
+
     #[test]
     fn test_parameters_schemas() {
         let stats_tool = CacheStatsTool::new();
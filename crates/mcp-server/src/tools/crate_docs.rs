@@ -8,14 +8,15 @@ use tracing::debug;
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::{endpoints::docs_modules::service::DocsService, DocsClient};
 use rustacean_docs_core::{
-    models::docs::CrateDocsRequest,
+    constants::DEFAULT_CRATE_DOCS_TTL,
+    models::docs::{CrateDocsRequest, CrateDocsResponse},
     types::{CrateName, Version},
     Error,
 };
 
 use crate::tools::{
-    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
-    ToolInput,
+    crate_cache_tags, registry_cache_scope, CacheConfig, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolErrorContext, ToolHandler, ToolInput, VersionResolution, STALE_FIELD,
 };
 
 // Type alias for our specific cache implementation
@@ -28,21 +29,30 @@ pub struct CrateDocsToolInput {
     pub crate_name: String,
     /// Specific version to query (optional, defaults to latest)
     pub version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
 }
 
 impl ToolInput for CrateDocsToolInput {
     fn validate(&self) -> Result<(), Error> {
         ParameterValidator::validate_crate_name(&self.crate_name, "get_crate_docs")?;
         ParameterValidator::validate_version(&self.version, "get_crate_docs")?;
+        ParameterValidator::validate_registry(&self.registry, "get_crate_docs")?;
         Ok(())
     }
 
     fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
         match &self.version {
             Some(version) => format!("{}:{}:{}", tool_name, self.crate_name, version),
             None => format!("{}:{}:latest", tool_name, self.crate_name),
         }
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
 }
 
 impl CrateDocsToolInput {
@@ -69,6 +79,19 @@ impl CrateDocsTool {
     pub fn new() -> Self {
         Self
     }
+
+    /// Fetch crate docs through get_crate_docs for tools built on them, with
+    /// whether they were served from an expired cache entry so those tools
+    /// can flag their own responses as stale
+    pub(crate) async fn fetch(
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<(CrateDocsResponse, bool)> {
+        let docs = Self::new().execute(params, client, cache).await?;
+        let stale = docs.get(STALE_FIELD) == Some(&Value::Bool(true));
+        Ok((serde_json::from_value(docs)?, stale))
+    }
 }
 
 #[async_trait::async_trait]
@@ -82,13 +105,29 @@ impl ToolHandler for CrateDocsTool {
         debug!("Executing get_crate_docs tool with params: {}", params);
 
         // Parse input parameters
-        let input: CrateDocsToolInput = serde_json::from_value(params.clone()).map_err(|e| {
-            anyhow::anyhow!(
-                "{}: {}",
-                ErrorHandler::parameter_parsing_context("get_crate_docs"),
-                e
-            )
-        })?;
+        let mut input: CrateDocsToolInput =
+            serde_json::from_value(params.clone()).map_err(|e| {
+                anyhow::anyhow!(
+                    "{}: {}",
+                    ErrorHandler::parameter_parsing_context("get_crate_docs"),
+                    e
+                )
+            })?;
+
+        // Follow the versions pinned by analyze_project unless one is given
+        if input.version.is_none() {
+            input.version = client.version_pins().get(&input.crate_name);
+        }
+
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "get_crate_docs",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
 
         debug!(
             crate_name = %input.crate_name,
@@ -97,17 +136,17 @@ impl ToolHandler for CrateDocsTool {
         );
 
         // Use unified cache strategy
-        CacheStrategy::execute_with_cache(
+        let value = CacheStrategy::execute_with_cache(
             "get_crate_docs",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_CRATE_DOCS_TTL),
             client,
             cache,
             |input, client| async move {
                 // Create docs service without internal cache since we're using server-level cache
                 let docs_service = DocsService::new(
-                    (*client).clone(),
+                    client.for_registry(input.registry.as_deref())?,
                     0,                                 // disable internal cache
                     std::time::Duration::from_secs(0), // no TTL needed
                 );
@@ -137,7 +176,9 @@ impl ToolHandler for CrateDocsTool {
                 Ok(serde_json::to_value(docs_response)?)
             },
         )
-        .await
+        .await?;
+
+        Ok(VersionResolution::annotate(resolution, value))
     }
 
     fn description(&self) -> &str {
@@ -156,8 +197,12 @@ impl ToolHandler for CrateDocsTool {
                 },
                 "version": {
                     "type": "string",
-                    "description": "Optional version (defaults to latest stable version)",
-                    "examples": ["1.0.0", "0.11.4", "2.0.0-alpha.1"]
+                    "description": "Optional version (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release",
+                    "examples": ["1.0.0", "0.11.4", "2.0.0-alpha.1", "^1.2", "~0.11"]
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
                 }
             },
             "required": ["crate_name"],
@@ -175,6 +220,7 @@ impl Default for CrateDocsTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rustacean_docs_client::{DocsSourceMode, LocalDocsConfig, LocalDocsSource};
     use serde_json::json;
 
     #[test]
@@ -183,6 +229,7 @@ mod tests {
         let valid_input = CrateDocsToolInput {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
         };
         assert!(valid_input.validate().is_ok());
 
@@ -190,6 +237,7 @@ mod tests {
         let valid_no_version = CrateDocsToolInput {
             crate_name: "serde".to_string(),
             version: None,
+            registry: None,
         };
         assert!(valid_no_version.validate().is_ok());
 
@@ -197,6 +245,7 @@ mod tests {
         let empty_crate = CrateDocsToolInput {
             crate_name: "".to_string(),
             version: None,
+            registry: None,
         };
         assert!(empty_crate.validate().is_err());
 
@@ -204,6 +253,7 @@ mod tests {
         let whitespace_crate = CrateDocsToolInput {
             crate_name: "   ".to_string(),
             version: None,
+            registry: None,
         };
         assert!(whitespace_crate.validate().is_err());
 
@@ -211,6 +261,7 @@ mod tests {
         let invalid_crate = CrateDocsToolInput {
             crate_name: "invalid/crate@name".to_string(),
             version: None,
+            registry: None,
         };
         assert!(invalid_crate.validate().is_err());
 
@@ -218,6 +269,7 @@ mod tests {
         let empty_version = CrateDocsToolInput {
             crate_name: "tokio".to_string(),
             version: Some("".to_string()),
+            registry: None,
         };
         assert!(empty_version.validate().is_err());
 
@@ -225,12 +277,14 @@ mod tests {
         let valid_with_separators = CrateDocsToolInput {
             crate_name: "async-trait".to_string(),
             version: None,
+            registry: None,
         };
         assert!(valid_with_separators.validate().is_ok());
 
         let valid_with_underscores = CrateDocsToolInput {
             crate_name: "proc_macro2".to_string(),
             version: None,
+            registry: None,
         };
         assert!(valid_with_underscores.validate().is_ok());
     }
@@ -240,6 +294,7 @@ mod tests {
         let input_with_version = CrateDocsToolInput {
             crate_name: "tokio".to_string(),
             version: Some("1.35.0".to_string()),
+            registry: None,
         };
         let request = input_with_version.to_crate_docs_request().unwrap();
         assert_eq!(request.crate_name.as_str(), "tokio");
@@ -248,6 +303,7 @@ mod tests {
         let input_no_version = CrateDocsToolInput {
             crate_name: "serde".to_string(),
             version: None,
+            registry: None,
         };
         let request = input_no_version.to_crate_docs_request().unwrap();
         assert_eq!(request.crate_name.as_str(), "serde");
@@ -259,6 +315,7 @@ mod tests {
         let input1 = CrateDocsToolInput {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
         };
         let key1 = input1.cache_key("crate_docs");
         assert_eq!(key1, "crate_docs:tokio:1.0.0");
@@ -266,6 +323,7 @@ mod tests {
         let input2 = CrateDocsToolInput {
             crate_name: "serde".to_string(),
             version: None,
+            registry: None,
         };
         let key2 = input2.cache_key("crate_docs");
         assert_eq!(key2, "crate_docs:serde:latest");
@@ -274,6 +332,7 @@ mod tests {
         let input3 = CrateDocsToolInput {
             crate_name: "tokio".to_string(),
             version: Some("1.1.0".to_string()),
+            registry: None,
         };
         let key3 = input3.cache_key("crate_docs");
         assert_ne!(key1, key3);
@@ -284,6 +343,7 @@ mod tests {
         let input = CrateDocsToolInput {
             crate_name: "async-trait".to_string(),
             version: Some("0.1.68".to_string()),
+            registry: None,
         };
 
         // Test serialization
@@ -375,6 +435,89 @@ mod tests {
         let _tool_handler: &dyn ToolHandler = &tool;
     }
 
+    #[tokio::test]
+    async fn test_version_resolution_leaves_concrete_versions() {
+        let client = DocsClient::new().unwrap();
+
+        for version in [None, Some("1.0.0"), Some("latest")] {
+            let mut resolved = version.map(str::to_string);
+            let resolution =
+                VersionResolution::resolve("get_crate_docs", "demo", &mut resolved, None, &client)
+                    .await
+                    .unwrap();
+            assert!(resolution.is_none());
+            assert_eq!(resolved.as_deref(), version);
+        }
+
+        let resolution = VersionResolution {
+            requested: "^1.2".to_string(),
+            resolved: "1.2.5".to_string(),
+        };
+        let response = VersionResolution::annotate(Some(resolution), json!({"version": "1.2.5"}));
+        assert_eq!(response["version_resolution"]["requested"], "^1.2");
+        assert_eq!(response["version_resolution"]["resolved"], "1.2.5");
+        assert_eq!(
+            VersionResolution::annotate(None, json!({"version": "1.2.5"})),
+            json!({"version": "1.2.5"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_version_requirement_resolves_to_newest_match() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let index = root.join("registry/src/index.crates.io-0000");
+        for version in ["1.1.0", "1.2.0", "2.0.0"] {
+            let dir = index.join(format!("demo-crate-{version}"));
+            std::fs::create_dir_all(dir.join("src")).unwrap();
+            std::fs::write(
+                dir.join("Cargo.toml"),
+                format!("[package]\nname = \"demo-crate\"\nversion = \"{version}\"\n"),
+            )
+            .unwrap();
+            std::fs::write(
+                dir.join("src/lib.rs"),
+                "//! Demo crate.\n\npub struct Widget;\n",
+            )
+            .unwrap();
+        }
+
+        let source = LocalDocsSource::new(LocalDocsConfig {
+            registry_paths: vec![root.join("registry/src")],
+            workspace_paths: Vec::new(),
+        });
+        let client = Arc::new(
+            DocsClient::new()
+                .unwrap()
+                .with_local_docs(Arc::new(source), DocsSourceMode::LocalOnly),
+        );
+        let cache = Arc::new(RwLock::new(TieredCache::new(
+            vec![],
+            rustacean_docs_cache::WriteStrategy::WriteThrough,
+        )));
+
+        let result = CrateDocsTool::new()
+            .execute(
+                json!({"crate_name": "demo-crate", "version": "^1.1"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["version"], "1.2.0");
+        assert_eq!(result["version_resolution"]["requested"], "^1.1");
+        assert_eq!(result["version_resolution"]["resolved"], "1.2.0");
+
+        let missing = CrateDocsTool::new()
+            .execute(
+                json!({"crate_name": "demo-crate", "version": "~3"}),
+                &client,
+                &cache,
+            )
+            .await;
+        assert!(missing.is_err());
+    }
+
     // Integration tests with service-based architecture
     #[cfg(feature = "integration-tests")]
     mod integration_tests {
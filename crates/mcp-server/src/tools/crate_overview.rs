@@ -56,37 +56,33 @@ use tracing::debug;
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::{endpoints::docs_modules::service::DocsService, DocsClient};
 use rustacean_docs_core::{
+    constants::DEFAULT_CRATE_DOCS_TTL,
     models::docs::{CrateDocsRequest, CrateDocsResponse, CrateItem, ItemKind},
     types::{CrateName, Version},
     Error,
 };
 
 use crate::tools::{
-    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
-    ToolInput,
+    crate_cache_tags, registry_cache_scope, CacheConfig, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolErrorContext, ToolHandler, ToolInput, VersionResolution,
 };
 
 // Type alias for our specific cache implementation
 type ServerCache = TieredCache<String, Value>;
 
 /// Detail level for the crate overview output
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum DetailLevel {
     /// Just names and paths
     Compact,
     /// Names, paths, and brief descriptions (default)
+    #[default]
     Normal,
     /// Include signatures, async indicators, visibility
     Detailed,
 }
 
-impl Default for DetailLevel {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 /// Input parameters for the get_crate_overview tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrateOverviewToolInput {
@@ -96,16 +92,21 @@ pub struct CrateOverviewToolInput {
     pub version: Option<String>,
     /// Level of detail in the output (optional, defaults to normal)
     pub detail_level: Option<DetailLevel>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
 }
 
 impl ToolInput for CrateOverviewToolInput {
     fn validate(&self) -> Result<(), Error> {
         ParameterValidator::validate_crate_name(&self.crate_name, "get_crate_overview")?;
         ParameterValidator::validate_version(&self.version, "get_crate_overview")?;
+        ParameterValidator::validate_registry(&self.registry, "get_crate_overview")?;
         Ok(())
     }
 
     fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
         let detail_level = self.detail_level.unwrap_or_default();
         match &self.version {
             Some(version) => {
@@ -120,6 +121,10 @@ impl ToolInput for CrateOverviewToolInput {
             ),
         }
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
 }
 
 impl CrateOverviewToolInput {
@@ -344,7 +349,7 @@ impl ToolHandler for CrateOverviewTool {
         debug!("Executing get_crate_overview tool with params: {}", params);
 
         // Parse input parameters
-        let input: CrateOverviewToolInput =
+        let mut input: CrateOverviewToolInput =
             serde_json::from_value(params.clone()).map_err(|e| {
                 anyhow::anyhow!(
                     "{}: {}",
@@ -360,18 +365,28 @@ impl ToolHandler for CrateOverviewTool {
             "Processing crate overview request"
         );
 
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "get_crate_overview",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+
         // Use unified cache strategy
-        CacheStrategy::execute_with_cache(
+        let value = CacheStrategy::execute_with_cache(
             "get_crate_overview",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_CRATE_DOCS_TTL),
             client,
             cache,
             |input, client| async move {
                 // Create docs service without internal cache since we're using server-level cache
                 let docs_service = DocsService::new(
-                    (*client).clone(),
+                    client.for_registry(input.registry.as_deref())?,
                     0,                                 // disable internal cache
                     std::time::Duration::from_secs(0), // no TTL needed
                 );
@@ -410,7 +425,9 @@ impl ToolHandler for CrateOverviewTool {
                 }))
             },
         )
-        .await
+        .await?;
+
+        Ok(VersionResolution::annotate(resolution, value))
     }
 
     fn description(&self) -> &str {
@@ -440,7 +457,7 @@ impl ToolHandler for CrateOverviewTool {
                 },
                 "version": {
                     "type": "string",
-                    "description": "Specific version to analyze (defaults to latest stable version). Use semantic versioning format.",
+                    "description": "Specific version to analyze (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release",
                     "examples": ["1.0.0", "0.11.4", "2.0.0-alpha.1", "1.35.0"],
                     "pattern": "^\\d+\\.\\d+\\.\\d+(?:-[a-zA-Z0-9]+(?:\\.[a-zA-Z0-9]+)*)?$"
                 },
@@ -450,6 +467,10 @@ impl ToolHandler for CrateOverviewTool {
                     "enum": ["compact", "normal", "detailed"],
                     "default": "normal",
                     "examples": ["compact", "normal", "detailed"]
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
                 }
             },
             "required": ["crate_name"],
@@ -493,6 +514,7 @@ mod tests {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
             detail_level: Some(DetailLevel::Normal),
+            registry: None,
         };
         assert!(valid_input.validate().is_ok());
 
@@ -501,6 +523,7 @@ mod tests {
             crate_name: "serde".to_string(),
             version: None,
             detail_level: None,
+            registry: None,
         };
         assert!(valid_no_version.validate().is_ok());
 
@@ -509,6 +532,7 @@ mod tests {
             crate_name: "".to_string(),
             version: None,
             detail_level: None,
+            registry: None,
         };
         assert!(empty_crate.validate().is_err());
 
@@ -517,6 +541,7 @@ mod tests {
             crate_name: "invalid/crate@name".to_string(),
             version: None,
             detail_level: None,
+            registry: None,
         };
         assert!(invalid_crate.validate().is_err());
     }
@@ -527,6 +552,7 @@ mod tests {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
             detail_level: Some(DetailLevel::Compact),
+            registry: None,
         };
         let key1 = input1.cache_key("crate_overview");
         assert_eq!(key1, "crate_overview:tokio:1.0.0:Compact");
@@ -535,6 +561,7 @@ mod tests {
             crate_name: "serde".to_string(),
             version: None,
             detail_level: None,
+            registry: None,
         };
         let key2 = input2.cache_key("crate_overview");
         assert_eq!(key2, "crate_overview:serde:latest:Normal");
@@ -544,6 +571,7 @@ mod tests {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
             detail_level: Some(DetailLevel::Detailed),
+            registry: None,
         };
         let key3 = input3.cache_key("crate_overview");
         assert_ne!(key1, key3);
@@ -596,6 +624,7 @@ mod tests {
             crate_name: "async-trait".to_string(),
             version: Some("0.1.68".to_string()),
             detail_level: Some(DetailLevel::Detailed),
+            registry: None,
         };
 
         // Test serialization
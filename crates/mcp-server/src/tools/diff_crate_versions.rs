@@ -0,0 +1,427 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::{diff_crate_docs, DocsClient};
+use rustacean_docs_core::{models::docs::CrateDocsResponse, Error, ErrorBuilder, DEFAULT_VERSION};
+
+use crate::tools::{
+    crate_docs::CrateDocsTool, registry_cache_scope, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolHandler, ToolInput, VersionResolution,
+};
+
+// Type alias for our specific cache implementation
+type ServerCache = TieredCache<String, Value>;
+
+/// Input parameters for the diff_crate_versions tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffCrateVersionsToolInput {
+    /// Name of the crate to compare
+    pub crate_name: String,
+    /// Release to upgrade from (defaults to the version pinned by analyze_project)
+    #[serde(default)]
+    pub from_version: Option<String>,
+    /// Release to upgrade to (defaults to latest stable version)
+    #[serde(default)]
+    pub to_version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
+}
+
+impl ToolInput for DiffCrateVersionsToolInput {
+    fn validate(&self) -> Result<(), Error> {
+        ParameterValidator::validate_crate_name(&self.crate_name, "diff_crate_versions")?;
+        ParameterValidator::validate_version(&self.from_version, "diff_crate_versions")?;
+        ParameterValidator::validate_version(&self.to_version, "diff_crate_versions")?;
+        ParameterValidator::validate_registry(&self.registry, "diff_crate_versions")?;
+        Ok(())
+    }
+
+    fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
+        format!(
+            "{}:{}:{}:{}",
+            tool_name,
+            self.crate_name,
+            self.from_version.as_deref().unwrap_or("pinned"),
+            self.to_version.as_deref().unwrap_or(DEFAULT_VERSION)
+        )
+    }
+}
+
+/// Tool that compares the public API of two releases of a crate
+pub struct DiffCrateVersionsTool;
+
+impl DiffCrateVersionsTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Fetch the docs of one release through get_crate_docs, so both sides
+    /// of a diff share the crate docs cache, with whether they were stale
+    async fn fetch_docs(
+        input: &DiffCrateVersionsToolInput,
+        version: &str,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<(CrateDocsResponse, bool)> {
+        CrateDocsTool::fetch(
+            json!({
+                "crate_name": input.crate_name,
+                "version": version,
+                "registry": input.registry,
+            }),
+            client,
+            cache,
+        )
+        .await
+    }
+}
+
+impl Default for DiffCrateVersionsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for DiffCrateVersionsTool {
+    async fn execute(
+        &self,
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Value> {
+        debug!("Executing diff_crate_versions tool with params: {}", params);
+
+        // Parse input parameters
+        let mut input: DiffCrateVersionsToolInput =
+            serde_json::from_value(params).map_err(|e| {
+                anyhow::anyhow!(
+                    "{}: {}",
+                    ErrorHandler::parameter_parsing_context("diff_crate_versions"),
+                    e
+                )
+            })?;
+        input.validate()?;
+
+        // Upgrades usually start from the version the project builds against
+        if input.from_version.is_none() {
+            input.from_version = client.version_pins().get(&input.crate_name);
+        }
+        if input.from_version.is_none() {
+            return Err(ErrorBuilder::protocol()
+                .invalid_input(
+                    "diff_crate_versions",
+                    "from_version is required unless analyze_project pinned a version of the crate",
+                )
+                .into());
+        }
+
+        // Resolve semver requirements such as `~0.11` to concrete releases
+        let mut from_version = input.from_version.clone();
+        let from_resolution = VersionResolution::resolve(
+            "diff_crate_versions",
+            &input.crate_name,
+            &mut from_version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+        let mut to_version = input.to_version.clone();
+        let to_resolution = VersionResolution::resolve(
+            "diff_crate_versions",
+            &input.crate_name,
+            &mut to_version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+
+        let from_version = from_version.unwrap_or_else(|| DEFAULT_VERSION.to_string());
+        let to_version = to_version.unwrap_or_else(|| DEFAULT_VERSION.to_string());
+        let (old, old_stale) = Self::fetch_docs(&input, &from_version, client, cache).await?;
+        let (new, new_stale) = Self::fetch_docs(&input, &to_version, client, cache).await?;
+
+        let diff = diff_crate_docs(&old, &new);
+
+        debug!(
+            crate_name = %diff.crate_name,
+            from_version = %diff.from_version,
+            to_version = %diff.to_version,
+            added = diff.summary.added,
+            removed = diff.summary.removed,
+            changed = diff.summary.changed,
+            breaking = diff.summary.breaking,
+            "Crate versions compared"
+        );
+
+        let mut response = serde_json::to_value(&diff)?;
+        if from_resolution.is_some() || to_resolution.is_some() {
+            response["version_resolution"] = json!({
+                "from_version": from_resolution,
+                "to_version": to_resolution,
+            });
+        }
+        // A diff against stale docs is as stale as they are
+        if old_stale || new_stale {
+            response = CacheStrategy::mark_stale(response);
+        }
+        Ok(response)
+    }
+
+    fn description(&self) -> &str {
+        "Compare the public API of two releases of a crate to plan an upgrade. Reports the items added, removed and changed (signature, kind or deprecation) between the releases, grouped by module, and flags changes likely to break code written against the older release. Also reports whether Cargo treats the newer release as a semver-compatible upgrade."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate (e.g., \"reqwest\")",
+                    "minLength": 1,
+                    "pattern": "^[a-zA-Z0-9_-]+$"
+                },
+                "from_version": {
+                    "type": "string",
+                    "description": "Release to upgrade from, or a semver requirement such as \"~0.11\" resolved to the newest matching release. Defaults to the version pinned by analyze_project",
+                    "examples": ["0.11.27", "~0.11"]
+                },
+                "to_version": {
+                    "type": "string",
+                    "description": "Release to upgrade to (defaults to latest stable version), or a semver requirement such as \"^0.12\"",
+                    "examples": ["0.12.0", "^0.12", "latest"]
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
+                }
+            },
+            "required": ["crate_name"],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::{create_test_cache, seed_stale};
+    use rustacean_docs_cache::Cache;
+    use rustacean_docs_core::models::docs::{
+        CrateCategories, CrateItem, CrateSummary, ItemKind, Visibility,
+    };
+    use rustacean_docs_core::ProjectAnalysis;
+
+    fn docs(version: &str, items: Vec<(&str, ItemKind, &str, &str)>) -> CrateDocsResponse {
+        CrateDocsResponse {
+            name: "demo".to_string(),
+            version: version.to_string(),
+            summary: CrateSummary {
+                description: None,
+                module_count: 0,
+                struct_count: 0,
+                trait_count: 0,
+                function_count: 0,
+                enum_count: 0,
+                features: vec![],
+            },
+            categories: CrateCategories {
+                core_types: vec![],
+                traits: vec![],
+                modules: vec![],
+                functions: vec![],
+                macros: vec![],
+                constants: vec![],
+            },
+            items: items
+                .into_iter()
+                .map(|(name, kind, path, signature)| CrateItem {
+                    name: name.to_string(),
+                    kind,
+                    summary: None,
+                    path: path.to_string(),
+                    visibility: Visibility::Public,
+                    is_async: false,
+                    signature: Some(signature.to_string()),
+                    docs_path: Some(path.to_string()),
+                    deprecation: None,
+                })
+                .collect(),
+            examples: vec![],
+            docs_url: None,
+        }
+    }
+
+    /// Seed the entries get_crate_docs would have cached so no request is made
+    async fn seed(cache: &Arc<RwLock<ServerCache>>) {
+        let old = docs(
+            "0.1.0",
+            vec![
+                (
+                    "connect",
+                    ItemKind::Function,
+                    "fn.connect.html",
+                    "pub fn connect(addr: &str)",
+                ),
+                (
+                    "Legacy",
+                    ItemKind::Struct,
+                    "struct.Legacy.html",
+                    "pub struct Legacy",
+                ),
+            ],
+        );
+        let new = docs(
+            "0.2.0",
+            vec![
+                (
+                    "connect",
+                    ItemKind::Function,
+                    "fn.connect.html",
+                    "pub async fn connect(addr: &str)",
+                ),
+                (
+                    "Pool",
+                    ItemKind::Struct,
+                    "pool/struct.Pool.html",
+                    "pub struct Pool",
+                ),
+            ],
+        );
+        for (key, docs) in [
+            ("get_crate_docs:demo:0.1.0", old),
+            ("get_crate_docs:demo:0.2.0", new),
+        ] {
+            cache
+                .read()
+                .await
+                .insert(key.to_string(), serde_json::to_value(docs).unwrap())
+                .await
+                .unwrap();
+        }
+    }
+
+    fn input(from_version: Option<&str>) -> DiffCrateVersionsToolInput {
+        DiffCrateVersionsToolInput {
+            crate_name: "demo".to_string(),
+            from_version: from_version.map(str::to_string),
+            to_version: Some("0.2.0".to_string()),
+            registry: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_input_validation() {
+        assert!(input(Some("0.1.0")).validate().is_ok());
+        assert!(input(None).validate().is_ok());
+        assert!(input(Some(" ")).validate().is_err());
+
+        let mut bad_crate = input(Some("0.1.0"));
+        bad_crate.crate_name = "not a crate".to_string();
+        assert!(bad_crate.validate().is_err());
+
+        assert_eq!(
+            input(None).cache_key("diff_crate_versions"),
+            "diff_crate_versions:demo:pinned:0.2.0"
+        );
+    }
+
+    #[test]
+    fn test_parameters_schema() {
+        let schema = DiffCrateVersionsTool::new().parameters_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("from_version"));
+        assert!(properties.contains_key("to_version"));
+        assert_eq!(schema["required"], json!(["crate_name"]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_diffs_cached_crate_docs() {
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+        seed(&cache).await;
+
+        let result = DiffCrateVersionsTool::new()
+            .execute(
+                json!({"crate_name": "demo", "from_version": "0.1.0", "to_version": "0.2.0"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result["from_version"], "0.1.0");
+        assert_eq!(result["to_version"], "0.2.0");
+        assert_eq!(result["semver_compatible"], false);
+        assert_eq!(result["summary"]["added"], 1);
+        assert_eq!(result["summary"]["removed"], 1);
+        assert_eq!(result["summary"]["changed"], 1);
+        assert_eq!(result["summary"]["breaking"], 2);
+        assert!(result.get("version_resolution").is_none());
+        assert!(result.get("stale").is_none());
+
+        let root = &result["modules"][0];
+        assert_eq!(root["module"], "");
+        assert_eq!(root["changes"][0]["path"], "Legacy");
+        assert_eq!(root["changes"][0]["change"], "removed");
+        assert_eq!(root["changes"][1]["path"], "connect");
+        assert_eq!(root["changes"][1]["breaking"], true);
+        assert_eq!(result["modules"][1]["module"], "pool");
+    }
+
+    #[tokio::test]
+    async fn test_execute_defaults_to_pinned_version() {
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+        seed(&cache).await;
+
+        let tool = DiffCrateVersionsTool::new();
+        let params = json!({"crate_name": "demo", "to_version": "0.2.0"});
+        assert!(tool.execute(params.clone(), &client, &cache).await.is_err());
+
+        client.version_pins().pin_project(&ProjectAnalysis {
+            manifest_path: None,
+            lockfile_path: None,
+            package: None,
+            dependencies: vec![],
+            unresolved: vec![],
+            versions: [("demo".to_string(), "0.1.0".to_string())].into(),
+        });
+        let result = tool.execute(params, &client, &cache).await.unwrap();
+        assert_eq!(result["from_version"], "0.1.0");
+    }
+
+    #[tokio::test]
+    async fn test_execute_marks_diffs_of_stale_crate_docs() {
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+        seed(&cache).await;
+        let new = cache
+            .read()
+            .await
+            .get(&"get_crate_docs:demo:0.2.0".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        seed_stale(&cache, "get_crate_docs:demo:0.2.0", new).await;
+
+        let result = DiffCrateVersionsTool::new()
+            .execute(
+                json!({"crate_name": "demo", "from_version": "0.1.0", "to_version": "0.2.0"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["summary"]["added"], 1);
+        assert_eq!(result["stale"], true);
+    }
+}
@@ -0,0 +1,635 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::{
+    endpoints::docs_modules::service::DocsService,
+    search_index::{CrateCallables, SignatureQuery, TypePattern},
+    DocsClient,
+};
+use rustacean_docs_core::{
+    constants::{DEFAULT_CRATE_DOCS_TTL, MAX_SEARCH_LIMIT},
+    models::{
+        docs::CrateDocsRequest,
+        search::{SignatureMatch, SignatureSearchResponse},
+    },
+    types::{CrateName, Version},
+    Error, ErrorBuilder,
+};
+
+use crate::tools::{
+    crate_cache_tags, metadata::CrateMetadataTool, registry_cache_scope, CacheConfig,
+    CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler, ToolInput,
+    VersionResolution,
+};
+
+// Type alias for our specific cache implementation
+type ServerCache = TieredCache<String, Value>;
+
+/// Crates searched when following dependencies, unless overridden
+const DEFAULT_MAX_CRATES: usize = 10;
+
+/// Upper bound on `max_crates`
+const MAX_CRATES_LIMIT: usize = 25;
+
+/// Types and traits whose methods are collected per crate
+const MAX_METHOD_CONTAINERS: usize = 200;
+
+/// Input parameters for the find_by_signature tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureSearchToolInput {
+    /// Crate to search, and the root of the dependency tree
+    pub crate_name: String,
+    /// Specific version to query (defaults to latest stable version)
+    pub version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Parameter type patterns, matched in any order (e.g. `["Vec<u8>"]`)
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Return type pattern (e.g. `"Bytes"`)
+    pub output: Option<String>,
+    /// Also search the crate's normal dependencies, transitively
+    #[serde(default)]
+    pub include_dependencies: bool,
+    /// Maximum number of crates searched when following dependencies (default: 10, max: 25)
+    pub max_crates: Option<usize>,
+    /// Maximum number of results to return (default: 10, max: 50)
+    pub limit: Option<usize>,
+}
+
+impl ToolInput for SignatureSearchToolInput {
+    fn validate(&self) -> Result<(), Error> {
+        ParameterValidator::validate_crate_name(&self.crate_name, "find_by_signature")?;
+        ParameterValidator::validate_version(&self.version, "find_by_signature")?;
+        ParameterValidator::validate_registry(&self.registry, "find_by_signature")?;
+        ParameterValidator::validate_limit(&self.limit, "find_by_signature", MAX_SEARCH_LIMIT)?;
+        ParameterValidator::validate_limit(
+            &self.max_crates,
+            "find_by_signature",
+            MAX_CRATES_LIMIT,
+        )?;
+        self.signature_query()?;
+        Ok(())
+    }
+
+    fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}",
+            tool_name,
+            self.crate_name,
+            self.version.as_deref().unwrap_or("latest"),
+            self.inputs.join(","),
+            self.output.as_deref().unwrap_or(""),
+            self.include_dependencies,
+            self.max_crates(),
+            self.limit.unwrap_or(10)
+        )
+    }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
+}
+
+impl SignatureSearchToolInput {
+    /// Parse the type patterns into a query
+    pub fn signature_query(&self) -> Result<SignatureQuery, Error> {
+        if self.inputs.is_empty() && self.output.is_none() {
+            return Err(ErrorBuilder::protocol().invalid_input(
+                "find_by_signature",
+                "at least one of inputs or output is required",
+            ));
+        }
+
+        let parse = |pattern: &str| {
+            TypePattern::parse(pattern).ok_or_else(|| {
+                ErrorBuilder::protocol().invalid_input(
+                    "find_by_signature",
+                    format!("'{pattern}' is not a valid type pattern"),
+                )
+            })
+        };
+
+        Ok(SignatureQuery {
+            inputs: self
+                .inputs
+                .iter()
+                .map(|pattern| parse(pattern))
+                .collect::<Result<_, _>>()?,
+            output: self.output.as_deref().map(parse).transpose()?,
+        })
+    }
+
+    fn max_crates(&self) -> usize {
+        if self.include_dependencies {
+            self.max_crates.unwrap_or(DEFAULT_MAX_CRATES)
+        } else {
+            1
+        }
+    }
+}
+
+/// Cache entry for the functions and methods of one crate release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CallablesInput {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+impl ToolInput for CallablesInput {
+    fn validate(&self) -> Result<(), Error> {
+        ParameterValidator::validate_crate_name(&self.crate_name, "find_by_signature")
+    }
+
+    fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
+        format!(
+            "{}:{}:{}",
+            tool_name,
+            self.crate_name,
+            self.version.as_deref().unwrap_or("latest")
+        )
+    }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
+}
+
+impl CallablesInput {
+    fn to_crate_docs_request(&self) -> Result<CrateDocsRequest, Error> {
+        let crate_name = CrateName::new(&self.crate_name)
+            .map_err(|e| Error::Internal(format!("Invalid crate name: {e}")))?;
+
+        match &self.version {
+            Some(version) => {
+                let version = Version::new(version)
+                    .map_err(|e| Error::Internal(format!("Invalid version: {e}")))?;
+                Ok(CrateDocsRequest::with_version(crate_name, version))
+            }
+            None => Ok(CrateDocsRequest::new(crate_name)),
+        }
+    }
+}
+
+/// Tool handler for searching function and method signatures by type
+pub struct FindBySignatureTool;
+
+impl FindBySignatureTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Functions and methods of one crate, cached per release
+    async fn callables(
+        crate_name: &str,
+        version: Option<String>,
+        registry: Option<String>,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<CrateCallables> {
+        let input = CallablesInput {
+            crate_name: crate_name.to_string(),
+            version,
+            registry,
+        };
+
+        let value = CacheStrategy::execute_with_cache(
+            "crate_callables",
+            Value::Null,
+            input,
+            CacheConfig::default().with_ttl(DEFAULT_CRATE_DOCS_TTL),
+            client,
+            cache,
+            |input, client| async move {
+                let docs_service = DocsService::new(
+                    client.for_registry(input.registry.as_deref())?,
+                    0,                                 // disable internal cache
+                    std::time::Duration::from_secs(0), // no TTL needed
+                );
+
+                let callables = docs_service
+                    .get_crate_callables(input.to_crate_docs_request()?, MAX_METHOD_CONTAINERS)
+                    .await
+                    .crate_context(
+                        "collect signatures",
+                        &input.crate_name,
+                        input.version.as_deref(),
+                    )?;
+
+                Ok(serde_json::to_value(callables)?)
+            },
+        )
+        .await?;
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Names of the non-optional normal dependencies of a crate
+    async fn dependencies(
+        crate_name: &str,
+        version: Option<String>,
+        registry: Option<String>,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Vec<String>> {
+        let metadata = CrateMetadataTool::new()
+            .execute(
+                json!({
+                    "crate_name": crate_name,
+                    "version": version,
+                    "registry": registry,
+                }),
+                client,
+                cache,
+            )
+            .await?;
+
+        Ok(metadata["dependencies"]["list"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|dep| !dep["optional"].as_bool().unwrap_or(false))
+            .filter_map(|dep| dep["name"].as_str().map(str::to_string))
+            .collect())
+    }
+}
+
+impl Default for FindBySignatureTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for FindBySignatureTool {
+    async fn execute(
+        &self,
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Value> {
+        debug!("Executing find_by_signature tool with params: {}", params);
+
+        // Parse input parameters
+        let mut input: SignatureSearchToolInput = serde_json::from_value(params).map_err(|e| {
+            anyhow::anyhow!(
+                "{}: {}",
+                ErrorHandler::parameter_parsing_context("find_by_signature"),
+                e
+            )
+        })?;
+        input.validate()?;
+        let query = input.signature_query()?;
+        let max_crates = input.max_crates();
+
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "find_by_signature",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+
+        // Breadth-first over the dependency tree, root crate first.
+        // Dependencies are searched at their latest release.
+        let mut queue = VecDeque::from([(input.crate_name.clone(), input.version.clone())]);
+        let mut seen = HashSet::from([input.crate_name.clone()]);
+        let mut crates_searched = Vec::new();
+        let mut crates_skipped = Vec::new();
+        let mut matches = Vec::new();
+
+        while let Some((crate_name, version)) = queue.pop_front() {
+            if crates_searched.len() >= max_crates {
+                break;
+            }
+            let is_root = crates_searched.is_empty() && crates_skipped.is_empty();
+
+            let callables = match Self::callables(
+                &crate_name,
+                version.clone(),
+                input.registry.clone(),
+                client,
+                cache,
+            )
+            .await
+            {
+                Ok(callables) => callables,
+                Err(e) if is_root => return Err(e),
+                Err(e) => {
+                    warn!(crate_name = %crate_name, error = %e, "Skipping dependency");
+                    crates_skipped.push(crate_name);
+                    continue;
+                }
+            };
+
+            let index = callables.index();
+            matches.extend(
+                index
+                    .search_signature(&query)
+                    .into_iter()
+                    .map(|item| SignatureMatch {
+                        crate_name: callables.crate_name.clone(),
+                        version: callables.version.clone(),
+                        item,
+                    }),
+            );
+            crates_searched.push(format!("{}@{}", callables.crate_name, callables.version));
+
+            if input.include_dependencies {
+                match Self::dependencies(
+                    &crate_name,
+                    version,
+                    input.registry.clone(),
+                    client,
+                    cache,
+                )
+                .await
+                {
+                    Ok(dependencies) => {
+                        for dependency in dependencies {
+                            if seen.insert(dependency.clone()) {
+                                queue.push_back((dependency, None));
+                            }
+                        }
+                    }
+                    Err(e) => warn!(
+                        crate_name = %crate_name,
+                        error = %e,
+                        "Could not read dependencies"
+                    ),
+                }
+            }
+        }
+
+        // Stable sort keeps the root crate's matches ahead on equal scores
+        matches.sort_by_key(|m| std::cmp::Reverse(m.item.score));
+        let total = matches.len();
+        matches.truncate(input.limit.unwrap_or(10));
+
+        debug!(
+            crates_searched = crates_searched.len(),
+            total = total,
+            "Signature search completed"
+        );
+
+        let response = serde_json::to_value(SignatureSearchResponse {
+            inputs: input.inputs,
+            output: input.output,
+            crates_searched,
+            crates_skipped,
+            results: matches,
+            total,
+        })?;
+        Ok(VersionResolution::annotate(resolution, response))
+    }
+
+    fn description(&self) -> &str {
+        "Find functions and methods by type signature, like rustdoc's \"In Parameters\" / \"In Return Types\" search. Give parameter type patterns (matched in any order, the receiver included) and/or a return type pattern, e.g. inputs [\"Vec<u8>\"] and output \"Bytes\". Use _ or single capital letters (T, U) for any type and impl Trait to require a bound. Searches one crate or, with include_dependencies, its dependency tree; results are ranked by how concretely their types and generics unify with the query. Conversion trait impls (From, TryFrom, FromStr, FromIterator, AsRef, Default) are included as methods."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Crate to search, and the root of the dependency tree when include_dependencies is set"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release"
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
+                },
+                "inputs": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Parameter type patterns, matched in any order",
+                    "examples": [["Vec<u8>"], ["&str", "usize"], ["&mut impl Read"]]
+                },
+                "output": {
+                    "type": "string",
+                    "description": "Return type pattern",
+                    "examples": ["Bytes", "Result<String, _>", "Option<T>"]
+                },
+                "include_dependencies": {
+                    "type": "boolean",
+                    "description": "Also search the crate's non-optional dependencies, transitively, at their latest versions",
+                    "default": false
+                },
+                "max_crates": {
+                    "type": "integer",
+                    "description": "Maximum number of crates searched when following dependencies",
+                    "minimum": 1,
+                    "maximum": MAX_CRATES_LIMIT,
+                    "default": DEFAULT_MAX_CRATES
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results to return",
+                    "minimum": 1,
+                    "maximum": MAX_SEARCH_LIMIT,
+                    "default": 10
+                }
+            },
+            "required": ["crate_name"],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::create_test_cache;
+    use rustacean_docs_cache::Cache;
+    use rustacean_docs_core::models::docs::{CrateItem, ItemKind, Visibility};
+
+    fn callable(name: &str, kind: ItemKind, docs_path: &str, signature: &str) -> CrateItem {
+        CrateItem {
+            name: name.to_string(),
+            kind,
+            summary: None,
+            path: docs_path.to_string(),
+            visibility: Visibility::Public,
+            is_async: false,
+            signature: Some(signature.to_string()),
+            docs_path: Some(docs_path.to_string()),
+            deprecation: None,
+        }
+    }
+
+    async fn seed(
+        cache: &Arc<RwLock<ServerCache>>,
+        crate_name: &str,
+        items: Vec<CrateItem>,
+        dependencies: &[&str],
+    ) {
+        let callables = CrateCallables {
+            crate_name: crate_name.to_string(),
+            version: "1.0.0".to_string(),
+            items,
+        };
+        let metadata = json!({
+            "dependencies": {
+                "count": dependencies.len(),
+                "list": dependencies
+                    .iter()
+                    .map(|name| json!({"name": name, "optional": false}))
+                    .collect::<Vec<_>>()
+            }
+        });
+
+        let cache = cache.read().await;
+        cache
+            .insert(
+                format!("crate_callables:{crate_name}:latest"),
+                serde_json::to_value(callables).unwrap(),
+            )
+            .await
+            .unwrap();
+        cache
+            .insert(format!("metadata:{crate_name}:latest"), metadata)
+            .await
+            .unwrap();
+    }
+
+    fn input(inputs: &[&str], output: Option<&str>) -> SignatureSearchToolInput {
+        SignatureSearchToolInput {
+            crate_name: "app".to_string(),
+            version: None,
+            registry: None,
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            output: output.map(str::to_string),
+            include_dependencies: false,
+            max_crates: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn test_signature_search_tool_input_validation() {
+        assert!(input(&["Vec<u8>"], Some("Bytes")).validate().is_ok());
+        assert!(input(&[], Some("Bytes")).validate().is_ok());
+        assert!(input(&[], None).validate().is_err());
+        assert!(input(&["Vec<"], None).validate().is_err());
+
+        let mut too_many = input(&["u8"], None);
+        too_many.include_dependencies = true;
+        too_many.max_crates = Some(MAX_CRATES_LIMIT + 1);
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_parameters_schema() {
+        let schema = FindBySignatureTool::new().parameters_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for key in ["inputs", "output", "include_dependencies", "registry"] {
+            assert!(properties.contains_key(key), "missing {key}");
+        }
+        assert_eq!(schema["required"], json!(["crate_name"]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_across_dependency_tree() {
+        let tool = FindBySignatureTool::new();
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+
+        seed(
+            &cache,
+            "app",
+            vec![callable(
+                "load",
+                ItemKind::Function,
+                "fn.load.html",
+                "pub fn load(data: Vec<u8>) -> Config",
+            )],
+            &["bytes"],
+        )
+        .await;
+        seed(
+            &cache,
+            "bytes",
+            vec![
+                callable(
+                    "from",
+                    ItemKind::Method,
+                    "struct.Bytes.html",
+                    "fn from(value: Vec<u8>) -> Bytes",
+                ),
+                callable(
+                    "copy_from_slice",
+                    ItemKind::Method,
+                    "struct.Bytes.html#method.copy_from_slice",
+                    "pub fn copy_from_slice(data: &[u8]) -> Self",
+                ),
+            ],
+            &[],
+        )
+        .await;
+
+        // Only the root crate by default
+        let result = tool
+            .execute(
+                json!({"crate_name": "app", "inputs": ["Vec<u8>"]}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["crates_searched"], json!(["app@1.0.0"]));
+        assert_eq!(result["results"][0]["path"], "load");
+
+        let result = tool
+            .execute(
+                json!({
+                    "crate_name": "app",
+                    "inputs": ["Vec<u8>"],
+                    "output": "Bytes",
+                    "include_dependencies": true
+                }),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result["crates_searched"],
+            json!(["app@1.0.0", "bytes@1.0.0"])
+        );
+        assert_eq!(result["total"], 1);
+        assert_eq!(result["results"][0]["crate_name"], "bytes");
+        assert_eq!(result["results"][0]["path"], "Bytes::from");
+
+        let result = tool
+            .execute(
+                json!({
+                    "crate_name": "app",
+                    "output": "Bytes",
+                    "include_dependencies": true
+                }),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["total"], 2);
+    }
+}
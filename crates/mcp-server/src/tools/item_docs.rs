@@ -8,14 +8,15 @@ use tracing::debug;
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::{endpoints::docs_modules::service::DocsService, DocsClient};
 use rustacean_docs_core::{
-    models::docs::ItemDocsRequest,
+    constants::DEFAULT_ITEM_DOCS_TTL,
+    models::docs::{ItemDocsRequest, ItemDocsResponse, ItemDocsSection},
     types::{CrateName, ItemPath, Version},
     Error, ErrorBuilder,
 };
 
 use crate::tools::{
-    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
-    ToolInput,
+    crate_cache_tags, registry_cache_scope, CacheConfig, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolErrorContext, ToolHandler, ToolInput, VersionResolution,
 };
 
 // Type alias for our specific cache implementation
@@ -30,6 +31,12 @@ pub struct ItemDocsToolInput {
     pub item_path: String,
     /// Specific version to query (defaults to latest stable version)
     pub version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Optional sections to return (defaults to all sections)
+    #[serde(default)]
+    pub include: Option<Vec<ItemDocsSection>>,
 }
 
 impl ToolInput for ItemDocsToolInput {
@@ -40,10 +47,12 @@ impl ToolInput for ItemDocsToolInput {
                 .invalid_input("get_item_docs", "item_path cannot be empty"));
         }
         ParameterValidator::validate_version(&self.version, "get_item_docs")?;
+        ParameterValidator::validate_registry(&self.registry, "get_item_docs")?;
         Ok(())
     }
 
     fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
         match &self.version {
             Some(version) => format!(
                 "{}:{}:{}:{}",
@@ -55,9 +64,20 @@ impl ToolInput for ItemDocsToolInput {
             ),
         }
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
 }
 
 impl ItemDocsToolInput {
+    /// Drop the sections not requested through `include`
+    pub fn apply_include(&self, response: &mut ItemDocsResponse) {
+        if let Some(sections) = &self.include {
+            response.retain_sections(sections);
+        }
+    }
+
     /// Convert to internal ItemDocsRequest
     pub fn to_item_docs_request(&self) -> Result<ItemDocsRequest, Error> {
         let crate_name = CrateName::new(&self.crate_name)
@@ -104,7 +124,7 @@ impl ToolHandler for ItemDocsTool {
         debug!("Executing get_item_docs tool with params: {}", params);
 
         // Parse input parameters
-        let input: ItemDocsToolInput = serde_json::from_value(params.clone()).map_err(|e| {
+        let mut input: ItemDocsToolInput = serde_json::from_value(params.clone()).map_err(|e| {
             anyhow::anyhow!(
                 "{}: {}",
                 ErrorHandler::parameter_parsing_context("get_item_docs"),
@@ -112,6 +132,21 @@ impl ToolHandler for ItemDocsTool {
             )
         })?;
 
+        // Follow the versions pinned by analyze_project unless one is given
+        if input.version.is_none() {
+            input.version = client.version_pins().get(&input.crate_name);
+        }
+
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "get_item_docs",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+
         debug!(
             crate_name = %input.crate_name,
             item_path = %input.item_path,
@@ -119,18 +154,21 @@ impl ToolHandler for ItemDocsTool {
             "Processing item docs request"
         );
 
+        // The full response is cached; `include` only trims what is returned
+        let selection = input.clone();
+
         // Use unified cache strategy
-        CacheStrategy::execute_with_cache(
+        let value = CacheStrategy::execute_with_cache(
             "get_item_docs",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_ITEM_DOCS_TTL),
             client,
             cache,
             |input, client| async move {
                 // Create docs service without internal cache since we're using server-level cache
                 let docs_service = DocsService::new(
-                    (*client).clone(),
+                    client.for_registry(input.registry.as_deref())?,
                     0,                                 // disable internal cache
                     std::time::Duration::from_secs(0), // no TTL needed
                 );
@@ -155,6 +193,8 @@ impl ToolHandler for ItemDocsTool {
                     has_description = response.description.is_some(),
                     examples_count = response.examples.len(),
                     related_items_count = response.related_items.len(),
+                    associated_items_count = response.associated_items.len(),
+                    trait_impls_count = response.trait_impls.len(),
                     "Item documentation retrieved successfully"
                 );
 
@@ -162,7 +202,17 @@ impl ToolHandler for ItemDocsTool {
                 Ok(serde_json::to_value(response)?)
             },
         )
-        .await
+        .await?;
+
+        if selection.include.is_none() {
+            return Ok(VersionResolution::annotate(resolution, value));
+        }
+        let mut response: ItemDocsResponse = serde_json::from_value(value)?;
+        selection.apply_include(&mut response);
+        Ok(VersionResolution::annotate(
+            resolution,
+            serde_json::to_value(response)?,
+        ))
     }
 
     fn description(&self) -> &str {
@@ -183,7 +233,19 @@ impl ToolHandler for ItemDocsTool {
                 },
                 "version": {
                     "type": "string",
-                    "description": "Specific version to query (defaults to latest stable version)"
+                    "description": "Specific version to query (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release"
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
+                },
+                "include": {
+                    "type": "array",
+                    "description": "Sections to return besides the signature (defaults to all). \"methods\" covers inherent methods and associated items, \"trait_items\" the required and provided items of a trait",
+                    "items": {
+                        "type": "string",
+                        "enum": ["description", "examples", "methods", "trait_impls", "trait_items", "related_items"]
+                    }
                 }
             },
             "required": ["crate_name", "item_path"],
@@ -195,71 +257,9 @@ impl ToolHandler for ItemDocsTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::test_support::create_test_cache;
     use serde_json::json;
 
-    async fn create_test_cache() -> Arc<RwLock<ServerCache>> {
-        let temp_dir = std::env::temp_dir().join("item_docs_test_cache");
-        if temp_dir.exists() {
-            tokio::fs::remove_dir_all(&temp_dir).await.ok();
-        }
-        tokio::fs::create_dir_all(&temp_dir).await.unwrap();
-
-        // Create individual cache layers
-        let memory_cache = rustacean_docs_cache::MemoryCache::new(10);
-        let disk_cache = rustacean_docs_cache::DiskCache::new(&temp_dir);
-
-        // Wrap memory cache to match error type
-        struct MemoryCacheWrapper(rustacean_docs_cache::MemoryCache<String, Value>);
-
-        #[async_trait::async_trait]
-        impl rustacean_docs_cache::Cache for MemoryCacheWrapper {
-            type Key = String;
-            type Value = Value;
-            type Error = anyhow::Error;
-
-            async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
-                self.0
-                    .get(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
-                self.0
-                    .insert(key, value)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn remove(&self, key: &Self::Key) -> Result<(), Self::Error> {
-                self.0
-                    .remove(key)
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            async fn clear(&self) -> Result<(), Self::Error> {
-                self.0
-                    .clear()
-                    .await
-                    .map_err(|_| anyhow::anyhow!("Memory cache error"))
-            }
-
-            fn stats(&self) -> rustacean_docs_cache::CacheStats {
-                self.0.stats()
-            }
-        }
-
-        let cache = ServerCache::new(
-            vec![
-                Box::new(MemoryCacheWrapper(memory_cache)),
-                Box::new(disk_cache),
-            ],
-            rustacean_docs_cache::WriteStrategy::WriteThrough,
-        );
-        Arc::new(RwLock::new(cache))
-    }
-
     #[test]
     fn test_item_docs_tool_creation() {
         let tool = ItemDocsTool::new();
@@ -279,6 +279,8 @@ mod tests {
         assert!(properties.get("crate_name").is_some());
         assert!(properties.get("item_path").is_some());
         assert!(properties.get("version").is_some());
+        assert!(properties.get("registry").is_some());
+        assert!(properties.get("include").is_some());
 
         let required = schema.get("required").unwrap().as_array().unwrap();
         assert!(required.contains(&json!("crate_name")));
@@ -293,6 +295,8 @@ mod tests {
             crate_name: "tokio".to_string(),
             item_path: "spawn".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
+            include: None,
         };
         assert!(valid_input.validate().is_ok());
 
@@ -301,6 +305,8 @@ mod tests {
             crate_name: "".to_string(),
             item_path: "spawn".to_string(),
             version: None,
+            registry: None,
+            include: None,
         };
         assert!(empty_crate.validate().is_err());
 
@@ -309,6 +315,8 @@ mod tests {
             crate_name: "tokio".to_string(),
             item_path: "".to_string(),
             version: None,
+            registry: None,
+            include: None,
         };
         assert!(empty_path.validate().is_err());
 
@@ -317,6 +325,8 @@ mod tests {
             crate_name: "tokio".to_string(),
             item_path: "spawn".to_string(),
             version: Some("".to_string()),
+            registry: None,
+            include: None,
         };
         assert!(empty_version.validate().is_err());
     }
@@ -327,6 +337,8 @@ mod tests {
             crate_name: "tokio".to_string(),
             item_path: "spawn".to_string(),
             version: Some("1.35.0".to_string()),
+            registry: None,
+            include: None,
         };
         let request = input_with_version.to_item_docs_request().unwrap();
         assert_eq!(request.crate_name.as_str(), "tokio");
@@ -337,6 +349,8 @@ mod tests {
             crate_name: "serde".to_string(),
             item_path: "Serialize".to_string(),
             version: None,
+            registry: None,
+            include: None,
         };
         let request = input_no_version.to_item_docs_request().unwrap();
         assert_eq!(request.crate_name.as_str(), "serde");
@@ -350,6 +364,8 @@ mod tests {
             crate_name: "tokio".to_string(),
             item_path: "spawn".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
+            include: None,
         };
         let key1 = input1.cache_key("item_docs");
         assert_eq!(key1, "item_docs:tokio:spawn:1.0.0");
@@ -358,16 +374,64 @@ mod tests {
             crate_name: "serde".to_string(),
             item_path: "Serialize".to_string(),
             version: None,
+            registry: None,
+            include: None,
         };
         let key2 = input2.cache_key("item_docs");
         assert_eq!(key2, "item_docs:serde:Serialize:latest");
     }
 
+    #[test]
+    fn test_item_docs_tool_include_sections() {
+        let input: ItemDocsToolInput = serde_json::from_value(json!({
+            "crate_name": "tokio",
+            "item_path": "net::TcpStream",
+            "include": ["methods", "trait_impls"]
+        }))
+        .unwrap();
+        assert_eq!(
+            input.include,
+            Some(vec![ItemDocsSection::Methods, ItemDocsSection::TraitImpls])
+        );
+        // Every selection shares the cached full response
+        assert_eq!(
+            input.cache_key("item_docs"),
+            "item_docs:tokio:net::TcpStream:latest"
+        );
+
+        let mut response = ItemDocsResponse {
+            crate_name: "tokio".to_string(),
+            item_path: "net/struct.TcpStream.html".to_string(),
+            name: "TcpStream".to_string(),
+            kind: rustacean_docs_core::ItemKind::Struct,
+            signature: Some("pub struct TcpStream { /* private fields */ }".to_string()),
+            description: Some("A TCP stream.".to_string()),
+            examples: Vec::new(),
+            docs_url: None,
+            related_items: vec!["impl TcpStream".to_string()],
+            associated_items: Vec::new(),
+            trait_impls: Vec::new(),
+            required_items: Vec::new(),
+            provided_items: Vec::new(),
+        };
+        input.apply_include(&mut response);
+        assert!(response.signature.is_some());
+        assert!(response.description.is_none());
+        assert!(response.related_items.is_empty());
+
+        assert!(serde_json::from_value::<ItemDocsToolInput>(json!({
+            "crate_name": "tokio",
+            "item_path": "spawn",
+            "include": ["everything"]
+        }))
+        .is_err());
+    }
+
     #[tokio::test]
     async fn test_execute_missing_crate_name() {
         let tool = ItemDocsTool::new();
         let client = Arc::new(DocsClient::new().unwrap());
-        let cache = create_test_cache().await;
+        let (_cache_dir, cache) = create_test_cache();
 
         let params = json!({
             "item_path": "spawn"
@@ -385,7 +449,7 @@ mod tests {
     async fn test_execute_missing_item_path() {
         let tool = ItemDocsTool::new();
         let client = Arc::new(DocsClient::new().unwrap());
-        let cache = create_test_cache().await;
+        let (_cache_dir, cache) = create_test_cache();
 
         let params = json!({
             "crate_name": "tokio"
@@ -8,14 +8,15 @@ use tracing::debug;
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::{DocsClient, MetadataService};
 use rustacean_docs_core::{
+    constants::DEFAULT_METADATA_TTL,
     models::metadata::{CrateMetadata, CrateMetadataRequest},
     types::{CrateName, Version},
     Error,
 };
 
 use crate::tools::{
-    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
-    ToolInput,
+    crate_cache_tags, registry_cache_scope, CacheConfig, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolErrorContext, ToolHandler, ToolInput, VersionResolution,
 };
 
 // Type alias for our specific cache implementation
@@ -28,21 +29,35 @@ pub struct MetadataToolInput {
     pub crate_name: String,
     /// Specific version to query (defaults to latest stable version)
     pub version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
 }
 
 impl ToolInput for MetadataToolInput {
     fn validate(&self) -> Result<(), Error> {
         ParameterValidator::validate_crate_name(&self.crate_name, "get_crate_metadata")?;
         ParameterValidator::validate_version(&self.version, "get_crate_metadata")?;
+        ParameterValidator::validate_registry(&self.registry, "get_crate_metadata")?;
         Ok(())
     }
 
     fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
         match &self.version {
             Some(version) => format!("{}:{}:{}", tool_name, self.crate_name, version),
             None => format!("{}:{}:latest", tool_name, self.crate_name),
         }
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(
+            tool_name,
+            "metadata",
+            &self.crate_name,
+            self.version.as_deref(),
+        )
+    }
 }
 
 impl MetadataToolInput {
@@ -382,7 +397,7 @@ impl ToolHandler for CrateMetadataTool {
         debug!("Executing get_crate_metadata tool with params: {}", params);
 
         // Parse input parameters
-        let input: MetadataToolInput = serde_json::from_value(params.clone()).map_err(|e| {
+        let mut input: MetadataToolInput = serde_json::from_value(params.clone()).map_err(|e| {
             anyhow::anyhow!(
                 "{}: {}",
                 ErrorHandler::parameter_parsing_context("get_crate_metadata"),
@@ -395,12 +410,22 @@ impl ToolHandler for CrateMetadataTool {
             input.crate_name, input.version
         );
 
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "get_crate_metadata",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+
         // Use unified cache strategy
-        CacheStrategy::execute_with_cache(
+        let value = CacheStrategy::execute_with_cache(
             "metadata",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_METADATA_TTL),
             client,
             cache,
             |input, client| async move {
@@ -408,7 +433,8 @@ impl ToolHandler for CrateMetadataTool {
                 let request = input.to_request()?;
 
                 // Create metadata service with cloned client
-                let metadata_service = MetadataService::new((*client).clone());
+                let metadata_service =
+                    MetadataService::new(client.for_registry(input.registry.as_deref())?);
 
                 let metadata = metadata_service
                     .get_crate_metadata(&request)
@@ -430,7 +456,9 @@ impl ToolHandler for CrateMetadataTool {
                 }))
             },
         )
-        .await
+        .await?;
+
+        Ok(VersionResolution::annotate(resolution, value))
     }
 
     fn description(&self) -> &str {
@@ -447,7 +475,11 @@ impl ToolHandler for CrateMetadataTool {
                 },
                 "version": {
                     "type": "string",
-                    "description": "Specific version to query (defaults to latest stable version)"
+                    "description": "Specific version to query (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release"
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
                 }
             },
             "required": ["crate_name"],
@@ -467,6 +499,7 @@ mod tests {
         let valid_input = MetadataToolInput {
             crate_name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
         };
         assert!(valid_input.validate().is_ok());
 
@@ -474,6 +507,7 @@ mod tests {
         let invalid_input = MetadataToolInput {
             crate_name: "".to_string(),
             version: None,
+            registry: None,
         };
         assert!(invalid_input.validate().is_err());
 
@@ -481,6 +515,7 @@ mod tests {
         let invalid_version = MetadataToolInput {
             crate_name: "tokio".to_string(),
             version: Some("".to_string()),
+            registry: None,
         };
         assert!(invalid_version.validate().is_err());
     }
@@ -490,6 +525,7 @@ mod tests {
         let input = MetadataToolInput {
             crate_name: "serde".to_string(),
             version: Some("1.0.0".to_string()),
+            registry: None,
         };
 
         let request = input.to_request().unwrap();
@@ -499,6 +535,7 @@ mod tests {
         let input_no_version = MetadataToolInput {
             crate_name: "tokio".to_string(),
             version: None,
+            registry: None,
         };
 
         let request_no_version = input_no_version.to_request().unwrap();
@@ -1,30 +1,55 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tracing::{debug, trace};
+use tokio::task::JoinHandle;
+use tracing::{debug, trace, warn};
 
-use rustacean_docs_cache::{Cache, TieredCache};
-use rustacean_docs_client::DocsClient;
-use rustacean_docs_core::{Error, ErrorBuilder};
+use rustacean_docs_cache::{Cache, CacheOutcome, DiskCache, SingleFlight, TieredCache};
+use rustacean_docs_client::{
+    ClientConfig, DocsClient, DocsSourceMode, LocalDocsSource, MetadataService, RequestPriority,
+    ResponseStore,
+};
 
+use rustacean_docs_core::{
+    constants::DEFAULT_STALE_TTL, crate_tags, is_latest_version, kind_tag,
+    parse_version_requirement, tool_tag, CrateName, Error, ErrorBuilder,
+};
+
+use crate::config::{CacheSettings, ClientSettings};
+
+pub mod analyze_project;
+pub mod cache_backend;
 pub mod cache_ops;
 pub mod crate_docs;
 pub mod crate_overview;
+pub mod diff_crate_versions;
+pub mod find_by_signature;
 pub mod item_docs;
 pub mod metadata;
+pub mod prewarm;
 pub mod releases;
 pub mod search;
+pub mod search_items;
+#[cfg(test)]
+mod test_support;
 
 // Re-export tools for convenience
+pub use analyze_project::AnalyzeProjectTool;
 pub use cache_ops::{CacheInfoTool, CacheStatsTool, ClearCacheTool};
 pub use crate_docs::CrateDocsTool;
 pub use crate_overview::CrateOverviewTool;
+pub use diff_crate_versions::DiffCrateVersionsTool;
+pub use find_by_signature::FindBySignatureTool;
 pub use item_docs::ItemDocsTool;
 pub use metadata::CrateMetadataTool;
+pub use prewarm::{PrewarmCacheTool, PrewarmInput, PrewarmReport, PrewarmedCrate};
 pub use releases::RecentReleasesTool;
 pub use search::SearchTool;
+pub use search_items::SearchItemsTool;
 
 // Type alias for our specific cache implementation
 type ServerCache = TieredCache<String, Value>;
@@ -36,6 +61,25 @@ pub trait ToolInput: Serialize + for<'de> Deserialize<'de> + Send + Sync {
 
     /// Generate cache key for this input
     fn cache_key(&self, tool_name: &str) -> String;
+
+    /// Tags indexing the cached response for scoped invalidation
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        vec![tool_tag(tool_name)]
+    }
+}
+
+/// Cache tags for a tool call about one crate: the tool, the kind of data
+/// returned, the crate and, when a specific one was requested, its version
+pub fn crate_cache_tags(
+    tool_name: &str,
+    kind: &str,
+    crate_name: &str,
+    version: Option<&str>,
+) -> Vec<String> {
+    let version = version.filter(|version| !is_latest_version(version));
+    let mut tags = vec![tool_tag(tool_name), kind_tag(kind)];
+    tags.extend(crate_tags(crate_name, version));
+    tags
 }
 
 /// Shared parameter validation utilities
@@ -73,6 +117,21 @@ impl ParameterValidator {
         Ok(())
     }
 
+    /// Validate registry name format if provided
+    pub fn validate_registry(registry: &Option<String>, tool_name: &str) -> Result<(), Error> {
+        if let Some(ref registry) = registry {
+            if registry.trim().is_empty()
+                || !registry
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(ErrorBuilder::protocol()
+                    .invalid_input(tool_name, "registry must be a configured registry name"));
+            }
+        }
+        Ok(())
+    }
+
     /// Validate search query
     pub fn validate_query(query: &str, tool_name: &str) -> Result<(), Error> {
         if query.trim().is_empty() {
@@ -103,6 +162,80 @@ impl ParameterValidator {
     }
 }
 
+/// Cache key namespace for a tool, scoped to the requested registry so that
+/// results from different registries never share cache entries
+pub fn registry_cache_scope(tool_name: &str, registry: &Option<String>) -> String {
+    match registry {
+        Some(registry) => format!("{tool_name}@{registry}"),
+        None => tool_name.to_string(),
+    }
+}
+
+/// A semver requirement given as a tool's `version` parameter, and the
+/// release it resolved to
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct VersionResolution {
+    /// Requirement as given, e.g. `^1.2`
+    pub requested: String,
+    /// Newest non-yanked release matching the requirement
+    pub resolved: String,
+}
+
+impl VersionResolution {
+    /// Replace a version requirement (`^1.2`, `~0.11`, `>=1, <2`, `1.*`) with
+    /// the concrete version it resolves to. Concrete versions and `latest`
+    /// are left as they are.
+    pub async fn resolve(
+        tool_name: &str,
+        crate_name: &str,
+        version: &mut Option<String>,
+        registry: Option<&str>,
+        client: &DocsClient,
+    ) -> Result<Option<Self>> {
+        let Some(requirement) = version.as_deref().and_then(parse_version_requirement) else {
+            return Ok(None);
+        };
+
+        ParameterValidator::validate_crate_name(crate_name, tool_name)?;
+        let name = CrateName::new(crate_name)
+            .map_err(|e| Error::Internal(format!("Invalid crate name: {e}")))?;
+
+        let resolved = MetadataService::new(client.for_registry(registry)?)
+            .resolve_version_requirement(&name, &requirement)
+            .await
+            .crate_context(
+                "resolve version requirement",
+                crate_name,
+                version.as_deref(),
+            )?
+            .to_string();
+
+        debug!(
+            tool = tool_name,
+            crate_name = crate_name,
+            requirement = %requirement,
+            resolved = %resolved,
+            "Resolved version requirement"
+        );
+
+        Ok(version.replace(resolved.clone()).map(|requested| Self {
+            requested,
+            resolved,
+        }))
+    }
+
+    /// Report the resolution in a tool response as `version_resolution`
+    pub fn annotate(resolution: Option<Self>, mut response: Value) -> Value {
+        if let (Some(resolution), Some(object)) = (resolution, response.as_object_mut()) {
+            object.insert(
+                "version_resolution".to_string(),
+                serde_json::json!(resolution),
+            );
+        }
+        response
+    }
+}
+
 /// Cache configuration for tools
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
@@ -112,6 +245,13 @@ pub struct CacheConfig {
     pub key_prefix: Option<String>,
     /// Whether to cache responses even on errors (usually false)
     pub cache_errors: bool,
+    /// How long a cached response stays fresh (defaults to the cache layers' TTL)
+    pub ttl: Option<Duration>,
+    /// How long after `ttl` a response may still be served stale (requires `ttl`)
+    pub stale_ttl: Option<Duration>,
+    /// Serve stale responses immediately and refresh them on a background task,
+    /// instead of refreshing first and serving stale only if that fails
+    pub revalidate_in_background: bool,
 }
 
 impl Default for CacheConfig {
@@ -120,6 +260,9 @@ impl Default for CacheConfig {
             enabled: true,
             key_prefix: None,
             cache_errors: false,
+            ttl: None,
+            stale_ttl: Some(Duration::from_secs(DEFAULT_STALE_TTL)),
+            revalidate_in_background: true,
         }
     }
 }
@@ -129,19 +272,35 @@ impl CacheConfig {
     pub fn disabled() -> Self {
         Self {
             enabled: false,
-            key_prefix: None,
-            cache_errors: false,
+            ..Self::default()
         }
     }
 
     /// Create cache config with custom key prefix
     pub fn with_prefix(prefix: impl Into<String>) -> Self {
         Self {
-            enabled: true,
             key_prefix: Some(prefix.into()),
-            cache_errors: false,
+            ..Self::default()
         }
     }
+
+    /// Expire cached responses after `ttl_secs` seconds
+    pub fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl = Some(Duration::from_secs(ttl_secs));
+        self
+    }
+
+    /// Serve expired responses for up to `stale_ttl_secs` more seconds (0 never serves them)
+    pub fn with_stale_ttl(mut self, stale_ttl_secs: u64) -> Self {
+        self.stale_ttl = (stale_ttl_secs > 0).then(|| Duration::from_secs(stale_ttl_secs));
+        self
+    }
+
+    /// Refresh stale responses before answering, serving them only when the refresh fails
+    pub fn revalidate_in_foreground(mut self) -> Self {
+        self.revalidate_in_background = false;
+        self
+    }
 }
 
 /// Client factory for creating clients when ownership is needed
@@ -155,6 +314,67 @@ impl ClientFactory {
         DocsClient::new()
             .map_err(|e| anyhow::anyhow!("{}: {}", ErrorHandler::client_creation_context(), e))
     }
+
+    /// Create the shared client from server settings, configuring the
+    /// registries and attaching the local documentation source unless the
+    /// server is configured remote-only
+    pub fn from_settings(settings: &ClientSettings) -> Result<DocsClient> {
+        let registries = settings.registry_set();
+        let default_registry = registries
+            .get(settings.default_registry_name())
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Default registry '{}' is not configured",
+                    settings.default_registry_name()
+                )
+            })?;
+
+        let config = ClientConfig {
+            retry: settings.retry_config(),
+            rate_limits: settings.rate_limit_config(),
+            fixtures: Some(settings.fixture_config()),
+            allow_http: settings.allow_http,
+            ..Default::default()
+        };
+        let client = DocsClient::with_config(config)
+            .map_err(|e| anyhow::anyhow!("{}: {}", ErrorHandler::client_creation_context(), e))?
+            .with_registry(default_registry)
+            .with_registries(registries);
+        if settings.docs_source == DocsSourceMode::RemoteOnly {
+            return Ok(client);
+        }
+
+        let source = LocalDocsSource::new(settings.local_docs_config());
+        Ok(client.with_local_docs(Arc::new(source), settings.docs_source))
+    }
+
+    /// Keep upstream responses for conditional revalidation next to the
+    /// cache directory, unless disabled in the cache settings.
+    ///
+    /// The responses live in a sibling directory so that clearing the
+    /// cache does not force every page to be downloaded again. They share
+    /// the disk cache's size limit and eviction policy, and the returned
+    /// garbage collection task must be aborted when the client is dropped.
+    pub fn with_response_store(
+        client: DocsClient,
+        settings: &CacheSettings,
+        cache_dir: &Path,
+    ) -> (DocsClient, Option<JoinHandle<()>>) {
+        if settings.response_ttl_secs == 0 {
+            return (client, None);
+        }
+        let store = DiskCache::new(cache_dir.with_file_name("rustacean-docs-responses"))
+            .with_ttl(Duration::from_secs(settings.response_ttl_secs))
+            .with_max_size(settings.disk_max_size_mb * 1024 * 1024)
+            .with_eviction_policy(settings.disk_eviction_policy);
+        let gc_task = (settings.disk_gc_interval_secs > 0)
+            .then(|| store.spawn_gc(Duration::from_secs(settings.disk_gc_interval_secs)));
+        (
+            client.with_response_store(ResponseStore::new(store)),
+            gc_task,
+        )
+    }
 }
 
 /// Standard response metadata
@@ -325,11 +545,35 @@ where
     }
 }
 
+/// Field of a cache envelope holding the response
+const CACHED_RESPONSE_FIELD: &str = "cached_response";
+/// Field of a cache envelope holding the end of the freshness window in unix milliseconds
+const FRESH_UNTIL_FIELD: &str = "fresh_until_ms";
+/// Field added to responses served from an expired cache entry
+const STALE_FIELD: &str = "stale";
+
+/// Tool operations in flight, keyed by cache key, so concurrent identical
+/// calls share one upstream fetch
+static IN_FLIGHT: LazyLock<SingleFlight<String, Result<Value, Arc<anyhow::Error>>>> =
+    LazyLock::new(SingleFlight::new);
+
+/// A cached response and whether it is still fresh
+enum CacheLookup {
+    Fresh(Value),
+    Stale(Value),
+    Miss,
+}
+
 /// Unified cache execution strategy for tools
 pub struct CacheStrategy;
 
 impl CacheStrategy {
-    /// Execute a tool with unified caching strategy
+    /// Execute a tool with unified caching strategy.
+    ///
+    /// Concurrent calls with the same cache key share a single run of
+    /// `operation`. Responses older than `ttl` but within `stale_ttl` are
+    /// served with `stale: true`, either straight away while a background task
+    /// refreshes them, or after a foreground refresh fails.
     pub async fn execute_with_cache<F, Fut, I>(
         tool_name: &str,
         _params: Value,
@@ -340,9 +584,9 @@ impl CacheStrategy {
         operation: F,
     ) -> Result<Value>
     where
-        F: FnOnce(I, Arc<DocsClient>) -> Fut + Send,
-        Fut: std::future::Future<Output = Result<Value>> + Send,
-        I: ToolInput,
+        F: FnOnce(I, Arc<DocsClient>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<Value>> + Send + 'static,
+        I: ToolInput + 'static,
     {
         // Validate input
         input.validate()?;
@@ -360,66 +604,283 @@ impl CacheStrategy {
         };
 
         // Try to get from cache first
-        {
-            let cache_guard = cache.read().await;
-            if let Ok(Some(cached_result)) = cache_guard.get(&cache_key).await {
+        let stale = match Self::lookup(cache, &cache_key, cache_config.stale_ttl).await {
+            CacheLookup::Fresh(cached_result) => {
                 trace!(
                     tool = tool_name,
                     cache_key = %cache_key,
                     "Cache hit"
                 );
+                Self::record(cache, tool_name, &cache_key, CacheOutcome::Hit).await;
                 return Ok(cached_result);
             }
-        }
+            CacheLookup::Stale(cached_result) if cache_config.revalidate_in_background => {
+                debug!(
+                    tool = tool_name,
+                    cache_key = %cache_key,
+                    "Stale cache hit, refreshing in the background"
+                );
+                Self::record(cache, tool_name, &cache_key, CacheOutcome::Stale).await;
+                Self::spawn_revalidation(
+                    tool_name,
+                    cache_key,
+                    input,
+                    cache_config,
+                    client,
+                    cache,
+                    operation,
+                );
+                return Ok(Self::mark_stale(cached_result));
+            }
+            CacheLookup::Stale(cached_result) => Some(cached_result),
+            CacheLookup::Miss => None,
+        };
 
         trace!(
             tool = tool_name,
             cache_key = %cache_key,
+            stale = stale.is_some(),
             "Cache miss, executing operation"
         );
 
         // Cache miss - execute operation
-        let result = operation(input, client.clone()).await;
-
-        // Store in cache if successful (or if cache_errors is enabled)
-        match &result {
-            Ok(response) => {
-                let cache_guard = cache.read().await;
-                if let Err(e) = cache_guard
-                    .insert(cache_key.clone(), response.clone())
-                    .await
-                {
-                    debug!(
+        let result = Self::fetch_once(
+            tool_name,
+            &cache_key,
+            input,
+            &cache_config,
+            client,
+            cache,
+            operation,
+        )
+        .await;
+        let outcome = match (&result, &stale) {
+            (Ok(_), _) => CacheOutcome::Miss,
+            (Err(_), Some(_)) => CacheOutcome::Stale,
+            (Err(_), None) => CacheOutcome::Error,
+        };
+        Self::record(cache, tool_name, &cache_key, outcome).await;
+
+        match result {
+            Ok(response) => Ok(response),
+            Err(e) => match stale {
+                Some(cached_result) => {
+                    warn!(
                         tool = tool_name,
                         cache_key = %cache_key,
                         error = %e,
-                        "Failed to cache result"
+                        "Refresh failed, serving stale cached result"
                     );
+                    Ok(Self::mark_stale(cached_result))
                 }
-                trace!(
-                    tool = tool_name,
-                    cache_key = %cache_key,
-                    "Result cached"
-                );
+                None => {
+                    if cache_config.cache_errors {
+                        // Optionally cache errors as well (usually not desired)
+                        debug!(
+                            tool = tool_name,
+                            cache_key = %cache_key,
+                            error = %e,
+                            "Caching error result"
+                        );
+                    }
+                    Err(e)
+                }
+            },
+        }
+    }
+
+    /// Count a lookup in the cache's per-tool and per-namespace metrics
+    async fn record(
+        cache: &Arc<RwLock<ServerCache>>,
+        tool_name: &str,
+        cache_key: &str,
+        outcome: CacheOutcome,
+    ) {
+        cache
+            .read()
+            .await
+            .metrics()
+            .record_lookup(tool_name, cache_key, outcome);
+    }
+
+    /// Look up a cached response and check its freshness window. Entries
+    /// cached without one are fresh until the cache expires them; entries
+    /// past it are stale for `stale_ttl` and missing after that.
+    async fn lookup(
+        cache: &Arc<RwLock<ServerCache>>,
+        cache_key: &String,
+        stale_ttl: Option<Duration>,
+    ) -> CacheLookup {
+        let cached = {
+            let cache_guard = cache.read().await;
+            cache_guard.get(cache_key).await
+        };
+        let Ok(Some(cached)) = cached else {
+            return CacheLookup::Miss;
+        };
+
+        match cached {
+            Value::Object(mut envelope)
+                if envelope.len() == 2 && envelope.contains_key(CACHED_RESPONSE_FIELD) =>
+            {
+                match envelope.get(FRESH_UNTIL_FIELD).and_then(Value::as_u64) {
+                    Some(fresh_until) => {
+                        let response = envelope.remove(CACHED_RESPONSE_FIELD).unwrap_or_default();
+                        let fresh_until = u128::from(fresh_until);
+                        let stale_until =
+                            fresh_until + stale_ttl.map(|ttl| ttl.as_millis()).unwrap_or_default();
+                        let now = now_millis();
+                        if now < fresh_until {
+                            CacheLookup::Fresh(response)
+                        } else if now < stale_until {
+                            CacheLookup::Stale(response)
+                        } else {
+                            CacheLookup::Miss
+                        }
+                    }
+                    None => CacheLookup::Fresh(Value::Object(envelope)),
+                }
+            }
+            cached => CacheLookup::Fresh(cached),
+        }
+    }
+
+    /// Cache a response, keeping it for its stale window past `ttl`
+    async fn store(
+        tool_name: &str,
+        cache: &Arc<RwLock<ServerCache>>,
+        cache_key: &str,
+        response: &Value,
+        tags: Vec<String>,
+        cache_config: &CacheConfig,
+    ) {
+        let (cached, ttl) = match (cache_config.ttl, cache_config.stale_ttl) {
+            (Some(ttl), Some(stale_ttl)) => {
+                let fresh_until = (now_millis() + ttl.as_millis()) as u64;
+                let envelope = serde_json::json!({
+                    CACHED_RESPONSE_FIELD: response,
+                    FRESH_UNTIL_FIELD: fresh_until,
+                });
+                (envelope, Some(ttl + stale_ttl))
             }
-            Err(e) if cache_config.cache_errors => {
-                // Optionally cache errors as well (usually not desired)
+            (ttl, _) => (response.clone(), ttl),
+        };
+
+        let cache_guard = cache.read().await;
+        if let Err(e) = cache_guard
+            .insert_tagged(cache_key.to_string(), cached, ttl, tags)
+            .await
+        {
+            debug!(
+                tool = tool_name,
+                cache_key = %cache_key,
+                error = %e,
+                "Failed to cache result"
+            );
+        }
+        trace!(
+            tool = tool_name,
+            cache_key = %cache_key,
+            "Result cached"
+        );
+    }
+
+    /// Run `operation` and cache its response, or wait for the identical
+    /// call already in flight and share its outcome
+    async fn fetch_once<F, Fut, I>(
+        tool_name: &str,
+        cache_key: &str,
+        input: I,
+        cache_config: &CacheConfig,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+        operation: F,
+    ) -> Result<Value>
+    where
+        F: FnOnce(I, Arc<DocsClient>) -> Fut,
+        Fut: std::future::Future<Output = Result<Value>>,
+        I: ToolInput,
+    {
+        IN_FLIGHT
+            .run(cache_key.to_string(), || async move {
+                let tags = input.cache_tags(tool_name);
+                let result = operation(input, client.clone()).await;
+                if let Ok(response) = &result {
+                    Self::store(tool_name, cache, cache_key, response, tags, cache_config).await;
+                }
+                result.map_err(Arc::new)
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+    }
+
+    /// Refresh a stale entry on a background task, unless a refresh or
+    /// fetch for it is already running
+    fn spawn_revalidation<F, Fut, I>(
+        tool_name: &str,
+        cache_key: String,
+        input: I,
+        cache_config: CacheConfig,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+        operation: F,
+    ) where
+        F: FnOnce(I, Arc<DocsClient>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<Value>> + Send + 'static,
+        I: ToolInput + 'static,
+    {
+        if IN_FLIGHT.is_running(&cache_key) {
+            trace!(tool = tool_name, cache_key = %cache_key, "Refresh already running");
+            return;
+        }
+
+        let tool_name = tool_name.to_string();
+        let client = Arc::new(
+            client
+                .as_ref()
+                .clone()
+                .with_priority(RequestPriority::Background),
+        );
+        let cache = cache.clone();
+        tokio::spawn(async move {
+            let result = Self::fetch_once(
+                &tool_name,
+                &cache_key,
+                input,
+                &cache_config,
+                &client,
+                &cache,
+                operation,
+            )
+            .await;
+            if let Err(e) = result {
                 debug!(
-                    tool = tool_name,
+                    tool = %tool_name,
                     cache_key = %cache_key,
                     error = %e,
-                    "Caching error result"
+                    "Background refresh failed, keeping stale cached result"
                 );
             }
-            Err(_) => {
-                // Don't cache errors by default
-            }
-        }
+        });
+    }
 
-        result
+    /// Flag a response as served from an expired cache entry
+    fn mark_stale(mut response: Value) -> Value {
+        if let Value::Object(fields) = &mut response {
+            fields.insert(STALE_FIELD.to_string(), Value::Bool(true));
+        }
+        response
     }
 }
 
+/// Current time in unix milliseconds
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default()
+}
+
 #[async_trait::async_trait]
 pub trait ToolHandler: Send + Sync {
     async fn execute(
@@ -432,3 +893,260 @@ pub trait ToolHandler: Send + Sync {
     fn description(&self) -> &str;
     fn parameters_schema(&self) -> Value;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::{create_test_cache, seed_stale};
+    use serde_json::json;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct TestInput {
+        name: String,
+    }
+
+    impl ToolInput for TestInput {
+        fn validate(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn cache_key(&self, tool_name: &str) -> String {
+            format!("{}:{}", tool_name, self.name)
+        }
+    }
+
+    /// Run a test tool call for `name`; each test uses its own name so calls
+    /// from tests running in parallel are not coalesced
+    async fn execute(
+        cache: &Arc<RwLock<ServerCache>>,
+        name: &str,
+        cache_config: CacheConfig,
+        result: Result<Value>,
+    ) -> Result<Value> {
+        let client = Arc::new(DocsClient::new().unwrap());
+        CacheStrategy::execute_with_cache(
+            "test_tool",
+            Value::Null,
+            TestInput {
+                name: name.to_string(),
+            },
+            cache_config,
+            &client,
+            cache,
+            |_, _| async move { result },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_fresh_responses_are_cached() {
+        let (_cache_dir, cache) = create_test_cache();
+        let config = CacheConfig::default().with_ttl(60);
+
+        let response = execute(&cache, "fresh", config.clone(), Ok(json!({"v": 1})))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({"v": 1}));
+
+        // Served from the cache without calling the failing operation
+        let response = execute(
+            &cache,
+            "fresh",
+            config,
+            Err(anyhow::anyhow!("upstream down")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, json!({"v": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_stale_hit_is_served_and_refreshed_in_background() {
+        let (_cache_dir, cache) = create_test_cache();
+        seed_stale(&cache, "test_tool:background", json!({"v": 1})).await;
+        let config = CacheConfig::default().with_ttl(60);
+
+        let response = execute(&cache, "background", config.clone(), Ok(json!({"v": 2})))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({"v": 1, "stale": true}));
+
+        // Wait for the background refresh to land
+        let mut refreshed = Value::Null;
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            refreshed = execute(
+                &cache,
+                "background",
+                config.clone(),
+                Err(anyhow::anyhow!("unused")),
+            )
+            .await
+            .unwrap();
+            if refreshed == json!({"v": 2}) {
+                break;
+            }
+        }
+        assert_eq!(refreshed, json!({"v": 2}));
+    }
+
+    #[tokio::test]
+    async fn test_stale_response_served_when_refresh_fails() {
+        let (_cache_dir, cache) = create_test_cache();
+        seed_stale(&cache, "test_tool:foreground", json!({"v": 1})).await;
+        let config = CacheConfig::default()
+            .with_ttl(60)
+            .revalidate_in_foreground();
+
+        let response = execute(
+            &cache,
+            "foreground",
+            config.clone(),
+            Err(anyhow::anyhow!("circuit breaker open")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response, json!({"v": 1, "stale": true}));
+
+        // A successful refresh replaces the stale entry
+        let response = execute(&cache, "foreground", config, Ok(json!({"v": 2})))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({"v": 2}));
+    }
+
+    #[tokio::test]
+    async fn test_failures_without_stale_response_are_errors() {
+        let (_cache_dir, cache) = create_test_cache();
+        let config = CacheConfig::default().with_ttl(60);
+        assert!(execute(
+            &cache,
+            "error",
+            config,
+            Err(anyhow::anyhow!("upstream down"))
+        )
+        .await
+        .is_err());
+
+        // Without a stale window, expired entries are not served
+        let (_cache_dir, cache) = create_test_cache();
+        seed_stale(&cache, "test_tool:error", json!({"v": 1})).await;
+        let config = CacheConfig::default()
+            .with_ttl(60)
+            .with_stale_ttl(0)
+            .revalidate_in_foreground();
+        assert!(execute(
+            &cache,
+            "error",
+            config,
+            Err(anyhow::anyhow!("upstream down"))
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lookups_are_counted_by_outcome() {
+        let (_cache_dir, cache) = create_test_cache();
+        let config = CacheConfig::default().with_ttl(60);
+
+        for _ in 0..2 {
+            execute(&cache, "metrics", config.clone(), Ok(json!({"v": 1})))
+                .await
+                .unwrap();
+        }
+        let _ = execute(
+            &cache,
+            "metrics_error",
+            config,
+            Err(anyhow::anyhow!("upstream down")),
+        )
+        .await;
+
+        let snapshot = cache.read().await.metrics().snapshot();
+        let outcomes = &snapshot.tools["test_tool"];
+        assert_eq!(
+            (
+                outcomes.hits,
+                outcomes.misses,
+                outcomes.stale,
+                outcomes.errors
+            ),
+            (1, 1, 0, 1)
+        );
+        assert_eq!(snapshot.namespaces["test_tool"].total(), 3);
+        let layers: Vec<_> = snapshot.layers.iter().map(|layer| &layer.name).collect();
+        assert_eq!(layers, ["memory", "disk"]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_calls_share_one_fetch() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let (_cache_dir, cache) = create_test_cache();
+        let client = Arc::new(DocsClient::new().unwrap());
+
+        let calls: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                let client = client.clone();
+                tokio::spawn(async move {
+                    CacheStrategy::execute_with_cache(
+                        "test_tool",
+                        Value::Null,
+                        TestInput {
+                            name: "coalesce".to_string(),
+                        },
+                        CacheConfig::default().with_ttl(60),
+                        &client,
+                        &cache,
+                        |_, _| async move {
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            let run = RUNS.fetch_add(1, Ordering::SeqCst) + 1;
+                            Ok(json!({ "run": run }))
+                        },
+                    )
+                    .await
+                })
+            })
+            .collect();
+
+        for call in calls {
+            assert_eq!(call.await.unwrap().unwrap(), json!({"run": 1}));
+        }
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cached_responses_are_tagged_for_invalidation() {
+        let (_cache_dir, cache) = create_test_cache();
+        let config = CacheConfig::default().with_ttl(60);
+        execute(&cache, "tags", config, Ok(json!({"v": 1})))
+            .await
+            .unwrap();
+
+        let other_tool = rustacean_docs_cache::CacheScope::Tool("other_tool".to_string());
+        assert_eq!(cache.read().await.invalidate(&other_tool).await.unwrap(), 0);
+        let tool = rustacean_docs_cache::CacheScope::Tool("test_tool".to_string());
+        assert_eq!(cache.read().await.invalidate(&tool).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_crate_cache_tags() {
+        assert_eq!(
+            crate_cache_tags("get_crate_docs", "docs", "Tokio", Some("1.38")),
+            vec![
+                "tool:get_crate_docs",
+                "kind:docs",
+                "crate:tokio",
+                "crate:tokio@1.38"
+            ]
+        );
+        // "latest" is not a version worth its own tag
+        assert_eq!(
+            crate_cache_tags("get_crate_docs", "docs", "tokio", Some("latest")).len(),
+            3
+        );
+    }
+}
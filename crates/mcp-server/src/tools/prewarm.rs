@@ -0,0 +1,584 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+use tokio::task::{JoinError, JoinSet};
+use tracing::{debug, info, warn};
+
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::{DocsClient, Lockfile, RequestPriority};
+use rustacean_docs_core::{Error, ErrorBuilder};
+
+use crate::tools::{
+    CrateDocsTool, CrateMetadataTool, CrateOverviewTool, ErrorHandler, ItemDocsTool,
+    ParameterValidator, ToolHandler,
+};
+
+// Type alias for our specific cache implementation
+type ServerCache = TieredCache<String, Value>;
+
+/// Page fetches running at once unless `concurrency` says otherwise
+const DEFAULT_CONCURRENCY: usize = 4;
+/// Upper bound for `concurrency`, to stay polite to docs.rs and crates.io
+const MAX_CONCURRENCY: usize = 16;
+/// Item pages fetched per crate unless `max_items` says otherwise
+const DEFAULT_MAX_ITEMS: usize = 20;
+
+/// Input parameters for the prewarm_cache tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrewarmInput {
+    /// Crates to prewarm, as `name` or `name@version`
+    #[serde(default)]
+    pub crates: Vec<String>,
+    /// `Cargo.lock` whose registry packages are prewarmed at their locked versions
+    #[serde(default)]
+    pub lockfile: Option<String>,
+    /// Configured registry to fetch from (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Item pages to fetch per crate (default: 20)
+    #[serde(default)]
+    pub max_items: Option<usize>,
+    /// Pages fetched at once (default: 4)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+impl PrewarmInput {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.crates.is_empty() && self.lockfile.is_none() {
+            return Err(ErrorBuilder::protocol()
+                .invalid_input("prewarm_cache", "specify crates, a lockfile or both"));
+        }
+        if self
+            .lockfile
+            .as_deref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            return Err(
+                ErrorBuilder::protocol().invalid_input("prewarm_cache", "lockfile cannot be empty")
+            );
+        }
+        for spec in &self.crates {
+            let target = PrewarmTarget::parse(spec);
+            ParameterValidator::validate_crate_name(&target.crate_name, "prewarm_cache")?;
+            ParameterValidator::validate_version(&target.version, "prewarm_cache")?;
+        }
+        ParameterValidator::validate_registry(&self.registry, "prewarm_cache")?;
+        if self
+            .concurrency
+            .is_some_and(|concurrency| !(1..=MAX_CONCURRENCY).contains(&concurrency))
+        {
+            return Err(ErrorBuilder::protocol().invalid_input(
+                "prewarm_cache",
+                format!("concurrency must be between 1 and {MAX_CONCURRENCY}"),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn max_items(&self) -> usize {
+        self.max_items.unwrap_or(DEFAULT_MAX_ITEMS)
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(DEFAULT_CONCURRENCY)
+    }
+
+    /// The crates named directly followed by the registry packages of the
+    /// lockfile, without duplicates
+    pub fn targets(&self) -> Result<Vec<PrewarmTarget>> {
+        let mut targets: Vec<PrewarmTarget> = self
+            .crates
+            .iter()
+            .map(|spec| PrewarmTarget::parse(spec))
+            .collect();
+
+        if let Some(path) = &self.lockfile {
+            let lockfile = Lockfile::load(Path::new(path))?;
+            targets.extend(
+                lockfile
+                    .packages()
+                    .iter()
+                    .filter(|package| package.is_registry())
+                    .map(|package| PrewarmTarget {
+                        crate_name: package.name.clone(),
+                        version: Some(package.version.to_string()),
+                    }),
+            );
+        }
+
+        let mut unique = Vec::with_capacity(targets.len());
+        for target in targets {
+            if !unique.contains(&target) {
+                unique.push(target);
+            }
+        }
+        Ok(unique)
+    }
+}
+
+/// A crate release to prewarm; `None` warms the latest release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrewarmTarget {
+    pub crate_name: String,
+    pub version: Option<String>,
+}
+
+impl PrewarmTarget {
+    /// Parse `name` or `name@version`
+    pub fn parse(spec: &str) -> Self {
+        match spec.trim().split_once('@') {
+            Some((name, version)) => Self {
+                crate_name: name.to_string(),
+                version: Some(version.to_string()),
+            },
+            None => Self {
+                crate_name: spec.trim().to_string(),
+                version: None,
+            },
+        }
+    }
+}
+
+/// A page that could not be fetched
+#[derive(Debug, Clone, Serialize)]
+pub struct PrewarmFailure {
+    /// Tool whose response was being cached, with the item path for item pages
+    pub page: String,
+    pub error: String,
+}
+
+/// Outcome of prewarming one crate
+#[derive(Debug, Clone, Serialize)]
+pub struct PrewarmedCrate {
+    pub crate_name: String,
+    pub version: Option<String>,
+    /// Pages now in the cache
+    pub pages: usize,
+    pub failures: Vec<PrewarmFailure>,
+}
+
+/// Outcome of a prewarm run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrewarmReport {
+    pub crates: Vec<PrewarmedCrate>,
+    pub pages: usize,
+    pub failed_pages: usize,
+    pub duration_ms: u128,
+}
+
+/// Tool that fetches the docs, overview, item pages and metadata of a list
+/// of crates ahead of time so later calls are served from the cache
+pub struct PrewarmCacheTool;
+
+impl PrewarmCacheTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Prewarm every target of `input`, calling `progress` as each crate
+    /// finishes
+    pub async fn prewarm(
+        &self,
+        input: PrewarmInput,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+        mut progress: impl FnMut(usize, usize, &PrewarmedCrate),
+    ) -> Result<PrewarmReport> {
+        input.validate()?;
+        let targets = input.targets()?;
+        let started = Instant::now();
+
+        info!(
+            crates = targets.len(),
+            concurrency = input.concurrency(),
+            "Prewarming cache"
+        );
+
+        // Nobody waits on prewarming, so its requests yield to tool calls
+        let client = Arc::new(
+            client
+                .as_ref()
+                .clone()
+                .with_priority(RequestPriority::Background),
+        );
+        // Each crate fetches its pages one at a time, so capping the running
+        // crates caps the requests in flight
+        let mut pending = targets.iter().cloned();
+        let mut running = HashMap::new();
+        let mut tasks = JoinSet::new();
+        let mut report = PrewarmReport::default();
+        loop {
+            while tasks.len() < input.concurrency() {
+                let Some(target) = pending.next() else {
+                    break;
+                };
+                let client = Arc::clone(&client);
+                let cache = Arc::clone(cache);
+                let registry = input.registry.clone();
+                let max_items = input.max_items();
+                let handle = tasks.spawn({
+                    let target = target.clone();
+                    async move { prewarm_crate(target, registry, max_items, &client, &cache).await }
+                });
+                running.insert(handle.id(), target);
+            }
+
+            let Some(joined) = tasks.join_next_with_id().await else {
+                break;
+            };
+            let warmed = match joined {
+                Ok((id, warmed)) => {
+                    running.remove(&id);
+                    warmed
+                }
+                Err(e) => match running.remove(&e.id()) {
+                    Some(target) => PrewarmedCrate::aborted(target, e),
+                    None => return Err(e.into()),
+                },
+            };
+            report.pages += warmed.pages;
+            report.failed_pages += warmed.failures.len();
+            progress(report.crates.len() + 1, targets.len(), &warmed);
+            report.crates.push(warmed);
+        }
+        report.duration_ms = started.elapsed().as_millis();
+
+        info!(
+            crates = report.crates.len(),
+            pages = report.pages,
+            failed_pages = report.failed_pages,
+            duration_ms = report.duration_ms as u64,
+            "Cache prewarmed"
+        );
+        Ok(report)
+    }
+}
+
+impl Default for PrewarmCacheTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fetch the pages of one crate through the regular tools, one at a time
+async fn prewarm_crate(
+    target: PrewarmTarget,
+    registry: Option<String>,
+    max_items: usize,
+    client: &Arc<DocsClient>,
+    cache: &Arc<RwLock<ServerCache>>,
+) -> PrewarmedCrate {
+    let mut warmed = PrewarmedCrate {
+        crate_name: target.crate_name.clone(),
+        version: target.version.clone(),
+        pages: 0,
+        failures: Vec::new(),
+    };
+    let mut params = json!({ "crate_name": target.crate_name });
+    if let Some(version) = &target.version {
+        params["version"] = json!(version);
+    }
+    if let Some(registry) = &registry {
+        params["registry"] = json!(registry);
+    }
+
+    let pages: [(&str, &dyn ToolHandler); 3] = [
+        ("get_crate_docs", &CrateDocsTool::new()),
+        ("get_crate_overview", &CrateOverviewTool::new()),
+        ("get_crate_metadata", &CrateMetadataTool::new()),
+    ];
+    let mut item_paths = Vec::new();
+    for (page, tool) in pages {
+        match tool.execute(params.clone(), client, cache).await {
+            Ok(response) => {
+                warmed.pages += 1;
+                if page == "get_crate_docs" {
+                    item_paths = item_paths_of(&response, max_items);
+                }
+            }
+            Err(e) => warmed.record_failure(page.to_string(), e),
+        }
+    }
+
+    for item_path in item_paths {
+        let mut params = params.clone();
+        params["item_path"] = json!(item_path);
+        match ItemDocsTool::new().execute(params, client, cache).await {
+            Ok(_) => warmed.pages += 1,
+            Err(e) => warmed.record_failure(format!("get_item_docs:{item_path}"), e),
+        }
+    }
+
+    debug!(
+        crate_name = %warmed.crate_name,
+        version = ?warmed.version,
+        pages = warmed.pages,
+        failures = warmed.failures.len(),
+        "Crate prewarmed"
+    );
+    warmed
+}
+
+impl PrewarmedCrate {
+    /// The outcome of a crate whose task panicked or was cancelled
+    fn aborted(target: PrewarmTarget, error: JoinError) -> Self {
+        let mut warmed = Self {
+            crate_name: target.crate_name,
+            version: target.version,
+            pages: 0,
+            failures: Vec::new(),
+        };
+        warmed.record_failure("prewarm_cache".to_string(), error.into());
+        warmed
+    }
+
+    fn record_failure(&mut self, page: String, error: anyhow::Error) {
+        warn!(crate_name = %self.crate_name, page = %page, "Prewarm fetch failed: {error}");
+        self.failures.push(PrewarmFailure {
+            page,
+            error: error.to_string(),
+        });
+    }
+}
+
+/// The paths of the first `max_items` items listed in a get_crate_docs response
+fn item_paths_of(docs: &Value, max_items: usize) -> Vec<String> {
+    docs["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item["path"].as_str())
+        .take(max_items)
+        .map(str::to_string)
+        .collect()
+}
+
+#[async_trait]
+impl ToolHandler for PrewarmCacheTool {
+    async fn execute(
+        &self,
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Value> {
+        debug!("Executing prewarm_cache tool with params: {}", params);
+
+        let input: PrewarmInput = serde_json::from_value(params).map_err(|e| {
+            anyhow::anyhow!(
+                "{}: {}",
+                ErrorHandler::parameter_parsing_context("prewarm_cache"),
+                e
+            )
+        })?;
+
+        let report = self
+            .prewarm(input, client, cache, |done, total, warmed| {
+                info!(
+                    crate_name = %warmed.crate_name,
+                    pages = warmed.pages,
+                    failures = warmed.failures.len(),
+                    "Prewarmed {done}/{total} crates"
+                );
+            })
+            .await?;
+
+        Ok(serde_json::to_value(report)?)
+    }
+
+    fn description(&self) -> &str {
+        "Preload the cache for a list of crates or every registry package in a Cargo.lock: crate docs, overview, metadata and the first item pages of each crate are fetched with bounded concurrency, so later calls work quickly or offline. Reports the pages cached and the fetches that failed for each crate."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crates": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Crates to prewarm, as \"name\" for the latest release or \"name@version\"",
+                    "examples": [["serde", "tokio@1.38.0"]]
+                },
+                "lockfile": {
+                    "type": "string",
+                    "description": "Path to a Cargo.lock; every registry package is prewarmed at its locked version"
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to fetch from instead of the default"
+                },
+                "max_items": {
+                    "type": "integer",
+                    "description": "Item pages to fetch per crate (0 skips item pages)",
+                    "minimum": 0,
+                    "default": DEFAULT_MAX_ITEMS
+                },
+                "concurrency": {
+                    "type": "integer",
+                    "description": "Pages fetched at once",
+                    "minimum": 1,
+                    "maximum": MAX_CONCURRENCY,
+                    "default": DEFAULT_CONCURRENCY
+                }
+            },
+            "required": [],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::create_test_cache;
+    use rustacean_docs_cache::Cache;
+    use rustacean_docs_client::Registry;
+
+    #[test]
+    fn test_prewarm_input_validation() {
+        let input = |params: Value| serde_json::from_value::<PrewarmInput>(params).unwrap();
+
+        assert!(input(json!({"crates": ["serde", "tokio@1.38.0"]}))
+            .validate()
+            .is_ok());
+        assert!(input(json!({})).validate().is_err());
+        assert!(input(json!({"crates": ["not a crate"]}))
+            .validate()
+            .is_err());
+        assert!(input(json!({"crates": ["serde@"]})).validate().is_err());
+        assert!(input(json!({"lockfile": " "})).validate().is_err());
+        assert!(input(json!({"crates": ["serde"], "concurrency": 0}))
+            .validate()
+            .is_err());
+        assert!(serde_json::from_value::<PrewarmInput>(json!({"crate": "serde"})).is_err());
+    }
+
+    #[test]
+    fn test_targets_merge_crates_and_lockfile() {
+        let temp = tempfile::tempdir().unwrap();
+        let project_dir = temp.path().to_path_buf();
+        let lockfile = project_dir.join("Cargo.lock");
+        std::fs::write(
+            &lockfile,
+            r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["serde"]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        let input = PrewarmInput {
+            crates: vec!["tokio".to_string(), "serde@1.0.200".to_string()],
+            lockfile: Some(lockfile.display().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            input.targets().unwrap(),
+            vec![
+                PrewarmTarget {
+                    crate_name: "tokio".to_string(),
+                    version: None
+                },
+                PrewarmTarget {
+                    crate_name: "serde".to_string(),
+                    version: Some("1.0.200".to_string())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_paths_of_crate_docs() {
+        let docs = json!({
+            "items": [
+                {"name": "Serialize", "path": "trait.Serialize.html"},
+                {"name": "Error", "path": "de/struct.Error.html"},
+            ]
+        });
+        assert_eq!(item_paths_of(&docs, 1), vec!["trait.Serialize.html"]);
+        assert_eq!(item_paths_of(&docs, 10).len(), 2);
+        assert!(item_paths_of(&json!({}), 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_panicked_crate_is_reported_as_failed() {
+        let error = tokio::spawn(async { panic!("parser blew up") })
+            .await
+            .unwrap_err();
+        let warmed = PrewarmedCrate::aborted(PrewarmTarget::parse("serde@1.0.200"), error);
+
+        assert_eq!(warmed.crate_name, "serde");
+        assert_eq!(warmed.version.as_deref(), Some("1.0.200"));
+        assert_eq!(warmed.pages, 0);
+        assert_eq!(warmed.failures.len(), 1);
+        assert_eq!(warmed.failures[0].page, "prewarm_cache");
+        assert!(warmed.failures[0].error.contains("panicked"));
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_reports_failures_per_page() {
+        // Every fetch fails fast; the crate docs are already cached
+        let registry =
+            Registry::new("offline", "https://127.0.0.1:9").with_api_url("https://127.0.0.1:9");
+        let client = Arc::new(DocsClient::new().unwrap().with_registry(registry));
+        let (_cache_dir, cache) = create_test_cache();
+        cache
+            .read()
+            .await
+            .insert(
+                "get_crate_docs:demo:0.9.1".to_string(),
+                json!({"name": "demo", "version": "0.9.1", "items": [{"path": "struct.Demo.html"}]}),
+            )
+            .await
+            .unwrap();
+
+        let mut progress = Vec::new();
+        let report = PrewarmCacheTool::new()
+            .prewarm(
+                PrewarmInput {
+                    crates: vec!["demo@0.9.1".to_string()],
+                    ..Default::default()
+                },
+                &client,
+                &cache,
+                |done, total, warmed| progress.push((done, total, warmed.crate_name.clone())),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(progress, vec![(1, 1, "demo".to_string())]);
+        assert_eq!(report.crates.len(), 1);
+        assert_eq!(report.pages, 1);
+        let pages: Vec<_> = report.crates[0]
+            .failures
+            .iter()
+            .map(|failure| failure.page.as_str())
+            .collect();
+        assert_eq!(
+            pages,
+            vec![
+                "get_crate_overview",
+                "get_crate_metadata",
+                "get_item_docs:struct.Demo.html"
+            ]
+        );
+        assert_eq!(report.failed_pages, 3);
+    }
+}
@@ -7,7 +7,10 @@ use tracing::{debug, instrument};
 
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::{endpoints::releases::ReleasesService, DocsClient};
-use rustacean_docs_core::{models::docs::RecentReleasesRequest, Error};
+use rustacean_docs_core::{
+    constants::DEFAULT_RECENT_RELEASES_TTL, kind_tag, models::docs::RecentReleasesRequest,
+    tool_tag, Error,
+};
 
 use crate::tools::{
     CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
@@ -33,6 +36,10 @@ impl ToolInput for ReleasesToolInput {
     fn cache_key(&self, tool_name: &str) -> String {
         format!("{}:limit:{}", tool_name, self.limit.unwrap_or(20))
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        vec![tool_tag(tool_name), kind_tag("releases")]
+    }
 }
 
 impl ReleasesToolInput {
@@ -87,7 +94,7 @@ impl ToolHandler for RecentReleasesTool {
             "list_recent_releases",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_RECENT_RELEASES_TTL),
             client,
             cache,
             |input, client| async move {
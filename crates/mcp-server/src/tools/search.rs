@@ -7,11 +7,13 @@ use tracing::debug;
 
 use rustacean_docs_cache::TieredCache;
 use rustacean_docs_client::DocsClient;
-use rustacean_docs_core::{models::search::SearchRequest, Error};
+use rustacean_docs_core::{
+    constants::DEFAULT_SEARCH_TTL, kind_tag, models::search::SearchRequest, tool_tag, Error,
+};
 
 use crate::tools::{
-    CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator, ToolErrorContext, ToolHandler,
-    ToolInput,
+    registry_cache_scope, CacheConfig, CacheStrategy, ErrorHandler, ParameterValidator,
+    ToolErrorContext, ToolHandler, ToolInput,
 };
 
 // Type alias for our specific cache implementation
@@ -24,18 +26,27 @@ pub struct SearchToolInput {
     pub query: String,
     /// Maximum number of results to return (default: 10, max recommended: 50)
     pub limit: Option<usize>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
 }
 
 impl ToolInput for SearchToolInput {
     fn validate(&self) -> Result<(), Error> {
         ParameterValidator::validate_query(&self.query, "search_crate")?;
         ParameterValidator::validate_limit(&self.limit, "search_crate", 100)?;
+        ParameterValidator::validate_registry(&self.registry, "search_crate")?;
         Ok(())
     }
 
     fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
         format!("{}:{}:{}", tool_name, self.query, self.limit.unwrap_or(10))
     }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        vec![tool_tag(tool_name), kind_tag("search")]
+    }
 }
 
 impl SearchToolInput {
@@ -87,7 +98,7 @@ impl ToolHandler for SearchTool {
             "search_crate",
             params,
             input,
-            CacheConfig::default(),
+            CacheConfig::default().with_ttl(DEFAULT_SEARCH_TTL),
             client,
             cache,
             |input, client| async move {
@@ -96,6 +107,7 @@ impl ToolHandler for SearchTool {
 
                 // Execute search
                 let search_response = client
+                    .for_registry(input.registry.as_deref())?
                     .search_crates(search_request)
                     .await
                     .search_context(&input.query)?;
@@ -133,6 +145,10 @@ impl ToolHandler for SearchTool {
                     "minimum": 1,
                     "maximum": 100,
                     "default": 10
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
                 }
             },
             "required": ["query"],
@@ -158,6 +174,7 @@ mod tests {
         let valid_input = SearchToolInput {
             query: "tokio".to_string(),
             limit: Some(20),
+            registry: None,
         };
         assert!(valid_input.validate().is_ok());
 
@@ -165,6 +182,7 @@ mod tests {
         let empty_query = SearchToolInput {
             query: "".to_string(),
             limit: None,
+            registry: None,
         };
         assert!(empty_query.validate().is_err());
 
@@ -172,6 +190,7 @@ mod tests {
         let whitespace_query = SearchToolInput {
             query: "   ".to_string(),
             limit: None,
+            registry: None,
         };
         assert!(whitespace_query.validate().is_err());
 
@@ -179,6 +198,7 @@ mod tests {
         let zero_limit = SearchToolInput {
             query: "serde".to_string(),
             limit: Some(0),
+            registry: None,
         };
         assert!(zero_limit.validate().is_err());
 
@@ -186,6 +206,7 @@ mod tests {
         let high_limit = SearchToolInput {
             query: "serde".to_string(),
             limit: Some(200),
+            registry: None,
         };
         assert!(high_limit.validate().is_err());
 
@@ -193,6 +214,7 @@ mod tests {
         let no_limit = SearchToolInput {
             query: "reqwest".to_string(),
             limit: None,
+            registry: None,
         };
         assert!(no_limit.validate().is_ok());
     }
@@ -202,6 +224,7 @@ mod tests {
         let input_with_limit = SearchToolInput {
             query: "tokio".to_string(),
             limit: Some(25),
+            registry: None,
         };
         let request = input_with_limit.to_search_request();
         assert_eq!(request.query, "tokio");
@@ -210,6 +233,7 @@ mod tests {
         let input_no_limit = SearchToolInput {
             query: "serde".to_string(),
             limit: None,
+            registry: None,
         };
         let request = input_no_limit.to_search_request();
         assert_eq!(request.query, "serde");
@@ -221,6 +245,7 @@ mod tests {
         let input1 = SearchToolInput {
             query: "tokio".to_string(),
             limit: Some(20),
+            registry: None,
         };
         let key1 = input1.cache_key("search");
         assert_eq!(key1, "search:tokio:20");
@@ -228,6 +253,7 @@ mod tests {
         let input2 = SearchToolInput {
             query: "serde".to_string(),
             limit: None,
+            registry: None,
         };
         let key2 = input2.cache_key("search");
         assert_eq!(key2, "search:serde:10");
@@ -236,9 +262,25 @@ mod tests {
         let input3 = SearchToolInput {
             query: "tokio".to_string(),
             limit: Some(30),
+            registry: None,
         };
         let key3 = input3.cache_key("search");
         assert_ne!(key1, key3);
+
+        // Alternate registries get their own cache namespace
+        let input4 = SearchToolInput {
+            query: "tokio".to_string(),
+            limit: Some(20),
+            registry: Some("internal".to_string()),
+        };
+        assert!(input4.validate().is_ok());
+        assert_eq!(input4.cache_key("search"), "search@internal:tokio:20");
+
+        let invalid_registry = SearchToolInput {
+            registry: Some("not a registry".to_string()),
+            ..input4
+        };
+        assert!(invalid_registry.validate().is_err());
     }
 
     #[test]
@@ -246,6 +288,7 @@ mod tests {
         let input = SearchToolInput {
             query: "async-trait".to_string(),
             limit: Some(15),
+            registry: None,
         };
 
         // Test serialization
@@ -381,6 +424,7 @@ mod tests {
             let input = SearchToolInput {
                 query: "test-crate".to_string(),
                 limit: Some(20),
+                registry: None,
             };
 
             let cache_key = input.cache_key("search");
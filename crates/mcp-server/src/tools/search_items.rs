@@ -0,0 +1,459 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use rustacean_docs_cache::TieredCache;
+use rustacean_docs_client::{DocsClient, ItemSearchIndex};
+use rustacean_docs_core::{
+    constants::MAX_SEARCH_LIMIT,
+    models::{docs::ItemKind, search::ItemSearchRequest},
+    types::{CrateName, Version},
+    Error, ErrorBuilder,
+};
+
+use crate::tools::{
+    crate_cache_tags, crate_docs::CrateDocsTool, registry_cache_scope, CacheStrategy, ErrorHandler,
+    ParameterValidator, ToolHandler, ToolInput, VersionResolution,
+};
+
+// Type alias for our specific cache implementation
+type ServerCache = TieredCache<String, Value>;
+
+/// Item kinds accepted by the `kind` parameter
+const KIND_NAMES: &[&str] = &[
+    "module",
+    "struct",
+    "enum",
+    "trait",
+    "function",
+    "method",
+    "macro",
+    "constant",
+    "type_alias",
+    "union",
+];
+
+/// Input parameters for the search_items tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemSearchToolInput {
+    /// Name of the crate whose items are searched
+    pub crate_name: String,
+    /// Item name, documentation words, or a signature such as `fn(&str) -> Result<_, _>`
+    pub query: String,
+    /// Specific version to query (defaults to latest stable version)
+    pub version: Option<String>,
+    /// Configured registry to query (defaults to the server's default registry)
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// Only return items of this kind (e.g. "struct", "function")
+    pub kind: Option<String>,
+    /// Maximum number of results to return (default: 10, max: 50)
+    pub limit: Option<usize>,
+}
+
+impl ToolInput for ItemSearchToolInput {
+    fn validate(&self) -> Result<(), Error> {
+        ParameterValidator::validate_crate_name(&self.crate_name, "search_items")?;
+        ParameterValidator::validate_query(&self.query, "search_items")?;
+        ParameterValidator::validate_version(&self.version, "search_items")?;
+        ParameterValidator::validate_registry(&self.registry, "search_items")?;
+        ParameterValidator::validate_limit(&self.limit, "search_items", MAX_SEARCH_LIMIT)?;
+        self.item_kind()?;
+        Ok(())
+    }
+
+    fn cache_key(&self, tool_name: &str) -> String {
+        let tool_name = &registry_cache_scope(tool_name, &self.registry);
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            tool_name,
+            self.crate_name,
+            self.version.as_deref().unwrap_or("latest"),
+            self.query,
+            self.kind.as_deref().unwrap_or("any"),
+            self.limit.unwrap_or(10)
+        )
+    }
+
+    fn cache_tags(&self, tool_name: &str) -> Vec<String> {
+        crate_cache_tags(tool_name, "docs", &self.crate_name, self.version.as_deref())
+    }
+}
+
+impl ItemSearchToolInput {
+    /// Parse the `kind` filter, accepting the short forms used in Rust source
+    pub fn item_kind(&self) -> Result<Option<ItemKind>, Error> {
+        let Some(kind) = &self.kind else {
+            return Ok(None);
+        };
+
+        let kind = match kind.trim().to_lowercase().as_str() {
+            "module" | "mod" => ItemKind::Module,
+            "struct" => ItemKind::Struct,
+            "enum" => ItemKind::Enum,
+            "trait" => ItemKind::Trait,
+            "function" | "fn" => ItemKind::Function,
+            "method" => ItemKind::Method,
+            "macro" => ItemKind::Macro,
+            "constant" | "const" => ItemKind::Constant,
+            "type_alias" | "type" => ItemKind::TypeAlias,
+            "union" => ItemKind::Union,
+            other => {
+                return Err(ErrorBuilder::protocol().invalid_input(
+                    "search_items",
+                    format!(
+                        "unknown kind '{other}', expected one of: {}",
+                        KIND_NAMES.join(", ")
+                    ),
+                ))
+            }
+        };
+        Ok(Some(kind))
+    }
+
+    /// Convert to internal ItemSearchRequest
+    pub fn to_item_search_request(&self) -> Result<ItemSearchRequest, Error> {
+        let crate_name = CrateName::new(&self.crate_name)
+            .map_err(|e| Error::Internal(format!("Invalid crate name: {e}")))?;
+
+        let mut request = ItemSearchRequest::new(crate_name, &self.query);
+        if let Some(version) = &self.version {
+            let version = Version::new(version)
+                .map_err(|e| Error::Internal(format!("Invalid version: {e}")))?;
+            request = request.with_version(version);
+        }
+        if let Some(kind) = self.item_kind()? {
+            request = request.with_kind(kind);
+        }
+        if let Some(limit) = self.limit {
+            request = request.with_limit(limit);
+        }
+        Ok(request)
+    }
+}
+
+/// Tool handler for ranked search over the items of a single crate
+pub struct SearchItemsTool;
+
+impl SearchItemsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SearchItemsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for SearchItemsTool {
+    async fn execute(
+        &self,
+        params: Value,
+        client: &Arc<DocsClient>,
+        cache: &Arc<RwLock<ServerCache>>,
+    ) -> Result<Value> {
+        debug!("Executing search_items tool with params: {}", params);
+
+        // Parse input parameters
+        let mut input: ItemSearchToolInput = serde_json::from_value(params).map_err(|e| {
+            anyhow::anyhow!(
+                "{}: {}",
+                ErrorHandler::parameter_parsing_context("search_items"),
+                e
+            )
+        })?;
+        input.validate()?;
+
+        // Resolve semver requirements such as `^1.2` to a concrete release
+        let resolution = VersionResolution::resolve(
+            "search_items",
+            &input.crate_name,
+            &mut input.version,
+            input.registry.as_deref(),
+            client,
+        )
+        .await?;
+        let request = input.to_item_search_request()?;
+
+        // The index is built from the crate docs, which are cached under
+        // get_crate_docs so every query against a release shares one fetch
+        let (docs, stale) = CrateDocsTool::fetch(
+            json!({
+                "crate_name": input.crate_name,
+                "version": input.version,
+                "registry": input.registry,
+            }),
+            client,
+            cache,
+        )
+        .await?;
+
+        let index = ItemSearchIndex::from_docs(&docs);
+        let response = index.search(&request);
+
+        debug!(
+            crate_name = %response.crate_name,
+            version = %response.version,
+            indexed_items = index.len(),
+            total = response.total,
+            mode = ?response.mode,
+            "Item search completed"
+        );
+
+        let response = VersionResolution::annotate(resolution, serde_json::to_value(response)?);
+        Ok(if stale {
+            CacheStrategy::mark_stale(response)
+        } else {
+            response
+        })
+    }
+
+    fn description(&self) -> &str {
+        "Search the items of a crate by name, documentation text, or type signature. Text queries rank exact name matches first, then partial name, path and documentation matches; signature queries such as \"fn(&str) -> Result<_, _>\" or \"-> Vec<u8>\" match function parameter and return types, with _ and single capital letters as wildcards. Returns each item's kind, path, summary and docs page."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate (e.g., \"tokio\")"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Item name, words from the documentation, or a type signature",
+                    "examples": ["Mutex", "spawn blocking", "sync::Mutex", "fn(&str) -> Result<_, _>", "-> Bytes"]
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version (defaults to latest stable version), or a semver requirement such as \"^1.2\" resolved to the newest matching release"
+                },
+                "registry": {
+                    "type": "string",
+                    "description": "Name of a configured registry to query instead of the default (e.g. an internal registry)"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Only return items of this kind",
+                    "enum": KIND_NAMES
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results to return",
+                    "minimum": 1,
+                    "maximum": MAX_SEARCH_LIMIT,
+                    "default": 10
+                }
+            },
+            "required": ["crate_name", "query"],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::{create_test_cache, seed_stale};
+    use rustacean_docs_cache::Cache;
+    use rustacean_docs_core::models::docs::{
+        CrateCategories, CrateDocsResponse, CrateItem, CrateSummary, Visibility,
+    };
+
+    fn sample_docs() -> CrateDocsResponse {
+        let item =
+            |name: &str, kind: ItemKind, path: &str, summary: &str, signature: &str| CrateItem {
+                name: name.to_string(),
+                kind,
+                summary: Some(summary.to_string()),
+                path: path.to_string(),
+                visibility: Visibility::Public,
+                is_async: false,
+                signature: Some(signature.to_string()),
+                docs_path: Some(path.to_string()),
+                deprecation: None,
+            };
+
+        CrateDocsResponse {
+            name: "demo".to_string(),
+            version: "1.0.0".to_string(),
+            summary: CrateSummary {
+                description: None,
+                module_count: 0,
+                struct_count: 1,
+                trait_count: 0,
+                function_count: 1,
+                enum_count: 0,
+                features: vec![],
+            },
+            categories: CrateCategories {
+                core_types: vec![],
+                traits: vec![],
+                modules: vec![],
+                functions: vec![],
+                macros: vec![],
+                constants: vec![],
+            },
+            items: vec![
+                item(
+                    "Config",
+                    ItemKind::Struct,
+                    "struct.Config.html",
+                    "Parsed configuration",
+                    "pub struct Config",
+                ),
+                item(
+                    "parse_config",
+                    ItemKind::Function,
+                    "fn.parse_config.html",
+                    "Parse a configuration string",
+                    "pub fn parse_config(input: &str) -> Result<Config, Error>",
+                ),
+            ],
+            examples: vec![],
+            docs_url: None,
+        }
+    }
+
+    fn input(query: &str) -> ItemSearchToolInput {
+        ItemSearchToolInput {
+            crate_name: "demo".to_string(),
+            query: query.to_string(),
+            version: None,
+            registry: None,
+            kind: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn test_item_search_tool_input_validation() {
+        assert!(input("Config").validate().is_ok());
+        assert!(input("  ").validate().is_err());
+
+        let mut with_kind = input("Config");
+        with_kind.kind = Some("fn".to_string());
+        assert!(with_kind.validate().is_ok());
+        assert_eq!(with_kind.item_kind().unwrap(), Some(ItemKind::Function));
+
+        with_kind.kind = Some("widget".to_string());
+        assert!(with_kind.validate().is_err());
+
+        let mut too_many = input("Config");
+        too_many.limit = Some(MAX_SEARCH_LIMIT + 1);
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_cache_key_generation() {
+        let mut search = input("Config");
+        assert_eq!(
+            search.cache_key("search_items"),
+            "search_items:demo:latest:Config:any:10"
+        );
+
+        search.kind = Some("struct".to_string());
+        search.registry = Some("internal".to_string());
+        assert_eq!(
+            search.cache_key("search_items"),
+            "search_items@internal:demo:latest:Config:struct:10"
+        );
+    }
+
+    #[test]
+    fn test_parameters_schema() {
+        let schema = SearchItemsTool::new().parameters_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("query"));
+        assert!(properties.contains_key("kind"));
+        assert!(properties.contains_key("registry"));
+        assert_eq!(schema["required"], json!(["crate_name", "query"]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_uses_cached_crate_docs() {
+        let tool = SearchItemsTool::new();
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+
+        // Seed the entry get_crate_docs would have cached so no request is made
+        cache
+            .read()
+            .await
+            .insert(
+                "get_crate_docs:demo:latest".to_string(),
+                serde_json::to_value(sample_docs()).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let result = tool
+            .execute(
+                json!({"crate_name": "demo", "query": "config"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["mode"], "text");
+        assert_eq!(result["total"], 2);
+        assert_eq!(result["results"][0]["name"], "Config");
+        assert!(result.get("stale").is_none());
+
+        let result = tool
+            .execute(
+                json!({"crate_name": "demo", "query": "fn(&str) -> Result<_, _>"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["mode"], "signature");
+        assert_eq!(result["results"][0]["path"], "parse_config");
+    }
+
+    #[tokio::test]
+    async fn test_execute_missing_query() {
+        let tool = SearchItemsTool::new();
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+
+        let result = tool
+            .execute(json!({"crate_name": "demo"}), &client, &cache)
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid input parameters"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_marks_results_from_stale_crate_docs() {
+        let client = Arc::new(DocsClient::new().unwrap());
+        let (_cache_dir, cache) = create_test_cache();
+        seed_stale(
+            &cache,
+            "get_crate_docs:demo:latest",
+            serde_json::to_value(sample_docs()).unwrap(),
+        )
+        .await;
+
+        let result = SearchItemsTool::new()
+            .execute(
+                json!({"crate_name": "demo", "query": "config"}),
+                &client,
+                &cache,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["total"], 2);
+        assert_eq!(result["stale"], true);
+    }
+}
@@ -0,0 +1,42 @@
+//! Helpers shared by the tool tests
+
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+use rustacean_docs_cache::{Cache, DiskCache, MemoryCache, TieredCache, WriteStrategy};
+
+use super::cache_backend::MemoryCacheWrapper;
+use super::{now_millis, CACHED_RESPONSE_FIELD, FRESH_UNTIL_FIELD};
+
+type ServerCache = TieredCache<String, Value>;
+
+/// A cache layered like the server's, a memory layer in front of a disk
+/// cache, in a fresh temporary directory that is removed when the returned
+/// `TempDir` is dropped
+pub(crate) fn create_test_cache() -> (TempDir, Arc<RwLock<ServerCache>>) {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = ServerCache::new(
+        vec![
+            Box::new(MemoryCacheWrapper(MemoryCache::new(100))),
+            Box::new(DiskCache::new(dir.path())),
+        ],
+        WriteStrategy::WriteThrough,
+    );
+    (dir, Arc::new(RwLock::new(cache)))
+}
+
+/// Seed `key` with a response whose freshness window ended a second ago
+pub(crate) async fn seed_stale(cache: &Arc<RwLock<ServerCache>>, key: &str, response: Value) {
+    let fresh_until = (now_millis() - 1000) as u64;
+    cache
+        .read()
+        .await
+        .insert(
+            key.to_string(),
+            json!({ CACHED_RESPONSE_FIELD: response, FRESH_UNTIL_FIELD: fresh_until }),
+        )
+        .await
+        .unwrap();
+}
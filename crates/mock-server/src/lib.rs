@@ -0,0 +1,241 @@
+//! Local stand-in for docs.rs and crates.io
+//!
+//! Serves a [`FixtureTree`] over HTTP on a single address: crates.io API
+//! requests under `/api/v1`, sparse index files under `/index`, and every
+//! other path from the docs tree, laid out like docs.rs. Point a
+//! [`DocsClient`] at it with [`MockServer::client`], or the server binaries
+//! with the variables from [`MockServer::env`], to run end-to-end without
+//! network access.
+
+pub mod tree;
+
+pub use tree::{FixtureTree, SearchParams};
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Path as UrlPath, Query, State},
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use rustacean_docs_client::{
+    fixtures::DEFAULT_FIXTURE_DIR, ClientConfig, DocsClient, FixtureConfig, FixtureMode,
+    RateLimitConfig,
+};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// The fixture tree shipped with this crate
+pub fn bundled_fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Routes answering docs.rs, crates.io API and sparse index requests from
+/// `tree`
+pub fn router(tree: Arc<FixtureTree>) -> Router {
+    Router::new()
+        .route("/api/v1/crates", get(search))
+        .route("/api/v1/crates/{name}", get(crate_info))
+        .route("/api/v1/crates/{name}/versions", get(versions))
+        .route(
+            "/api/v1/crates/{name}/{version}/dependencies",
+            get(dependencies),
+        )
+        .route("/index/{*path}", get(index_file))
+        .fallback(docs_file)
+        .with_state(tree)
+}
+
+/// A running mock server, stopped when dropped
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Serve the fixture tree at `root` on a free local port
+    pub async fn start(root: impl Into<PathBuf>) -> Result<Self> {
+        Self::bind(root, SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    /// Serve the fixture tree at `root` on `addr`
+    pub async fn bind(root: impl Into<PathBuf>, addr: SocketAddr) -> Result<Self> {
+        let tree = FixtureTree::load(root)?;
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind {addr}"))?;
+        let addr = listener.local_addr()?;
+        debug!(addr = %addr, root = %tree.root().display(), "Mock server listening");
+
+        let app = router(Arc::new(tree));
+        let task = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!(error = %e, "Mock server stopped");
+            }
+        });
+
+        Ok(Self {
+            url: format!("http://{addr}"),
+            task,
+        })
+    }
+
+    /// Base URL of the docs pages and the crates.io API
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Base URL of the sparse index
+    pub fn index_url(&self) -> String {
+        format!("{}/index", self.url)
+    }
+
+    /// A client sending every docs.rs and crates.io request to this server,
+    /// without rate limits
+    pub fn client(&self) -> Result<DocsClient> {
+        let config = ClientConfig {
+            allow_http: true,
+            rate_limits: RateLimitConfig::unlimited(),
+            fixtures: Some(FixtureConfig {
+                mode: FixtureMode::Off,
+                dir: PathBuf::from(DEFAULT_FIXTURE_DIR),
+            }),
+            ..Default::default()
+        };
+        Ok(DocsClient::with_config(config)?
+            .with_base_url(self.url.clone())
+            .with_api_url(self.url.clone())
+            .with_index_url(self.index_url()))
+    }
+
+    /// Environment variables pointing the server binaries at this server
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("RUSTACEAN_DOCS_BASE_URL", self.url.clone()),
+            ("RUSTACEAN_DOCS_CRATES_IO_API_URL", self.url.clone()),
+            ("RUSTACEAN_DOCS_CRATES_IO_INDEX_URL", self.index_url()),
+            ("RUSTACEAN_DOCS_ALLOW_HTTP", "true".to_string()),
+        ]
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+type SharedTree = State<Arc<FixtureTree>>;
+
+async fn search(State(tree): SharedTree, Query(params): Query<SearchParams>) -> Response {
+    Json(tree.search(&params)).into_response()
+}
+
+async fn crate_info(State(tree): SharedTree, UrlPath(name): UrlPath<String>) -> Response {
+    match tree.crate_response(&name) {
+        Some(response) => Json(response.clone()).into_response(),
+        None => api_not_found(),
+    }
+}
+
+async fn versions(State(tree): SharedTree, UrlPath(name): UrlPath<String>) -> Response {
+    match tree.versions_response(&name) {
+        Some(response) => Json(response).into_response(),
+        None => api_not_found(),
+    }
+}
+
+async fn dependencies(
+    State(tree): SharedTree,
+    UrlPath((name, version)): UrlPath<(String, String)>,
+) -> Response {
+    match tree.dependencies_response(&name, &version) {
+        Ok(Some(response)) => Json(response).into_response(),
+        Ok(None) => api_not_found(),
+        Err(e) => {
+            warn!(error = %e, "Unreadable dependencies fixture");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+async fn index_file(State(tree): SharedTree, UrlPath(path): UrlPath<String>) -> Response {
+    serve_file(tree.index_file(&path)).await
+}
+
+async fn docs_file(State(tree): SharedTree, uri: Uri) -> Response {
+    serve_file(tree.docs_file(uri.path())).await
+}
+
+/// crates.io's answer for unknown crates and versions
+fn api_not_found() -> Response {
+    let body = json!({ "errors": [{ "detail": "Not Found" }] });
+    (StatusCode::NOT_FOUND, Json(body)).into_response()
+}
+
+async fn serve_file(path: Option<PathBuf>) -> Response {
+    let Some(path) = path else {
+        return (StatusCode::NOT_FOUND, "Not Found").into_response();
+    };
+    match tokio::fs::read(&path).await {
+        Ok(body) => ([(header::CONTENT_TYPE, content_type(&path))], body).into_response(),
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Unreadable docs fixture");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str());
+    let file_name = path.file_name().and_then(|n| n.to_str());
+    match (extension, file_name) {
+        (Some("html"), _) => "text/html; charset=utf-8",
+        (Some("js"), _) => "application/javascript",
+        (Some("json"), _) | (None, Some("json")) => "application/json",
+        (Some("css"), _) => "text/css",
+        (Some("svg"), _) => "image/svg+xml",
+        (Some("txt"), _) | (None, _) => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serves_api_docs_and_index() {
+        let server = MockServer::start(bundled_fixtures()).await.unwrap();
+        let http = reqwest::Client::new();
+        let get = |path: &str| http.get(format!("{}{path}", server.url())).send();
+
+        let search = get("/api/v1/crates?q=itoa&per_page=5").await.unwrap();
+        assert_eq!(search.status(), 200);
+        let search: serde_json::Value = search.json().await.unwrap();
+        assert_eq!(search["crates"][0]["name"], "itoa");
+
+        let missing = get("/api/v1/crates/missing").await.unwrap();
+        assert_eq!(missing.status(), 404);
+
+        let page = get("/itoa/latest/itoa/").await.unwrap();
+        assert_eq!(page.status(), 200);
+        assert_eq!(
+            page.headers()[header::CONTENT_TYPE.as_str()],
+            "text/html; charset=utf-8"
+        );
+        assert!(page.text().await.unwrap().contains("item-table"));
+
+        let index = get("/index/it/oa/itoa").await.unwrap();
+        assert!(index.text().await.unwrap().contains(r#""vers":"1.0.11""#));
+
+        assert_eq!(get("/crate/itoa/1.0.11/json").await.unwrap().status(), 404);
+    }
+}
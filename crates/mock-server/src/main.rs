@@ -0,0 +1,53 @@
+use anyhow::Result;
+use clap::Parser;
+use rustacean_docs_mock_server::{bundled_fixtures, MockServer};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "rustacean-docs-mock-server",
+    about = "Serve docs.rs pages and crates.io API responses from a fixture tree",
+    version
+)]
+struct Cli {
+    #[arg(
+        long,
+        help = "Fixture tree to serve (defaults to the bundled fixtures)"
+    )]
+    fixtures: Option<PathBuf>,
+
+    #[arg(long, help = "Address to listen on", default_value = "127.0.0.1:8081")]
+    bind: SocketAddr,
+
+    #[arg(short, long, help = "Set log level", default_value = "info")]
+    log_level: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&cli.log_level)),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let fixtures = cli.fixtures.unwrap_or_else(bundled_fixtures);
+    let server = MockServer::bind(&fixtures, cli.bind).await?;
+
+    eprintln!(
+        "Serving {} at {}. Point rustacean-docs at it with:",
+        fixtures.display(),
+        server.url()
+    );
+    for (name, value) in server.env() {
+        println!("export {name}={value}");
+    }
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
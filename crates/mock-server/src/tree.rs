@@ -0,0 +1,391 @@
+//! The fixture tree served by the mock server
+//!
+//! ```text
+//! <root>/
+//!   crates/<name>.json                        GET /api/v1/crates/<name>
+//!   dependencies/<name>/<version>.json        GET /api/v1/crates/<name>/<version>/dependencies
+//!   docs/<path>                               GET /<path> (docs.rs pages, rustdoc JSON, search-index files)
+//!   index/<path>                              GET /index/<path> (sparse index files)
+//! ```
+//!
+//! Crate files hold the crates.io response for the crate, `{"crate": ..,
+//! "versions": [..]}`. Search and version listings are derived from them.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Results per search page when `per_page` is not given
+const DEFAULT_PER_PAGE: usize = 10;
+
+/// Largest search page crates.io serves
+const MAX_PER_PAGE: usize = 100;
+
+/// Parameters of a crates.io search request
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub page: Option<usize>,
+    #[serde(default)]
+    pub per_page: Option<usize>,
+}
+
+/// Crates.io responses and docs files of a fixture directory
+#[derive(Debug, Clone)]
+pub struct FixtureTree {
+    root: PathBuf,
+    /// crates.io crate responses by normalized name
+    crates: BTreeMap<String, Value>,
+}
+
+impl FixtureTree {
+    /// Load the crate responses under `root/crates`; docs and index files
+    /// are read from disk on request
+    pub fn load(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let mut crates = BTreeMap::new();
+
+        let crates_dir = root.join("crates");
+        if crates_dir.is_dir() {
+            for entry in std::fs::read_dir(&crates_dir)
+                .with_context(|| format!("Failed to read {}", crates_dir.display()))?
+            {
+                let path = entry?.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let contents = std::fs::read(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let response: Value = serde_json::from_slice(&contents)
+                    .with_context(|| format!("Invalid crate fixture {}", path.display()))?;
+                let name = response["crate"]["name"]
+                    .as_str()
+                    .with_context(|| format!("{} has no crate.name", path.display()))?;
+                crates.insert(normalize(name), response);
+            }
+        }
+
+        Ok(Self { root, crates })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Names of the crates with API fixtures
+    pub fn crate_names(&self) -> impl Iterator<Item = &str> {
+        self.crates
+            .values()
+            .filter_map(|response| response["crate"]["name"].as_str())
+    }
+
+    /// `GET /api/v1/crates/<name>`
+    pub fn crate_response(&self, name: &str) -> Option<&Value> {
+        self.crates.get(&normalize(name))
+    }
+
+    /// `GET /api/v1/crates/<name>/versions`
+    pub fn versions_response(&self, name: &str) -> Option<Value> {
+        let versions = versions(self.crate_response(name)?);
+        Some(json!({
+            "versions": versions,
+            "meta": { "total": versions.len(), "next_page": null },
+        }))
+    }
+
+    /// `GET /api/v1/crates/<name>/<version>/dependencies`: the recorded
+    /// dependencies file, else those embedded in the version entry, else none
+    pub fn dependencies_response(&self, name: &str, version: &str) -> Result<Option<Value>> {
+        let Some(response) = self.crate_response(name) else {
+            return Ok(None);
+        };
+        let Some(entry) = versions(response)
+            .iter()
+            .find(|entry| entry["num"].as_str() == Some(version))
+        else {
+            return Ok(None);
+        };
+
+        let crate_name = response["crate"]["name"].as_str().unwrap_or(name);
+        let path = self
+            .root
+            .join("dependencies")
+            .join(crate_name)
+            .join(format!("{version}.json"));
+        if path.is_file() {
+            let contents = std::fs::read(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let response = serde_json::from_slice(&contents)
+                .with_context(|| format!("Invalid dependencies fixture {}", path.display()))?;
+            return Ok(Some(response));
+        }
+
+        let dependencies = entry.get("dependencies").cloned().unwrap_or(json!([]));
+        Ok(Some(json!({ "dependencies": dependencies })))
+    }
+
+    /// `GET /api/v1/crates?q=..`: crates whose name, description or keywords
+    /// contain every query term, in crates.io's response shape
+    pub fn search(&self, params: &SearchParams) -> Value {
+        let query = params.q.as_deref().unwrap_or("").trim().to_lowercase();
+        let terms: Vec<&str> = query.split_whitespace().collect();
+
+        let mut crates: Vec<Map<String, Value>> = self
+            .crates
+            .values()
+            .filter(|response| matches_terms(&response["crate"], &terms))
+            .map(|response| search_entry(response, &query))
+            .collect();
+
+        let downloads = |entry: &Map<String, Value>| entry["downloads"].as_u64().unwrap_or(0);
+        let text = |entry: &Map<String, Value>, field: &str| {
+            entry
+                .get(field)
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string()
+        };
+        match params.sort.as_deref() {
+            Some("recent-updates") => {
+                crates.sort_by_key(|entry| std::cmp::Reverse(text(entry, "updated_at")))
+            }
+            Some("new") => crates.sort_by_key(|entry| std::cmp::Reverse(text(entry, "created_at"))),
+            Some("downloads") => crates.sort_by_key(|entry| std::cmp::Reverse(downloads(entry))),
+            Some("alpha") => crates.sort_by_key(|entry| text(entry, "name")),
+            _ => crates.sort_by_key(|entry| {
+                let exact = entry["exact_match"].as_bool().unwrap_or(false);
+                (
+                    std::cmp::Reverse(exact),
+                    std::cmp::Reverse(downloads(entry)),
+                )
+            }),
+        }
+
+        let total = crates.len();
+        let per_page = params
+            .per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE);
+        let page = params.page.unwrap_or(1).max(1);
+        let page_link = |page: usize| {
+            let mut link = format!("?page={page}&per_page={per_page}");
+            if let Some(q) = &params.q {
+                link.push_str(&format!("&q={q}"));
+            }
+            if let Some(sort) = &params.sort {
+                link.push_str(&format!("&sort={sort}"));
+            }
+            link
+        };
+        let next_page = (page * per_page < total).then(|| page_link(page + 1));
+        let prev_page = (page > 1).then(|| page_link(page - 1));
+
+        let crates: Vec<_> = crates
+            .into_iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .collect();
+        json!({
+            "crates": crates,
+            "meta": { "total": total, "next_page": next_page, "prev_page": prev_page },
+        })
+    }
+
+    /// The docs file answering `path`, resolving `latest` to the newest
+    /// version directory and directories to their `index.html`
+    pub fn docs_file(&self, path: &str) -> Option<PathBuf> {
+        resolve(&self.root.join("docs"), path)
+    }
+
+    /// The sparse index file answering `path`
+    pub fn index_file(&self, path: &str) -> Option<PathBuf> {
+        resolve(&self.root.join("index"), path)
+    }
+}
+
+/// crates.io treats `-` and `_` in names as the same character
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+fn versions(response: &Value) -> &[Value] {
+    response["versions"].as_array().map_or(&[], Vec::as_slice)
+}
+
+fn matches_terms(krate: &Value, terms: &[&str]) -> bool {
+    let mut haystack = String::new();
+    for field in ["name", "description"] {
+        if let Some(text) = krate[field].as_str() {
+            haystack.push_str(&text.to_lowercase());
+            haystack.push(' ');
+        }
+    }
+    for keyword in krate["keywords"].as_array().into_iter().flatten() {
+        if let Some(keyword) = keyword.as_str() {
+            haystack.push_str(&keyword.to_lowercase());
+            haystack.push(' ');
+        }
+    }
+    let haystack = haystack.replace('_', "-");
+    terms
+        .iter()
+        .all(|term| haystack.contains(&term.replace('_', "-")))
+}
+
+/// The crate object of a search result, with the fields crates.io always
+/// sends filled in when the fixture leaves them out
+fn search_entry(response: &Value, query: &str) -> Map<String, Value> {
+    let mut entry = response["crate"].as_object().cloned().unwrap_or_default();
+    let name = entry
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let max_version = entry.get("max_version").cloned().unwrap_or(json!("0.0.0"));
+
+    entry.insert(
+        "exact_match".to_string(),
+        json!(!query.is_empty() && normalize(&name) == normalize(query)),
+    );
+    entry.entry("id").or_insert_with(|| json!(name));
+    entry
+        .entry("newest_version")
+        .or_insert_with(|| max_version.clone());
+    entry
+        .entry("default_version")
+        .or_insert_with(|| max_version.clone());
+    entry
+        .entry("num_versions")
+        .or_insert_with(|| json!(versions(response).len()));
+    entry.entry("badges").or_insert_with(|| json!([]));
+    entry.entry("links").or_insert_with(|| json!({}));
+    entry.entry("yanked").or_insert(json!(false));
+    entry
+}
+
+/// The file under `base` for a URL path. `..` segments are refused, a
+/// missing `latest` directory stands for the newest version directory
+/// beside it, and directories answer with their `index.html`.
+fn resolve(base: &Path, path: &str) -> Option<PathBuf> {
+    let mut resolved = base.to_path_buf();
+    for segment in path.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        if !matches!(
+            Path::new(segment).components().next(),
+            Some(Component::Normal(_))
+        ) {
+            return None;
+        }
+        let candidate = resolved.join(segment);
+        resolved = if segment == "latest" && !candidate.exists() {
+            newest_version_dir(&resolved)?
+        } else {
+            candidate
+        };
+    }
+
+    if resolved.is_dir() {
+        resolved.push("index.html");
+    }
+    resolved.is_file().then_some(resolved)
+}
+
+/// The subdirectory of `dir` named after the highest version, preferring
+/// stable releases
+fn newest_version_dir(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let version = semver::Version::parse(entry.file_name().to_str()?).ok()?;
+            entry
+                .file_type()
+                .ok()?
+                .is_dir()
+                .then(|| (version.pre.is_empty(), version, entry.path()))
+        })
+        .max_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)))
+        .map(|(_, _, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled() -> FixtureTree {
+        FixtureTree::load(crate::bundled_fixtures()).unwrap()
+    }
+
+    #[test]
+    fn test_search_filters_sorts_and_pages() {
+        let tree = bundled();
+        let results = tree.search(&SearchParams {
+            q: Some("itoa".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(results["crates"][0]["name"], "itoa");
+        assert_eq!(results["crates"][0]["exact_match"], true);
+        assert_eq!(results["crates"][0]["badges"], json!([]));
+
+        let all = tree.search(&SearchParams {
+            per_page: Some(1),
+            sort: Some("recent-updates".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(all["meta"]["total"], tree.crate_names().count());
+        assert_eq!(all["crates"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            all["meta"]["next_page"],
+            "?page=2&per_page=1&sort=recent-updates"
+        );
+
+        let none = tree.search(&SearchParams {
+            q: Some("no such crate anywhere".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(none["meta"]["total"], 0);
+    }
+
+    #[test]
+    fn test_crate_versions_and_dependencies() {
+        let tree = bundled();
+        assert!(tree.crate_response("itoa").is_some());
+        assert!(tree.crate_response("missing").is_none());
+
+        let versions = tree.versions_response("itoa").unwrap();
+        assert_eq!(versions["versions"][0]["num"], "1.0.11");
+
+        let dependencies = tree
+            .dependencies_response("itoa", "1.0.11")
+            .unwrap()
+            .unwrap();
+        assert_eq!(dependencies["dependencies"][0]["crate_id"], "no-panic");
+        assert!(tree
+            .dependencies_response("itoa", "9.9.9")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_docs_paths_resolve_latest_and_index() {
+        let tree = bundled();
+        let docs = tree.root().join("docs");
+        assert_eq!(
+            tree.docs_file("/itoa/latest/itoa/"),
+            Some(docs.join("itoa/1.0.11/itoa/index.html"))
+        );
+        assert_eq!(
+            tree.docs_file("/itoa/1.0.11/itoa/struct.Buffer.html"),
+            Some(docs.join("itoa/1.0.11/itoa/struct.Buffer.html"))
+        );
+        assert!(tree
+            .docs_file("/itoa/1.0.11/../../crates/itoa.json")
+            .is_none());
+        assert!(tree.docs_file("/itoa/1.0.11/itoa/missing.html").is_none());
+        assert!(tree.index_file("/it/oa/itoa").is_some());
+    }
+}